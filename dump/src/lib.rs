@@ -17,7 +17,7 @@ mod writer;
 
 pub use error::Error;
 pub use reader::{DumpReader, UpdateFile};
-pub use writer::DumpWriter;
+pub use writer::{DumpCompression, DumpCompressionError, DumpWriter};
 
 const CURRENT_DUMP_VERSION: Version = Version::V6;
 
@@ -132,6 +132,10 @@ pub enum KindDump {
         instance_uid: Option<InstanceUid>,
     },
     SnapshotCreation,
+    DumpImport {
+        dump_uid: String,
+        import_keys: bool,
+    },
 }
 
 impl From<Task> for TaskDump {
@@ -197,6 +201,9 @@ impl From<KindWithContent> for KindDump {
                 KindDump::DumpCreation { keys, instance_uid }
             }
             KindWithContent::SnapshotCreation => KindDump::SnapshotCreation,
+            KindWithContent::DumpImport { dump_uid, import_keys } => {
+                KindDump::DumpImport { dump_uid, import_keys }
+            }
         }
     }
 }
@@ -222,7 +229,9 @@ pub(crate) mod test {
     use uuid::Uuid;
 
     use crate::reader::Document;
-    use crate::{DumpReader, DumpWriter, IndexMetadata, KindDump, TaskDump, Version};
+    use crate::{
+        DumpCompression, DumpReader, DumpWriter, IndexMetadata, KindDump, TaskDump, Version,
+    };
 
     pub fn create_test_instance_uid() -> Uuid {
         Uuid::parse_str("9e15e977-f2ae-4761-943f-1eaf75fd736d").unwrap()
@@ -274,6 +283,7 @@ pub(crate) mod test {
                 sort_facet_values_by: Setting::Set(
                     btreemap! { S("age") => FacetValuesSort::Count },
                 ),
+                hierarchical_facets: Setting::NotSet,
             }),
             pagination: Setting::NotSet,
             embedders: Setting::NotSet,
@@ -300,6 +310,7 @@ pub(crate) mod test {
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 12,
                         indexed_documents: Some(10),
+                        embedding_cache: None,
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
@@ -323,6 +334,7 @@ pub(crate) mod test {
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 2,
                         indexed_documents: None,
+                        embedding_cache: None,
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
@@ -361,6 +373,10 @@ pub(crate) mod test {
                 actions: vec![Action::DocumentsAll],
                 indexes: vec![IndexUidPattern::from_str("doggos").unwrap()],
                 expires_at: Some(datetime!(4130-03-14 12:21 UTC)),
+                search_rules: Default::default(),
+                rate_limit: None,
+                daily_quota: None,
+                tenant_token_keys: None,
                 created_at: datetime!(1960-11-15 0:00 UTC),
                 updated_at: datetime!(2022-11-10 0:00 UTC),
             },
@@ -371,6 +387,10 @@ pub(crate) mod test {
                 actions: vec![Action::All],
                 indexes: vec![IndexUidPattern::all()],
                 expires_at: None,
+                search_rules: Default::default(),
+                rate_limit: None,
+                daily_quota: None,
+                tenant_token_keys: None,
                 created_at: datetime!(0000-01-01 00:01 UTC),
                 updated_at: datetime!(1964-05-04 17:25 UTC),
             },
@@ -381,6 +401,10 @@ pub(crate) mod test {
                 actions: vec![],
                 indexes: vec![],
                 expires_at: None,
+                search_rules: Default::default(),
+                rate_limit: None,
+                daily_quota: None,
+                tenant_token_keys: None,
                 created_at: datetime!(400-02-29 0:00 UTC),
                 updated_at: datetime!(1024-02-29 0:00 UTC),
             },
@@ -388,8 +412,13 @@ pub(crate) mod test {
     }
 
     pub fn create_test_dump() -> File {
+        create_test_dump_with_compression(DumpCompression::Gzip)
+    }
+
+    pub fn create_test_dump_with_compression(compression: DumpCompression) -> File {
         let instance_uid = create_test_instance_uid();
-        let dump = DumpWriter::new(Some(instance_uid)).unwrap();
+        let file = tempfile::tempfile().unwrap();
+        let dump = DumpWriter::new(Some(instance_uid), file, compression).unwrap();
 
         // ========== Adding an index
         let documents = create_test_documents();
@@ -413,6 +442,7 @@ pub(crate) mod test {
                     update.push_document(u).unwrap();
                 }
             }
+            update.flush().unwrap();
         }
         task_queue.flush().unwrap();
 
@@ -431,8 +461,7 @@ pub(crate) mod test {
         dump.create_experimental_features(features).unwrap();
 
         // create the dump
-        let mut file = tempfile::tempfile().unwrap();
-        dump.persist_to(&mut file).unwrap();
+        let mut file = dump.finish().unwrap();
         file.rewind().unwrap();
 
         file
@@ -445,8 +474,18 @@ pub(crate) mod test {
     #[test]
     fn test_creating_and_read_dump() {
         let mut file = create_test_dump();
-        let mut dump = DumpReader::open(&mut file).unwrap();
+        let dump = DumpReader::open(&mut file).unwrap();
+        check_test_dump(dump);
+    }
+
+    #[test]
+    fn test_creating_and_read_zstd_dump() {
+        let mut file = create_test_dump_with_compression(DumpCompression::Zstd);
+        let dump = DumpReader::open(&mut file).unwrap();
+        check_test_dump(dump);
+    }
 
+    fn check_test_dump(mut dump: DumpReader) {
         // ==== checking the top level infos
         assert_eq!(dump.version(), Version::V6);
         assert!(dump.date().is_some());
@@ -132,11 +132,13 @@ impl CompatV5ToV6 {
                             v6::Details::DocumentAdditionOrUpdate {
                                 received_documents: received_documents as u64,
                                 indexed_documents,
+                                embedding_cache: None,
                             }
                         }
-                        v5::Details::Settings { settings } => {
-                            v6::Details::SettingsUpdate { settings: Box::new(settings.into()) }
-                        }
+                        v5::Details::Settings { settings } => v6::Details::SettingsUpdate {
+                            settings: Box::new(settings.into()),
+                            embedding_cache: None,
+                        },
                         v5::Details::IndexInfo { primary_key } => {
                             v6::Details::IndexInfo { primary_key }
                         }
@@ -186,6 +188,10 @@ impl CompatV5ToV6 {
                     })
                     .collect(),
                 expires_at: key.expires_at,
+                search_rules: Default::default(),
+                rate_limit: None,
+                daily_quota: None,
+                tenant_token_keys: None,
                 created_at: key.created_at,
                 updated_at: key.updated_at,
             })
@@ -367,6 +373,7 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
                 v5::Setting::Set(faceting) => v6::Setting::Set(v6::FacetingSettings {
                     max_values_per_facet: faceting.max_values_per_facet.into(),
                     sort_facet_values_by: v6::Setting::NotSet,
+                    hierarchical_facets: v6::Setting::NotSet,
                 }),
                 v5::Setting::Reset => v6::Setting::Reset,
                 v5::Setting::NotSet => v6::Setting::NotSet,
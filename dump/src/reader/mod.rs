@@ -1,5 +1,5 @@
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::{BufRead, BufReader, Read};
 
 use flate2::bufread::GzDecoder;
 use serde::Deserialize;
@@ -23,6 +23,9 @@ mod v6;
 pub type Document = serde_json::Map<String, serde_json::Value>;
 pub type UpdateFile = dyn Iterator<Item = Result<Document>>;
 
+/// The magic number every zstd frame starts with.
+const ZSTD_MAGIC_NUMBER: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
 pub enum DumpReader {
     Current(V6Reader),
     Compat(CompatV5ToV6),
@@ -32,9 +35,16 @@ impl DumpReader {
     pub fn open(dump: impl Read) -> Result<DumpReader> {
         let path = TempDir::new()?;
         let mut dump = BufReader::new(dump);
-        let gz = GzDecoder::new(&mut dump);
-        let mut archive = tar::Archive::new(gz);
-        archive.unpack(path.path())?;
+        // The dumps are gzipped by default but can also be compressed with zstd.
+        if dump.fill_buf()?.starts_with(&ZSTD_MAGIC_NUMBER) {
+            let zstd = zstd::Decoder::with_buffer(dump)?;
+            let mut archive = tar::Archive::new(zstd);
+            archive.unpack(path.path())?;
+        } else {
+            let gz = GzDecoder::new(&mut dump);
+            let mut archive = tar::Archive::new(gz);
+            archive.unpack(path.path())?;
+        }
 
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
@@ -108,6 +118,16 @@ impl DumpReader {
         }
     }
 
+    pub fn audit_log(
+        &mut self,
+    ) -> Result<Box<dyn Iterator<Item = Result<v6::AuditLogEntry>> + '_>> {
+        match self {
+            DumpReader::Current(current) => Ok(current.audit_log()),
+            // the audit log didn't exist before the v6 dumps.
+            DumpReader::Compat(_) => Ok(Box::new(std::iter::empty())),
+        }
+    }
+
     pub fn features(&self) -> Result<Option<v6::RuntimeTogglableFeatures>> {
         match self {
             DumpReader::Current(current) => Ok(current.features()),
@@ -19,6 +19,7 @@ pub type Unchecked = meilisearch_types::settings::Unchecked;
 
 pub type Task = crate::TaskDump;
 pub type Key = meilisearch_types::keys::Key;
+pub type AuditLogEntry = meilisearch_types::audit_log::AuditLogEntry;
 pub type RuntimeTogglableFeatures = meilisearch_types::features::RuntimeTogglableFeatures;
 
 // ===== Other types to clarify the code of the compat module
@@ -49,6 +50,7 @@ pub struct V6Reader {
     metadata: Metadata,
     tasks: BufReader<File>,
     keys: BufReader<File>,
+    audit_log: Option<BufReader<File>>,
     features: Option<RuntimeTogglableFeatures>,
 }
 
@@ -78,11 +80,19 @@ impl V6Reader {
             None
         };
 
+        let audit_log = match File::open(dump.path().join("audit-log.jsonl")) {
+            Ok(audit_log) => Some(BufReader::new(audit_log)),
+            // The dumps created before the audit log was introduced don't contain it.
+            Err(error) if error.kind() == ErrorKind::NotFound => None,
+            Err(error) => return Err(error.into()),
+        };
+
         Ok(V6Reader {
             metadata: serde_json::from_reader(&*meta_file)?,
             instance_uid,
             tasks: BufReader::new(File::open(dump.path().join("tasks").join("queue.jsonl"))?),
             keys: BufReader::new(File::open(dump.path().join("keys.jsonl"))?),
+            audit_log,
             features,
             dump,
         })
@@ -151,6 +161,15 @@ impl V6Reader {
         )
     }
 
+    pub fn audit_log(&mut self) -> Box<dyn Iterator<Item = Result<AuditLogEntry>> + '_> {
+        match &mut self.audit_log {
+            Some(audit_log) => Box::new(
+                audit_log.lines().map(|line| -> Result<_> { Ok(serde_json::from_str(&line?)?) }),
+            ),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
     pub fn features(&self) -> Option<RuntimeTogglableFeatures> {
         self.features
     }
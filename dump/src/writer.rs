@@ -1,86 +1,218 @@
-use std::fs::{self, File};
-use std::io::{BufWriter, Write};
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, Write};
+use std::str::FromStr;
 
 use flate2::write::GzEncoder;
 use flate2::Compression;
+use meilisearch_types::audit_log::AuditLogEntry;
 use meilisearch_types::features::RuntimeTogglableFeatures;
 use meilisearch_types::keys::Key;
 use meilisearch_types::settings::{Checked, Settings};
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
-use tempfile::TempDir;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::reader::Document;
 use crate::{IndexMetadata, Metadata, Result, TaskDump, CURRENT_DUMP_VERSION};
 
-pub struct DumpWriter {
-    dir: TempDir,
+/// The compression algorithm used to write the tarball of a dump.
+///
+/// The [`DumpReader`](crate::DumpReader) detects it on its own, there is no need to remember it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DumpCompression {
+    #[default]
+    Gzip,
+    Zstd,
 }
 
-impl DumpWriter {
-    pub fn new(instance_uuid: Option<Uuid>) -> Result<DumpWriter> {
-        let dir = TempDir::new()?;
+impl Display for DumpCompression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DumpCompression::Gzip => Display::fmt("gzip", f),
+            DumpCompression::Zstd => Display::fmt("zstd", f),
+        }
+    }
+}
 
-        if let Some(instance_uuid) = instance_uuid {
-            fs::write(
-                dir.path().join("instance_uid.uuid"),
-                instance_uuid.as_hyphenated().to_string(),
-            )?;
+impl FromStr for DumpCompression {
+    type Err = DumpCompressionError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "gzip" => Ok(DumpCompression::Gzip),
+            "zstd" => Ok(DumpCompression::Zstd),
+            _ => Err(DumpCompressionError(s.to_owned())),
         }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unsupported dump compression `{0}`. Supported values are `gzip` and `zstd`.")]
+pub struct DumpCompressionError(String);
 
+enum Encoder<W: Write> {
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> Encoder<W> {
+    fn new(writer: W, compression: DumpCompression) -> io::Result<Self> {
+        match compression {
+            DumpCompression::Gzip => {
+                Ok(Encoder::Gzip(GzEncoder::new(writer, Compression::default())))
+            }
+            DumpCompression::Zstd => {
+                Ok(Encoder::Zstd(zstd::Encoder::new(writer, zstd::DEFAULT_COMPRESSION_LEVEL)?))
+            }
+        }
+    }
+
+    fn finish(self) -> io::Result<W> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.finish(),
+            Encoder::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.write(buf),
+            Encoder::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.flush(),
+            Encoder::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Writes a dump as a compressed tarball, directly into the writer it was created with.
+///
+/// The entries are appended to the archive as soon as they are complete. Only the entry
+/// that is currently being written (the documents of an index, the task queue...) is
+/// buffered in a temporary file, so we never need to hold the whole dump twice on disk.
+pub struct DumpWriter<W: Write> {
+    archive: RefCell<tar::Builder<Encoder<W>>>,
+    mtime: u64,
+}
+
+impl<W: Write> DumpWriter<W> {
+    pub fn new(
+        instance_uuid: Option<Uuid>,
+        writer: W,
+        compression: DumpCompression,
+    ) -> Result<DumpWriter<W>> {
         let metadata = Metadata {
             dump_version: CURRENT_DUMP_VERSION,
             db_version: env!("CARGO_PKG_VERSION").to_string(),
             dump_date: OffsetDateTime::now_utc(),
         };
-        fs::write(dir.path().join("metadata.json"), serde_json::to_string(&metadata)?)?;
+        let mtime = metadata.dump_date.unix_timestamp().max(0) as u64;
+        let mut archive = tar::Builder::new(Encoder::new(writer, compression)?);
+        let dump = DumpWriter { archive: RefCell::new(archive), mtime };
+
+        if let Some(instance_uuid) = instance_uuid {
+            dump.append_data(
+                "instance_uid.uuid",
+                instance_uuid.as_hyphenated().to_string().as_bytes(),
+            )?;
+        }
 
-        std::fs::create_dir(dir.path().join("indexes"))?;
+        dump.append_data("metadata.json", &serde_json::to_vec(&metadata)?)?;
+        dump.append_dir("indexes")?;
 
-        Ok(DumpWriter { dir })
+        Ok(dump)
     }
 
-    pub fn create_index(&self, index_name: &str, metadata: &IndexMetadata) -> Result<IndexWriter> {
-        IndexWriter::new(self.dir.path().join("indexes").join(index_name), metadata)
+    pub fn create_index(
+        &self,
+        index_name: &str,
+        metadata: &IndexMetadata,
+    ) -> Result<IndexWriter<'_, W>> {
+        IndexWriter::new(self, format!("indexes/{index_name}"), metadata)
     }
 
-    pub fn create_keys(&self) -> Result<KeyWriter> {
-        KeyWriter::new(self.dir.path().to_path_buf())
+    pub fn create_keys(&self) -> Result<KeyWriter<'_, W>> {
+        KeyWriter::new(self)
     }
 
-    pub fn create_tasks_queue(&self) -> Result<TaskWriter> {
-        TaskWriter::new(self.dir.path().join("tasks"))
+    pub fn create_audit_log(&self) -> Result<AuditLogWriter<'_, W>> {
+        AuditLogWriter::new(self)
+    }
+
+    pub fn create_tasks_queue(&self) -> Result<TaskWriter<'_, W>> {
+        TaskWriter::new(self, "tasks")
     }
 
     pub fn create_experimental_features(&self, features: RuntimeTogglableFeatures) -> Result<()> {
-        Ok(std::fs::write(
-            self.dir.path().join("experimental-features.json"),
-            serde_json::to_string(&features)?,
-        )?)
-    }
-
-    pub fn persist_to(self, mut writer: impl Write) -> Result<()> {
-        let gz_encoder = GzEncoder::new(&mut writer, Compression::default());
-        let mut tar_encoder = tar::Builder::new(gz_encoder);
-        tar_encoder.append_dir_all(".", self.dir.path())?;
-        let gz_encoder = tar_encoder.into_inner()?;
-        gz_encoder.finish()?;
+        self.append_data("experimental-features.json", &serde_json::to_vec(&features)?)
+    }
+
+    /// Terminates the archive and returns the writer it was written to.
+    ///
+    /// Everything that was not flushed by its own writer before this call is lost.
+    pub fn finish(self) -> Result<W> {
+        let encoder = self.archive.into_inner().into_inner()?;
+        let mut writer = encoder.finish()?;
         writer.flush()?;
 
+        Ok(writer)
+    }
+
+    fn append_dir(&self, path: &str) -> Result<()> {
+        let mut header = self.header(tar::EntryType::Directory, 0o755, 0);
+        self.archive.borrow_mut().append_data(&mut header, path, io::empty())?;
+        Ok(())
+    }
+
+    fn append_data(&self, path: &str, data: &[u8]) -> Result<()> {
+        let mut header = self.header(tar::EntryType::Regular, 0o644, data.len() as u64);
+        self.archive.borrow_mut().append_data(&mut header, path, data)?;
         Ok(())
     }
+
+    /// Appends the content of a buffered temporary file to the archive.
+    fn append_buffer(&self, path: &str, buffer: BufWriter<File>) -> Result<()> {
+        let mut file = buffer.into_inner().map_err(|e| e.into_error())?;
+        let size = file.stream_position()?;
+        file.rewind()?;
+        let mut header = self.header(tar::EntryType::Regular, 0o644, size);
+        self.archive.borrow_mut().append_data(&mut header, path, file)?;
+        Ok(())
+    }
+
+    fn header(&self, entry_type: tar::EntryType, mode: u32, size: u64) -> tar::Header {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(entry_type);
+        header.set_mode(mode);
+        header.set_size(size);
+        header.set_mtime(self.mtime);
+        header
+    }
+}
+
+/// Creates the temporary file in which an entry of the archive is buffered.
+fn buffer() -> Result<BufWriter<File>> {
+    Ok(BufWriter::new(tempfile::tempfile()?))
 }
 
-pub struct KeyWriter {
+pub struct KeyWriter<'a, W: Write> {
+    dump: &'a DumpWriter<W>,
     keys: BufWriter<File>,
 }
 
-impl KeyWriter {
-    pub(crate) fn new(path: PathBuf) -> Result<Self> {
-        let keys = File::create(path.join("keys.jsonl"))?;
-        Ok(KeyWriter { keys: BufWriter::new(keys) })
+impl<'a, W: Write> KeyWriter<'a, W> {
+    pub(crate) fn new(dump: &'a DumpWriter<W>) -> Result<Self> {
+        Ok(KeyWriter { dump, keys: buffer()? })
     }
 
     pub fn push_key(&mut self, key: &Key) -> Result<()> {
@@ -89,51 +221,70 @@ impl KeyWriter {
         Ok(())
     }
 
-    pub fn flush(mut self) -> Result<()> {
-        self.keys.flush()?;
+    pub fn flush(self) -> Result<()> {
+        self.dump.append_buffer("keys.jsonl", self.keys)
+    }
+}
+
+pub struct AuditLogWriter<'a, W: Write> {
+    dump: &'a DumpWriter<W>,
+    entries: BufWriter<File>,
+}
+
+impl<'a, W: Write> AuditLogWriter<'a, W> {
+    pub(crate) fn new(dump: &'a DumpWriter<W>) -> Result<Self> {
+        Ok(AuditLogWriter { dump, entries: buffer()? })
+    }
+
+    pub fn push_entry(&mut self, entry: &AuditLogEntry) -> Result<()> {
+        self.entries.write_all(&serde_json::to_vec(entry)?)?;
+        self.entries.write_all(b"\n")?;
         Ok(())
     }
+
+    pub fn flush(self) -> Result<()> {
+        self.dump.append_buffer("audit-log.jsonl", self.entries)
+    }
 }
 
-pub struct TaskWriter {
+pub struct TaskWriter<'a, W: Write> {
+    dump: &'a DumpWriter<W>,
+    path: &'static str,
     queue: BufWriter<File>,
-    update_files: PathBuf,
 }
 
-impl TaskWriter {
-    pub(crate) fn new(path: PathBuf) -> Result<Self> {
-        std::fs::create_dir(&path)?;
+impl<'a, W: Write> TaskWriter<'a, W> {
+    pub(crate) fn new(dump: &'a DumpWriter<W>, path: &'static str) -> Result<Self> {
+        dump.append_dir(path)?;
+        dump.append_dir(&format!("{path}/update_files"))?;
 
-        let queue = File::create(path.join("queue.jsonl"))?;
-        let update_files = path.join("update_files");
-        std::fs::create_dir(&update_files)?;
-
-        Ok(TaskWriter { queue: BufWriter::new(queue), update_files })
+        Ok(TaskWriter { dump, path, queue: buffer()? })
     }
 
     /// Pushes tasks in the dump.
     /// If the tasks has an associated `update_file` it'll use the `task_id` as its name.
-    pub fn push_task(&mut self, task: &TaskDump) -> Result<UpdateFile> {
+    pub fn push_task(&mut self, task: &TaskDump) -> Result<UpdateFile<'a, W>> {
         self.queue.write_all(&serde_json::to_vec(task)?)?;
         self.queue.write_all(b"\n")?;
 
-        Ok(UpdateFile::new(self.update_files.join(format!("{}.jsonl", task.uid))))
+        Ok(UpdateFile::new(self.dump, format!("{}/update_files/{}.jsonl", self.path, task.uid)))
     }
 
-    pub fn flush(mut self) -> Result<()> {
-        self.queue.flush()?;
-        Ok(())
+    pub fn flush(self) -> Result<()> {
+        self.dump.append_buffer(&format!("{}/queue.jsonl", self.path), self.queue)
     }
 }
 
-pub struct UpdateFile {
-    path: PathBuf,
+/// The documents of an update file are only written in the dump once it is flushed.
+pub struct UpdateFile<'a, W: Write> {
+    dump: &'a DumpWriter<W>,
+    path: String,
     writer: Option<BufWriter<File>>,
 }
 
-impl UpdateFile {
-    pub(crate) fn new(path: PathBuf) -> UpdateFile {
-        UpdateFile { path, writer: None }
+impl<'a, W: Write> UpdateFile<'a, W> {
+    pub(crate) fn new(dump: &'a DumpWriter<W>, path: String) -> UpdateFile<'a, W> {
+        UpdateFile { dump, path, writer: None }
     }
 
     pub fn push_document(&mut self, document: &Document) -> Result<()> {
@@ -141,37 +292,37 @@ impl UpdateFile {
             writer.write_all(&serde_json::to_vec(document)?)?;
             writer.write_all(b"\n")?;
         } else {
-            let file = File::create(&self.path).unwrap();
-            self.writer = Some(BufWriter::new(file));
+            self.writer = Some(buffer()?);
             self.push_document(document)?;
         }
         Ok(())
     }
 
     pub fn flush(self) -> Result<()> {
-        if let Some(mut writer) = self.writer {
-            writer.flush()?;
+        if let Some(writer) = self.writer {
+            self.dump.append_buffer(&self.path, writer)?;
         }
         Ok(())
     }
 }
 
-pub struct IndexWriter {
+/// The documents of an index are written in the dump along with its settings.
+pub struct IndexWriter<'a, W: Write> {
+    dump: &'a DumpWriter<W>,
+    path: String,
     documents: BufWriter<File>,
-    settings: File,
 }
 
-impl IndexWriter {
-    pub(self) fn new(path: PathBuf, metadata: &IndexMetadata) -> Result<Self> {
-        std::fs::create_dir(&path)?;
-
-        let metadata_file = File::create(path.join("metadata.json"))?;
-        serde_json::to_writer(metadata_file, metadata)?;
+impl<'a, W: Write> IndexWriter<'a, W> {
+    pub(self) fn new(
+        dump: &'a DumpWriter<W>,
+        path: String,
+        metadata: &IndexMetadata,
+    ) -> Result<Self> {
+        dump.append_dir(&path)?;
+        dump.append_data(&format!("{path}/metadata.json"), &serde_json::to_vec(metadata)?)?;
 
-        let documents = File::create(path.join("documents.jsonl"))?;
-        let settings = File::create(path.join("settings.json"))?;
-
-        Ok(IndexWriter { documents: BufWriter::new(documents), settings })
+        Ok(IndexWriter { dump, path, documents: buffer()? })
     }
 
     pub fn push_document(&mut self, document: &Map<String, Value>) -> Result<()> {
@@ -185,15 +336,17 @@ impl IndexWriter {
         Ok(())
     }
 
-    pub fn settings(mut self, settings: &Settings<Checked>) -> Result<()> {
-        self.settings.write_all(&serde_json::to_vec(&settings)?)?;
-        Ok(())
+    pub fn settings(self, settings: &Settings<Checked>) -> Result<()> {
+        self.dump.append_buffer(&format!("{}/documents.jsonl", self.path), self.documents)?;
+        self.dump
+            .append_data(&format!("{}/settings.json", self.path), &serde_json::to_vec(&settings)?)
     }
 }
 
 #[cfg(test)]
 pub(crate) mod test {
     use std::fmt::Write;
+    use std::fs;
     use std::io::BufReader;
     use std::path::Path;
     use std::str::FromStr;
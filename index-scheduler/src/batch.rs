@@ -21,12 +21,15 @@ use std::collections::{BTreeSet, HashSet};
 use std::ffi::OsStr;
 use std::fmt;
 use std::fs::{self, File};
-use std::io::BufWriter;
+use std::io::{BufReader, BufWriter};
+use std::num::NonZeroUsize;
 
 use dump::IndexMetadata;
 use meilisearch_types::error::Code;
 use meilisearch_types::heed::{RoTxn, RwTxn};
-use meilisearch_types::milli::documents::{obkv_to_object, DocumentsBatchReader};
+use meilisearch_types::milli::documents::{
+    obkv_to_object, DocumentsBatchBuilder, DocumentsBatchReader,
+};
 use meilisearch_types::milli::heed::CompactionOption;
 use meilisearch_types::milli::update::{
     IndexDocumentsConfig, IndexDocumentsMethod, IndexerConfig, Settings as MilliSettings,
@@ -36,14 +39,20 @@ use meilisearch_types::settings::{apply_settings_to_builder, Settings, Unchecked
 use meilisearch_types::tasks::{Details, IndexSwap, Kind, KindWithContent, Status, Task};
 use meilisearch_types::{compression, Index, VERSION_FILE_NAME};
 use roaring::RoaringBitmap;
+use time::format_description::FormatItem;
 use time::macros::format_description;
-use time::OffsetDateTime;
+use time::{OffsetDateTime, PrimitiveDateTime};
 use uuid::Uuid;
 
 use crate::autobatcher::{self, BatchKind};
 use crate::utils::{self, swap_index_uid_in_task};
 use crate::{Error, IndexScheduler, MustStopProcessing, ProcessingTasks, Result, TaskId};
 
+/// The format of the dump uids, which are also the names of the dump files.
+const DUMP_UID_FORMAT: &[FormatItem<'static>] = format_description!(
+    "[year repr:full][month repr:numerical][day padding:zero]-[hour padding:zero][minute padding:zero][second padding:zero][subsecond digits:3]"
+);
+
 /// Represents a combination of tasks that can all be processed at the same time.
 ///
 /// A batch contains the set of tasks that it represents (accessible through
@@ -62,6 +71,7 @@ pub(crate) enum Batch {
     TaskDeletions(Vec<Task>),
     SnapshotCreation(Vec<Task>),
     Dump(Task),
+    DumpImport(Task),
     IndexOperation {
         op: IndexOperation,
         must_create_index: bool,
@@ -146,6 +156,7 @@ impl Batch {
         match self {
             Batch::TaskCancelation { task, .. }
             | Batch::Dump(task)
+            | Batch::DumpImport(task)
             | Batch::IndexCreation { task, .. }
             | Batch::IndexUpdate { task, .. } => {
                 RoaringBitmap::from_sorted_iter(std::iter::once(task.uid)).unwrap()
@@ -189,6 +200,7 @@ impl Batch {
             | TaskDeletions(_)
             | SnapshotCreation(_)
             | Dump(_)
+            | DumpImport(_)
             | IndexSwap { .. } => None,
             IndexOperation { op, .. } => Some(op.index_uid()),
             IndexCreation { index_uid, .. }
@@ -208,6 +220,7 @@ impl fmt::Display for Batch {
             Batch::TaskDeletions(_) => f.write_str("TaskDeletion")?,
             Batch::SnapshotCreation(_) => f.write_str("SnapshotCreation")?,
             Batch::Dump(_) => f.write_str("Dump")?,
+            Batch::DumpImport(_) => f.write_str("DumpImport")?,
             Batch::IndexOperation { op, .. } => write!(f, "{op}")?,
             Batch::IndexCreation { .. } => f.write_str("IndexCreation")?,
             Batch::IndexUpdate { .. } => f.write_str("IndexUpdate")?,
@@ -520,7 +533,8 @@ impl IndexScheduler {
     /// 2. We get the *next* task to delete.
     /// 3. We get the *next* snapshot to process.
     /// 4. We get the *next* dump to process.
-    /// 5. We get the *next* tasks to process for a specific index.
+    /// 5. We get the *next* dump import to process.
+    /// 6. We get the *next* tasks to process for a specific index.
     #[tracing::instrument(level = "trace", skip(self, rtxn), target = "indexing::scheduler")]
     pub(crate) fn create_next_batch(&self, rtxn: &RoTxn) -> Result<Option<Batch>> {
         #[cfg(test)]
@@ -565,7 +579,15 @@ impl IndexScheduler {
             )));
         }
 
-        // 5. We make a batch from the unprioritised tasks. Start by taking the next enqueued task.
+        // 5. we batch the dump imports.
+        let to_import = self.get_kind(rtxn, Kind::DumpImport)? & enqueued;
+        if let Some(to_import) = to_import.min() {
+            return Ok(Some(Batch::DumpImport(
+                self.get_task(rtxn, to_import)?.ok_or(Error::CorruptedTaskQueue)?,
+            )));
+        }
+
+        // 6. We make a batch from the unprioritised tasks. Start by taking the next enqueued task.
         let task_id = if let Some(task_id) = enqueued.min() { task_id } else { return Ok(None) };
         let task = self.get_task(rtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
 
@@ -787,7 +809,7 @@ impl IndexScheduler {
                 // TODO We can't use the open_auth_store_env function here but we should
                 let auth = milli::heed::EnvOpenOptions::new()
                     .map_size(1024 * 1024 * 1024) // 1 GiB
-                    .max_dbs(2)
+                    .max_dbs(4)
                     .open(&self.auth_path)?;
                 auth.copy_to_file(dst.join("data.mdb"), CompactionOption::Enabled)?;
 
@@ -831,7 +853,15 @@ impl IndexScheduler {
                     } else {
                         unreachable!();
                     };
-                let dump = dump::DumpWriter::new(*instance_uid)?;
+                let dump_uid = started_at.format(DUMP_UID_FORMAT).unwrap();
+
+                // The dump is streamed in a tempfile that is only persisted with a .dump extension once complete.
+                let mut temp_dump_file = tempfile::NamedTempFile::new_in(&self.dumps_path)?;
+                let dump = dump::DumpWriter::new(
+                    *instance_uid,
+                    BufWriter::new(temp_dump_file.as_file_mut()),
+                    self.dump_compression,
+                )?;
 
                 // 1. dump the keys
                 let mut dump_keys = dump.create_keys()?;
@@ -840,6 +870,17 @@ impl IndexScheduler {
                 }
                 dump_keys.flush()?;
 
+                // 1.1 dump the audit log
+                let mut auth = meilisearch_auth::HeedAuthStore::open(&self.auth_path)?;
+                // The env is shared with the `AuthController`, we must not close it on drop.
+                auth.set_drop_on_close(false);
+                let mut dump_audit_log = dump.create_audit_log()?;
+                auth.for_each_audit_log_entry(|entry| -> Result<()> {
+                    dump_audit_log.push_entry(&entry)?;
+                    Ok(())
+                })?;
+                dump_audit_log.flush()?;
+
                 let rtxn = self.env.read_txn()?;
 
                 // 2. dump the tasks
@@ -933,22 +974,108 @@ impl IndexScheduler {
                 let features = self.features().runtime_features();
                 dump.create_experimental_features(features)?;
 
-                let dump_uid = started_at.format(format_description!(
-                    "[year repr:full][month repr:numerical][day padding:zero]-[hour padding:zero][minute padding:zero][second padding:zero][subsecond digits:3]"
-                )).unwrap();
-
                 if self.must_stop_processing.get() {
                     return Err(Error::AbortedTask);
                 }
+                dump.finish()?;
                 let path = self.dumps_path.join(format!("{}.dump", dump_uid));
-                let file = File::create(path)?;
-                dump.persist_to(BufWriter::new(file))?;
+                temp_dump_file.persist(path)?;
+
+                // 5. Remove the oldest dumps if we must only keep the most recent ones.
+                if let Some(retention) = self.dump_retention {
+                    if let Err(e) = self.remove_outdated_dumps(retention) {
+                        tracing::error!(
+                            error = &e as &dyn std::error::Error,
+                            "Could not remove the outdated dumps"
+                        );
+                    }
+                }
 
                 // if we reached this step we can tell the scheduler we succeeded to dump ourselves.
                 task.status = Status::Succeeded;
                 task.details = Some(Details::Dump { dump_uid: Some(dump_uid) });
                 Ok(vec![task])
             }
+            Batch::DumpImport(mut task) => {
+                let (dump_uid, import_keys) =
+                    if let KindWithContent::DumpImport { dump_uid, import_keys } = &task.kind {
+                        (dump_uid.clone(), *import_keys)
+                    } else {
+                        unreachable!();
+                    };
+
+                let path = self.dumps_path.join(format!("{}.dump", dump_uid));
+                let file = match File::open(path) {
+                    Ok(file) => file,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        return Err(Error::DumpNotFound(dump_uid));
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                let mut dump_reader = dump::DumpReader::open(file)?;
+
+                // 1. Make sure none of the dumped indexes exist before touching anything.
+                let index_readers = dump_reader.indexes()?.collect::<dump::Result<Vec<_>>>()?;
+                let rtxn = self.env.read_txn()?;
+                for index_reader in &index_readers {
+                    let uid = &index_reader.metadata().uid;
+                    if self.index_mapper.exists(&rtxn, uid)? {
+                        return Err(Error::IndexAlreadyExists(uid.to_string()));
+                    }
+                }
+                drop(rtxn);
+
+                let mut imported_indexes = Vec::new();
+                let res = || -> Result<()> {
+                    // 2. Import the indexes.
+                    for index_reader in index_readers {
+                        self.import_dumped_index(index_reader, &mut imported_indexes)?;
+                    }
+
+                    // 3. Import the keys that do not already exist in this instance and the audit log.
+                    if import_keys {
+                        let mut auth = meilisearch_auth::HeedAuthStore::open(&self.auth_path)?;
+                        // The env is shared with the `AuthController`, we must not close it on drop.
+                        auth.set_drop_on_close(false);
+                        auth.import_dumped_keys(
+                            dump_reader.keys()?.map(|key| key.map_err(Error::from)),
+                            false,
+                        )?;
+                        auth.import_dumped_audit_log(
+                            dump_reader.audit_log()?.map(|entry| entry.map_err(Error::from)),
+                            false,
+                        )?;
+                    }
+                    Ok(())
+                };
+
+                // If anything went wrong we delete the indexes we created to leave the instance untouched.
+                // The cleanup is best effort, the error of the import is the one we report.
+                if let Err(error) = res() {
+                    for uid in &imported_indexes {
+                        let res = self
+                            .env
+                            .write_txn()
+                            .map_err(Error::from)
+                            .and_then(|wtxn| self.index_mapper.delete_index(wtxn, uid));
+                        if let Err(e) = res {
+                            tracing::error!(
+                                index_uid = %uid,
+                                error = &e as &dyn std::error::Error,
+                                "Could not delete an index created by a failed dump import"
+                            );
+                        }
+                    }
+                    return Err(error);
+                }
+
+                task.status = Status::Succeeded;
+                task.details = Some(Details::DumpImport {
+                    dump_uid,
+                    imported_indexes: Some(imported_indexes.len() as u64),
+                });
+                Ok(vec![task])
+            }
             Batch::IndexOperation { op, must_create_index } => {
                 let index_uid = op.index_uid().to_string();
                 let index = if must_create_index {
@@ -1066,6 +1193,7 @@ impl IndexScheduler {
                     Err(Error::IndexNotFound(_)) if index_has_been_created => (),
                     Err(e) => return Err(e),
                 }
+                self.forget_query_embeddings(&index_uid);
 
                 // We set all the tasks details to the default value.
                 for task in &mut tasks {
@@ -1111,6 +1239,10 @@ impl IndexScheduler {
                     self.apply_index_swap(&mut wtxn, task.uid, &swap.indexes.0, &swap.indexes.1)?;
                 }
                 wtxn.commit()?;
+                for swap in swaps {
+                    self.forget_query_embeddings(&swap.indexes.0);
+                    self.forget_query_embeddings(&swap.indexes.1);
+                }
                 task.status = Status::Succeeded;
                 Ok(vec![task])
             }
@@ -1159,6 +1291,133 @@ impl IndexScheduler {
         Ok(())
     }
 
+    /// Removes the oldest dumps of the dumps folder to only keep the `retention` most recent ones.
+    ///
+    /// Only the files named after a dump uid are considered, any other file is left untouched.
+    fn remove_outdated_dumps(&self, retention: NonZeroUsize) -> Result<()> {
+        let mut dump_uids = Vec::new();
+        for entry in fs::read_dir(&self.dumps_path)? {
+            let path = entry?.path();
+            if path.extension() != Some(OsStr::new("dump")) {
+                continue;
+            }
+            if let Some(stem) = path.file_stem().and_then(OsStr::to_str) {
+                if PrimitiveDateTime::parse(stem, DUMP_UID_FORMAT).is_ok() {
+                    dump_uids.push(stem.to_string());
+                }
+            }
+        }
+
+        // The dump uids are dates, sorting them sorts the dumps from the oldest to the most recent.
+        dump_uids.sort_unstable();
+        let outdated = dump_uids.len().saturating_sub(retention.get());
+        for dump_uid in &dump_uids[..outdated] {
+            fs::remove_file(self.dumps_path.join(format!("{dump_uid}.dump")))?;
+            tracing::info!(%dump_uid, "Removed an outdated dump");
+        }
+
+        Ok(())
+    }
+
+    /// Create the index described by the `index_reader` and import its settings and documents.
+    ///
+    /// The uid of the index is pushed in `created_indexes` as soon as the index is created,
+    /// so that the caller can delete it if the import fails midway.
+    fn import_dumped_index(
+        &self,
+        mut index_reader: dump::DumpIndexReader,
+        created_indexes: &mut Vec<String>,
+    ) -> Result<()> {
+        let metadata = index_reader.metadata().clone();
+        tracing::info!(index_uid = %metadata.uid, "Importing an index from a dump");
+
+        let date = Some((metadata.created_at, metadata.updated_at));
+        let wtxn = self.env.write_txn()?;
+        let index = self.index_mapper.create_index(wtxn, &metadata.uid, date)?;
+        created_indexes.push(metadata.uid.clone());
+
+        let documents_count = self.import_dumped_index_content(&index, &mut index_reader)?;
+        tracing::info!(index_uid = %metadata.uid, documents_count, "Index imported from a dump");
+
+        // Storing the stats is not critical, we do not want to fail the import for it.
+        let res = || -> Result<()> {
+            let index_rtxn = index.read_txn()?;
+            let stats = crate::index_mapper::IndexStats::new(&index, &index_rtxn)?;
+            let mut wtxn = self.env.write_txn()?;
+            self.index_mapper.store_stats_of(&mut wtxn, &metadata.uid, &stats)?;
+            wtxn.commit()?;
+            Ok(())
+        };
+        match res() {
+            Ok(_) => (),
+            Err(e) => tracing::error!(
+                error = &e as &dyn std::error::Error,
+                "Could not write the stats of the index"
+            ),
+        }
+
+        Ok(())
+    }
+
+    /// Imports the primary key, the settings and the documents of a dumped index in `index`,
+    /// both when importing a dump at launch and when processing a `dumpImport` task.
+    ///
+    /// Returns the number of imported documents.
+    pub fn import_dumped_index_content(
+        &self,
+        index: &Index,
+        index_reader: &mut dump::DumpIndexReader,
+    ) -> Result<u64> {
+        let must_stop_processing = self.must_stop_processing.clone();
+        let indexer_config = self.index_mapper.indexer_config();
+        let mut index_wtxn = index.write_txn()?;
+
+        // 1. Import the primary key and the settings.
+        let mut builder = MilliSettings::new(&mut index_wtxn, index, indexer_config);
+        if let Some(primary_key) = &index_reader.metadata().primary_key {
+            builder.set_primary_key(primary_key.clone());
+        }
+        let settings = index_reader.settings()?;
+        apply_settings_to_builder(&settings, &mut builder);
+        builder.execute(
+            |indexing_step| tracing::debug!(update = ?indexing_step),
+            || must_stop_processing.get(),
+        )?;
+
+        // 2. Import the documents by recreating the obkv batch format accepted by the index.
+        let mut builder = DocumentsBatchBuilder::new(BufWriter::new(tempfile::tempfile()?));
+        for document in index_reader.documents()? {
+            if self.must_stop_processing.get() {
+                return Err(Error::AbortedTask);
+            }
+            builder.append_json_object(&document?)?;
+        }
+        let file = builder.into_inner()?.into_inner().map_err(|e| e.into_error())?;
+        let reader =
+            DocumentsBatchReader::from_reader(BufReader::new(file)).map_err(milli::Error::from)?;
+
+        let embedder_configs = index.embedding_configs(&index_wtxn)?;
+        let embedders = self.embedders(embedder_configs)?;
+
+        let builder = milli::update::IndexDocuments::new(
+            &mut index_wtxn,
+            index,
+            indexer_config,
+            IndexDocumentsConfig {
+                update_method: IndexDocumentsMethod::ReplaceDocuments,
+                ..Default::default()
+            },
+            |indexing_step| tracing::trace!(?indexing_step, "Update"),
+            || must_stop_processing.get(),
+        )?;
+        let (builder, user_result) = builder.with_embedders(embedders).add_documents(reader)?;
+        let documents_count = user_result.map_err(milli::Error::from)?;
+        builder.execute()?;
+        index_wtxn.commit()?;
+
+        Ok(documents_count)
+    }
+
     /// Process the index operation on the given index.
     ///
     /// ## Return
@@ -1280,6 +1539,7 @@ impl IndexScheduler {
                                     task.details = Some(Details::DocumentAdditionOrUpdate {
                                         received_documents,
                                         indexed_documents: Some(count),
+                                        embedding_cache: None,
                                     })
                                 }
                                 Err(e) => {
@@ -1287,6 +1547,7 @@ impl IndexScheduler {
                                     task.details = Some(Details::DocumentAdditionOrUpdate {
                                         received_documents,
                                         indexed_documents: Some(0),
+                                        embedding_cache: None,
                                     });
                                     task.error = Some(milli::Error::from(e).into());
                                 }
@@ -1320,6 +1581,19 @@ impl IndexScheduler {
                 if !tasks.iter().all(|res| res.error.is_some()) {
                     let addition = builder.execute()?;
                     tracing::info!(indexing_result = ?addition, "document indexing done");
+
+                    // The documents of the batched tasks are indexed together, the cache
+                    // statistics can only be attributed when a single task succeeded.
+                    let mut succeeded = tasks.iter_mut().filter(|task| task.error.is_none());
+                    if let (Some(task), None) = (succeeded.next(), succeeded.next()) {
+                        if let Some(Details::DocumentAdditionOrUpdate { embedding_cache, .. }) =
+                            &mut task.details
+                        {
+                            if !addition.embedding_cache.is_empty() {
+                                *embedding_cache = Some(addition.embedding_cache);
+                            }
+                        }
+                    }
                 } else if primary_key_has_been_set {
                     // Everything failed but we've set a primary key.
                     // We need to remove it.
@@ -1381,13 +1655,16 @@ impl IndexScheduler {
 
                 Ok(vec![task])
             }
-            IndexOperation::Settings { index_uid: _, settings, mut tasks } => {
+            IndexOperation::Settings { index_uid, settings, mut tasks } => {
                 let indexer_config = self.index_mapper.indexer_config();
                 let mut builder = milli::update::Settings::new(index_wtxn, index, indexer_config);
 
                 for (task, (_, settings)) in tasks.iter_mut().zip(settings) {
                     let checked_settings = settings.clone().check();
-                    task.details = Some(Details::SettingsUpdate { settings: Box::new(settings) });
+                    task.details = Some(Details::SettingsUpdate {
+                        settings: Box::new(settings),
+                        embedding_cache: None,
+                    });
                     apply_settings_to_builder(&checked_settings, &mut builder);
 
                     // We can apply the status right now and if an update fail later
@@ -1396,10 +1673,23 @@ impl IndexScheduler {
                 }
 
                 let must_stop_processing = self.must_stop_processing.clone();
-                builder.execute(
+                let embedding_cache = builder.execute(
                     |indexing_step| tracing::debug!(update = ?indexing_step),
                     || must_stop_processing.get(),
                 )?;
+                self.forget_query_embeddings(&index_uid);
+
+                // The settings of the batched tasks are applied together, the cache
+                // statistics can only be attributed when there is a single task.
+                if let [task] = tasks.as_mut_slice() {
+                    if let Some(Details::SettingsUpdate { embedding_cache: stats, .. }) =
+                        &mut task.details
+                    {
+                        if !embedding_cache.is_empty() {
+                            *stats = Some(embedding_cache);
+                        }
+                    }
+                }
 
                 Ok(tasks)
             }
@@ -74,6 +74,8 @@ pub enum Error {
     SwapIndexesNotFound(Vec<String>),
     #[error("Corrupted dump.")]
     CorruptedDump,
+    #[error("Dump `{0}` not found.")]
+    DumpNotFound(String),
     #[error(
         "Task `{field}` `{date}` is invalid. It should follow the YYYY-MM-DD or RFC 3339 date-time format."
     )]
@@ -116,6 +118,8 @@ pub enum Error {
     #[error(transparent)]
     Dump(#[from] dump::Error),
     #[error(transparent)]
+    Auth(#[from] meilisearch_auth::error::AuthControllerError),
+    #[error(transparent)]
     Heed(#[from] heed::Error),
     #[error(transparent)]
     Milli(#[from] milli::Error),
@@ -171,6 +175,7 @@ impl Error {
             | Error::NoSpaceLeftInTaskQueue
             | Error::SwapIndexesNotFound(_)
             | Error::CorruptedDump
+            | Error::DumpNotFound(_)
             | Error::InvalidTaskDate { .. }
             | Error::InvalidTaskUids { .. }
             | Error::InvalidTaskStatuses { .. }
@@ -182,6 +187,7 @@ impl Error {
             | Error::TaskCancelationWithEmptyQuery
             | Error::AbortedTask
             | Error::Dump(_)
+            | Error::Auth(_)
             | Error::Heed(_)
             | Error::Milli(_)
             | Error::ProcessBatchPanicked
@@ -226,7 +232,9 @@ impl ErrorCode for Error {
             Error::TaskCancelationWithEmptyQuery => Code::MissingTaskFilters,
             // TODO: not sure of the Code to use
             Error::NoSpaceLeftInTaskQueue => Code::NoSpaceLeftOnDevice,
+            Error::DumpNotFound(_) => Code::DumpNotFound,
             Error::Dump(e) => e.error_code(),
+            Error::Auth(e) => e.error_code(),
             Error::Milli(e) => e.error_code(),
             Error::ProcessBatchPanicked => Code::Internal,
             Error::Heed(e) => e.error_code(),
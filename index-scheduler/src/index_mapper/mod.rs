@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
@@ -107,6 +108,9 @@ pub struct IndexStats {
     pub used_database_size: u64,
     /// Association of every field name with the number of times it occurs in the documents.
     pub field_distribution: FieldDistribution,
+    /// Association of every embedder name with the size taken by its vectors, in bytes.
+    #[serde(default)]
+    pub vector_storage_size: BTreeMap<String, u64>,
     /// Creation date of the index.
     pub created_at: OffsetDateTime,
     /// Date of the last update of the index.
@@ -125,6 +129,7 @@ impl IndexStats {
             database_size: index.on_disk_size()?,
             used_database_size: index.used_size()?,
             field_distribution: index.field_distribution(rtxn)?,
+            vector_storage_size: index.vector_storage_size(rtxn)?,
             created_at: index.created_at(rtxn)?,
             updated_at: index.updated_at(rtxn)?,
         })
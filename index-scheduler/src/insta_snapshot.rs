@@ -175,10 +175,11 @@ fn snapshot_details(d: &Details) -> String {
         Details::DocumentAdditionOrUpdate {
             received_documents,
             indexed_documents,
+            ..
         } => {
             format!("{{ received_documents: {received_documents}, indexed_documents: {indexed_documents:?} }}")
         }
-        Details::SettingsUpdate { settings } => {
+        Details::SettingsUpdate { settings, .. } => {
             format!("{{ settings: {settings:?} }}")
         }
         Details::IndexInfo { primary_key } => {
@@ -214,6 +215,9 @@ fn snapshot_details(d: &Details) -> String {
         Details::IndexSwap { swaps } => {
             format!("{{ swaps: {swaps:?} }}")
         }
+        Details::DumpImport { dump_uid, imported_indexes } => {
+            format!("{{ dump_uid: {dump_uid:?}, imported_indexes: {imported_indexes:?} }}")
+        }
     }
 }
 
@@ -35,6 +35,7 @@ pub type TaskId = u32;
 use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::{self, BufReader, Read};
+use std::num::NonZeroUsize;
 use std::ops::{Bound, RangeBounds};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering::{self, Relaxed};
@@ -42,7 +43,7 @@ use std::sync::atomic::{AtomicBool, AtomicU32};
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
-use dump::{KindDump, TaskDump, UpdateFile};
+use dump::{DumpCompression, KindDump, TaskDump, UpdateFile};
 pub use error::Error;
 pub use features::RoFeatures;
 use file_store::FileStore;
@@ -55,7 +56,9 @@ use meilisearch_types::heed::types::{SerdeBincode, SerdeJson, Str, I128};
 use meilisearch_types::heed::{self, Database, Env, PutFlags, RoTxn, RwTxn};
 use meilisearch_types::milli::documents::DocumentsBatchBuilder;
 use meilisearch_types::milli::update::IndexerConfig;
-use meilisearch_types::milli::vector::{Embedder, EmbedderOptions, EmbeddingConfigs};
+use meilisearch_types::milli::vector::{
+    Embedder, EmbedderOptions, EmbeddingConfigs, QueryEmbeddingCache, QueryEmbeddingStats,
+};
 use meilisearch_types::milli::{self, CboRoaringBitmapCodec, Index, RoaringBitmapCodec, BEU32};
 use meilisearch_types::task_view::TaskView;
 use meilisearch_types::tasks::{Kind, KindWithContent, Status, Task};
@@ -247,6 +250,14 @@ pub struct IndexSchedulerOptions {
     pub snapshots_path: PathBuf,
     /// The path to the folder containing the dumps.
     pub dumps_path: PathBuf,
+    /// The compression algorithm used to write the dumps.
+    pub dump_compression: DumpCompression,
+    /// The interval at which a dump creation task is automatically registered, if any.
+    pub dump_interval: Option<Duration>,
+    /// The number of dumps to keep in the dumps folder, the oldest ones are removed first.
+    pub dump_retention: Option<NonZeroUsize>,
+    /// The path to the file containing the instance uid of Meilisearch.
+    pub instance_uid_path: PathBuf,
     /// The URL on which we must send the tasks statuses
     pub webhook_url: Option<String>,
     /// The value we will send into the Authorization HTTP header on the webhook URL
@@ -277,8 +288,13 @@ pub struct IndexSchedulerOptions {
     pub max_number_of_batched_tasks: usize,
     /// The experimental features enabled for this instance.
     pub instance_features: InstanceTogglableFeatures,
+    /// The number of search queries whose embedding is cached for each embedder, 0 disables the cache.
+    pub query_embedding_cache_size: usize,
 }
 
+/// index uid -> embedder name -> the options of the embedder and the cache of its query embeddings
+type QueryEmbeddings = HashMap<String, HashMap<String, (EmbedderOptions, QueryEmbeddingCache)>>;
+
 /// Structure which holds meilisearch's indexes and schedules the tasks
 /// to be performed on them.
 pub struct IndexScheduler {
@@ -350,6 +366,18 @@ pub struct IndexScheduler {
     /// The path used to create the dumps.
     pub(crate) dumps_path: PathBuf,
 
+    /// The compression algorithm used to write the dumps.
+    pub(crate) dump_compression: DumpCompression,
+
+    /// The interval at which a dump creation task is automatically registered, if any.
+    pub(crate) dump_interval: Option<Duration>,
+
+    /// The number of dumps to keep in the dumps folder.
+    pub(crate) dump_retention: Option<NonZeroUsize>,
+
+    /// The path to the file containing the instance uid, read when scheduling the dumps.
+    pub(crate) instance_uid_path: PathBuf,
+
     /// The path used to create the snapshots.
     pub(crate) snapshots_path: PathBuf,
 
@@ -361,6 +389,15 @@ pub struct IndexScheduler {
 
     embedders: Arc<RwLock<HashMap<EmbedderOptions, Arc<Embedder>>>>,
 
+    /// The embeddings of the last search queries, for each embedder of each index.
+    query_embeddings: Arc<RwLock<QueryEmbeddings>>,
+
+    /// How the embeddings of the search queries were obtained.
+    query_embedding_stats: Arc<QueryEmbeddingStats>,
+
+    /// The number of search queries whose embedding is cached for each embedder.
+    query_embedding_cache_size: usize,
+
     // ================= test
     // The next entry is dedicated to the tests.
     /// Provide a way to set a breakpoint in multiple part of the scheduler.
@@ -404,11 +441,18 @@ impl IndexScheduler {
             puffin_frame: self.puffin_frame.clone(),
             snapshots_path: self.snapshots_path.clone(),
             dumps_path: self.dumps_path.clone(),
+            dump_compression: self.dump_compression,
+            dump_interval: self.dump_interval,
+            dump_retention: self.dump_retention,
+            instance_uid_path: self.instance_uid_path.clone(),
             auth_path: self.auth_path.clone(),
             version_file_path: self.version_file_path.clone(),
             webhook_url: self.webhook_url.clone(),
             webhook_authorization_header: self.webhook_authorization_header.clone(),
             embedders: self.embedders.clone(),
+            query_embeddings: self.query_embeddings.clone(),
+            query_embedding_stats: self.query_embedding_stats.clone(),
+            query_embedding_cache_size: self.query_embedding_cache_size,
             #[cfg(test)]
             test_breakpoint_sdr: self.test_breakpoint_sdr.clone(),
             #[cfg(test)]
@@ -504,12 +548,19 @@ impl IndexScheduler {
             max_number_of_tasks: options.max_number_of_tasks,
             max_number_of_batched_tasks: options.max_number_of_batched_tasks,
             dumps_path: options.dumps_path,
+            dump_compression: options.dump_compression,
+            dump_interval: options.dump_interval,
+            dump_retention: options.dump_retention,
+            instance_uid_path: options.instance_uid_path,
             snapshots_path: options.snapshots_path,
             auth_path: options.auth_path,
             version_file_path: options.version_file_path,
             webhook_url: options.webhook_url,
             webhook_authorization_header: options.webhook_authorization_header,
             embedders: Default::default(),
+            query_embeddings: Default::default(),
+            query_embedding_stats: Default::default(),
+            query_embedding_cache_size: options.query_embedding_cache_size,
 
             #[cfg(test)]
             test_breakpoint_sdr,
@@ -609,6 +660,23 @@ impl IndexScheduler {
     /// This function will execute in a different thread and must be called
     /// only once per index scheduler.
     fn run(&self) {
+        if let Some(dump_interval) = self.dump_interval {
+            let scheduler = self.private_clone();
+            std::thread::Builder::new()
+                .name(String::from("register-dump-tasks"))
+                .spawn(move || loop {
+                    std::thread::sleep(dump_interval);
+                    match scheduler.register_scheduled_dump() {
+                        Ok(Some(_)) => (),
+                        Ok(None) => tracing::info!(
+                            "Skipping a scheduled dump, the previous one is not finished yet"
+                        ),
+                        Err(e) => tracing::error!("Error while registering dump: {e}"),
+                    }
+                })
+                .unwrap();
+        }
+
         let run = self.private_clone();
         std::thread::Builder::new()
             .name(String::from("scheduler"))
@@ -1000,6 +1068,30 @@ impl IndexScheduler {
         }
     }
 
+    /// Register a dump creation task as if it was requested by the user,
+    /// with the API keys currently stored in the auth store.
+    ///
+    /// Nothing is registered while another dump creation task is enqueued or processing.
+    fn register_scheduled_dump(&self) -> Result<Option<Task>> {
+        let rtxn = self.env.read_txn()?;
+        let pending_dumps =
+            self.get_kind(&rtxn, Kind::DumpCreation)? & self.get_status(&rtxn, Status::Enqueued)?;
+        drop(rtxn);
+        if !pending_dumps.is_empty() {
+            return Ok(None);
+        }
+
+        let mut auth = meilisearch_auth::HeedAuthStore::open(&self.auth_path)?;
+        // The env is shared with the `AuthController`, we must not close it on drop.
+        auth.set_drop_on_close(false);
+        let keys = auth.list_api_keys()?;
+        let instance_uid = std::fs::read_to_string(&self.instance_uid_path)
+            .ok()
+            .and_then(|uid| uid.trim().parse().ok());
+
+        self.register(KindWithContent::DumpCreation { keys, instance_uid }, None, false).map(Some)
+    }
+
     /// Register a new task in the scheduler.
     ///
     /// If it fails and data was associated with the task, it tries to delete the associated data.
@@ -1497,7 +1589,7 @@ impl IndexScheduler {
     ) -> Result<EmbeddingConfigs> {
         let res: Result<_> = embedding_configs
             .into_iter()
-            .map(|(name, milli::vector::EmbeddingConfig { embedder_options, prompt })| {
+            .map(|(name, milli::vector::EmbeddingConfig { embedder_options, prompt, .. })| {
                 let prompt =
                     Arc::new(prompt.try_into().map_err(meilisearch_types::milli::Error::from)?);
                 // optimistically return existing embedder
@@ -1524,6 +1616,49 @@ impl IndexScheduler {
         res.map(EmbeddingConfigs::new)
     }
 
+    /// Returns the cache of the embeddings of the search queries for an embedder of an index.
+    ///
+    /// The cache is emptied when the options of the embedder change.
+    pub fn query_embedding_cache(
+        &self,
+        index_uid: &str,
+        embedder_name: &str,
+        options: &EmbedderOptions,
+    ) -> QueryEmbeddingCache {
+        {
+            let query_embeddings = self.query_embeddings.read().unwrap();
+            let cached =
+                query_embeddings.get(index_uid).and_then(|caches| caches.get(embedder_name));
+            if let Some((cached_options, cache)) = cached {
+                if cached_options == options {
+                    return cache.clone();
+                }
+            }
+        }
+
+        let cache = QueryEmbeddingCache::new(
+            self.query_embedding_cache_size,
+            self.query_embedding_stats.clone(),
+        );
+        let mut query_embeddings = self.query_embeddings.write().unwrap();
+        query_embeddings
+            .entry(index_uid.to_owned())
+            .or_default()
+            .insert(embedder_name.to_owned(), (options.clone(), cache.clone()));
+        cache
+    }
+
+    /// Drops the caches of the embeddings of the search queries of an index,
+    /// whose embedders may have been removed along with its settings.
+    pub(crate) fn forget_query_embeddings(&self, index_uid: &str) {
+        self.query_embeddings.write().unwrap().remove(index_uid);
+    }
+
+    /// How the embeddings of the search queries were obtained since the start of Meilisearch.
+    pub fn query_embedding_stats(&self) -> &QueryEmbeddingStats {
+        &self.query_embedding_stats
+    }
+
     /// Blocks the thread until the test handle asks to progress to/through this breakpoint.
     ///
     /// Two messages are sent through the channel for each breakpoint.
@@ -1663,6 +1798,9 @@ impl<'a> Dump<'a> {
                     KindWithContent::DumpCreation { keys, instance_uid }
                 }
                 KindDump::SnapshotCreation => KindWithContent::SnapshotCreation,
+                KindDump::DumpImport { dump_uid, import_keys } => {
+                    KindWithContent::DumpImport { dump_uid, import_keys }
+                }
             },
         };
 
@@ -1829,6 +1967,10 @@ mod tests {
                 indexes_path: tempdir.path().join("indexes"),
                 snapshots_path: tempdir.path().join("snapshots"),
                 dumps_path: tempdir.path().join("dumps"),
+                dump_compression: DumpCompression::default(),
+                dump_interval: None,
+                dump_retention: None,
+                instance_uid_path: tempdir.path().join("instance-uid"),
                 webhook_url: None,
                 webhook_authorization_header: None,
                 task_db_size: 1000 * 1000, // 1 MB, we don't use MiB on purpose.
@@ -1842,6 +1984,7 @@ mod tests {
                 max_number_of_tasks: 1_000_000,
                 max_number_of_batched_tasks: usize::MAX,
                 instance_features: Default::default(),
+                query_embedding_cache_size: 0,
             };
             configuration(&mut options);
 
@@ -4893,6 +5036,40 @@ mod tests {
         snapshot!(snapshot_index_scheduler(&index_scheduler), name: "cancel_processed");
     }
 
+    #[test]
+    fn scheduled_dumps_with_retention() {
+        let (index_scheduler, mut handle) =
+            IndexScheduler::test_with_custom_config(vec![], |config| {
+                config.dump_retention = Some(NonZeroUsize::new(2).unwrap());
+            });
+
+        let mut dump_uids = Vec::new();
+        for _ in 0..3 {
+            let task = index_scheduler.register_scheduled_dump().unwrap().unwrap();
+            // a dump is not scheduled while the previous one is not processed.
+            assert!(index_scheduler.register_scheduled_dump().unwrap().is_none());
+            handle.advance_one_successful_batch();
+
+            let task = index_scheduler.get_task(&index_scheduler.read_txn().unwrap(), task.uid);
+            match task.unwrap().unwrap().details {
+                Some(meilisearch_types::tasks::Details::Dump { dump_uid: Some(dump_uid) }) => {
+                    dump_uids.push(dump_uid)
+                }
+                details => panic!("unexpected details: {details:?}"),
+            }
+        }
+
+        // only the two most recent dumps are kept.
+        let mut dumps: Vec<_> = std::fs::read_dir(&index_scheduler.dumps_path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name.ends_with(".dump"))
+            .collect();
+        dumps.sort();
+        let expected: Vec<_> = dump_uids[1..].iter().map(|uid| format!("{uid}.dump")).collect();
+        assert_eq!(dumps, expected);
+    }
+
     #[test]
     fn basic_set_taskid() {
         let (index_scheduler, _handle) = IndexScheduler::test(true, vec![]);
@@ -258,6 +258,7 @@ pub fn swap_index_uid_in_task(task: &mut Task, swap: (&str, &str)) {
         K::TaskCancelation { .. }
         | K::TaskDeletion { .. }
         | K::DumpCreation { .. }
+        | K::DumpImport { .. }
         | K::SnapshotCreation => (),
     };
     if let Some(Details::IndexSwap { swaps }) = &mut task.details {
@@ -397,7 +398,11 @@ impl IndexScheduler {
                             assert_eq!(&sw1, sw2);
                         }
                     }
-                    Details::DocumentAdditionOrUpdate { received_documents, indexed_documents } => {
+                    Details::DocumentAdditionOrUpdate {
+                        received_documents,
+                        indexed_documents,
+                        ..
+                    } => {
                         assert_eq!(kind.as_kind(), Kind::DocumentAdditionOrUpdate);
                         match indexed_documents {
                             Some(indexed_documents) => {
@@ -416,7 +421,7 @@ impl IndexScheduler {
                             }
                         }
                     }
-                    Details::SettingsUpdate { settings: _ } => {
+                    Details::SettingsUpdate { .. } => {
                         assert_eq!(kind.as_kind(), Kind::SettingsUpdate);
                     }
                     Details::IndexInfo { primary_key: pk1 } => match &kind {
@@ -527,6 +532,23 @@ impl IndexScheduler {
                     Details::Dump { dump_uid: _ } => {
                         assert_eq!(kind.as_kind(), Kind::DumpCreation);
                     }
+                    Details::DumpImport { dump_uid, imported_indexes } => {
+                        match &kind {
+                            KindWithContent::DumpImport { dump_uid: uid, .. } => {
+                                assert_eq!(&dump_uid, uid);
+                            }
+                            _ => panic!(),
+                        }
+                        match status {
+                            Status::Enqueued | Status::Processing => {
+                                assert!(imported_indexes.is_none())
+                            }
+                            Status::Succeeded => assert!(imported_indexes.is_some()),
+                            Status::Failed | Status::Canceled => {
+                                assert_eq!(imported_indexes, Some(0))
+                            }
+                        }
+                    }
                 }
             }
 
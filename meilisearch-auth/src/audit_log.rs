@@ -0,0 +1,54 @@
+//! Entries of the audit log, accumulated in memory and written to the auth store in the
+//! background so that the requests don't wait for a write transaction.
+
+use std::sync::{Mutex, Weak};
+use std::time::Duration;
+
+use meilisearch_types::audit_log::AuditLogEntry;
+
+use crate::store::HeedAuthStore;
+
+/// The interval at which the entries recorded in memory are written in the auth store.
+const AUDIT_LOG_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+pub struct AuditLogRecorder {
+    pending: Mutex<Vec<AuditLogEntry>>,
+}
+
+impl AuditLogRecorder {
+    pub fn record(&self, entry: AuditLogEntry) {
+        self.pending.lock().unwrap().push(entry);
+    }
+
+    /// Appends the entries recorded since the last flush to the audit log.
+    ///
+    /// If the write fails, the entries are kept for the next flush.
+    pub fn flush(&self, store: &HeedAuthStore) -> crate::error::Result<()> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if let Err(error) = store.append_audit_log_entries(pending.clone()) {
+            let mut current = self.pending.lock().unwrap();
+            current.splice(0..0, pending);
+            return Err(error);
+        }
+        Ok(())
+    }
+}
+
+/// Spawns a thread flushing the audit log in the store until the auth controller is dropped.
+pub fn spawn_audit_log_flusher(store: Weak<HeedAuthStore>, recorder: Weak<AuditLogRecorder>) {
+    let spawned =
+        std::thread::Builder::new().name(String::from("audit-log-flusher")).spawn(move || loop {
+            std::thread::sleep(AUDIT_LOG_FLUSH_INTERVAL);
+            let (Some(store), Some(recorder)) = (store.upgrade(), recorder.upgrade()) else {
+                break;
+            };
+            if let Err(error) = recorder.flush(&store) {
+                tracing::error!(%error, "Could not flush the audit log");
+            }
+        });
+
+    if let Err(error) = spawned {
+        tracing::error!(%error, "Could not spawn the thread flushing the audit log");
+    }
+}
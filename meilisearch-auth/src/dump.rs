@@ -1,9 +1,13 @@
 use std::fs::File;
 use std::io::{BufReader, Write};
 use std::path::Path;
+use std::result::Result as StdResult;
 
+use meilisearch_types::audit_log::AuditLogEntry;
+use meilisearch_types::keys::Key;
 use serde_json::Deserializer;
 
+use crate::error::AuthControllerError;
 use crate::{AuthController, HeedAuthStore, Result};
 
 const KEYS_PATH: &str = "keys";
@@ -44,3 +48,50 @@ impl AuthController {
         Ok(())
     }
 }
+
+impl HeedAuthStore {
+    /// Imports the keys of a dump.
+    ///
+    /// When `replace` is set the keys of this instance are deleted first, otherwise the keys of
+    /// the dump that already exist in this instance are skipped.
+    pub fn import_dumped_keys<E: From<AuthControllerError>>(
+        &self,
+        keys: impl IntoIterator<Item = StdResult<Key, E>>,
+        replace: bool,
+    ) -> StdResult<(), E> {
+        if replace {
+            self.delete_all_keys()?;
+        }
+        for key in keys {
+            let key = key?;
+            if !replace && self.get_api_key(key.uid)?.is_some() {
+                tracing::warn!(key_uid = %key.uid, "Skipping an API key that already exists");
+                continue;
+            }
+            self.put_api_key(key)?;
+        }
+        Ok(())
+    }
+
+    /// Imports the audit log of a dump.
+    ///
+    /// When `replace` is set the entries keep their uid, otherwise they are appended after the
+    /// entries of this instance.
+    pub fn import_dumped_audit_log<E: From<AuthControllerError>>(
+        &self,
+        entries: impl IntoIterator<Item = StdResult<AuditLogEntry, E>>,
+        replace: bool,
+    ) -> StdResult<(), E> {
+        let mut appended = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if replace {
+                self.raw_insert_audit_log_entry(&entry)?;
+            } else {
+                appended.push(entry);
+            }
+        }
+        self.append_audit_log_entries(appended)?;
+        Ok(())
+    }
+}
@@ -11,10 +11,26 @@ pub enum AuthControllerError {
     ApiKeyNotFound(String),
     #[error("`uid` field value `{0}` is already an existing API key.")]
     ApiKeyAlreadyExists(String),
+    #[error("Too many requests were made with this API key. Retry after {retry_after}s.")]
+    RateLimitExceeded { retry_after: u64 },
+    #[error("The daily quota of this API key is exhausted. Retry after {retry_after}s.")]
+    DailyQuotaExceeded { retry_after: u64 },
     #[error("Internal error: {0}")]
     Internal(Box<dyn Error + Send + Sync + 'static>),
 }
 
+impl AuthControllerError {
+    /// The number of seconds to wait before making a new request with a key that exceeded its limits.
+    pub fn retry_after(&self) -> Option<u64> {
+        match self {
+            Self::RateLimitExceeded { retry_after } | Self::DailyQuotaExceeded { retry_after } => {
+                Some(*retry_after)
+            }
+            _ => None,
+        }
+    }
+}
+
 internal_error!(
     AuthControllerError: meilisearch_types::milli::heed::Error,
     std::io::Error,
@@ -27,6 +43,8 @@ impl ErrorCode for AuthControllerError {
         match self {
             Self::ApiKeyNotFound(_) => Code::ApiKeyNotFound,
             Self::ApiKeyAlreadyExists(_) => Code::ApiKeyAlreadyExists,
+            Self::RateLimitExceeded { .. } => Code::ApiKeyRateLimitExceeded,
+            Self::DailyQuotaExceeded { .. } => Code::ApiKeyDailyQuotaExceeded,
             Self::Internal(_) => Code::Internal,
         }
     }
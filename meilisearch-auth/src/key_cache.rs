@@ -0,0 +1,122 @@
+//! Lookup tables derived from the API keys and the master keys, kept in memory to avoid
+//! computing the HMAC of every key on each request. They are rebuilt when the keys change.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use meilisearch_types::keys::{TenantTokenKeys, TenantTokenPublicKey};
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::store::{generate_key_as_hexa, keys_version, master_key_fingerprint};
+use crate::HeedAuthStore;
+
+pub struct KeyCache {
+    /// The master keys the keys can be derived from, ordered by generation,
+    /// including the previous master keys that expired.
+    master_keys: Vec<String>,
+    tables: RwLock<Option<Tables>>,
+}
+
+struct Tables {
+    /// The version of the keys the tables were built from.
+    version: u64,
+    /// The uid of each key with the generation of the master key, by encoded key.
+    encoded_keys: HashMap<String, (Uuid, usize)>,
+    /// The fingerprint of the master key of each generation.
+    fingerprints: Vec<String>,
+    /// The tenant token keys of the keys, parsed the first time a token signed by them is used.
+    tenant_token_keys: Mutex<HashMap<Uuid, Option<Arc<ParsedTenantTokenKeys>>>>,
+}
+
+/// The tenant token keys of a key with their public keys already parsed.
+pub struct ParsedTenantTokenKeys {
+    pub keys: TenantTokenKeys,
+    pub public_keys: Vec<TenantTokenPublicKey>,
+}
+
+impl KeyCache {
+    pub fn new(master_keys: Vec<String>) -> Self {
+        Self { master_keys, tables: RwLock::new(None) }
+    }
+
+    /// Returns the uid of the key, with the generation of the master key it was derived from.
+    pub fn uid_and_generation(
+        &self,
+        store: &HeedAuthStore,
+        encoded_key: &[u8],
+    ) -> Result<Option<(Uuid, usize)>> {
+        let Ok(encoded_key) = std::str::from_utf8(encoded_key) else { return Ok(None) };
+        self.with_tables(store, |tables| tables.encoded_keys.get(encoded_key).copied())
+    }
+
+    /// Returns the fingerprint of the master key of a generation.
+    pub fn fingerprint(&self, store: &HeedAuthStore, generation: usize) -> Result<Option<String>> {
+        self.with_tables(store, |tables| tables.fingerprints.get(generation).cloned())
+    }
+
+    /// Returns the generation of the master key identified by a fingerprint.
+    pub fn generation(&self, store: &HeedAuthStore, fingerprint: &str) -> Result<Option<usize>> {
+        self.with_tables(store, |tables| {
+            tables.fingerprints.iter().position(|candidate| candidate == fingerprint)
+        })
+    }
+
+    /// Returns the tenant token keys of a key, if it has some.
+    pub fn tenant_token_keys(
+        &self,
+        store: &HeedAuthStore,
+        uid: Uuid,
+    ) -> Result<Option<Arc<ParsedTenantTokenKeys>>> {
+        self.with_tables(store, |tables| {
+            if let Some(parsed) = tables.tenant_token_keys.lock().unwrap().get(&uid) {
+                return Ok(parsed.clone());
+            }
+
+            let parsed =
+                store.get_api_key(uid)?.and_then(|key| key.tenant_token_keys).and_then(|keys| {
+                    // the keys were validated when the key was created.
+                    let public_keys = keys.public_keys().ok()?;
+                    Some(Arc::new(ParsedTenantTokenKeys { keys, public_keys }))
+                });
+            tables.tenant_token_keys.lock().unwrap().insert(uid, parsed.clone());
+            Ok(parsed)
+        })?
+    }
+
+    fn with_tables<T>(&self, store: &HeedAuthStore, f: impl FnOnce(&Tables) -> T) -> Result<T> {
+        let version = keys_version();
+        {
+            let tables = self.tables.read().unwrap();
+            if let Some(tables) = tables.as_ref().filter(|tables| tables.version == version) {
+                return Ok(f(tables));
+            }
+        }
+
+        let tables = self.build_tables(store, version)?;
+        let output = f(&tables);
+        *self.tables.write().unwrap() = Some(tables);
+        Ok(output)
+    }
+
+    fn build_tables(&self, store: &HeedAuthStore, version: u64) -> Result<Tables> {
+        let uids: Vec<_> = store.list_api_keys()?.into_iter().map(|key| key.uid).collect();
+        let mut encoded_keys = HashMap::with_capacity(uids.len() * self.master_keys.len());
+        for (generation, master_key) in self.master_keys.iter().enumerate() {
+            for &uid in &uids {
+                encoded_keys
+                    .entry(generate_key_as_hexa(uid, master_key.as_bytes()))
+                    .or_insert((uid, generation));
+            }
+        }
+
+        let salt = store.master_key_fingerprint_salt()?;
+        let fingerprints = self
+            .master_keys
+            .iter()
+            .map(|master_key| master_key_fingerprint(master_key.as_bytes(), &salt))
+            .collect();
+
+        Ok(Tables { version, encoded_keys, fingerprints, tenant_token_keys: Mutex::default() })
+    }
+}
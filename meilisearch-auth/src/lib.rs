@@ -1,26 +1,44 @@
+mod audit_log;
 mod dump;
 pub mod error;
+mod key_cache;
+mod rate_limit;
 mod store;
+mod usage;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::RangeBounds;
 use std::path::Path;
 use std::sync::Arc;
 
+use audit_log::{spawn_audit_log_flusher, AuditLogRecorder};
 use error::{AuthControllerError, Result};
+use key_cache::KeyCache;
+pub use key_cache::ParsedTenantTokenKeys;
 use maplit::hashset;
+use meilisearch_types::audit_log::AuditLogEntry;
 use meilisearch_types::index_uid_pattern::IndexUidPattern;
-use meilisearch_types::keys::{Action, CreateApiKey, Key, PatchApiKey};
+use meilisearch_types::keys::{Action, CreateApiKey, Key, KeySearchRules, PatchApiKey};
 use meilisearch_types::milli::update::Setting;
+use rate_limit::RateLimiter;
 use serde::{Deserialize, Serialize};
-pub use store::open_auth_store_env;
-use store::{generate_key_as_hexa, HeedAuthStore};
+pub use store::{generate_key_as_hexa, open_auth_store_env, HeedAuthStore};
 use time::OffsetDateTime;
+pub use usage::KeyUsage;
+use usage::{spawn_usage_flusher, UsageRecorder};
 use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct AuthController {
     store: Arc<HeedAuthStore>,
+    rate_limiter: Arc<RateLimiter>,
+    usages: Arc<UsageRecorder>,
+    audit_log: Arc<AuditLogRecorder>,
+    key_cache: Arc<KeyCache>,
     master_key: Option<String>,
+    /// The master keys used before the current one, from the most recent to the oldest.
+    previous_master_keys: Vec<String>,
+    previous_master_keys_expire_at: Option<OffsetDateTime>,
 }
 
 impl AuthController {
@@ -29,9 +47,67 @@ impl AuthController {
 
         if store.is_empty()? {
             generate_default_keys(&store)?;
+        } else {
+            // the keys of a previous version don't grant the actions added since then, like `dumps.import`.
+            store.index_all_keys_actions()?;
         }
 
-        Ok(Self { store: Arc::new(store), master_key: master_key.clone() })
+        let store = Arc::new(store);
+        let usages = Arc::new(UsageRecorder::default());
+        spawn_usage_flusher(Arc::downgrade(&store), Arc::downgrade(&usages));
+        let audit_log = Arc::new(AuditLogRecorder::default());
+        spawn_audit_log_flusher(Arc::downgrade(&store), Arc::downgrade(&audit_log));
+
+        Ok(Self {
+            store,
+            rate_limiter: Arc::new(RateLimiter::default()),
+            usages,
+            audit_log,
+            key_cache: Arc::new(KeyCache::new(master_key.iter().cloned().collect())),
+            master_key: master_key.clone(),
+            previous_master_keys: Vec::new(),
+            previous_master_keys_expire_at: None,
+        })
+    }
+
+    /// Keeps accepting the API keys and tenant tokens derived from the previous master keys,
+    /// ordered from the most recent to the oldest, until they expire.
+    pub fn with_previous_master_keys(
+        mut self,
+        previous_master_keys: Vec<String>,
+        expire_at: Option<OffsetDateTime>,
+    ) -> Self {
+        self.previous_master_keys = previous_master_keys;
+        self.previous_master_keys_expire_at = expire_at;
+        let master_keys = self.master_key.iter().chain(&self.previous_master_keys).cloned();
+        self.key_cache = Arc::new(KeyCache::new(master_keys.collect()));
+        self
+    }
+
+    /// Returns the master keys the API keys can be derived from, with their generation:
+    /// `0` for the current master key, `1` for the previous one, and so on.
+    fn master_key_generations(&self) -> impl Iterator<Item = (usize, &str)> {
+        let previous_expired = self
+            .previous_master_keys_expire_at
+            .map_or(false, |expire_at| OffsetDateTime::now_utc() >= expire_at);
+        let previous: &[String] = if previous_expired { &[] } else { &self.previous_master_keys };
+
+        self.master_key
+            .iter()
+            .flat_map(move |master_key| std::iter::once(master_key).chain(previous))
+            .map(String::as_str)
+            .enumerate()
+    }
+
+    /// Returns the current generation of the master key identified by a fingerprint,
+    /// or `None` if this master key is not accepted anymore.
+    pub fn master_key_generation(&self, fingerprint: &str) -> Result<Option<usize>> {
+        let generation = self.key_cache.generation(&self.store, fingerprint)?;
+        Ok(generation.filter(|generation| self.is_generation_accepted(*generation)))
+    }
+
+    fn is_generation_accepted(&self, generation: usize) -> bool {
+        generation < self.master_key_generations().count()
     }
 
     /// Return `Ok(())` if the auth controller is able to access one of its database.
@@ -77,13 +153,56 @@ impl AuthController {
             .ok_or_else(|| AuthControllerError::ApiKeyNotFound(uid.to_string()))
     }
 
+    /// Returns the tenant token keys of a key with their public keys parsed,
+    /// or `None` if the key doesn't exist or has no tenant token keys.
+    pub fn tenant_token_keys(&self, uid: Uuid) -> Result<Option<Arc<ParsedTenantTokenKeys>>> {
+        self.key_cache.tenant_token_keys(&self.store, uid)
+    }
+
+    /// Counts a request made with a key, returns an error if the key exceeded its rate limit or its daily quota.
+    ///
+    /// Returns whether the key has a rate limit or a daily quota, the other keys are not counted.
+    pub fn consume_rate_limit(&self, uid: Uuid) -> Result<bool> {
+        self.rate_limiter.consume(uid, &self.store)
+    }
+
+    /// Records a request made with a key, derived from the master key of the given generation
+    /// unless it is a tenant token signed with a public key. The usages are written in the store periodically.
+    pub fn record_key_usage(
+        &self,
+        uid: Uuid,
+        action: Action,
+        generation: Option<usize>,
+    ) -> Result<()> {
+        let fingerprint = match generation {
+            Some(generation) => self.key_cache.fingerprint(&self.store, generation)?,
+            None => None,
+        };
+        self.usages.record(uid, action, fingerprint);
+        Ok(())
+    }
+
+    /// Returns the usage of a key as of the last flush.
+    pub fn get_key_usage(&self, uid: Uuid) -> Result<KeyUsage> {
+        Ok(self.store.get_key_usage(uid)?.unwrap_or_default())
+    }
+
+    /// Writes the usages recorded since the last flush in the store.
+    pub fn flush_key_usages(&self) -> Result<()> {
+        self.usages.flush(&self.store)
+    }
+
     pub fn get_optional_uid_from_encoded_key(&self, encoded_key: &[u8]) -> Result<Option<Uuid>> {
-        match &self.master_key {
-            Some(master_key) => {
-                self.store.get_uid_from_encoded_key(encoded_key, master_key.as_bytes())
-            }
-            None => Ok(None),
-        }
+        Ok(self.get_uid_and_generation_from_encoded_key(encoded_key)?.map(|(uid, _)| uid))
+    }
+
+    /// Returns the uid of the key, with the generation of the master key it was derived from.
+    pub fn get_uid_and_generation_from_encoded_key(
+        &self,
+        encoded_key: &[u8],
+    ) -> Result<Option<(Uuid, usize)>> {
+        let uid_and_generation = self.key_cache.uid_and_generation(&self.store, encoded_key)?;
+        Ok(uid_and_generation.filter(|(_, generation)| self.is_generation_accepted(*generation)))
     }
 
     pub fn get_uid_from_encoded_key(&self, encoded_key: &str) -> Result<Uuid> {
@@ -99,10 +218,22 @@ impl AuthController {
         let key = self.get_key(uid)?;
 
         let key_authorized_indexes = SearchRules::Set(key.indexes.into_iter().collect());
+        let key_search_rules = SearchRules::Map(
+            key.search_rules
+                .into_iter()
+                .map(|(pattern, rules)| (pattern, Some(IndexSearchRules::from(rules))))
+                .collect(),
+        );
 
         let allow_index_creation = self.is_key_authorized(uid, Action::IndexesAdd, None)?;
 
-        Ok(AuthFilter { search_rules, key_authorized_indexes, allow_index_creation })
+        Ok(AuthFilter {
+            key_uid: Some(uid),
+            search_rules,
+            key_authorized_indexes,
+            key_search_rules,
+            allow_index_creation,
+        })
     }
 
     pub fn list_keys(&self) -> Result<Vec<Key>> {
@@ -111,6 +242,7 @@ impl AuthController {
 
     pub fn delete_key(&self, uid: Uuid) -> Result<()> {
         if self.store.delete_api_key(uid)? {
+            self.usages.remove(uid);
             Ok(())
         } else {
             Err(AuthControllerError::ApiKeyNotFound(uid.to_string()))
@@ -127,6 +259,15 @@ impl AuthController {
         self.master_key.as_ref().map(|master_key| generate_key_as_hexa(uid, master_key.as_bytes()))
     }
 
+    /// Generate the keys of a key id from every accepted master key, with their generation.
+    pub fn generate_keys(&self, uid: Uuid) -> Vec<(usize, String)> {
+        self.master_key_generations()
+            .map(|(generation, master_key)| {
+                (generation, generate_key_as_hexa(uid, master_key.as_bytes()))
+            })
+            .collect()
+    }
+
     /// Check if the provided key is authorized to make a specific action
     /// without checking if the key is valid.
     pub fn is_key_authorized(
@@ -149,11 +290,52 @@ impl AuthController {
             Some(Some(exp)) => Ok(OffsetDateTime::now_utc() < exp),
             // no expiration date.
             Some(None) => Ok(true),
-            // action or index forbidden.
-            None => Ok(false),
+            // the action may still be granted by a broader action, like `settings.update`.
+            None => match action.parent() {
+                Some(parent) => self.is_key_authorized(uid, parent, index),
+                // the actions of the `*` keys created before this action existed don't include it.
+                None if action != Action::All => self.is_key_authorized(uid, Action::All, index),
+                // action or index forbidden.
+                None => Ok(false),
+            },
         }
     }
 
+    /// Records an administrative request in the audit log. The entries are written in the store
+    /// in the background, their uid is assigned then.
+    pub fn record_audit_log_entry(&self, entry: AuditLogEntry) {
+        self.audit_log.record(entry);
+    }
+
+    /// Returns at most `limit` entries of the audit log in the range of uids matching the filter,
+    /// from the most recent one.
+    pub fn audit_log(
+        &self,
+        uids: impl RangeBounds<u64>,
+        filter: impl Fn(&AuditLogEntry) -> bool,
+        limit: usize,
+    ) -> Result<Vec<AuditLogEntry>> {
+        // the entries recorded since the last flush must be visible.
+        self.audit_log.flush(&self.store)?;
+        self.store.audit_log(uids, filter, limit)
+    }
+
+    /// Replaces the keys of this instance by the ones of a dump.
+    pub fn import_dumped_keys<E: From<AuthControllerError>>(
+        &mut self,
+        keys: impl IntoIterator<Item = std::result::Result<Key, E>>,
+    ) -> std::result::Result<(), E> {
+        self.store.import_dumped_keys(keys, true)
+    }
+
+    /// Replaces the audit log of this instance by the one of a dump.
+    pub fn import_dumped_audit_log<E: From<AuthControllerError>>(
+        &mut self,
+        entries: impl IntoIterator<Item = std::result::Result<AuditLogEntry, E>>,
+    ) -> std::result::Result<(), E> {
+        self.store.import_dumped_audit_log(entries, true)
+    }
+
     /// Delete all the keys in the DB.
     pub fn raw_delete_all_keys(&mut self) -> Result<()> {
         self.store.delete_all_keys()
@@ -167,22 +349,32 @@ impl AuthController {
 }
 
 pub struct AuthFilter {
+    /// The uid of the API key the request was authenticated with, `None` for the master key.
+    key_uid: Option<Uuid>,
     search_rules: Option<SearchRules>,
     key_authorized_indexes: SearchRules,
+    /// The search restrictions of the key, applied on top of the search rules of its tenant tokens.
+    key_search_rules: SearchRules,
     allow_index_creation: bool,
 }
 
 impl Default for AuthFilter {
     fn default() -> Self {
         Self {
+            key_uid: None,
             search_rules: None,
             key_authorized_indexes: SearchRules::default(),
+            key_search_rules: SearchRules::Map(HashMap::new()),
             allow_index_creation: true,
         }
     }
 }
 
 impl AuthFilter {
+    pub fn key_uid(&self) -> Option<Uuid> {
+        self.key_uid
+    }
+
     #[inline]
     pub fn allow_index_creation(&self, index: &str) -> bool {
         self.allow_index_creation && self.is_index_authorized(index)
@@ -190,8 +382,10 @@ impl AuthFilter {
 
     pub fn with_allowed_indexes(allowed_indexes: HashSet<IndexUidPattern>) -> Self {
         Self {
+            key_uid: None,
             search_rules: None,
             key_authorized_indexes: SearchRules::Set(allowed_indexes),
+            key_search_rules: SearchRules::Map(HashMap::new()),
             allow_index_creation: false,
         }
     }
@@ -219,7 +413,11 @@ impl AuthFilter {
             return None;
         }
         let search_rules = self.search_rules.as_ref().unwrap_or(&self.key_authorized_indexes);
-        search_rules.get_index_search_rules(index)
+        let rules = search_rules.get_index_search_rules(index);
+        match (rules, self.key_search_rules.get_index_search_rules(index)) {
+            (Some(rules), Some(key_rules)) => Some(rules.restrict(key_rules)),
+            (rules, key_rules) => rules.or(key_rules),
+        }
     }
 }
 
@@ -299,9 +497,54 @@ impl IntoIterator for SearchRules {
 /// Contains the rules to apply on the top of the search query for a specific index.
 ///
 /// filter: search filter to apply in addition to query filters.
-#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+/// attributesToRetrieve: the only attributes that can be retrieved, highlighted or cropped.
+/// limit: maximum number of hits returned by a search.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct IndexSearchRules {
     pub filter: Option<serde_json::Value>,
+    #[serde(default)]
+    pub attributes_to_retrieve: Option<BTreeSet<String>>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl IndexSearchRules {
+    /// Combines two sets of rules into the most restrictive one.
+    pub fn restrict(self, other: Self) -> Self {
+        // the elements of an array of filters are joined with an AND, but the nested arrays with an OR
+        let filter = match (self.filter, other.filter) {
+            (Some(filter), Some(other)) => {
+                let as_array = |filter: serde_json::Value| match filter {
+                    serde_json::Value::Array(filter) => filter,
+                    filter => vec![filter],
+                };
+                Some(serde_json::Value::Array([as_array(filter), as_array(other)].concat()))
+            }
+            (filter, other) => filter.or(other),
+        };
+        let attributes_to_retrieve =
+            match (self.attributes_to_retrieve, other.attributes_to_retrieve) {
+                (Some(attributes), Some(other)) if attributes.contains("*") => Some(other),
+                (Some(attributes), Some(other)) if other.contains("*") => Some(attributes),
+                (Some(attributes), Some(other)) => {
+                    Some(attributes.intersection(&other).cloned().collect())
+                }
+                (attributes, other) => attributes.or(other),
+            };
+        let limit = match (self.limit, other.limit) {
+            (Some(limit), Some(other)) => Some(limit.min(other)),
+            (limit, other) => limit.or(other),
+        };
+        Self { filter, attributes_to_retrieve, limit }
+    }
+}
+
+impl From<KeySearchRules> for IndexSearchRules {
+    fn from(rules: KeySearchRules) -> Self {
+        let KeySearchRules { filter, attributes_to_retrieve, limit } = rules;
+        Self { filter, attributes_to_retrieve, limit }
+    }
 }
 
 fn generate_default_keys(store: &HeedAuthStore) -> Result<()> {
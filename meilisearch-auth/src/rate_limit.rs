@@ -0,0 +1,115 @@
+//! In-memory accounting of the requests made with the API keys having a rate limit or a daily quota.
+//!
+//! The usages are not persisted, a restart of Meilisearch resets them.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::Instant;
+
+use meilisearch_types::keys::KeyRateLimit;
+use time::{Date, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::error::{AuthControllerError, Result};
+use crate::store::keys_version;
+use crate::HeedAuthStore;
+
+#[derive(Default)]
+pub struct RateLimiter {
+    /// The limits of the keys having a rate limit or a daily quota, with the version of the keys
+    /// they were read at. The other keys are not limited and never reach the usages.
+    limits: RwLock<(Option<u64>, HashMap<Uuid, KeyLimits>)>,
+    usages: Mutex<HashMap<Uuid, KeyUsage>>,
+}
+
+#[derive(Clone, Copy)]
+struct KeyLimits {
+    rate_limit: Option<KeyRateLimit>,
+    daily_quota: Option<u64>,
+}
+
+struct KeyUsage {
+    /// The number of requests that can be made right away, refilled over time at the rate of the key.
+    tokens: f64,
+    last_refill: Instant,
+    day: Date,
+    requests_of_the_day: u64,
+}
+
+impl RateLimiter {
+    /// Counts a request made with the key, or returns an error if the key exceeded
+    /// its rate limit or its daily quota. Returns whether the key is limited at all.
+    ///
+    /// A rejected request is not counted in the daily quota.
+    pub fn consume(&self, uid: Uuid, store: &HeedAuthStore) -> Result<bool> {
+        let Some(limits) = self.limits_of(uid, store)? else { return Ok(false) };
+
+        let now = Instant::now();
+        let now_utc = OffsetDateTime::now_utc();
+        let mut usages = self.usages.lock().unwrap();
+        let usage = usages.entry(uid).or_insert_with(|| KeyUsage {
+            tokens: limits.rate_limit.map_or(0.0, |rate_limit| rate_limit.burst() as f64),
+            last_refill: now,
+            day: now_utc.date(),
+            requests_of_the_day: 0,
+        });
+
+        if usage.day != now_utc.date() {
+            usage.day = now_utc.date();
+            usage.requests_of_the_day = 0;
+        }
+
+        if let Some(daily_quota) = limits.daily_quota {
+            if usage.requests_of_the_day >= daily_quota {
+                let tomorrow =
+                    now_utc.date().next_day().unwrap_or(Date::MAX).midnight().assume_utc();
+                let retry_after = (tomorrow - now_utc).whole_seconds().max(1) as u64;
+                return Err(AuthControllerError::DailyQuotaExceeded { retry_after });
+            }
+        }
+
+        if let Some(rate_limit) = limits.rate_limit {
+            let rate = rate_limit.requests_per_second as f64;
+            let burst = rate_limit.burst() as f64;
+            let elapsed = now.duration_since(usage.last_refill).as_secs_f64();
+            usage.tokens = (usage.tokens + elapsed * rate).min(burst);
+            usage.last_refill = now;
+
+            if usage.tokens < 1.0 {
+                let retry_after = ((1.0 - usage.tokens) / rate).ceil().max(1.0) as u64;
+                return Err(AuthControllerError::RateLimitExceeded { retry_after });
+            }
+            usage.tokens -= 1.0;
+        }
+
+        usage.requests_of_the_day += 1;
+        Ok(true)
+    }
+
+    /// Returns the limits of the key, reading the limited keys from the store again
+    /// only when the keys changed since the last time.
+    fn limits_of(&self, uid: Uuid, store: &HeedAuthStore) -> Result<Option<KeyLimits>> {
+        let version = keys_version();
+        {
+            let limits = self.limits.read().unwrap();
+            if limits.0 == Some(version) {
+                return Ok(limits.1.get(&uid).copied());
+            }
+        }
+
+        let limits: HashMap<_, _> = store
+            .list_api_keys()?
+            .into_iter()
+            .filter(|key| key.rate_limit.is_some() || key.daily_quota.is_some())
+            .map(|key| {
+                (key.uid, KeyLimits { rate_limit: key.rate_limit, daily_quota: key.daily_quota })
+            })
+            .collect();
+        // forget the usages of the deleted keys.
+        self.usages.lock().unwrap().retain(|uid, _| limits.contains_key(uid));
+
+        let key_limits = limits.get(&uid).copied();
+        *self.limits.write().unwrap() = (Some(version), limits);
+        Ok(key_limits)
+    }
+}
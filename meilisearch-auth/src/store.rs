@@ -1,20 +1,25 @@
 use std::borrow::Cow;
 use std::cmp::Reverse;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::create_dir_all;
+use std::ops::RangeBounds;
 use std::path::Path;
 use std::result::Result as StdResult;
 use std::str;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use hmac::{Hmac, Mac};
+use meilisearch_types::audit_log::AuditLogEntry;
 use meilisearch_types::heed::BoxedError;
 use meilisearch_types::index_uid_pattern::IndexUidPattern;
 use meilisearch_types::keys::KeyId;
-use meilisearch_types::milli;
 use meilisearch_types::milli::heed::types::{Bytes, DecodeIgnore, SerdeJson};
 use meilisearch_types::milli::heed::{Database, Env, EnvOpenOptions, RwTxn};
+use meilisearch_types::milli::{self, BEU64};
+use rand::rngs::OsRng;
+use rand::RngCore;
 use sha2::Sha256;
 use thiserror::Error;
 use time::OffsetDateTime;
@@ -22,18 +27,42 @@ use uuid::fmt::Hyphenated;
 use uuid::Uuid;
 
 use super::error::{AuthControllerError, Result};
+use super::usage::KeyUsage;
 use super::{Action, Key};
 
 const AUTH_STORE_SIZE: usize = 1_073_741_824; //1GiB
 const AUTH_DB_PATH: &str = "auth";
 const KEY_DB_NAME: &str = "api-keys";
 const KEY_ID_ACTION_INDEX_EXPIRATION_DB_NAME: &str = "keyid-action-index-expiration";
+const KEY_USAGE_DB_NAME: &str = "api-key-usages";
+const AUDIT_LOG_DB_NAME: &str = "audit-log";
+/// The number of entries kept in the audit log, the oldest entries are deleted past it.
+pub const MAX_AUDIT_LOG_ENTRIES: u64 = 100_000;
+/// The key of the salt of the master key fingerprints in the usages database, that can't be the
+/// uid of a key. It is cleared with the usages, the only ones holding fingerprints.
+const FINGERPRINT_SALT_KEY: &[u8] = b"master-key-fingerprint-salt";
+const FINGERPRINT_SALT_SIZE: usize = 32;
+
+/// Incremented each time the keys of a store of this process are modified, including by the
+/// dump imports that do not go through the `AuthController`, to invalidate the caches of the keys.
+static KEYS_VERSION: AtomicU64 = AtomicU64::new(0);
+
+/// The current version of the keys, see [`KEYS_VERSION`].
+pub fn keys_version() -> u64 {
+    KEYS_VERSION.load(Ordering::Acquire)
+}
+
+fn bump_keys_version() {
+    KEYS_VERSION.fetch_add(1, Ordering::AcqRel);
+}
 
 #[derive(Clone)]
 pub struct HeedAuthStore {
     env: Arc<Env>,
     keys: Database<Bytes, SerdeJson<Key>>,
     action_keyid_index_expiration: Database<KeyIdActionCodec, SerdeJson<Option<OffsetDateTime>>>,
+    usages: Database<Bytes, SerdeJson<KeyUsage>>,
+    audit_log: Database<BEU64, SerdeJson<AuditLogEntry>>,
     should_close_on_drop: bool,
 }
 
@@ -48,21 +77,37 @@ impl Drop for HeedAuthStore {
 pub fn open_auth_store_env(path: &Path) -> milli::heed::Result<milli::heed::Env> {
     let mut options = EnvOpenOptions::new();
     options.map_size(AUTH_STORE_SIZE); // 1GB
-    options.max_dbs(2);
+    options.max_dbs(4);
     options.open(path)
 }
 
 impl HeedAuthStore {
     pub fn new(path: impl AsRef<Path>) -> Result<Self> {
-        let path = path.as_ref().join(AUTH_DB_PATH);
-        create_dir_all(&path)?;
-        let env = Arc::new(open_auth_store_env(path.as_ref())?);
+        Self::open(path.as_ref().join(AUTH_DB_PATH))
+    }
+
+    /// Open the auth store located directly at `env_path`, creating it if needed.
+    ///
+    /// If the env is already opened in this process, the same env is shared.
+    pub fn open(env_path: impl AsRef<Path>) -> Result<Self> {
+        let path = env_path.as_ref();
+        create_dir_all(path)?;
+        let env = Arc::new(open_auth_store_env(path)?);
         let mut wtxn = env.write_txn()?;
         let keys = env.create_database(&mut wtxn, Some(KEY_DB_NAME))?;
         let action_keyid_index_expiration =
             env.create_database(&mut wtxn, Some(KEY_ID_ACTION_INDEX_EXPIRATION_DB_NAME))?;
+        let usages = env.create_database(&mut wtxn, Some(KEY_USAGE_DB_NAME))?;
+        let audit_log = env.create_database(&mut wtxn, Some(AUDIT_LOG_DB_NAME))?;
         wtxn.commit()?;
-        Ok(Self { env, keys, action_keyid_index_expiration, should_close_on_drop: true })
+        Ok(Self {
+            env,
+            keys,
+            action_keyid_index_expiration,
+            usages,
+            audit_log,
+            should_close_on_drop: true,
+        })
     }
 
     /// Return `Ok(())` if the auth store is able to access one of its database.
@@ -93,13 +138,41 @@ impl HeedAuthStore {
     }
 
     pub fn put_api_key(&self, key: Key) -> Result<Key> {
-        let uid = key.uid;
         let mut wtxn = self.env.write_txn()?;
 
-        self.keys.put(&mut wtxn, uid.as_bytes(), &key)?;
+        self.keys.put(&mut wtxn, key.uid.as_bytes(), &key)?;
+        self.index_key_actions(&mut wtxn, &key)?;
+
+        wtxn.commit()?;
+        bump_keys_version();
+
+        Ok(key)
+    }
+
+    /// Indexes again the actions of all the keys, so that the keys written by a previous
+    /// version also grant the actions added since then to `*` or to the `*` of a category.
+    pub fn index_all_keys_actions(&self) -> Result<()> {
+        let mut wtxn = self.env.write_txn()?;
+        let keys = self
+            .keys
+            .remap_key_type::<DecodeIgnore>()
+            .iter(&wtxn)?
+            .map(|result| result.map(|(_, key)| key))
+            .collect::<StdResult<Vec<_>, _>>()?;
+        for key in &keys {
+            self.index_key_actions(&mut wtxn, key)?;
+        }
+        wtxn.commit()?;
+        bump_keys_version();
+
+        Ok(())
+    }
+
+    fn index_key_actions(&self, wtxn: &mut RwTxn, key: &Key) -> Result<()> {
+        let uid = key.uid;
 
         // delete key from inverted database before refilling it.
-        self.delete_key_from_inverted_db(&mut wtxn, &uid)?;
+        self.delete_key_from_inverted_db(wtxn, &uid)?;
         // create inverted database.
         let db = self.action_keyid_index_expiration;
 
@@ -129,7 +202,7 @@ impl HeedAuthStore {
                     actions.extend([Action::SettingsGet, Action::SettingsUpdate].iter());
                 }
                 Action::DumpsAll => {
-                    actions.insert(Action::DumpsCreate);
+                    actions.extend([Action::DumpsCreate, Action::DumpsImport]);
                 }
                 Action::SnapshotsAll => {
                     actions.insert(Action::SnapshotsCreate);
@@ -153,12 +226,12 @@ impl HeedAuthStore {
         for action in actions {
             if no_index_restriction {
                 // If there is no index restriction we put None.
-                db.put(&mut wtxn, &(&uid, &action, None), &key.expires_at)?;
+                db.put(wtxn, &(&uid, &action, None), &key.expires_at)?;
             } else {
                 // else we create a key for each index.
                 for index in key.indexes.iter() {
                     db.put(
-                        &mut wtxn,
+                        wtxn,
                         &(&uid, &action, Some(index.to_string().as_bytes())),
                         &key.expires_at,
                     )?;
@@ -166,9 +239,7 @@ impl HeedAuthStore {
             }
         }
 
-        wtxn.commit()?;
-
-        Ok(key)
+        Ok(())
     }
 
     pub fn get_api_key(&self, uid: Uuid) -> Result<Option<Key>> {
@@ -176,38 +247,13 @@ impl HeedAuthStore {
         self.keys.get(&rtxn, uid.as_bytes()).map_err(|e| e.into())
     }
 
-    pub fn get_uid_from_encoded_key(
-        &self,
-        encoded_key: &[u8],
-        master_key: &[u8],
-    ) -> Result<Option<Uuid>> {
-        let rtxn = self.env.read_txn()?;
-        let uid = self
-            .keys
-            .remap_data_type::<DecodeIgnore>()
-            .iter(&rtxn)?
-            .filter_map(|res| match res {
-                Ok((uid, _)) => {
-                    let (uid, _) = try_split_array_at(uid)?;
-                    let uid = Uuid::from_bytes(*uid);
-                    if generate_key_as_hexa(uid, master_key).as_bytes() == encoded_key {
-                        Some(uid)
-                    } else {
-                        None
-                    }
-                }
-                Err(_) => None,
-            })
-            .next();
-
-        Ok(uid)
-    }
-
     pub fn delete_api_key(&self, uid: Uuid) -> Result<bool> {
         let mut wtxn = self.env.write_txn()?;
         let existing = self.keys.delete(&mut wtxn, uid.as_bytes())?;
         self.delete_key_from_inverted_db(&mut wtxn, &uid)?;
+        self.usages.delete(&mut wtxn, uid.as_bytes())?;
         wtxn.commit()?;
+        bump_keys_version();
 
         Ok(existing)
     }
@@ -215,7 +261,9 @@ impl HeedAuthStore {
     pub fn delete_all_keys(&self) -> Result<()> {
         let mut wtxn = self.env.write_txn()?;
         self.keys.clear(&mut wtxn)?;
+        self.usages.clear(&mut wtxn)?;
         wtxn.commit()?;
+        bump_keys_version();
         Ok(())
     }
 
@@ -230,6 +278,115 @@ impl HeedAuthStore {
         Ok(list)
     }
 
+    pub fn get_key_usage(&self, uid: Uuid) -> Result<Option<KeyUsage>> {
+        let rtxn = self.env.read_txn()?;
+        self.usages.get(&rtxn, uid.as_bytes()).map_err(|e| e.into())
+    }
+
+    /// Returns the random salt of the master key fingerprints, generating it the first time.
+    pub fn master_key_fingerprint_salt(&self) -> Result<Vec<u8>> {
+        let usages = self.usages.remap_data_type::<Bytes>();
+        let rtxn = self.env.read_txn()?;
+        if let Some(salt) = usages.get(&rtxn, FINGERPRINT_SALT_KEY)? {
+            return Ok(salt.to_vec());
+        }
+        drop(rtxn);
+
+        let mut wtxn = self.env.write_txn()?;
+        // the salt may have been generated since the read transaction.
+        let salt = match usages.get(&wtxn, FINGERPRINT_SALT_KEY)? {
+            Some(salt) => salt.to_vec(),
+            None => {
+                let mut salt = vec![0; FINGERPRINT_SALT_SIZE];
+                OsRng.fill_bytes(&mut salt);
+                usages.put(&mut wtxn, FINGERPRINT_SALT_KEY, &salt)?;
+                salt
+            }
+        };
+        wtxn.commit()?;
+        Ok(salt)
+    }
+
+    /// Adds the usages to the stored ones, ignoring the keys that were deleted in the meantime.
+    pub fn merge_key_usages(&self, usages: &HashMap<Uuid, KeyUsage>) -> Result<()> {
+        let mut wtxn = self.env.write_txn()?;
+        for (uid, usage) in usages {
+            if self.keys.remap_data_type::<DecodeIgnore>().get(&wtxn, uid.as_bytes())?.is_none() {
+                continue;
+            }
+            let mut stored = self.usages.get(&wtxn, uid.as_bytes())?.unwrap_or_default();
+            stored.merge(usage.clone());
+            self.usages.put(&mut wtxn, uid.as_bytes(), &stored)?;
+        }
+        wtxn.commit()?;
+        Ok(())
+    }
+
+    /// Appends entries to the audit log, their uids are replaced by the ones following the last entry.
+    ///
+    /// Only the last [`MAX_AUDIT_LOG_ENTRIES`] entries are kept.
+    pub fn append_audit_log_entries(&self, entries: Vec<AuditLogEntry>) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut wtxn = self.env.write_txn()?;
+        let mut next_uid = self.audit_log.last(&wtxn)?.map_or(0, |(uid, _)| uid + 1);
+        for mut entry in entries {
+            entry.uid = next_uid;
+            self.audit_log.put(&mut wtxn, &entry.uid, &entry)?;
+            next_uid += 1;
+        }
+        if let Some(oldest_kept) = next_uid.checked_sub(MAX_AUDIT_LOG_ENTRIES) {
+            self.audit_log.delete_range(&mut wtxn, &(..oldest_kept))?;
+        }
+        wtxn.commit()?;
+        Ok(())
+    }
+
+    /// Inserts an entry in the audit log with its uid, when importing a dump.
+    pub fn raw_insert_audit_log_entry(&self, entry: &AuditLogEntry) -> Result<()> {
+        let mut wtxn = self.env.write_txn()?;
+        self.audit_log.put(&mut wtxn, &entry.uid, entry)?;
+        wtxn.commit()?;
+        Ok(())
+    }
+
+    /// Returns at most `limit` entries of the audit log in the range of uids matching the filter,
+    /// from the most recent one.
+    pub fn audit_log(
+        &self,
+        uids: impl RangeBounds<u64>,
+        filter: impl Fn(&AuditLogEntry) -> bool,
+        limit: usize,
+    ) -> Result<Vec<AuditLogEntry>> {
+        let rtxn = self.env.read_txn()?;
+        let range = (uids.start_bound().cloned(), uids.end_bound().cloned());
+        let mut entries = Vec::new();
+        for result in self.audit_log.rev_range(&rtxn, &range)? {
+            if entries.len() >= limit {
+                break;
+            }
+            let (_, entry) = result?;
+            if filter(&entry) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Calls `f` on every entry of the audit log, from the oldest one.
+    pub fn for_each_audit_log_entry<E: From<AuthControllerError>>(
+        &self,
+        mut f: impl FnMut(AuditLogEntry) -> StdResult<(), E>,
+    ) -> StdResult<(), E> {
+        let rtxn = self.env.read_txn().map_err(AuthControllerError::from)?;
+        for result in self.audit_log.iter(&rtxn).map_err(AuthControllerError::from)? {
+            let (_, entry) = result.map_err(AuthControllerError::from)?;
+            f(entry)?;
+        }
+        Ok(())
+    }
+
     pub fn get_expiration_date(
         &self,
         uid: Uuid,
@@ -337,6 +494,17 @@ pub struct InvalidActionError {
     pub action_byte: u8,
 }
 
+/// A stable identifier of a master key that doesn't reveal it. It is salted so that a guessed
+/// master key can't be checked against a fingerprint without the salt of the auth store.
+pub fn master_key_fingerprint(master_key: &[u8], salt: &[u8]) -> String {
+    // new_from_slice function never fail.
+    let mut mac = Hmac::<Sha256>::new_from_slice(salt).unwrap();
+    mac.update(master_key);
+
+    let result = mac.finalize();
+    format!("{:x}", result.into_bytes())
+}
+
 pub fn generate_key_as_hexa(uid: Uuid, master_key: &[u8]) -> String {
     // format uid as hyphenated allowing user to generate their own keys.
     let mut uid_buffer = [0; Hyphenated::LENGTH];
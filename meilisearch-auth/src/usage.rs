@@ -0,0 +1,99 @@
+//! Usage of the API keys, accumulated in memory and periodically flushed to the auth store
+//! to avoid a write transaction per request.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, Weak};
+use std::time::Duration;
+
+use meilisearch_types::keys::Action;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::store::HeedAuthStore;
+
+/// The interval at which the usages recorded in memory are written in the auth store.
+const USAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyUsage {
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_used_at: Option<OffsetDateTime>,
+    /// The number of requests made with the key, per action.
+    pub request_counts: BTreeMap<Action, u64>,
+    /// The fingerprint of the master key the key last used was derived from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub master_key_fingerprint: Option<String>,
+}
+
+impl KeyUsage {
+    pub fn merge(&mut self, other: KeyUsage) {
+        if other.last_used_at >= self.last_used_at && other.master_key_fingerprint.is_some() {
+            self.master_key_fingerprint = other.master_key_fingerprint;
+        }
+        self.last_used_at = self.last_used_at.max(other.last_used_at);
+        for (action, count) in other.request_counts {
+            *self.request_counts.entry(action).or_default() += count;
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct UsageRecorder {
+    pending: Mutex<HashMap<Uuid, KeyUsage>>,
+}
+
+impl UsageRecorder {
+    pub fn record(&self, uid: Uuid, action: Action, master_key_fingerprint: Option<String>) {
+        let mut pending = self.pending.lock().unwrap();
+        let usage = pending.entry(uid).or_default();
+        usage.last_used_at = Some(OffsetDateTime::now_utc());
+        if master_key_fingerprint.is_some() {
+            usage.master_key_fingerprint = master_key_fingerprint;
+        }
+        *usage.request_counts.entry(action).or_default() += 1;
+    }
+
+    /// Writes the usages recorded since the last flush in the store.
+    ///
+    /// If the write fails, the usages are kept for the next flush.
+    pub fn flush(&self, store: &HeedAuthStore) -> crate::error::Result<()> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(error) = store.merge_key_usages(&pending) {
+            let mut current = self.pending.lock().unwrap();
+            for (uid, usage) in pending {
+                current.entry(uid).or_default().merge(usage);
+            }
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Forgets the usage of a key, when it is deleted.
+    pub fn remove(&self, uid: Uuid) {
+        self.pending.lock().unwrap().remove(&uid);
+    }
+}
+
+/// Spawns a thread flushing the usages in the store until the auth controller is dropped.
+pub fn spawn_usage_flusher(store: Weak<HeedAuthStore>, recorder: Weak<UsageRecorder>) {
+    let spawned =
+        std::thread::Builder::new().name(String::from("key-usage-flusher")).spawn(move || loop {
+            std::thread::sleep(USAGE_FLUSH_INTERVAL);
+            let (Some(store), Some(recorder)) = (store.upgrade(), recorder.upgrade()) else {
+                break;
+            };
+            if let Err(error) = recorder.flush(&store) {
+                tracing::error!(%error, "Could not flush the usage of the API keys");
+            }
+        });
+
+    if let Err(error) = spawned {
+        tracing::error!(%error, "Could not spawn the thread flushing the usage of the API keys");
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::keys::Action;
+
+/// An administrative request made to Meilisearch, as recorded in the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    /// Increases with each entry appended to the audit log.
+    pub uid: u64,
+    /// The uid of the API key the request was made with, `None` for the master key.
+    pub key_uid: Option<Uuid>,
+    pub action: Action,
+    pub index_uid: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    /// The method, the path and the response status of the request, e.g. `DELETE /indexes/movies 202`.
+    pub summary: String,
+}
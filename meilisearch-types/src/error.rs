@@ -20,6 +20,9 @@ pub struct ResponseError {
     error_type: String,
     #[serde(rename = "link")]
     error_link: String,
+    /// The number of seconds to wait before retrying, sent in the `Retry-After` header.
+    #[serde(skip)]
+    retry_after: Option<u64>,
 }
 
 impl ResponseError {
@@ -33,8 +36,14 @@ impl ResponseError {
             error_code: code.name(),
             error_type: code.type_(),
             error_link: code.url(),
+            retry_after: None,
         }
     }
+
+    pub fn with_retry_after(mut self, retry_after: u64) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
 }
 
 impl fmt::Display for ResponseError {
@@ -60,7 +69,9 @@ impl aweb::error::ResponseError for ResponseError {
         let mut builder = HttpResponseBuilder::new(self.status_code());
         builder.content_type("application/json");
 
-        if self.code == StatusCode::SERVICE_UNAVAILABLE {
+        if let Some(retry_after) = self.retry_after {
+            builder.insert_header((header::RETRY_AFTER, retry_after));
+        } else if self.code == StatusCode::SERVICE_UNAVAILABLE {
             builder.insert_header((header::RETRY_AFTER, "10"));
         }
 
@@ -184,7 +195,9 @@ macro_rules! make_error_codes {
 // An exhaustive list of all the error codes used by meilisearch.
 make_error_codes! {
 ApiKeyAlreadyExists                   , InvalidRequest       , CONFLICT ;
+ApiKeyDailyQuotaExceeded              , Auth                 , TOO_MANY_REQUESTS ;
 ApiKeyNotFound                        , InvalidRequest       , NOT_FOUND ;
+ApiKeyRateLimitExceeded               , Auth                 , TOO_MANY_REQUESTS ;
 BadParameter                          , InvalidRequest       , BAD_REQUEST;
 BadRequest                            , InvalidRequest       , BAD_REQUEST;
 DatabaseSizeLimitReached              , Internal             , INTERNAL_SERVER_ERROR;
@@ -195,9 +208,13 @@ DumpProcessFailed                     , Internal             , INTERNAL_SERVER_E
 DuplicateIndexFound                   , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyActions                , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyCreatedAt              , InvalidRequest       , BAD_REQUEST;
+ImmutableApiKeyDailyQuota             , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyExpiresAt              , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyIndexes                , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyKey                    , InvalidRequest       , BAD_REQUEST;
+ImmutableApiKeyRateLimit              , InvalidRequest       , BAD_REQUEST;
+ImmutableApiKeySearchRules            , InvalidRequest       , BAD_REQUEST;
+ImmutableApiKeyTenantTokenKeys        , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyUid                    , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyUpdatedAt              , InvalidRequest       , BAD_REQUEST;
 ImmutableIndexCreatedAt               , InvalidRequest       , BAD_REQUEST;
@@ -212,13 +229,26 @@ IndexPrimaryKeyNoCandidateFound       , InvalidRequest       , BAD_REQUEST ;
 Internal                              , Internal             , INTERNAL_SERVER_ERROR ;
 InvalidApiKey                         , Auth                 , FORBIDDEN ;
 InvalidApiKeyActions                  , InvalidRequest       , BAD_REQUEST ;
+InvalidApiKeyDailyQuota               , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyDescription              , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyExpiresAt                , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyIndexes                  , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyLimit                    , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyName                     , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyOffset                   , InvalidRequest       , BAD_REQUEST ;
+InvalidApiKeyRateLimit                , InvalidRequest       , BAD_REQUEST ;
+InvalidApiKeySearchRules              , InvalidRequest       , BAD_REQUEST ;
+InvalidApiKeyTenantTokenKeys          , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyUid                      , InvalidRequest       , BAD_REQUEST ;
+InvalidApiKeyUnusedSince              , InvalidRequest       , BAD_REQUEST ;
+InvalidAuditLogAction                 , InvalidRequest       , BAD_REQUEST ;
+InvalidAuditLogAfterTimestamp         , InvalidRequest       , BAD_REQUEST ;
+InvalidAuditLogAfterUid               , InvalidRequest       , BAD_REQUEST ;
+InvalidAuditLogBeforeTimestamp        , InvalidRequest       , BAD_REQUEST ;
+InvalidAuditLogBeforeUid              , InvalidRequest       , BAD_REQUEST ;
+InvalidAuditLogIndexUid               , InvalidRequest       , BAD_REQUEST ;
+InvalidAuditLogKeyUid                 , InvalidRequest       , BAD_REQUEST ;
+InvalidAuditLogLimit                  , InvalidRequest       , BAD_REQUEST ;
 InvalidContentType                    , InvalidRequest       , UNSUPPORTED_MEDIA_TYPE ;
 InvalidDocumentCsvDelimiter           , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentFields                 , InvalidRequest       , BAD_REQUEST ;
@@ -230,12 +260,19 @@ InvalidVectorsType                    , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentId                     , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentLimit                  , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentOffset                 , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentRetrieveVectors        , InvalidRequest       , BAD_REQUEST ;
+InvalidDumpImportKeys                 , InvalidRequest       , BAD_REQUEST ;
+InvalidDumpUid                        , InvalidRequest       , BAD_REQUEST ;
 InvalidEmbedder                       , InvalidRequest       , BAD_REQUEST ;
 InvalidHybridQuery                    , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexLimit                     , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexOffset                    , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexPrimaryKey                , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexUid                       , InvalidRequest       , BAD_REQUEST ;
+InvalidRenderDocument                 , InvalidRequest       , BAD_REQUEST ;
+InvalidRenderDocumentId               , InvalidRequest       , BAD_REQUEST ;
+InvalidRenderRetrieveEmbeddings       , InvalidRequest       , BAD_REQUEST ;
+InvalidRenderTemplate                 , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToSearchOn     , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToCrop         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToHighlight    , InvalidRequest       , BAD_REQUEST ;
@@ -243,12 +280,18 @@ InvalidSearchAttributesToRetrieve     , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropLength               , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropMarker               , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFacets                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetHierarchyDepth      , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetHistogram           , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetRanges              , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchSemanticRatio            , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFusion                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchRankConstant             , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchFacetName           , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFilter                   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHighlightPostTag         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHighlightPreTag          , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHitsPerPage              , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchImage                    , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchLimit                    , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchMatchingStrategy         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchOffset                   , InvalidRequest       , BAD_REQUEST ;
@@ -256,6 +299,7 @@ InvalidSearchPage                     , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchQ                        , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchQuery               , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchName                , InvalidRequest       , BAD_REQUEST ;
+InvalidFacetSearchRanges              , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchVector                   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchShowMatchesPosition      , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchShowRankingScore         , InvalidRequest       , BAD_REQUEST ;
@@ -304,6 +348,7 @@ MissingApiKeyIndexes                  , InvalidRequest       , BAD_REQUEST ;
 MissingAuthorizationHeader            , Auth                 , UNAUTHORIZED ;
 MissingContentType                    , InvalidRequest       , UNSUPPORTED_MEDIA_TYPE ;
 MissingDocumentId                     , InvalidRequest       , BAD_REQUEST ;
+MissingDumpUid                        , InvalidRequest       , BAD_REQUEST ;
 MissingFacetSearchFacetName           , InvalidRequest       , BAD_REQUEST ;
 MissingIndexUid                       , InvalidRequest       , BAD_REQUEST ;
 MissingMasterKey                      , Auth                 , UNAUTHORIZED ;
@@ -362,6 +407,11 @@ impl ErrorCode for milli::Error {
                     | UserError::InvalidOpenAiModelDimensions { .. }
                     | UserError::InvalidOpenAiModelDimensionsMax { .. }
                     | UserError::InvalidSettingsDimensions { .. }
+                    | UserError::InvalidSettingsChunking { .. }
+                    | UserError::InvalidSettingsImageField { .. }
+                    | UserError::InvalidSettingsChunkingWithImages { .. }
+                    | UserError::InvalidSettingsQuantization { .. }
+                    | UserError::InvalidSettingsRescoring { .. }
                     | UserError::InvalidUrl { .. }
                     | UserError::InvalidPrompt(_) => Code::InvalidSettingsEmbedders,
                     UserError::TooManyEmbedders(_) => Code::InvalidSettingsEmbedders,
@@ -386,6 +436,7 @@ impl ErrorCode for milli::Error {
                     UserError::InvalidVectorsMapType { .. } => Code::InvalidVectorsType,
                     UserError::InvalidVectorsType { .. } => Code::InvalidVectorsType,
                     UserError::TooManyVectors(_, _) => Code::TooManyVectors,
+                    UserError::TooManyFacetSearchRanges { .. } => Code::InvalidFacetSearchRanges,
                     UserError::SortError(_) => Code::InvalidSearchSort,
                     UserError::InvalidMinTypoWordLenSetting(_, _) => {
                         Code::InvalidSettingsTypoTolerance
@@ -488,6 +539,15 @@ impl fmt::Display for deserr_codes::InvalidSearchSemanticRatio {
     }
 }
 
+impl fmt::Display for deserr_codes::InvalidSearchFacetHierarchyDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value of `facetHierarchyDepth` is invalid, expected an integer greater than `0`."
+        )
+    }
+}
+
 #[macro_export]
 macro_rules! internal_error {
     ($target:ty : $($other:path), *) => {
@@ -1,9 +1,12 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::Infallible;
 use std::hash::Hash;
 use std::str::FromStr;
 
 use deserr::{DeserializeError, Deserr, MergeWithError, ValuePointerRef};
 use enum_iterator::Sequence;
+use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey};
 use milli::update::Setting;
 use serde::{Deserialize, Serialize};
 use time::format_description::well_known::Rfc3339;
@@ -47,11 +50,30 @@ pub struct CreateApiKey {
     pub indexes: Vec<IndexUidPattern>,
     #[deserr(error = DeserrJsonError<InvalidApiKeyExpiresAt>, try_from(Option<String>) = parse_expiration_date -> ParseOffsetDateTimeError, missing_field_error = DeserrJsonError::missing_api_key_expires_at)]
     pub expires_at: Option<OffsetDateTime>,
+    #[deserr(default, error = DeserrJsonError<InvalidApiKeySearchRules>)]
+    pub search_rules: BTreeMap<IndexUidPattern, KeySearchRules>,
+    #[deserr(default, error = DeserrJsonError<InvalidApiKeyRateLimit>)]
+    pub rate_limit: Option<KeyRateLimit>,
+    #[deserr(default, error = DeserrJsonError<InvalidApiKeyDailyQuota>)]
+    pub daily_quota: Option<u64>,
+    #[deserr(default, error = DeserrJsonError<InvalidApiKeyTenantTokenKeys>)]
+    pub tenant_token_keys: Option<TenantTokenKeys>,
 }
 
 impl CreateApiKey {
     pub fn to_key(self) -> Key {
-        let CreateApiKey { description, name, uid, actions, indexes, expires_at } = self;
+        let CreateApiKey {
+            description,
+            name,
+            uid,
+            actions,
+            indexes,
+            expires_at,
+            search_rules,
+            rate_limit,
+            daily_quota,
+            tenant_token_keys,
+        } = self;
         let now = OffsetDateTime::now_utc();
         Key {
             description,
@@ -60,6 +82,10 @@ impl CreateApiKey {
             actions,
             indexes,
             expires_at,
+            search_rules,
+            rate_limit,
+            daily_quota,
+            tenant_token_keys,
             created_at: now,
             updated_at: now,
         }
@@ -76,6 +102,12 @@ fn deny_immutable_fields_api_key(
         "actions" => immutable_field_error(field, accepted, Code::ImmutableApiKeyActions),
         "indexes" => immutable_field_error(field, accepted, Code::ImmutableApiKeyIndexes),
         "expiresAt" => immutable_field_error(field, accepted, Code::ImmutableApiKeyExpiresAt),
+        "searchRules" => immutable_field_error(field, accepted, Code::ImmutableApiKeySearchRules),
+        "rateLimit" => immutable_field_error(field, accepted, Code::ImmutableApiKeyRateLimit),
+        "dailyQuota" => immutable_field_error(field, accepted, Code::ImmutableApiKeyDailyQuota),
+        "tenantTokenKeys" => {
+            immutable_field_error(field, accepted, Code::ImmutableApiKeyTenantTokenKeys)
+        }
         "createdAt" => immutable_field_error(field, accepted, Code::ImmutableApiKeyCreatedAt),
         "updatedAt" => immutable_field_error(field, accepted, Code::ImmutableApiKeyUpdatedAt),
         _ => deserr::take_cf_content(DeserrJsonError::<BadRequest>::error::<Infallible>(
@@ -106,6 +138,14 @@ pub struct Key {
     pub indexes: Vec<IndexUidPattern>,
     #[serde(with = "time::serde::rfc3339::option")]
     pub expires_at: Option<OffsetDateTime>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub search_rules: BTreeMap<IndexUidPattern, KeySearchRules>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<KeyRateLimit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_quota: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_token_keys: Option<TenantTokenKeys>,
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
@@ -123,6 +163,10 @@ impl Key {
             actions: vec![Action::All],
             indexes: vec![IndexUidPattern::all()],
             expires_at: None,
+            search_rules: BTreeMap::new(),
+            rate_limit: None,
+            daily_quota: None,
+            tenant_token_keys: None,
             created_at: now,
             updated_at: now,
         }
@@ -138,12 +182,180 @@ impl Key {
             actions: vec![Action::Search],
             indexes: vec![IndexUidPattern::all()],
             expires_at: None,
+            search_rules: BTreeMap::new(),
+            rate_limit: None,
+            daily_quota: None,
+            tenant_token_keys: None,
             created_at: now,
             updated_at: now,
         }
     }
 }
 
+/// Restrictions applied on top of the searches made with a key on the indexes matching a pattern,
+/// like the search rules of a tenant token.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidApiKeySearchRules>, rename_all = camelCase, deny_unknown_fields)]
+pub struct KeySearchRules {
+    /// A filter added to the filter of every search.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub filter: Option<serde_json::Value>,
+    /// The only attributes that can be retrieved, highlighted or cropped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub attributes_to_retrieve: Option<BTreeSet<String>>,
+    /// The maximum number of hits returned by a search.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub limit: Option<usize>,
+}
+
+/// The number of requests per second a key can make, with the number of requests it can make in a
+/// burst after being idle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidApiKeyRateLimit>, rename_all = camelCase, deny_unknown_fields, validate = validate_rate_limit -> DeserrJsonError<InvalidApiKeyRateLimit>)]
+pub struct KeyRateLimit {
+    pub requests_per_second: u32,
+    /// Defaults to `requests_per_second`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub burst: Option<u32>,
+}
+
+impl KeyRateLimit {
+    pub fn burst(&self) -> u32 {
+        self.burst.unwrap_or(self.requests_per_second)
+    }
+}
+
+fn validate_rate_limit<E: DeserializeError>(
+    rate_limit: KeyRateLimit,
+    location: ValuePointerRef,
+) -> Result<KeyRateLimit, E> {
+    if rate_limit.requests_per_second == 0 || rate_limit.burst == Some(0) {
+        return Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            deserr::ErrorKind::Unexpected {
+                msg: "`requestsPerSecond` and `burst` must be greater than `0`.".to_string(),
+            },
+            location,
+        )));
+    }
+    Ok(rate_limit)
+}
+
+/// The public keys of an identity provider, verifying the tenant tokens it signs with an asymmetric
+/// algorithm (`RS256`, `ES256`...) on behalf of the key.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidApiKeyTenantTokenKeys>, rename_all = camelCase, deny_unknown_fields, validate = validate_tenant_token_keys -> DeserrJsonError<InvalidApiKeyTenantTokenKeys>)]
+pub struct TenantTokenKeys {
+    /// PEM encoded RSA or EC public keys.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[deserr(default)]
+    pub public_keys: Vec<String>,
+    /// A JWKS document, its keys are matched with the `kid` header of the tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub jwks: Option<serde_json::Value>,
+    /// The claim of the tokens containing their search rules, defaults to `searchRules`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub search_rules_claim: Option<String>,
+    /// When set, the `iss` claim of the tokens must be this issuer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub issuer: Option<String>,
+    /// When set, the `aud` claim of the tokens must contain this audience.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub audience: Option<String>,
+}
+
+/// A public key able to verify the tenant tokens signed with one of its algorithms.
+pub struct TenantTokenPublicKey {
+    pub key_id: Option<String>,
+    pub key: DecodingKey,
+    pub algorithms: &'static [Algorithm],
+}
+
+const RSA_ALGORITHMS: &[Algorithm] = &[Algorithm::RS256, Algorithm::RS384, Algorithm::RS512];
+const EC_ALGORITHMS: &[Algorithm] = &[Algorithm::ES256, Algorithm::ES384];
+
+impl TenantTokenKeys {
+    pub fn search_rules_claim(&self) -> &str {
+        self.search_rules_claim.as_deref().unwrap_or("searchRules")
+    }
+
+    /// Parses the PEM encoded keys and the keys of the JWKS document.
+    pub fn public_keys(&self) -> Result<Vec<TenantTokenPublicKey>, String> {
+        let mut public_keys = Vec::new();
+
+        for (i, pem) in self.public_keys.iter().enumerate() {
+            let public_key = if let Ok(key) = DecodingKey::from_rsa_pem(pem.as_bytes()) {
+                TenantTokenPublicKey { key_id: None, key, algorithms: RSA_ALGORITHMS }
+            } else if let Ok(key) = DecodingKey::from_ec_pem(pem.as_bytes()) {
+                TenantTokenPublicKey { key_id: None, key, algorithms: EC_ALGORITHMS }
+            } else {
+                return Err(format!(
+                    "`publicKeys[{i}]` is not a PEM encoded RSA or EC public key."
+                ));
+            };
+            public_keys.push(public_key);
+        }
+
+        if let Some(jwks) = &self.jwks {
+            let jwks: JwkSet = serde_json::from_value(jwks.clone())
+                .map_err(|e| format!("`jwks` is not a valid JWKS document: {e}."))?;
+            for (i, jwk) in jwks.keys.iter().enumerate() {
+                let algorithms = match jwk.algorithm {
+                    AlgorithmParameters::RSA(_) => RSA_ALGORITHMS,
+                    AlgorithmParameters::EllipticCurve(_) => EC_ALGORITHMS,
+                    _ => return Err(format!("`jwks.keys[{i}]` is not an RSA or EC public key.")),
+                };
+                let key = DecodingKey::from_jwk(jwk)
+                    .map_err(|e| format!("`jwks.keys[{i}]` is not a valid public key: {e}."))?;
+                public_keys.push(TenantTokenPublicKey {
+                    key_id: jwk.common.key_id.clone(),
+                    key,
+                    algorithms,
+                });
+            }
+        }
+
+        Ok(public_keys)
+    }
+}
+
+fn validate_tenant_token_keys<E: DeserializeError>(
+    tenant_token_keys: TenantTokenKeys,
+    location: ValuePointerRef,
+) -> Result<TenantTokenKeys, E> {
+    let error = if tenant_token_keys.public_keys.is_empty() && tenant_token_keys.jwks.is_none() {
+        Some("At least one of `publicKeys` or `jwks` must be provided.".to_string())
+    } else if tenant_token_keys.search_rules_claim.as_deref() == Some("") {
+        Some("`searchRulesClaim` must not be empty.".to_string())
+    } else if tenant_token_keys.issuer.as_deref() == Some("") {
+        Some("`issuer` must not be empty.".to_string())
+    } else if tenant_token_keys.audience.as_deref() == Some("") {
+        Some("`audience` must not be empty.".to_string())
+    } else {
+        tenant_token_keys.public_keys().err()
+    };
+
+    match error {
+        Some(msg) => Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            deserr::ErrorKind::Unexpected { msg },
+            location,
+        ))),
+        None => Ok(tenant_token_keys),
+    }
+}
+
 fn parse_expiration_date(
     string: Option<String>,
 ) -> std::result::Result<Option<OffsetDateTime>, ParseOffsetDateTimeError> {
@@ -179,7 +391,20 @@ fn parse_expiration_date(
     }
 }
 
-#[derive(Copy, Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Sequence, Deserr)]
+#[derive(
+    Copy,
+    Clone,
+    Serialize,
+    Deserialize,
+    Debug,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Sequence,
+    Deserr,
+)]
 #[repr(u8)]
 pub enum Action {
     #[serde(rename = "*")]
@@ -284,6 +509,126 @@ pub enum Action {
     #[serde(rename = "experimental.update")]
     #[deserr(rename = "experimental.update")]
     ExperimentalFeaturesUpdate,
+    #[serde(rename = "dumps.import")]
+    #[deserr(rename = "dumps.import")]
+    DumpsImport,
+    #[serde(rename = "settings.filterableAttributes.get")]
+    #[deserr(rename = "settings.filterableAttributes.get")]
+    SettingsFilterableAttributesGet,
+    #[serde(rename = "settings.filterableAttributes.update")]
+    #[deserr(rename = "settings.filterableAttributes.update")]
+    SettingsFilterableAttributesUpdate,
+    #[serde(rename = "settings.sortableAttributes.get")]
+    #[deserr(rename = "settings.sortableAttributes.get")]
+    SettingsSortableAttributesGet,
+    #[serde(rename = "settings.sortableAttributes.update")]
+    #[deserr(rename = "settings.sortableAttributes.update")]
+    SettingsSortableAttributesUpdate,
+    #[serde(rename = "settings.displayedAttributes.get")]
+    #[deserr(rename = "settings.displayedAttributes.get")]
+    SettingsDisplayedAttributesGet,
+    #[serde(rename = "settings.displayedAttributes.update")]
+    #[deserr(rename = "settings.displayedAttributes.update")]
+    SettingsDisplayedAttributesUpdate,
+    #[serde(rename = "settings.typoTolerance.get")]
+    #[deserr(rename = "settings.typoTolerance.get")]
+    SettingsTypoToleranceGet,
+    #[serde(rename = "settings.typoTolerance.update")]
+    #[deserr(rename = "settings.typoTolerance.update")]
+    SettingsTypoToleranceUpdate,
+    #[serde(rename = "settings.searchableAttributes.get")]
+    #[deserr(rename = "settings.searchableAttributes.get")]
+    SettingsSearchableAttributesGet,
+    #[serde(rename = "settings.searchableAttributes.update")]
+    #[deserr(rename = "settings.searchableAttributes.update")]
+    SettingsSearchableAttributesUpdate,
+    #[serde(rename = "settings.stopWords.get")]
+    #[deserr(rename = "settings.stopWords.get")]
+    SettingsStopWordsGet,
+    #[serde(rename = "settings.stopWords.update")]
+    #[deserr(rename = "settings.stopWords.update")]
+    SettingsStopWordsUpdate,
+    #[serde(rename = "settings.nonSeparatorTokens.get")]
+    #[deserr(rename = "settings.nonSeparatorTokens.get")]
+    SettingsNonSeparatorTokensGet,
+    #[serde(rename = "settings.nonSeparatorTokens.update")]
+    #[deserr(rename = "settings.nonSeparatorTokens.update")]
+    SettingsNonSeparatorTokensUpdate,
+    #[serde(rename = "settings.separatorTokens.get")]
+    #[deserr(rename = "settings.separatorTokens.get")]
+    SettingsSeparatorTokensGet,
+    #[serde(rename = "settings.separatorTokens.update")]
+    #[deserr(rename = "settings.separatorTokens.update")]
+    SettingsSeparatorTokensUpdate,
+    #[serde(rename = "settings.dictionary.get")]
+    #[deserr(rename = "settings.dictionary.get")]
+    SettingsDictionaryGet,
+    #[serde(rename = "settings.dictionary.update")]
+    #[deserr(rename = "settings.dictionary.update")]
+    SettingsDictionaryUpdate,
+    #[serde(rename = "settings.synonyms.get")]
+    #[deserr(rename = "settings.synonyms.get")]
+    SettingsSynonymsGet,
+    #[serde(rename = "settings.synonyms.update")]
+    #[deserr(rename = "settings.synonyms.update")]
+    SettingsSynonymsUpdate,
+    #[serde(rename = "settings.distinctAttribute.get")]
+    #[deserr(rename = "settings.distinctAttribute.get")]
+    SettingsDistinctAttributeGet,
+    #[serde(rename = "settings.distinctAttribute.update")]
+    #[deserr(rename = "settings.distinctAttribute.update")]
+    SettingsDistinctAttributeUpdate,
+    #[serde(rename = "settings.proximityPrecision.get")]
+    #[deserr(rename = "settings.proximityPrecision.get")]
+    SettingsProximityPrecisionGet,
+    #[serde(rename = "settings.proximityPrecision.update")]
+    #[deserr(rename = "settings.proximityPrecision.update")]
+    SettingsProximityPrecisionUpdate,
+    #[serde(rename = "settings.rankingRules.get")]
+    #[deserr(rename = "settings.rankingRules.get")]
+    SettingsRankingRulesGet,
+    #[serde(rename = "settings.rankingRules.update")]
+    #[deserr(rename = "settings.rankingRules.update")]
+    SettingsRankingRulesUpdate,
+    #[serde(rename = "settings.faceting.get")]
+    #[deserr(rename = "settings.faceting.get")]
+    SettingsFacetingGet,
+    #[serde(rename = "settings.faceting.update")]
+    #[deserr(rename = "settings.faceting.update")]
+    SettingsFacetingUpdate,
+    #[serde(rename = "settings.pagination.get")]
+    #[deserr(rename = "settings.pagination.get")]
+    SettingsPaginationGet,
+    #[serde(rename = "settings.pagination.update")]
+    #[deserr(rename = "settings.pagination.update")]
+    SettingsPaginationUpdate,
+    #[serde(rename = "settings.embedders.get")]
+    #[deserr(rename = "settings.embedders.get")]
+    SettingsEmbeddersGet,
+    #[serde(rename = "settings.embedders.update")]
+    #[deserr(rename = "settings.embedders.update")]
+    SettingsEmbeddersUpdate,
+    #[serde(rename = "settings.searchCutoffMs.get")]
+    #[deserr(rename = "settings.searchCutoffMs.get")]
+    SettingsSearchCutoffMsGet,
+    #[serde(rename = "settings.searchCutoffMs.update")]
+    #[deserr(rename = "settings.searchCutoffMs.update")]
+    SettingsSearchCutoffMsUpdate,
+    #[serde(rename = "auditLog.get")]
+    #[deserr(rename = "auditLog.get")]
+    AuditLogGet,
+    #[serde(rename = "experimental.vectorStore.update")]
+    #[deserr(rename = "experimental.vectorStore.update")]
+    ExperimentalVectorStoreUpdate,
+    #[serde(rename = "experimental.metrics.update")]
+    #[deserr(rename = "experimental.metrics.update")]
+    ExperimentalMetricsUpdate,
+    #[serde(rename = "experimental.logsRoute.update")]
+    #[deserr(rename = "experimental.logsRoute.update")]
+    ExperimentalLogsRouteUpdate,
+    #[serde(rename = "experimental.exportPuffinReports.update")]
+    #[deserr(rename = "experimental.exportPuffinReports.update")]
+    ExperimentalExportPuffinReportsUpdate,
 }
 
 impl Action {
@@ -323,6 +668,48 @@ impl Action {
             KEYS_DELETE => Some(Self::KeysDelete),
             EXPERIMENTAL_FEATURES_GET => Some(Self::ExperimentalFeaturesGet),
             EXPERIMENTAL_FEATURES_UPDATE => Some(Self::ExperimentalFeaturesUpdate),
+            DUMPS_IMPORT => Some(Self::DumpsImport),
+            SETTINGS_FILTERABLE_ATTRIBUTES_GET => Some(Self::SettingsFilterableAttributesGet),
+            SETTINGS_FILTERABLE_ATTRIBUTES_UPDATE => Some(Self::SettingsFilterableAttributesUpdate),
+            SETTINGS_SORTABLE_ATTRIBUTES_GET => Some(Self::SettingsSortableAttributesGet),
+            SETTINGS_SORTABLE_ATTRIBUTES_UPDATE => Some(Self::SettingsSortableAttributesUpdate),
+            SETTINGS_DISPLAYED_ATTRIBUTES_GET => Some(Self::SettingsDisplayedAttributesGet),
+            SETTINGS_DISPLAYED_ATTRIBUTES_UPDATE => Some(Self::SettingsDisplayedAttributesUpdate),
+            SETTINGS_TYPO_TOLERANCE_GET => Some(Self::SettingsTypoToleranceGet),
+            SETTINGS_TYPO_TOLERANCE_UPDATE => Some(Self::SettingsTypoToleranceUpdate),
+            SETTINGS_SEARCHABLE_ATTRIBUTES_GET => Some(Self::SettingsSearchableAttributesGet),
+            SETTINGS_SEARCHABLE_ATTRIBUTES_UPDATE => Some(Self::SettingsSearchableAttributesUpdate),
+            SETTINGS_STOP_WORDS_GET => Some(Self::SettingsStopWordsGet),
+            SETTINGS_STOP_WORDS_UPDATE => Some(Self::SettingsStopWordsUpdate),
+            SETTINGS_NON_SEPARATOR_TOKENS_GET => Some(Self::SettingsNonSeparatorTokensGet),
+            SETTINGS_NON_SEPARATOR_TOKENS_UPDATE => Some(Self::SettingsNonSeparatorTokensUpdate),
+            SETTINGS_SEPARATOR_TOKENS_GET => Some(Self::SettingsSeparatorTokensGet),
+            SETTINGS_SEPARATOR_TOKENS_UPDATE => Some(Self::SettingsSeparatorTokensUpdate),
+            SETTINGS_DICTIONARY_GET => Some(Self::SettingsDictionaryGet),
+            SETTINGS_DICTIONARY_UPDATE => Some(Self::SettingsDictionaryUpdate),
+            SETTINGS_SYNONYMS_GET => Some(Self::SettingsSynonymsGet),
+            SETTINGS_SYNONYMS_UPDATE => Some(Self::SettingsSynonymsUpdate),
+            SETTINGS_DISTINCT_ATTRIBUTE_GET => Some(Self::SettingsDistinctAttributeGet),
+            SETTINGS_DISTINCT_ATTRIBUTE_UPDATE => Some(Self::SettingsDistinctAttributeUpdate),
+            SETTINGS_PROXIMITY_PRECISION_GET => Some(Self::SettingsProximityPrecisionGet),
+            SETTINGS_PROXIMITY_PRECISION_UPDATE => Some(Self::SettingsProximityPrecisionUpdate),
+            SETTINGS_RANKING_RULES_GET => Some(Self::SettingsRankingRulesGet),
+            SETTINGS_RANKING_RULES_UPDATE => Some(Self::SettingsRankingRulesUpdate),
+            SETTINGS_FACETING_GET => Some(Self::SettingsFacetingGet),
+            SETTINGS_FACETING_UPDATE => Some(Self::SettingsFacetingUpdate),
+            SETTINGS_PAGINATION_GET => Some(Self::SettingsPaginationGet),
+            SETTINGS_PAGINATION_UPDATE => Some(Self::SettingsPaginationUpdate),
+            SETTINGS_EMBEDDERS_GET => Some(Self::SettingsEmbeddersGet),
+            SETTINGS_EMBEDDERS_UPDATE => Some(Self::SettingsEmbeddersUpdate),
+            SETTINGS_SEARCH_CUTOFF_MS_GET => Some(Self::SettingsSearchCutoffMsGet),
+            SETTINGS_SEARCH_CUTOFF_MS_UPDATE => Some(Self::SettingsSearchCutoffMsUpdate),
+            AUDIT_LOG_GET => Some(Self::AuditLogGet),
+            EXPERIMENTAL_VECTOR_STORE_UPDATE => Some(Self::ExperimentalVectorStoreUpdate),
+            EXPERIMENTAL_METRICS_UPDATE => Some(Self::ExperimentalMetricsUpdate),
+            EXPERIMENTAL_LOGS_ROUTE_UPDATE => Some(Self::ExperimentalLogsRouteUpdate),
+            EXPERIMENTAL_EXPORT_PUFFIN_REPORTS_UPDATE => {
+                Some(Self::ExperimentalExportPuffinReportsUpdate)
+            }
             _otherwise => None,
         }
     }
@@ -330,6 +717,73 @@ impl Action {
     pub const fn repr(&self) -> u8 {
         *self as u8
     }
+
+    /// Returns the broader action also granting this one, for the actions restricted to a single setting
+    /// or a single experimental feature.
+    pub const fn parent(&self) -> Option<Self> {
+        use Action::*;
+        match self {
+            SettingsFilterableAttributesGet
+            | SettingsSortableAttributesGet
+            | SettingsDisplayedAttributesGet
+            | SettingsTypoToleranceGet
+            | SettingsSearchableAttributesGet
+            | SettingsStopWordsGet
+            | SettingsNonSeparatorTokensGet
+            | SettingsSeparatorTokensGet
+            | SettingsDictionaryGet
+            | SettingsSynonymsGet
+            | SettingsDistinctAttributeGet
+            | SettingsProximityPrecisionGet
+            | SettingsRankingRulesGet
+            | SettingsFacetingGet
+            | SettingsPaginationGet
+            | SettingsEmbeddersGet
+            | SettingsSearchCutoffMsGet => Some(SettingsGet),
+            SettingsFilterableAttributesUpdate
+            | SettingsSortableAttributesUpdate
+            | SettingsDisplayedAttributesUpdate
+            | SettingsTypoToleranceUpdate
+            | SettingsSearchableAttributesUpdate
+            | SettingsStopWordsUpdate
+            | SettingsNonSeparatorTokensUpdate
+            | SettingsSeparatorTokensUpdate
+            | SettingsDictionaryUpdate
+            | SettingsSynonymsUpdate
+            | SettingsDistinctAttributeUpdate
+            | SettingsProximityPrecisionUpdate
+            | SettingsRankingRulesUpdate
+            | SettingsFacetingUpdate
+            | SettingsPaginationUpdate
+            | SettingsEmbeddersUpdate
+            | SettingsSearchCutoffMsUpdate => Some(SettingsUpdate),
+            ExperimentalVectorStoreUpdate
+            | ExperimentalMetricsUpdate
+            | ExperimentalLogsRouteUpdate
+            | ExperimentalExportPuffinReportsUpdate => Some(ExperimentalFeaturesUpdate),
+            _otherwise => None,
+        }
+    }
+
+    /// Returns `true` if the action changes the configuration of the instance rather than its documents,
+    /// such requests are recorded in the audit log.
+    pub const fn is_administrative(&self) -> bool {
+        use Action::*;
+        matches!(
+            self,
+            SettingsUpdate
+                | IndexesAdd
+                | IndexesUpdate
+                | IndexesDelete
+                | IndexesSwap
+                | KeysAdd
+                | KeysUpdate
+                | KeysDelete
+                | DumpsCreate
+                | DumpsImport
+                | ExperimentalFeaturesUpdate
+        ) || matches!(self.parent(), Some(SettingsUpdate | ExperimentalFeaturesUpdate))
+    }
 }
 
 pub mod actions {
@@ -368,4 +822,45 @@ pub mod actions {
     pub const KEYS_DELETE: u8 = KeysDelete.repr();
     pub const EXPERIMENTAL_FEATURES_GET: u8 = ExperimentalFeaturesGet.repr();
     pub const EXPERIMENTAL_FEATURES_UPDATE: u8 = ExperimentalFeaturesUpdate.repr();
+    pub const DUMPS_IMPORT: u8 = DumpsImport.repr();
+    pub const SETTINGS_FILTERABLE_ATTRIBUTES_GET: u8 = SettingsFilterableAttributesGet.repr();
+    pub const SETTINGS_FILTERABLE_ATTRIBUTES_UPDATE: u8 = SettingsFilterableAttributesUpdate.repr();
+    pub const SETTINGS_SORTABLE_ATTRIBUTES_GET: u8 = SettingsSortableAttributesGet.repr();
+    pub const SETTINGS_SORTABLE_ATTRIBUTES_UPDATE: u8 = SettingsSortableAttributesUpdate.repr();
+    pub const SETTINGS_DISPLAYED_ATTRIBUTES_GET: u8 = SettingsDisplayedAttributesGet.repr();
+    pub const SETTINGS_DISPLAYED_ATTRIBUTES_UPDATE: u8 = SettingsDisplayedAttributesUpdate.repr();
+    pub const SETTINGS_TYPO_TOLERANCE_GET: u8 = SettingsTypoToleranceGet.repr();
+    pub const SETTINGS_TYPO_TOLERANCE_UPDATE: u8 = SettingsTypoToleranceUpdate.repr();
+    pub const SETTINGS_SEARCHABLE_ATTRIBUTES_GET: u8 = SettingsSearchableAttributesGet.repr();
+    pub const SETTINGS_SEARCHABLE_ATTRIBUTES_UPDATE: u8 = SettingsSearchableAttributesUpdate.repr();
+    pub const SETTINGS_STOP_WORDS_GET: u8 = SettingsStopWordsGet.repr();
+    pub const SETTINGS_STOP_WORDS_UPDATE: u8 = SettingsStopWordsUpdate.repr();
+    pub const SETTINGS_NON_SEPARATOR_TOKENS_GET: u8 = SettingsNonSeparatorTokensGet.repr();
+    pub const SETTINGS_NON_SEPARATOR_TOKENS_UPDATE: u8 = SettingsNonSeparatorTokensUpdate.repr();
+    pub const SETTINGS_SEPARATOR_TOKENS_GET: u8 = SettingsSeparatorTokensGet.repr();
+    pub const SETTINGS_SEPARATOR_TOKENS_UPDATE: u8 = SettingsSeparatorTokensUpdate.repr();
+    pub const SETTINGS_DICTIONARY_GET: u8 = SettingsDictionaryGet.repr();
+    pub const SETTINGS_DICTIONARY_UPDATE: u8 = SettingsDictionaryUpdate.repr();
+    pub const SETTINGS_SYNONYMS_GET: u8 = SettingsSynonymsGet.repr();
+    pub const SETTINGS_SYNONYMS_UPDATE: u8 = SettingsSynonymsUpdate.repr();
+    pub const SETTINGS_DISTINCT_ATTRIBUTE_GET: u8 = SettingsDistinctAttributeGet.repr();
+    pub const SETTINGS_DISTINCT_ATTRIBUTE_UPDATE: u8 = SettingsDistinctAttributeUpdate.repr();
+    pub const SETTINGS_PROXIMITY_PRECISION_GET: u8 = SettingsProximityPrecisionGet.repr();
+    pub const SETTINGS_PROXIMITY_PRECISION_UPDATE: u8 = SettingsProximityPrecisionUpdate.repr();
+    pub const SETTINGS_RANKING_RULES_GET: u8 = SettingsRankingRulesGet.repr();
+    pub const SETTINGS_RANKING_RULES_UPDATE: u8 = SettingsRankingRulesUpdate.repr();
+    pub const SETTINGS_FACETING_GET: u8 = SettingsFacetingGet.repr();
+    pub const SETTINGS_FACETING_UPDATE: u8 = SettingsFacetingUpdate.repr();
+    pub const SETTINGS_PAGINATION_GET: u8 = SettingsPaginationGet.repr();
+    pub const SETTINGS_PAGINATION_UPDATE: u8 = SettingsPaginationUpdate.repr();
+    pub const SETTINGS_EMBEDDERS_GET: u8 = SettingsEmbeddersGet.repr();
+    pub const SETTINGS_EMBEDDERS_UPDATE: u8 = SettingsEmbeddersUpdate.repr();
+    pub const SETTINGS_SEARCH_CUTOFF_MS_GET: u8 = SettingsSearchCutoffMsGet.repr();
+    pub const SETTINGS_SEARCH_CUTOFF_MS_UPDATE: u8 = SettingsSearchCutoffMsUpdate.repr();
+    pub const AUDIT_LOG_GET: u8 = AuditLogGet.repr();
+    pub const EXPERIMENTAL_VECTOR_STORE_UPDATE: u8 = ExperimentalVectorStoreUpdate.repr();
+    pub const EXPERIMENTAL_METRICS_UPDATE: u8 = ExperimentalMetricsUpdate.repr();
+    pub const EXPERIMENTAL_LOGS_ROUTE_UPDATE: u8 = ExperimentalLogsRouteUpdate.repr();
+    pub const EXPERIMENTAL_EXPORT_PUFFIN_REPORTS_UPDATE: u8 =
+        ExperimentalExportPuffinReportsUpdate.repr();
 }
@@ -97,9 +97,23 @@ pub struct TypoSettings {
     pub disable_on_attributes: Setting<BTreeSet<String>>,
 }
 
+fn validate_faceting_settings<E: DeserializeError>(
+    s: FacetingSettings,
+    location: ValuePointerRef,
+) -> Result<FacetingSettings, E> {
+    if let Setting::Set(hierarchical_facets) = &s.hierarchical_facets {
+        if let Some((field, _)) =
+            hierarchical_facets.iter().find(|(_, separator)| separator.trim().is_empty())
+        {
+            return Err(deserr::take_cf_content(E::error::<Infallible>(None, ErrorKind::Unexpected { msg: format!("`hierarchicalFacets` setting is invalid. The separator of `{field}` must contain at least one non-whitespace character.") }, location)));
+        }
+    }
+    Ok(s)
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
-#[deserr(rename_all = camelCase, deny_unknown_fields)]
+#[deserr(rename_all = camelCase, deny_unknown_fields, validate = validate_faceting_settings -> DeserrJsonError<InvalidSettingsFaceting>)]
 pub struct FacetingSettings {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
@@ -107,6 +121,11 @@ pub struct FacetingSettings {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
     pub sort_facet_values_by: Setting<BTreeMap<String, FacetValuesSort>>,
+    /// The fields whose values are paths, associated with the separator of their segments.
+    /// Only the fields that are also in the `filterableAttributes` are split, the others are ignored.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub hierarchical_facets: Setting<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
@@ -540,7 +559,11 @@ pub fn apply_settings_to_builder(
     }
 
     match faceting {
-        Setting::Set(FacetingSettings { max_values_per_facet, sort_facet_values_by }) => {
+        Setting::Set(FacetingSettings {
+            max_values_per_facet,
+            sort_facet_values_by,
+            hierarchical_facets,
+        }) => {
             match max_values_per_facet {
                 Setting::Set(val) => builder.set_max_values_per_facet(*val),
                 Setting::Reset => builder.reset_max_values_per_facet(),
@@ -553,10 +576,16 @@ pub fn apply_settings_to_builder(
                 Setting::Reset => builder.reset_sort_facet_values_by(),
                 Setting::NotSet => (),
             }
+            match hierarchical_facets {
+                Setting::Set(val) => builder.set_hierarchical_facets(val.clone()),
+                Setting::Reset => builder.reset_hierarchical_facets(),
+                Setting::NotSet => (),
+            }
         }
         Setting::Reset => {
             builder.reset_max_values_per_facet();
             builder.reset_sort_facet_values_by();
+            builder.reset_hierarchical_facets();
         }
         Setting::NotSet => (),
     }
@@ -658,6 +687,7 @@ pub fn settings(
                 .map(|(name, sort)| (name, sort.into()))
                 .collect(),
         ),
+        hierarchical_facets: Setting::Set(index.hierarchical_facets(rtxn)?),
     };
 
     let pagination = PaginationSettings {
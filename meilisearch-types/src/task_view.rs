@@ -1,3 +1,4 @@
+use milli::vector::EmbeddingCacheStats;
 use serde::Serialize;
 use time::{Duration, OffsetDateTime};
 
@@ -70,25 +71,32 @@ pub struct DetailsView {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dump_uid: Option<Option<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub imported_indexes: Option<Option<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(flatten)]
     pub settings: Option<Box<Settings<Unchecked>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub swaps: Option<Vec<IndexSwap>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_cache: Option<EmbeddingCacheStats>,
 }
 
 impl From<Details> for DetailsView {
     fn from(details: Details) -> Self {
         match details {
-            Details::DocumentAdditionOrUpdate { received_documents, indexed_documents } => {
-                DetailsView {
-                    received_documents: Some(received_documents),
-                    indexed_documents: Some(indexed_documents),
-                    ..DetailsView::default()
-                }
-            }
-            Details::SettingsUpdate { mut settings } => {
+            Details::DocumentAdditionOrUpdate {
+                received_documents,
+                indexed_documents,
+                embedding_cache,
+            } => DetailsView {
+                received_documents: Some(received_documents),
+                indexed_documents: Some(indexed_documents),
+                embedding_cache,
+                ..DetailsView::default()
+            },
+            Details::SettingsUpdate { mut settings, embedding_cache } => {
                 settings.hide_secrets();
-                DetailsView { settings: Some(settings), ..DetailsView::default() }
+                DetailsView { settings: Some(settings), embedding_cache, ..DetailsView::default() }
             }
             Details::IndexInfo { primary_key } => {
                 DetailsView { primary_key: Some(primary_key), ..DetailsView::default() }
@@ -135,6 +143,11 @@ impl From<Details> for DetailsView {
             Details::IndexSwap { swaps } => {
                 DetailsView { swaps: Some(swaps), ..Default::default() }
             }
+            Details::DumpImport { dump_uid, imported_indexes } => DetailsView {
+                dump_uid: Some(Some(dump_uid)),
+                imported_indexes: Some(imported_indexes),
+                ..DetailsView::default()
+            },
         }
     }
 }
@@ -5,6 +5,7 @@ use std::str::FromStr;
 
 use enum_iterator::Sequence;
 use milli::update::IndexDocumentsMethod;
+use milli::vector::EmbeddingCacheStats;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize, Serializer};
 use time::{Duration, OffsetDateTime};
@@ -43,6 +44,7 @@ impl Task {
 
         match &self.kind {
             DumpCreation { .. }
+            | DumpImport { .. }
             | SnapshotCreation
             | TaskCancelation { .. }
             | TaskDeletion { .. }
@@ -78,7 +80,8 @@ impl Task {
             | KindWithContent::TaskCancelation { .. }
             | KindWithContent::TaskDeletion { .. }
             | KindWithContent::DumpCreation { .. }
-            | KindWithContent::SnapshotCreation => None,
+            | KindWithContent::SnapshotCreation
+            | KindWithContent::DumpImport { .. } => None,
         }
     }
 }
@@ -138,6 +141,10 @@ pub enum KindWithContent {
         instance_uid: Option<InstanceUid>,
     },
     SnapshotCreation,
+    DumpImport {
+        dump_uid: String,
+        import_keys: bool,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -162,6 +169,7 @@ impl KindWithContent {
             KindWithContent::TaskDeletion { .. } => Kind::TaskDeletion,
             KindWithContent::DumpCreation { .. } => Kind::DumpCreation,
             KindWithContent::SnapshotCreation => Kind::SnapshotCreation,
+            KindWithContent::DumpImport { .. } => Kind::DumpImport,
         }
     }
 
@@ -170,6 +178,7 @@ impl KindWithContent {
 
         match self {
             DumpCreation { .. }
+            | DumpImport { .. }
             | SnapshotCreation
             | TaskCancelation { .. }
             | TaskDeletion { .. } => vec![],
@@ -200,6 +209,7 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    embedding_cache: None,
                 })
             }
             KindWithContent::DocumentDeletion { index_uid: _, documents_ids } => {
@@ -217,9 +227,10 @@ impl KindWithContent {
             KindWithContent::DocumentClear { .. } | KindWithContent::IndexDeletion { .. } => {
                 Some(Details::ClearAll { deleted_documents: None })
             }
-            KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
-            }
+            KindWithContent::SettingsUpdate { new_settings, .. } => Some(Details::SettingsUpdate {
+                settings: new_settings.clone(),
+                embedding_cache: None,
+            }),
             KindWithContent::IndexCreation { primary_key, .. }
             | KindWithContent::IndexUpdate { primary_key, .. } => {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
@@ -239,6 +250,9 @@ impl KindWithContent {
             }),
             KindWithContent::DumpCreation { .. } => Some(Details::Dump { dump_uid: None }),
             KindWithContent::SnapshotCreation => None,
+            KindWithContent::DumpImport { dump_uid, .. } => {
+                Some(Details::DumpImport { dump_uid: dump_uid.clone(), imported_indexes: None })
+            }
         }
     }
 
@@ -248,6 +262,7 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: Some(0),
+                    embedding_cache: None,
                 })
             }
             KindWithContent::DocumentDeletion { index_uid: _, documents_ids } => {
@@ -265,9 +280,10 @@ impl KindWithContent {
             KindWithContent::DocumentClear { .. } => {
                 Some(Details::ClearAll { deleted_documents: None })
             }
-            KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
-            }
+            KindWithContent::SettingsUpdate { new_settings, .. } => Some(Details::SettingsUpdate {
+                settings: new_settings.clone(),
+                embedding_cache: None,
+            }),
             KindWithContent::IndexDeletion { .. } => None,
             KindWithContent::IndexCreation { primary_key, .. }
             | KindWithContent::IndexUpdate { primary_key, .. } => {
@@ -288,6 +304,9 @@ impl KindWithContent {
             }),
             KindWithContent::DumpCreation { .. } => Some(Details::Dump { dump_uid: None }),
             KindWithContent::SnapshotCreation => None,
+            KindWithContent::DumpImport { dump_uid, .. } => {
+                Some(Details::DumpImport { dump_uid: dump_uid.clone(), imported_indexes: Some(0) })
+            }
         }
     }
 }
@@ -299,14 +318,16 @@ impl From<&KindWithContent> for Option<Details> {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    embedding_cache: None,
                 })
             }
             KindWithContent::DocumentDeletion { .. } => None,
             KindWithContent::DocumentDeletionByFilter { .. } => None,
             KindWithContent::DocumentClear { .. } => None,
-            KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
-            }
+            KindWithContent::SettingsUpdate { new_settings, .. } => Some(Details::SettingsUpdate {
+                settings: new_settings.clone(),
+                embedding_cache: None,
+            }),
             KindWithContent::IndexDeletion { .. } => None,
             KindWithContent::IndexCreation { primary_key, .. } => {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
@@ -327,6 +348,9 @@ impl From<&KindWithContent> for Option<Details> {
             }),
             KindWithContent::DumpCreation { .. } => Some(Details::Dump { dump_uid: None }),
             KindWithContent::SnapshotCreation => None,
+            KindWithContent::DumpImport { dump_uid, .. } => {
+                Some(Details::DumpImport { dump_uid: dump_uid.clone(), imported_indexes: None })
+            }
         }
     }
 }
@@ -404,6 +428,7 @@ pub enum Kind {
     TaskDeletion,
     DumpCreation,
     SnapshotCreation,
+    DumpImport,
 }
 
 impl Kind {
@@ -419,7 +444,8 @@ impl Kind {
             | Kind::TaskCancelation
             | Kind::TaskDeletion
             | Kind::DumpCreation
-            | Kind::SnapshotCreation => false,
+            | Kind::SnapshotCreation
+            | Kind::DumpImport => false,
         }
     }
 }
@@ -437,6 +463,7 @@ impl Display for Kind {
             Kind::TaskDeletion => write!(f, "taskDeletion"),
             Kind::DumpCreation => write!(f, "dumpCreation"),
             Kind::SnapshotCreation => write!(f, "snapshotCreation"),
+            Kind::DumpImport => write!(f, "dumpImport"),
         }
     }
 }
@@ -466,6 +493,8 @@ impl FromStr for Kind {
             Ok(Kind::DumpCreation)
         } else if kind.eq_ignore_ascii_case("snapshotCreation") {
             Ok(Kind::SnapshotCreation)
+        } else if kind.eq_ignore_ascii_case("dumpImport") {
+            Ok(Kind::DumpImport)
         } else {
             Err(ParseTaskKindError(kind.to_owned()))
         }
@@ -494,9 +523,19 @@ impl fmt::Display for ParseTaskKindError {
 impl std::error::Error for ParseTaskKindError {}
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[rustfmt::skip]
 pub enum Details {
-    DocumentAdditionOrUpdate { received_documents: u64, indexed_documents: Option<u64> },
-    SettingsUpdate { settings: Box<Settings<Unchecked>> },
+    DocumentAdditionOrUpdate {
+        received_documents: u64,
+        indexed_documents: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        embedding_cache: Option<EmbeddingCacheStats>,
+    },
+    SettingsUpdate {
+        settings: Box<Settings<Unchecked>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        embedding_cache: Option<EmbeddingCacheStats>,
+    },
     IndexInfo { primary_key: Option<String> },
     DocumentDeletion { provided_ids: usize, deleted_documents: Option<u64> },
     DocumentDeletionByFilter { original_filter: String, deleted_documents: Option<u64> },
@@ -505,6 +544,7 @@ pub enum Details {
     TaskDeletion { matched_tasks: u64, deleted_tasks: Option<u64>, original_filter: String },
     Dump { dump_uid: Option<String> },
     IndexSwap { swaps: Vec<IndexSwap> },
+    DumpImport { dump_uid: String, imported_indexes: Option<u64> },
 }
 
 impl Details {
@@ -521,6 +561,7 @@ impl Details {
             Self::ClearAll { deleted_documents } => *deleted_documents = Some(0),
             Self::TaskCancelation { canceled_tasks, .. } => *canceled_tasks = Some(0),
             Self::TaskDeletion { deleted_tasks, .. } => *deleted_tasks = Some(0),
+            Self::DumpImport { imported_indexes, .. } => *imported_indexes = Some(0),
             Self::SettingsUpdate { .. }
             | Self::IndexInfo { .. }
             | Self::Dump { .. }
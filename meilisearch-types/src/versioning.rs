@@ -17,29 +17,34 @@ pub fn create_version_file(db_path: &Path) -> io::Result<()> {
 
 /// Ensures Meilisearch version is compatible with the database, returns an error versions mismatch.
 pub fn check_version_file(db_path: &Path) -> anyhow::Result<()> {
+    let (major, minor, patch) = get_version(db_path)?;
+
+    if major != VERSION_MAJOR || minor != VERSION_MINOR {
+        return Err(VersionFileError::VersionMismatch { major, minor, patch }.into());
+    }
+
+    Ok(())
+}
+
+/// Returns the major, minor and patch versions of the database, read from its VERSION file.
+pub fn get_version(db_path: &Path) -> anyhow::Result<(String, String, String)> {
     let version_path = db_path.join(VERSION_FILE_NAME);
 
     match fs::read_to_string(version_path) {
         Ok(version) => {
             let version_components = version.split('.').collect::<Vec<_>>();
-            let (major, minor, patch) = match &version_components[..] {
-                [major, minor, patch] => (major.to_string(), minor.to_string(), patch.to_string()),
-                _ => return Err(VersionFileError::MalformedVersionFile.into()),
-            };
-
-            if major != VERSION_MAJOR || minor != VERSION_MINOR {
-                return Err(VersionFileError::VersionMismatch { major, minor, patch }.into());
-            }
-        }
-        Err(error) => {
-            return match error.kind() {
-                ErrorKind::NotFound => Err(VersionFileError::MissingVersionFile.into()),
-                _ => Err(error.into()),
+            match &version_components[..] {
+                [major, minor, patch] => {
+                    Ok((major.to_string(), minor.to_string(), patch.to_string()))
+                }
+                _ => Err(VersionFileError::MalformedVersionFile.into()),
             }
         }
+        Err(error) => match error.kind() {
+            ErrorKind::NotFound => Err(VersionFileError::MissingVersionFile.into()),
+            _ => Err(error.into()),
+        },
     }
-
-    Ok(())
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -1,6 +1,7 @@
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fs;
 use std::mem::take;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -8,6 +9,7 @@ use std::time::{Duration, Instant};
 use actix_web::http::header::USER_AGENT;
 use actix_web::HttpRequest;
 use byte_unit::Byte;
+use dump::DumpCompression;
 use http::header::CONTENT_TYPE;
 use index_scheduler::IndexScheduler;
 use meilisearch_auth::{AuthController, AuthFilter};
@@ -35,7 +37,7 @@ use crate::routes::indexes::documents::UpdateDocumentsQuery;
 use crate::routes::indexes::facet_search::FacetSearchQuery;
 use crate::routes::{create_all_stats, Stats};
 use crate::search::{
-    FacetSearchResult, MatchingStrategy, SearchQuery, SearchQueryWithIndex, SearchResult,
+    FacetSearchResult, Fusion, MatchingStrategy, SearchQuery, SearchQueryWithIndex, SearchResult,
     DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
     DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT, DEFAULT_SEMANTIC_RATIO,
 };
@@ -238,6 +240,8 @@ struct Infos {
     env: String,
     experimental_enable_metrics: bool,
     experimental_search_queue_size: usize,
+    experimental_query_embedding_cache_size: usize,
+    experimental_embedding_cache_size: usize,
     experimental_logs_mode: LogMode,
     experimental_replication_parameters: bool,
     experimental_enable_logs_route: bool,
@@ -247,6 +251,9 @@ struct Infos {
     db_path: bool,
     import_dump: bool,
     dump_dir: bool,
+    dump_compression: DumpCompression,
+    schedule_dump: Option<u64>,
+    dump_retention: Option<usize>,
     ignore_missing_dump: bool,
     ignore_dump_if_db_exists: bool,
     import_snapshot: bool,
@@ -280,6 +287,7 @@ impl From<Opt> for Infos {
             db_path,
             experimental_enable_metrics,
             experimental_search_queue_size,
+            experimental_query_embedding_cache_size,
             experimental_logs_mode,
             experimental_replication_parameters,
             experimental_enable_logs_route,
@@ -287,6 +295,8 @@ impl From<Opt> for Infos {
             experimental_max_number_of_batched_tasks,
             http_addr,
             master_key: _,
+            previous_master_keys: _,
+            previous_master_keys_expire_at: _,
             env,
             task_webhook_url,
             task_webhook_authorization_header,
@@ -309,6 +319,9 @@ impl From<Opt> for Infos {
             ignore_missing_dump,
             ignore_dump_if_db_exists,
             dump_dir,
+            dump_compression,
+            schedule_dump,
+            dump_retention,
             log_level,
             indexer_options,
             config_file_path,
@@ -321,8 +334,12 @@ impl From<Opt> for Infos {
             ScheduleSnapshot::Enabled(interval) => Some(interval),
         };
 
-        let IndexerOpts { max_indexing_memory, max_indexing_threads, skip_index_budget: _ } =
-            indexer_options;
+        let IndexerOpts {
+            max_indexing_memory,
+            max_indexing_threads,
+            experimental_embedding_cache_size,
+            skip_index_budget: _,
+        } = indexer_options;
 
         // We're going to override every sensible information.
         // We consider information sensible if it contains a path, an address, or a key.
@@ -330,6 +347,8 @@ impl From<Opt> for Infos {
             env,
             experimental_enable_metrics,
             experimental_search_queue_size,
+            experimental_query_embedding_cache_size,
+            experimental_embedding_cache_size,
             experimental_logs_mode,
             experimental_replication_parameters,
             experimental_enable_logs_route,
@@ -338,6 +357,9 @@ impl From<Opt> for Infos {
             db_path: db_path != PathBuf::from("./data.ms"),
             import_dump: import_dump.is_some(),
             dump_dir: dump_dir != PathBuf::from("dumps/"),
+            dump_compression,
+            schedule_dump: schedule_dump.map(NonZeroU64::get),
+            dump_retention: dump_retention.map(NonZeroUsize::get),
             ignore_missing_dump,
             ignore_dump_if_db_exists,
             import_snapshot: import_snapshot.is_some(),
@@ -586,11 +608,15 @@ pub struct SearchAggregator {
     // vector
     // The maximum number of floats in a vector request
     max_vector_size: usize,
+    // Whether an image was embedded as the query vector
+    image: bool,
     // Whether the semantic ratio passed to a hybrid search equals the default ratio.
     semantic_ratio: bool,
     // Whether a non-default embedder was specified
     embedder: bool,
     hybrid: bool,
+    // Whether the results of a hybrid search were fused with reciprocal rank fusion
+    reciprocal_rank_fusion: bool,
 
     // every time a search is done, we increment the counter linked to the used settings
     matching_strategy: HashMap<String, usize>,
@@ -625,6 +651,7 @@ impl SearchAggregator {
         let SearchQuery {
             q,
             vector,
+            image,
             offset,
             limit,
             page,
@@ -639,6 +666,9 @@ impl SearchAggregator {
             filter,
             sort,
             facets: _,
+            facet_ranges: _,
+            facet_histogram: _,
+            facet_hierarchy_depth: _,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -695,6 +725,7 @@ impl SearchAggregator {
         if let Some(ref vector) = vector {
             ret.max_vector_size = vector.len();
         }
+        ret.image = image.is_some();
 
         if query.is_finite_pagination() {
             let limit = hits_per_page.unwrap_or_else(DEFAULT_SEARCH_LIMIT);
@@ -722,6 +753,7 @@ impl SearchAggregator {
             ret.semantic_ratio = hybrid.semantic_ratio != DEFAULT_SEMANTIC_RATIO();
             ret.embedder = hybrid.embedder.is_some();
             ret.hybrid = true;
+            ret.reciprocal_rank_fusion = hybrid.fusion == Fusion::ReciprocalRank;
         }
 
         ret
@@ -736,6 +768,9 @@ impl SearchAggregator {
             semantic_hit_count: _,
             facet_distribution: _,
             facet_stats: _,
+            facet_ranges: _,
+            facet_histogram: _,
+            facet_hierarchy: _,
             degraded,
             used_negative_operator,
         } = result;
@@ -769,6 +804,7 @@ impl SearchAggregator {
             attributes_to_search_on_total_number_of_uses,
             max_terms_number,
             max_vector_size,
+            image,
             matching_strategy,
             max_limit,
             max_offset,
@@ -788,6 +824,7 @@ impl SearchAggregator {
             semantic_ratio,
             embedder,
             hybrid,
+            reciprocal_rank_fusion,
             total_degraded,
             total_used_negative_operator,
         } = other;
@@ -838,9 +875,11 @@ impl SearchAggregator {
 
         // vector
         self.max_vector_size = self.max_vector_size.max(max_vector_size);
+        self.image |= image;
         self.semantic_ratio |= semantic_ratio;
         self.hybrid |= hybrid;
         self.embedder |= embedder;
+        self.reciprocal_rank_fusion |= reciprocal_rank_fusion;
 
         // pagination
         self.max_limit = self.max_limit.max(max_limit);
@@ -893,6 +932,7 @@ impl SearchAggregator {
             attributes_to_search_on_total_number_of_uses,
             max_terms_number,
             max_vector_size,
+            image,
             matching_strategy,
             max_limit,
             max_offset,
@@ -912,6 +952,7 @@ impl SearchAggregator {
             semantic_ratio,
             embedder,
             hybrid,
+            reciprocal_rank_fusion,
             total_degraded,
             total_used_negative_operator,
         } = self;
@@ -954,11 +995,13 @@ impl SearchAggregator {
                 },
                 "vector": {
                     "max_vector_size": max_vector_size,
+                    "image": image,
                 },
                 "hybrid": {
                     "enabled": hybrid,
                     "semantic_ratio": semantic_ratio,
                     "embedder": embedder,
+                    "reciprocal_rank_fusion": reciprocal_rank_fusion,
                 },
                 "pagination": {
                    "max_limit": max_limit,
@@ -1036,6 +1079,7 @@ impl MultiSearchAggregator {
                     index_uid,
                     q: _,
                     vector: _,
+                    image: _,
                     offset: _,
                     limit: _,
                     page: _,
@@ -1050,6 +1094,9 @@ impl MultiSearchAggregator {
                     filter: _,
                     sort: _,
                     facets: _,
+                    facet_ranges: _,
+                    facet_histogram: _,
+                    facet_hierarchy_depth: _,
                     highlight_pre_tag: _,
                     highlight_post_tag: _,
                     crop_marker: _,
@@ -1197,6 +1244,7 @@ impl FacetSearchAggregator {
         let FacetSearchQuery {
             facet_query: _,
             facet_name,
+            ranges,
             vector,
             q,
             filter,
@@ -1217,7 +1265,8 @@ impl FacetSearchAggregator {
             || filter.is_some()
             || *matching_strategy != MatchingStrategy::default()
             || attributes_to_search_on.is_some()
-            || hybrid.is_some();
+            || hybrid.is_some()
+            || ranges.is_some();
 
         ret
     }
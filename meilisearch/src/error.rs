@@ -59,6 +59,18 @@ pub enum MeilisearchHttpError {
     Join(#[from] JoinError),
     #[error("Invalid request: missing `hybrid` parameter when both `q` and `vector` are present.")]
     MissingSearchHybrid,
+    #[error("Invalid request: `image` and `vector` cannot be passed together, the image is embedded as the query vector.")]
+    SearchImageWithVector,
+    #[error("Invalid request: the embedder `{0}` does not accept images. Only `rest` embedders with the `textAndImage` input type can embed the `image` search parameter.")]
+    EmbedderWithoutImages(String),
+    #[error(
+        "Invalid request: expected either a `documentId` or a `document` object, but not both."
+    )]
+    InvalidRenderDocument,
+    #[error("The search rules of the API key do not allow the `{attribute}` attribute in `{parameter}`.")]
+    ForbiddenSearchAttribute { parameter: &'static str, attribute: String },
+    #[error("The search rules of the API key restrict the retrievable attributes, `showMatchesPosition` cannot be used.")]
+    ForbiddenSearchMatchesPosition,
 }
 
 impl ErrorCode for MeilisearchHttpError {
@@ -86,6 +98,11 @@ impl ErrorCode for MeilisearchHttpError {
             MeilisearchHttpError::DocumentFormat(e) => e.error_code(),
             MeilisearchHttpError::Join(_) => Code::Internal,
             MeilisearchHttpError::MissingSearchHybrid => Code::MissingSearchHybrid,
+            MeilisearchHttpError::SearchImageWithVector
+            | MeilisearchHttpError::EmbedderWithoutImages(_) => Code::InvalidSearchImage,
+            MeilisearchHttpError::InvalidRenderDocument => Code::InvalidRenderDocument,
+            MeilisearchHttpError::ForbiddenSearchAttribute { .. }
+            | MeilisearchHttpError::ForbiddenSearchMatchesPosition => Code::InvalidApiKey,
         }
     }
 }
@@ -9,8 +9,25 @@ use actix_web::FromRequest;
 pub use error::AuthenticationError;
 use futures::future::err;
 use futures::Future;
+use meilisearch_auth::error::AuthControllerError;
 use meilisearch_auth::{AuthController, AuthFilter};
 use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::keys::Action;
+use uuid::Uuid;
+
+/// Inserted in the extensions of a request guarded by an action, authorized or not,
+/// to record it in the audit log.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestedAction {
+    pub action: Action,
+    /// The uid of the API key the request was made with, `None` for the master key
+    /// or for a rejected request made without a known API key.
+    pub key_uid: Option<Uuid>,
+}
+
+/// Inserted in the extensions of a request once it has been counted against the limits of its
+/// API key, for the routes extracting several `GuardedData`.
+struct RateLimitConsumed;
 
 pub struct GuardedData<P, D> {
     data: D,
@@ -23,6 +40,26 @@ impl<P, D> GuardedData<P, D> {
         &self.filters
     }
 
+    /// Checks that the API key of the request also allows another action, on an index or globally,
+    /// for the routes whose parameters require more than the action they are guarded by.
+    pub fn check_action(
+        &self,
+        auth: &AuthController,
+        action: Action,
+        index: Option<&str>,
+    ) -> Result<(), ResponseError> {
+        let authorized = match self.filters.key_uid() {
+            // the master key, or no master key at all
+            None => true,
+            Some(uid) => auth.is_key_authorized(uid, action, index).unwrap_or(false),
+        };
+        if authorized {
+            Ok(())
+        } else {
+            Err(AuthenticationError::InvalidToken.into())
+        }
+    }
+
     async fn auth_bearer(
         auth: Data<AuthController>,
         token: String,
@@ -89,8 +126,71 @@ impl<P: Policy + 'static, D: 'static + Clone> FromRequest for GuardedData<P, D>
 
     fn from_request(
         req: &actix_web::HttpRequest,
-        _payload: &mut actix_web::dev::Payload,
+        payload: &mut actix_web::dev::Payload,
     ) -> Self::Future {
+        let authentication = Self::authenticate_request(req, payload);
+        let req = req.clone();
+        Box::pin(async move {
+            let guarded = match authentication.await {
+                Ok(guarded) => guarded,
+                Err(error) => {
+                    if let Some(action) = P::ACTION {
+                        let key_uid = bearer_key_uid(&req);
+                        req.extensions_mut().insert(RequestedAction { action, key_uid });
+                    }
+                    return Err(error);
+                }
+            };
+            if let Some(action) = P::ACTION {
+                let key_uid = guarded.filters.key_uid();
+                req.extensions_mut().insert(RequestedAction { action, key_uid });
+            }
+            if let Some(key_uid) = guarded.filters.key_uid() {
+                if req.extensions_mut().insert(RateLimitConsumed).is_none() {
+                    if let Some(auth) = req.app_data::<Data<AuthController>>() {
+                        consume_rate_limit(auth, key_uid)?;
+                    }
+                }
+            }
+            Ok(guarded)
+        })
+    }
+}
+
+/// Returns the uid of the API key used as the bearer token of a request, if it is one.
+fn bearer_key_uid(req: &actix_web::HttpRequest) -> Option<Uuid> {
+    let auth = req.app_data::<Data<AuthController>>()?;
+    let token = req.headers().get("Authorization")?.to_str().ok()?.strip_prefix("Bearer ")?;
+    auth.get_optional_uid_from_encoded_key(token.as_bytes()).ok()?
+}
+
+/// Counts the request against the limits of its API key, the keys without limits are not counted.
+fn consume_rate_limit(auth: &AuthController, key_uid: Uuid) -> Result<(), ResponseError> {
+    let result = match auth.consume_rate_limit(key_uid) {
+        Ok(false) => return Ok(()),
+        Ok(true) => Ok(()),
+        Err(error) => match error.retry_after() {
+            Some(retry_after) => Err((error, retry_after)),
+            None => return Err(error.into()),
+        },
+    };
+    let label = match &result {
+        Ok(()) => "accepted",
+        Err((AuthControllerError::RateLimitExceeded { .. }, _)) => "rate_limited",
+        Err(_) => "quota_exceeded",
+    };
+    crate::metrics::MEILISEARCH_API_KEY_REQUESTS_TOTAL
+        .with_label_values(&[&key_uid.to_string(), label])
+        .inc();
+
+    result.map_err(|(error, retry_after)| ResponseError::from(error).with_retry_after(retry_after))
+}
+
+impl<P: Policy + 'static, D: 'static + Clone> GuardedData<P, D> {
+    fn authenticate_request(
+        req: &actix_web::HttpRequest,
+        _payload: &mut actix_web::dev::Payload,
+    ) -> <Self as FromRequest>::Future {
         match req.app_data::<Data<AuthController>>().cloned() {
             Some(auth) => match req
                 .headers()
@@ -123,6 +223,9 @@ impl<P: Policy + 'static, D: 'static + Clone> FromRequest for GuardedData<P, D>
 }
 
 pub trait Policy {
+    /// The action the requests are authenticated for.
+    const ACTION: Option<Action> = None;
+
     fn authenticate(
         auth: Data<AuthController>,
         token: &str,
@@ -132,7 +235,7 @@ pub trait Policy {
 
 pub mod policies {
     use actix_web::web::Data;
-    use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+    use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
     use meilisearch_auth::{AuthController, AuthFilter, SearchRules};
     // reexport actions in policies in order to be used in routes configuration.
     pub use meilisearch_types::keys::{actions, Action};
@@ -146,7 +249,9 @@ pub mod policies {
         NotATenantToken,
         Invalid,
         Expired,
-        Valid(Uuid, SearchRules),
+        /// The key uid, the search rules and the generation of the master key the key was derived from,
+        /// if the token was signed with the key rather than with a public key of the key.
+        Valid(Uuid, SearchRules, Option<usize>),
     }
 
     fn tenant_token_validation() -> Validation {
@@ -162,13 +267,78 @@ pub mod policies {
         let mut validation = tenant_token_validation();
         validation.insecure_disable_signature_validation();
         let dummy_key = DecodingKey::from_secret(b"secret");
-        let token_data = decode::<Claims>(token, &dummy_key, &validation).ok()?;
+        let token_data = decode::<KeyIdClaim>(token, &dummy_key, &validation).ok()?;
 
         // get token fields without validating it.
-        let Claims { api_key_uid, .. } = token_data.claims;
+        let KeyIdClaim { api_key_uid } = token_data.claims;
         Some(api_key_uid)
     }
 
+    /// Decodes a tenant token with the keys derived from every accepted master key, or else with
+    /// the public keys registered on the key. Returns its claims with the generation of the master
+    /// key that was used, if any.
+    fn decode_tenant_token(
+        auth: &AuthController,
+        uid: Uuid,
+        token: &str,
+    ) -> Option<(Claims, Option<usize>)> {
+        auth.generate_keys(uid)
+            .into_iter()
+            .find_map(|(generation, key)| {
+                let key = DecodingKey::from_secret(key.as_bytes());
+                let data = decode::<Claims>(token, &key, &tenant_token_validation()).ok()?;
+                Some((data.claims, Some(generation)))
+            })
+            .or_else(|| Some((decode_public_key_tenant_token(auth, uid, token)?, None)))
+    }
+
+    /// Decodes a tenant token signed by an identity provider with one of the public keys of the key,
+    /// its search rules are read from the search rules claim of the key.
+    fn decode_public_key_tenant_token(
+        auth: &AuthController,
+        uid: Uuid,
+        token: &str,
+    ) -> Option<Claims> {
+        let header = decode_header(token).ok()?;
+        let tenant_token_keys = auth.tenant_token_keys(uid).ok()??;
+
+        let mut validation = tenant_token_validation();
+        validation.algorithms = vec![header.alg];
+        if let Some(issuer) = &tenant_token_keys.keys.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &tenant_token_keys.keys.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            // the tokens of an identity provider are often issued for an audience we don't know about.
+            None => validation.validate_aud = false,
+        }
+
+        let mut claims = tenant_token_keys
+            .public_keys
+            .iter()
+            .filter(|public_key| public_key.algorithms.contains(&header.alg))
+            .filter(|public_key| match (&header.kid, &public_key.key_id) {
+                (Some(kid), Some(key_id)) => kid == key_id,
+                _ => true,
+            })
+            .find_map(|public_key| {
+                decode::<serde_json::Map<String, serde_json::Value>>(
+                    token,
+                    &public_key.key,
+                    &validation,
+                )
+                .ok()
+            })?
+            .claims;
+
+        let search_rules = claims.remove(tenant_token_keys.keys.search_rules_claim())?;
+        Some(Claims {
+            search_rules: serde_json::from_value(search_rules).ok()?,
+            exp: claims.get("exp").and_then(serde_json::Value::as_i64),
+            api_key_uid: uid,
+        })
+    }
+
     fn is_keys_action(action: u8) -> bool {
         use actions::*;
         matches!(action, KEYS_GET | KEYS_CREATE | KEYS_UPDATE | KEYS_DELETE)
@@ -177,6 +347,8 @@ pub mod policies {
     pub struct ActionPolicy<const A: u8>;
 
     impl<const A: u8> Policy for ActionPolicy<A> {
+        const ACTION: Option<Action> = Action::from_repr(A);
+
         /// Attempts to grant authentication from a bearer token (that can be a tenant token or an API key), the requested Action,
         /// and a list of requested indexes.
         ///
@@ -194,15 +366,18 @@ pub mod policies {
                 return Some(AuthFilter::default());
             }
 
-            let (key_uuid, search_rules) =
+            let (key_uuid, search_rules, generation) =
                 match ActionPolicy::<A>::authenticate_tenant_token(&auth, token) {
-                    TenantTokenOutcome::Valid(key_uuid, search_rules) => {
-                        (key_uuid, Some(search_rules))
+                    TenantTokenOutcome::Valid(key_uuid, search_rules, generation) => {
+                        (key_uuid, Some(search_rules), generation)
                     }
                     TenantTokenOutcome::Expired => return None,
                     TenantTokenOutcome::Invalid => return None,
                     TenantTokenOutcome::NotATenantToken => {
-                        (auth.get_optional_uid_from_encoded_key(token.as_bytes()).ok()??, None)
+                        let (key_uuid, generation) = auth
+                            .get_uid_and_generation_from_encoded_key(token.as_bytes())
+                            .ok()??;
+                        (key_uuid, None, Some(generation))
                     }
                 };
 
@@ -212,6 +387,7 @@ pub mod policies {
             if auth.is_key_authorized(key_uuid, action, index).unwrap_or(false)
                 && index.map(|index| auth_filter.is_index_authorized(index)).unwrap_or(true)
             {
+                auth.record_key_usage(key_uuid, action, generation).ok()?;
                 return Some(auth_filter);
             }
 
@@ -219,6 +395,50 @@ pub mod policies {
         }
     }
 
+    /// Grants the keys allowed to update at least one experimental feature, the route then checks
+    /// that the key is allowed to update each feature of its payload.
+    pub struct ExperimentalFeaturesUpdatePolicy;
+
+    impl Policy for ExperimentalFeaturesUpdatePolicy {
+        const ACTION: Option<Action> = Some(Action::ExperimentalFeaturesUpdate);
+
+        fn authenticate(
+            auth: Data<AuthController>,
+            token: &str,
+            index: Option<&str>,
+        ) -> Option<AuthFilter> {
+            use actions::*;
+
+            ActionPolicy::<EXPERIMENTAL_FEATURES_UPDATE>::authenticate(auth.clone(), token, index)
+                .or_else(|| {
+                    ActionPolicy::<EXPERIMENTAL_VECTOR_STORE_UPDATE>::authenticate(
+                        auth.clone(),
+                        token,
+                        index,
+                    )
+                })
+                .or_else(|| {
+                    ActionPolicy::<EXPERIMENTAL_METRICS_UPDATE>::authenticate(
+                        auth.clone(),
+                        token,
+                        index,
+                    )
+                })
+                .or_else(|| {
+                    ActionPolicy::<EXPERIMENTAL_LOGS_ROUTE_UPDATE>::authenticate(
+                        auth.clone(),
+                        token,
+                        index,
+                    )
+                })
+                .or_else(|| {
+                    ActionPolicy::<EXPERIMENTAL_EXPORT_PUFFIN_REPORTS_UPDATE>::authenticate(
+                        auth, token, index,
+                    )
+                })
+        }
+    }
+
     impl<const A: u8> ActionPolicy<A> {
         fn authenticate_tenant_token(auth: &AuthController, token: &str) -> TenantTokenOutcome {
             // Only search action can be accessed by a tenant token.
@@ -233,33 +453,28 @@ pub mod policies {
             };
 
             // Check if tenant token is valid.
-            let key = if let Some(key) = auth.generate_key(uid) {
-                key
-            } else {
-                return TenantTokenOutcome::Invalid;
-            };
-
-            let data = if let Ok(data) = decode::<Claims>(
-                token,
-                &DecodingKey::from_secret(key.as_bytes()),
-                &tenant_token_validation(),
-            ) {
-                data
-            } else {
-                return TenantTokenOutcome::Invalid;
+            let (claims, generation) = match decode_tenant_token(auth, uid, token) {
+                Some(decoded) => decoded,
+                None => return TenantTokenOutcome::Invalid,
             };
 
             // Check if token is expired.
-            if let Some(exp) = data.claims.exp {
+            if let Some(exp) = claims.exp {
                 if OffsetDateTime::now_utc().unix_timestamp() > exp {
                     return TenantTokenOutcome::Expired;
                 }
             }
 
-            TenantTokenOutcome::Valid(uid, data.claims.search_rules)
+            TenantTokenOutcome::Valid(uid, claims.search_rules, generation)
         }
     }
 
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct KeyIdClaim {
+        api_key_uid: Uuid,
+    }
+
     #[derive(Debug, Serialize, Deserialize)]
     #[serde(rename_all = "camelCase")]
     struct Claims {
@@ -12,7 +12,6 @@ pub mod search;
 pub mod search_queue;
 
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
 use std::num::NonZeroUsize;
 use std::path::Path;
 use std::sync::Arc;
@@ -32,12 +31,9 @@ use extractors::payload::PayloadConfig;
 use http::header::CONTENT_TYPE;
 use index_scheduler::{IndexScheduler, IndexSchedulerOptions};
 use meilisearch_auth::AuthController;
-use meilisearch_types::milli::documents::{DocumentsBatchBuilder, DocumentsBatchReader};
-use meilisearch_types::milli::update::{IndexDocumentsConfig, IndexDocumentsMethod};
-use meilisearch_types::settings::apply_settings_to_builder;
 use meilisearch_types::tasks::KindWithContent;
 use meilisearch_types::versioning::{check_version_file, create_version_file};
-use meilisearch_types::{compression, milli, VERSION_FILE_NAME};
+use meilisearch_types::{compression, VERSION_FILE_NAME};
 pub use option::Opt;
 use option::ScheduleSnapshot;
 use search_queue::SearchQueue;
@@ -144,7 +140,7 @@ pub fn create_app(
         .configure(routes::configure)
         .configure(|s| dashboard(s, enable_dashboard));
 
-    let app = app.wrap(middleware::RouteMetrics);
+    let app = app.wrap(middleware::AuditLog).wrap(middleware::RouteMetrics);
     app.wrap(
         Cors::default()
             .send_wildcard()
@@ -287,7 +283,12 @@ fn open_or_create_database_unchecked(
 ) -> anyhow::Result<(IndexScheduler, AuthController)> {
     // we don't want to create anything in the data.ms yet, thus we
     // wrap our two builders in a closure that'll be executed later.
-    let auth_controller = AuthController::new(&opt.db_path, &opt.master_key);
+    let auth_controller = AuthController::new(&opt.db_path, &opt.master_key).map(|auth| {
+        auth.with_previous_master_keys(
+            opt.previous_master_keys.clone(),
+            opt.previous_master_keys_expire_at,
+        )
+    });
     let instance_features = opt.to_instance_features();
     let index_scheduler_builder = || -> anyhow::Result<_> {
         Ok(IndexScheduler::new(IndexSchedulerOptions {
@@ -298,6 +299,10 @@ fn open_or_create_database_unchecked(
             indexes_path: opt.db_path.join("indexes"),
             snapshots_path: opt.snapshot_dir.clone(),
             dumps_path: opt.dump_dir.clone(),
+            dump_compression: opt.dump_compression,
+            dump_interval: opt.schedule_dump.map(|interval| Duration::from_secs(interval.get())),
+            dump_retention: opt.dump_retention,
+            instance_uid_path: opt.db_path.join("instance-uid"),
             webhook_url: opt.task_webhook_url.as_ref().map(|url| url.to_string()),
             webhook_authorization_header: opt.task_webhook_authorization_header.clone(),
             task_db_size: opt.max_task_db_size.get_bytes() as usize,
@@ -311,6 +316,7 @@ fn open_or_create_database_unchecked(
             index_growth_amount: byte_unit::Byte::from_str("10GiB").unwrap().get_bytes() as usize,
             index_count: DEFAULT_INDEX_COUNT,
             instance_features,
+            query_embedding_cache_size: opt.experimental_query_embedding_cache_size,
         })?)
     };
 
@@ -372,20 +378,17 @@ fn import_dump(
     };
 
     // 2. Import the `Key`s.
-    let mut keys = Vec::new();
-    auth.raw_delete_all_keys()?;
-    for key in dump_reader.keys()? {
-        let key = key?;
-        auth.raw_insert_key(key.clone())?;
-        keys.push(key);
-    }
+    auth.import_dumped_keys(dump_reader.keys()?.map(|key| key.map_err(anyhow::Error::from)))?;
+
+    // 2.1. Import the audit log.
+    auth.import_dumped_audit_log(
+        dump_reader.audit_log()?.map(|entry| entry.map_err(anyhow::Error::from)),
+    )?;
 
     // 3. Import the runtime features.
     let features = dump_reader.features()?.unwrap_or_default();
     index_scheduler.put_runtime_features(features)?;
 
-    let indexer_config = index_scheduler.indexer_config();
-
     // /!\ The tasks must be imported AFTER importing the indexes or else the scheduler might
     // try to process tasks while we're trying to import the indexes.
 
@@ -398,59 +401,10 @@ fn import_dump(
         let date = Some((metadata.created_at, metadata.updated_at));
         let index = index_scheduler.create_raw_index(&metadata.uid, date)?;
 
-        let mut wtxn = index.write_txn()?;
-
-        let mut builder = milli::update::Settings::new(&mut wtxn, &index, indexer_config);
-        // 4.1 Import the primary key if there is one.
-        if let Some(ref primary_key) = metadata.primary_key {
-            builder.set_primary_key(primary_key.to_string());
-        }
-
-        // 4.2 Import the settings.
-        tracing::info!("Importing the settings.");
-        let settings = index_reader.settings()?;
-        apply_settings_to_builder(&settings, &mut builder);
-        builder
-            .execute(|indexing_step| tracing::debug!("update: {:?}", indexing_step), || false)?;
-
-        // 4.3 Import the documents.
-        // 4.3.1 We need to recreate the grenad+obkv format accepted by the index.
-        tracing::info!("Importing the documents.");
-        let file = tempfile::tempfile()?;
-        let mut builder = DocumentsBatchBuilder::new(BufWriter::new(file));
-        for document in index_reader.documents()? {
-            builder.append_json_object(&document?)?;
-        }
-
-        // This flush the content of the batch builder.
-        let file = builder.into_inner()?.into_inner()?;
-
-        // 4.3.2 We feed it to the milli index.
-        let reader = BufReader::new(file);
-        let reader = DocumentsBatchReader::from_reader(reader)?;
-
-        let embedder_configs = index.embedding_configs(&wtxn)?;
-        let embedders = index_scheduler.embedders(embedder_configs)?;
-
-        let builder = milli::update::IndexDocuments::new(
-            &mut wtxn,
-            &index,
-            indexer_config,
-            IndexDocumentsConfig {
-                update_method: IndexDocumentsMethod::ReplaceDocuments,
-                ..Default::default()
-            },
-            |indexing_step| tracing::trace!("update: {:?}", indexing_step),
-            || false,
-        )?;
-
-        let builder = builder.with_embedders(embedders);
-
-        let (builder, user_result) = builder.add_documents(reader)?;
-        let user_result = user_result?;
-        tracing::info!(documents_found = user_result, "{} documents found.", user_result);
-        builder.execute()?;
-        wtxn.commit()?;
+        // 4.1 Import the primary key, the settings and the documents.
+        let documents_count =
+            index_scheduler.import_dumped_index_content(&index, &mut index_reader)?;
+        tracing::info!(documents_found = documents_count, "{} documents found.", documents_count);
         tracing::info!("All documents successfully imported.");
     }
 
@@ -1,7 +1,7 @@
 use lazy_static::lazy_static;
 use prometheus::{
-    opts, register_histogram_vec, register_int_counter_vec, register_int_gauge,
-    register_int_gauge_vec, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
+    opts, register_gauge, register_histogram_vec, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Gauge, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
 };
 
 lazy_static! {
@@ -46,6 +46,32 @@ lazy_static! {
     pub static ref MEILISEARCH_LAST_UPDATE: IntGauge =
         register_int_gauge!(opts!("meilisearch_last_update", "Meilisearch Last Update"))
             .expect("Can't create a metric");
+    pub static ref MEILISEARCH_QUERY_EMBEDDING_CACHE_REQUESTS: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "meilisearch_query_embedding_cache_requests",
+            "Meilisearch number of search queries looked up in the query embedding caches"
+        ),
+        &["result"]
+    )
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_QUERY_EMBEDDING_REQUESTS: IntGauge = register_int_gauge!(opts!(
+        "meilisearch_query_embedding_requests",
+        "Meilisearch number of requests sent to the embedders to embed search queries"
+    ))
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_QUERY_EMBEDDING_DURATION_SECONDS: Gauge = register_gauge!(opts!(
+        "meilisearch_query_embedding_duration_seconds",
+        "Meilisearch total time spent embedding search queries"
+    ))
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_API_KEY_REQUESTS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "meilisearch_api_key_requests_total",
+            "Meilisearch number of requests made with each API key having a rate limit or a daily quota, accepted or rejected by its limits"
+        ),
+        &["key_uid", "result"]
+    )
+    .expect("Can't create a metric");
     pub static ref MEILISEARCH_IS_INDEXING: IntGauge =
         register_int_gauge!(opts!("meilisearch_is_indexing", "Meilisearch Is Indexing"))
             .expect("Can't create a metric");
@@ -7,7 +7,12 @@ use actix_web::web::Data;
 use actix_web::Error;
 use futures_util::future::LocalBoxFuture;
 use index_scheduler::IndexScheduler;
+use meilisearch_auth::AuthController;
+use meilisearch_types::audit_log::AuditLogEntry;
 use prometheus::HistogramTimer;
+use time::OffsetDateTime;
+
+use crate::extractors::authentication::RequestedAction;
 
 pub struct RouteMetrics;
 
@@ -90,3 +95,72 @@ where
         })
     }
 }
+
+/// Records the administrative requests in the audit log once they have been answered.
+pub struct AuditLog;
+
+impl<S, B> Transform<S, ServiceRequest> for AuditLog
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = AuditLogMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AuditLogMiddleware { service }))
+    }
+}
+
+pub struct AuditLogMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for AuditLogMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+
+            let request = res.request();
+            // the action is only known once the route tried to authenticate the request.
+            let requested = request.extensions().get::<RequestedAction>().copied();
+            let auth = request.app_data::<Data<AuthController>>();
+            if let (Some(RequestedAction { action, key_uid }), Some(auth)) = (requested, auth) {
+                if action.is_administrative() {
+                    auth.record_audit_log_entry(AuditLogEntry {
+                        uid: 0,
+                        key_uid,
+                        action,
+                        index_uid: request.match_info().get("index_uid").map(String::from),
+                        timestamp: OffsetDateTime::now_utc(),
+                        summary: format!(
+                            "{} {} {}",
+                            request.method(),
+                            request.path(),
+                            res.status().as_u16()
+                        ),
+                    });
+                }
+            }
+
+            Ok(res)
+        })
+    }
+}
@@ -2,7 +2,7 @@ use std::env::VarError;
 use std::ffi::OsStr;
 use std::fmt::Display;
 use std::io::{BufReader, Read};
-use std::num::ParseIntError;
+use std::num::{NonZeroU64, NonZeroUsize, ParseIntError};
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -11,8 +11,10 @@ use std::{env, fmt, fs};
 
 use byte_unit::{Byte, ByteError};
 use clap::Parser;
+use dump::DumpCompression;
 use meilisearch_types::features::InstanceTogglableFeatures;
 use meilisearch_types::milli::update::IndexerConfig;
+use meilisearch_types::milli::vector::DEFAULT_EMBEDDING_CACHE_SIZE;
 use meilisearch_types::milli::ThreadPoolNoAbortBuilder;
 use rustls::server::{
     AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient, ServerSessionMemoryCache,
@@ -21,6 +23,8 @@ use rustls::RootCertStore;
 use rustls_pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
 use serde::{Deserialize, Serialize};
 use sysinfo::{MemoryRefreshKind, RefreshKind, System};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 use url::Url;
 
 const POSSIBLE_ENV: [&str; 2] = ["development", "production"];
@@ -28,6 +32,8 @@ const POSSIBLE_ENV: [&str; 2] = ["development", "production"];
 const MEILI_DB_PATH: &str = "MEILI_DB_PATH";
 const MEILI_HTTP_ADDR: &str = "MEILI_HTTP_ADDR";
 const MEILI_MASTER_KEY: &str = "MEILI_MASTER_KEY";
+const MEILI_PREVIOUS_MASTER_KEYS: &str = "MEILI_PREVIOUS_MASTER_KEYS";
+const MEILI_PREVIOUS_MASTER_KEYS_EXPIRE_AT: &str = "MEILI_PREVIOUS_MASTER_KEYS_EXPIRE_AT";
 const MEILI_ENV: &str = "MEILI_ENV";
 const MEILI_TASK_WEBHOOK_URL: &str = "MEILI_TASK_WEBHOOK_URL";
 const MEILI_TASK_WEBHOOK_AUTHORIZATION_HEADER: &str = "MEILI_TASK_WEBHOOK_AUTHORIZATION_HEADER";
@@ -50,12 +56,17 @@ const MEILI_IMPORT_DUMP: &str = "MEILI_IMPORT_DUMP";
 const MEILI_IGNORE_MISSING_DUMP: &str = "MEILI_IGNORE_MISSING_DUMP";
 const MEILI_IGNORE_DUMP_IF_DB_EXISTS: &str = "MEILI_IGNORE_DUMP_IF_DB_EXISTS";
 const MEILI_DUMP_DIR: &str = "MEILI_DUMP_DIR";
+const MEILI_DUMP_COMPRESSION: &str = "MEILI_DUMP_COMPRESSION";
+const MEILI_SCHEDULE_DUMP: &str = "MEILI_SCHEDULE_DUMP";
+const MEILI_DUMP_RETENTION: &str = "MEILI_DUMP_RETENTION";
 const MEILI_LOG_LEVEL: &str = "MEILI_LOG_LEVEL";
 const MEILI_EXPERIMENTAL_LOGS_MODE: &str = "MEILI_EXPERIMENTAL_LOGS_MODE";
 const MEILI_EXPERIMENTAL_REPLICATION_PARAMETERS: &str = "MEILI_EXPERIMENTAL_REPLICATION_PARAMETERS";
 const MEILI_EXPERIMENTAL_ENABLE_LOGS_ROUTE: &str = "MEILI_EXPERIMENTAL_ENABLE_LOGS_ROUTE";
 const MEILI_EXPERIMENTAL_ENABLE_METRICS: &str = "MEILI_EXPERIMENTAL_ENABLE_METRICS";
 const MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE: &str = "MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE";
+const MEILI_EXPERIMENTAL_QUERY_EMBEDDING_CACHE_SIZE: &str =
+    "MEILI_EXPERIMENTAL_QUERY_EMBEDDING_CACHE_SIZE";
 const MEILI_EXPERIMENTAL_REDUCE_INDEXING_MEMORY_USAGE: &str =
     "MEILI_EXPERIMENTAL_REDUCE_INDEXING_MEMORY_USAGE";
 const MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS: &str =
@@ -70,9 +81,11 @@ const DEFAULT_SNAPSHOT_DIR: &str = "snapshots/";
 const DEFAULT_SNAPSHOT_INTERVAL_SEC: u64 = 86400;
 const DEFAULT_SNAPSHOT_INTERVAL_SEC_STR: &str = "86400";
 const DEFAULT_DUMP_DIR: &str = "dumps/";
+const DEFAULT_DUMP_INTERVAL_SEC_STR: &str = "86400";
 
 const MEILI_MAX_INDEXING_MEMORY: &str = "MEILI_MAX_INDEXING_MEMORY";
 const MEILI_MAX_INDEXING_THREADS: &str = "MEILI_MAX_INDEXING_THREADS";
+const MEILI_EXPERIMENTAL_EMBEDDING_CACHE_SIZE: &str = "MEILI_EXPERIMENTAL_EMBEDDING_CACHE_SIZE";
 const DEFAULT_LOG_EVERY_N: usize = 100_000;
 
 // Each environment (index and task-db) is taking space in the virtual address space.
@@ -191,6 +204,21 @@ pub struct Opt {
     #[clap(long, env = MEILI_MASTER_KEY)]
     pub master_key: Option<String>,
 
+    /// Sets the master keys used before the current one, separated by commas and ordered from the most recent to the oldest.
+    ///
+    /// The API keys and tenant tokens derived from these master keys are still accepted, so clients can migrate to the
+    /// keys derived from the new master key. The previous master keys themselves don't give access to the instance.
+    #[clap(long, env = MEILI_PREVIOUS_MASTER_KEYS, value_delimiter = ',')]
+    #[serde(default)]
+    pub previous_master_keys: Vec<String>,
+
+    /// Sets the end of the grace period of the previous master keys, as an RFC 3339 date-time.
+    ///
+    /// After this date, only the API keys and tenant tokens derived from the current master key are accepted.
+    #[clap(long, env = MEILI_PREVIOUS_MASTER_KEYS_EXPIRE_AT, value_parser = parse_rfc3339_date_time)]
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub previous_master_keys_expire_at: Option<OffsetDateTime>,
+
     /// Configures the instance's environment. Value must be either `production` or `development`.
     #[clap(long, env = MEILI_ENV, default_value_t = default_env(), value_parser = POSSIBLE_ENV)]
     #[serde(default = "default_env")]
@@ -332,6 +360,30 @@ pub struct Opt {
     #[serde(default = "default_dump_dir")]
     pub dump_dir: PathBuf,
 
+    /// Sets the compression algorithm used to write the dump files.
+    ///
+    /// Meilisearch currently supports two algorithms: `gzip` and `zstd`. Dumps compressed with
+    /// either of them can be imported, whatever the value of this option.
+    #[clap(long, env = MEILI_DUMP_COMPRESSION, default_value_t)]
+    #[serde(default)]
+    pub dump_compression: DumpCompression,
+
+    /// Activates scheduled dumps when provided. Dumps are not scheduled by default.
+    ///
+    /// When provided with a value, defines the interval between each dump, in seconds.
+    /// The scheduled dumps are registered as `dumpCreation` tasks in the task queue.
+    #[clap(long, env = MEILI_SCHEDULE_DUMP, num_args(0..=1), default_missing_value = default_dump_interval_sec(), value_name = "DUMP_INTERVAL_SEC")]
+    #[serde(default)]
+    pub schedule_dump: Option<NonZeroU64>,
+
+    /// Sets the maximum number of dump files kept in the dump directory.
+    ///
+    /// Once a dump is created, the oldest dumps are removed until there are no more than this number of them.
+    /// All the dumps are kept by default.
+    #[clap(long, env = MEILI_DUMP_RETENTION, value_name = "NUMBER_OF_DUMPS")]
+    #[serde(default)]
+    pub dump_retention: Option<NonZeroUsize>,
+
     /// Defines how much detail should be present in Meilisearch's logs.
     ///
     /// Meilisearch currently supports six log levels, listed in order of increasing verbosity: OFF, ERROR, WARN, INFO, DEBUG, TRACE.
@@ -355,6 +407,15 @@ pub struct Opt {
     #[serde(default)]
     pub experimental_search_queue_size: usize,
 
+    /// Experimental query embedding cache size.
+    ///
+    /// Lets you customize the number of search queries whose embedding is kept in memory for each embedder,
+    /// so that repeated semantic and hybrid searches don't have to call the embedder again.
+    /// Set it to 0 to disable the cache. The default value is 1000.
+    #[clap(long, env = MEILI_EXPERIMENTAL_QUERY_EMBEDDING_CACHE_SIZE, default_value_t = default_query_embedding_cache_size())]
+    #[serde(default = "default_query_embedding_cache_size")]
+    pub experimental_query_embedding_cache_size: usize,
+
     /// Experimental logs mode feature. For more information, see: <https://github.com/orgs/meilisearch/discussions/723>
     ///
     /// Change the mode of the logs on the console.
@@ -455,6 +516,8 @@ impl Opt {
             db_path,
             http_addr,
             master_key,
+            previous_master_keys,
+            previous_master_keys_expire_at,
             env,
             task_webhook_url,
             task_webhook_authorization_header,
@@ -472,6 +535,9 @@ impl Opt {
             snapshot_dir,
             schedule_snapshot,
             dump_dir,
+            dump_compression,
+            schedule_dump,
+            dump_retention,
             log_level,
             indexer_options,
             import_snapshot: _,
@@ -485,6 +551,7 @@ impl Opt {
             no_analytics,
             experimental_enable_metrics,
             experimental_search_queue_size,
+            experimental_query_embedding_cache_size,
             experimental_logs_mode,
             experimental_enable_logs_route,
             experimental_replication_parameters,
@@ -495,6 +562,17 @@ impl Opt {
         if let Some(master_key) = master_key {
             export_to_env_if_not_present(MEILI_MASTER_KEY, master_key);
         }
+        if !previous_master_keys.is_empty() {
+            export_to_env_if_not_present(
+                MEILI_PREVIOUS_MASTER_KEYS,
+                previous_master_keys.join(","),
+            );
+        }
+        if let Some(expire_at) =
+            previous_master_keys_expire_at.and_then(|d| d.format(&Rfc3339).ok())
+        {
+            export_to_env_if_not_present(MEILI_PREVIOUS_MASTER_KEYS_EXPIRE_AT, expire_at);
+        }
         export_to_env_if_not_present(MEILI_ENV, env);
         if let Some(task_webhook_url) = task_webhook_url {
             export_to_env_if_not_present(MEILI_TASK_WEBHOOK_URL, task_webhook_url.to_string());
@@ -539,6 +617,13 @@ impl Opt {
         }
 
         export_to_env_if_not_present(MEILI_DUMP_DIR, dump_dir);
+        export_to_env_if_not_present(MEILI_DUMP_COMPRESSION, dump_compression.to_string());
+        if let Some(dump_interval) = schedule_dump {
+            export_to_env_if_not_present(MEILI_SCHEDULE_DUMP, dump_interval.to_string());
+        }
+        if let Some(dump_retention) = dump_retention {
+            export_to_env_if_not_present(MEILI_DUMP_RETENTION, dump_retention.to_string());
+        }
         export_to_env_if_not_present(MEILI_LOG_LEVEL, log_level.to_string());
         export_to_env_if_not_present(
             MEILI_EXPERIMENTAL_ENABLE_METRICS,
@@ -548,6 +633,10 @@ impl Opt {
             MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE,
             experimental_search_queue_size.to_string(),
         );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_QUERY_EMBEDDING_CACHE_SIZE,
+            experimental_query_embedding_cache_size.to_string(),
+        );
         export_to_env_if_not_present(
             MEILI_EXPERIMENTAL_LOGS_MODE,
             experimental_logs_mode.to_string(),
@@ -636,6 +725,15 @@ pub struct IndexerOpts {
     #[serde(default)]
     pub max_indexing_threads: MaxThreads,
 
+    /// Experimental embedding cache size.
+    ///
+    /// Lets you customize the number of embeddings kept on disk by each index, so that the unchanged texts
+    /// of the documents are not sent to the embedders again when they are reindexed. Random embeddings are
+    /// evicted once the cache is full. Set it to 0 to disable the cache. The default value is 100000.
+    #[clap(long, env = MEILI_EXPERIMENTAL_EMBEDDING_CACHE_SIZE, default_value_t = default_embedding_cache_size())]
+    #[serde(default = "default_embedding_cache_size")]
+    pub experimental_embedding_cache_size: usize,
+
     /// Whether or not we want to determine the budget of virtual memory address space we have available dynamically
     /// (the default), or statically.
     ///
@@ -649,7 +747,12 @@ pub struct IndexerOpts {
 impl IndexerOpts {
     /// Exports the values to their corresponding env vars if they are not set.
     pub fn export_to_env(self) {
-        let IndexerOpts { max_indexing_memory, max_indexing_threads, skip_index_budget: _ } = self;
+        let IndexerOpts {
+            max_indexing_memory,
+            max_indexing_threads,
+            experimental_embedding_cache_size,
+            skip_index_budget: _,
+        } = self;
         if let Some(max_indexing_memory) = max_indexing_memory.0 {
             export_to_env_if_not_present(
                 MEILI_MAX_INDEXING_MEMORY,
@@ -660,6 +763,10 @@ impl IndexerOpts {
             MEILI_MAX_INDEXING_THREADS,
             max_indexing_threads.0.to_string(),
         );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_EMBEDDING_CACHE_SIZE,
+            experimental_embedding_cache_size.to_string(),
+        );
     }
 }
 
@@ -678,6 +785,7 @@ impl TryFrom<&IndexerOpts> for IndexerConfig {
             thread_pool: Some(thread_pool),
             max_positions_per_attributes: None,
             skip_index_budget: other.skip_index_budget,
+            embedding_cache_size: other.experimental_embedding_cache_size,
             ..Default::default()
         })
     }
@@ -859,6 +967,14 @@ fn default_limit_batched_tasks() -> usize {
     usize::MAX
 }
 
+fn default_query_embedding_cache_size() -> usize {
+    1000
+}
+
+fn default_embedding_cache_size() -> usize {
+    DEFAULT_EMBEDDING_CACHE_SIZE
+}
+
 fn default_snapshot_dir() -> PathBuf {
     PathBuf::from(DEFAULT_SNAPSHOT_DIR)
 }
@@ -871,6 +987,10 @@ fn default_dump_dir() -> PathBuf {
     PathBuf::from(DEFAULT_DUMP_DIR)
 }
 
+fn default_dump_interval_sec() -> &'static str {
+    DEFAULT_DUMP_INTERVAL_SEC_STR
+}
+
 /// Indicates if a snapshot was scheduled, and if yes with which interval.
 #[derive(Debug, Default, Copy, Clone, Deserialize, Serialize)]
 pub enum ScheduleSnapshot {
@@ -901,6 +1021,10 @@ impl FromStr for ScheduleSnapshot {
     }
 }
 
+fn parse_rfc3339_date_time(s: &str) -> Result<OffsetDateTime, time::error::Parse> {
+    OffsetDateTime::parse(s, &Rfc3339)
+}
+
 fn parse_schedule_snapshot(s: &str) -> Result<ScheduleSnapshot, ParseIntError> {
     Ok(if s.is_empty() { ScheduleSnapshot::Disabled } else { ScheduleSnapshot::from_str(s)? })
 }
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::str;
 
 use actix_web::web::Data;
@@ -5,12 +6,15 @@ use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
 use deserr::Deserr;
 use meilisearch_auth::error::AuthControllerError;
-use meilisearch_auth::AuthController;
+use meilisearch_auth::{AuthController, KeyUsage};
 use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
 use meilisearch_types::error::deserr_codes::*;
-use meilisearch_types::error::{Code, ResponseError};
-use meilisearch_types::keys::{CreateApiKey, Key, PatchApiKey};
+use meilisearch_types::error::{Code, InvalidTaskDateError, ResponseError};
+use meilisearch_types::index_uid_pattern::IndexUidPattern;
+use meilisearch_types::keys::{
+    CreateApiKey, Key, KeyRateLimit, KeySearchRules, PatchApiKey, TenantTokenKeys,
+};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use uuid::Uuid;
@@ -19,6 +23,7 @@ use super::PAGINATION_DEFAULT_LIMIT;
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::extractors::sequential_extractor::SeqHandler;
+use crate::routes::tasks::{deserialize_date, DeserializeDateOption};
 use crate::routes::Pagination;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -58,6 +63,8 @@ pub struct ListApiKeys {
     pub offset: Param<usize>,
     #[deserr(default = Param(PAGINATION_DEFAULT_LIMIT), error = DeserrQueryParamError<InvalidApiKeyLimit>)]
     pub limit: Param<usize>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidApiKeyUnusedSince>, try_from(Option<String>) = deserialize_unused_since -> InvalidTaskDateError)]
+    pub unused_since: Option<OffsetDateTime>,
 }
 
 impl ListApiKeys {
@@ -66,15 +73,32 @@ impl ListApiKeys {
     }
 }
 
+fn deserialize_unused_since(
+    value: Option<String>,
+) -> std::result::Result<Option<OffsetDateTime>, InvalidTaskDateError> {
+    value.map(|value| deserialize_date(&value, DeserializeDateOption::Before)).transpose()
+}
+
 pub async fn list_api_keys(
     auth_controller: GuardedData<ActionPolicy<{ actions::KEYS_GET }>, Data<AuthController>>,
     list_api_keys: AwebQueryParameter<ListApiKeys, DeserrQueryParamError>,
 ) -> Result<HttpResponse, ResponseError> {
-    let paginate = list_api_keys.into_inner().as_pagination();
+    let list_api_keys = list_api_keys.into_inner();
+    let unused_since = list_api_keys.unused_since;
+    let paginate = list_api_keys.as_pagination();
     let page_view = tokio::task::spawn_blocking(move || -> Result<_, AuthControllerError> {
+        auth_controller.flush_key_usages()?;
         let keys = auth_controller.list_keys()?;
-        let page_view = paginate
-            .auto_paginate_sized(keys.into_iter().map(|k| KeyView::from_key(k, &auth_controller)));
+        let keys: Vec<_> = keys
+            .into_iter()
+            .map(|k| KeyView::from_key(k, &auth_controller))
+            // the keys that were never used are unused since any date
+            .filter(|key| match (unused_since, key.last_used_at) {
+                (Some(unused_since), Some(last_used_at)) => last_used_at < unused_since,
+                _ => true,
+            })
+            .collect();
+        let page_view = paginate.auto_paginate_sized(keys.into_iter());
 
         Ok(page_view)
     })
@@ -94,6 +118,7 @@ pub async fn get_api_key(
         let uid =
             Uuid::parse_str(&key).or_else(|_| auth_controller.get_uid_from_encoded_key(&key))?;
         let key = auth_controller.get_key(uid)?;
+        auth_controller.flush_key_usages()?;
 
         Ok(KeyView::from_key(key, &auth_controller))
     })
@@ -155,15 +180,38 @@ struct KeyView {
     indexes: Vec<String>,
     #[serde(serialize_with = "time::serde::rfc3339::option::serialize")]
     expires_at: Option<OffsetDateTime>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    search_rules: BTreeMap<IndexUidPattern, KeySearchRules>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rate_limit: Option<KeyRateLimit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    daily_quota: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tenant_token_keys: Option<TenantTokenKeys>,
     #[serde(serialize_with = "time::serde::rfc3339::serialize")]
     created_at: OffsetDateTime,
     #[serde(serialize_with = "time::serde::rfc3339::serialize")]
     updated_at: OffsetDateTime,
+    #[serde(
+        serialize_with = "time::serde::rfc3339::option::serialize",
+        skip_serializing_if = "Option::is_none"
+    )]
+    last_used_at: Option<OffsetDateTime>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    request_counts: BTreeMap<Action, u64>,
+    /// The generation of the master key the key was derived from when it was last used,
+    /// `0` being the current master key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    master_key_generation: Option<usize>,
 }
 
 impl KeyView {
     fn from_key(key: Key, auth: &AuthController) -> Self {
         let generated_key = auth.generate_key(key.uid).unwrap_or_default();
+        let KeyUsage { last_used_at, request_counts, master_key_fingerprint } =
+            auth.get_key_usage(key.uid).unwrap_or_default();
+        let master_key_generation = master_key_fingerprint
+            .and_then(|fingerprint| auth.master_key_generation(&fingerprint).ok().flatten());
 
         KeyView {
             name: key.name,
@@ -173,8 +221,15 @@ impl KeyView {
             actions: key.actions,
             indexes: key.indexes.into_iter().map(|x| x.to_string()).collect(),
             expires_at: key.expires_at,
+            search_rules: key.search_rules,
+            rate_limit: key.rate_limit,
+            daily_quota: key.daily_quota,
+            tenant_token_keys: key.tenant_token_keys,
             created_at: key.created_at,
             updated_at: key.updated_at,
+            last_used_at,
+            request_counts,
+            master_key_generation,
         }
     }
 }
@@ -0,0 +1,133 @@
+use std::ops::Bound;
+use std::str::FromStr;
+
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::AwebQueryParameter;
+use deserr::Deserr;
+use meilisearch_auth::AuthController;
+use meilisearch_types::audit_log::AuditLogEntry;
+use meilisearch_types::deserr::query_params::Param;
+use meilisearch_types::deserr::DeserrQueryParamError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{Code, InvalidTaskDateError, ResponseError};
+use meilisearch_types::keys::Action;
+use serde::Serialize;
+use time::OffsetDateTime;
+use tracing::debug;
+use uuid::Uuid;
+
+use super::PAGINATION_DEFAULT_LIMIT;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::routes::tasks::{deserialize_date, DeserializeDateOption};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::get().to(SeqHandler(get_audit_log))));
+}
+
+#[derive(Deserr, Debug)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct AuditLogQuery {
+    #[deserr(default = Param(PAGINATION_DEFAULT_LIMIT), error = DeserrQueryParamError<InvalidAuditLogLimit>)]
+    pub limit: Param<usize>,
+    /// Only the entries with a smaller uid are returned, to get the next page of entries.
+    #[deserr(default, error = DeserrQueryParamError<InvalidAuditLogBeforeUid>)]
+    pub before_uid: Option<Param<u64>>,
+    /// Only the entries with a greater uid are returned, to get the entries appended since.
+    #[deserr(default, error = DeserrQueryParamError<InvalidAuditLogAfterUid>)]
+    pub after_uid: Option<Param<u64>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidAuditLogKeyUid>, try_from(Option<String>) = deserialize_key_uid -> uuid::Error)]
+    pub key_uid: Option<Uuid>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidAuditLogAction>)]
+    pub action: Option<Action>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidAuditLogIndexUid>)]
+    pub index_uid: Option<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidAuditLogAfterTimestamp>, try_from(Option<String>) = deserialize_after_timestamp -> InvalidTaskDateError)]
+    pub after_timestamp: Option<OffsetDateTime>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidAuditLogBeforeTimestamp>, try_from(Option<String>) = deserialize_before_timestamp -> InvalidTaskDateError)]
+    pub before_timestamp: Option<OffsetDateTime>,
+}
+
+fn deserialize_key_uid(value: Option<String>) -> std::result::Result<Option<Uuid>, uuid::Error> {
+    value.as_deref().map(Uuid::from_str).transpose()
+}
+
+fn deserialize_after_timestamp(
+    value: Option<String>,
+) -> std::result::Result<Option<OffsetDateTime>, InvalidTaskDateError> {
+    value.map(|value| deserialize_date(&value, DeserializeDateOption::After)).transpose()
+}
+
+fn deserialize_before_timestamp(
+    value: Option<String>,
+) -> std::result::Result<Option<OffsetDateTime>, InvalidTaskDateError> {
+    value.map(|value| deserialize_date(&value, DeserializeDateOption::Before)).transpose()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogView {
+    results: Vec<AuditLogEntry>,
+    limit: usize,
+    /// The `beforeUid` of the next page, `None` if there are no more entries.
+    next: Option<u64>,
+}
+
+pub async fn get_audit_log(
+    auth_controller: GuardedData<ActionPolicy<{ actions::AUDIT_LOG_GET }>, Data<AuthController>>,
+    params: AwebQueryParameter<AuditLogQuery, DeserrQueryParamError>,
+) -> Result<HttpResponse, ResponseError> {
+    let AuditLogQuery {
+        limit,
+        before_uid,
+        after_uid,
+        key_uid,
+        action,
+        index_uid,
+        after_timestamp,
+        before_timestamp,
+    } = params.into_inner();
+    let limit = limit.0;
+    let uids = (
+        after_uid.map_or(Bound::Unbounded, |Param(uid)| Bound::Excluded(uid)),
+        before_uid.map_or(Bound::Unbounded, |Param(uid)| Bound::Excluded(uid)),
+    );
+
+    let view = tokio::task::spawn_blocking(move || {
+        let filters = auth_controller.filters();
+        // one more entry is fetched to know if there is a next page.
+        let mut results = auth_controller.audit_log(
+            uids,
+            |entry| {
+                // the entries that don't concern an index are only visible to the keys on every index.
+                let authorized = match &entry.index_uid {
+                    Some(index) => filters.is_index_authorized(index),
+                    None => filters.all_indexes_authorized(),
+                };
+                authorized
+                    && key_uid.map_or(true, |uid| entry.key_uid == Some(uid))
+                    && action.map_or(true, |action| entry.action == action)
+                    && index_uid
+                        .as_ref()
+                        .map_or(true, |index| entry.index_uid.as_ref() == Some(index))
+                    && after_timestamp.map_or(true, |after| entry.timestamp > after)
+                    && before_timestamp.map_or(true, |before| entry.timestamp < before)
+            },
+            limit.saturating_add(1),
+        )?;
+        let next = if results.len() > limit {
+            results.truncate(limit);
+            results.last().map(|entry| entry.uid)
+        } else {
+            None
+        };
+        Ok::<_, ResponseError>(AuditLogView { results, limit, next })
+    })
+    .await
+    .map_err(|e| ResponseError::from_msg(e.to_string(), Code::Internal))??;
+
+    debug!(returns = ?view, "Get audit log");
+    Ok(HttpResponse::Ok().json(view))
+}
@@ -1,8 +1,12 @@
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
 use index_scheduler::IndexScheduler;
 use meilisearch_auth::AuthController;
-use meilisearch_types::error::ResponseError;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::tasks::KindWithContent;
 use serde_json::json;
 use tracing::debug;
@@ -15,7 +19,8 @@ use crate::routes::{get_task_id, is_dry_run, SummarizedTaskView};
 use crate::Opt;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::resource("").route(web::post().to(SeqHandler(create_dump))));
+    cfg.service(web::resource("").route(web::post().to(SeqHandler(create_dump))))
+        .service(web::resource("/import").route(web::post().to(SeqHandler(import_dump))));
 }
 
 pub async fn create_dump(
@@ -41,3 +46,54 @@ pub async fn create_dump(
     debug!(returns = ?task, "Create dump");
     Ok(HttpResponse::Accepted().json(task))
 }
+
+#[derive(Deserr, Debug)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct ImportDump {
+    #[deserr(error = DeserrJsonError<InvalidDumpUid>, missing_field_error = DeserrJsonError::missing_dump_uid)]
+    pub dump_uid: String,
+    #[deserr(default, error = DeserrJsonError<InvalidDumpImportKeys>)]
+    pub import_keys: bool,
+}
+
+pub async fn import_dump(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::DUMPS_IMPORT }>, Data<IndexScheduler>>,
+    body: AwebJson<ImportDump, DeserrJsonError>,
+    req: HttpRequest,
+    opt: web::Data<Opt>,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let ImportDump { dump_uid, import_keys } = body.into_inner();
+    debug!(parameters = ?dump_uid, "Import dump");
+
+    analytics.publish(
+        "Dump Imported".to_string(),
+        json!({ "import_keys": import_keys }),
+        Some(&req),
+    );
+
+    // The dump uid is the name of a file in the dump directory, it must not be a path.
+    if dump_uid.is_empty()
+        || dump_uid.starts_with('.')
+        || !dump_uid.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(ResponseError::from_msg(
+            format!("`{dump_uid}` is not a valid dump uid. A dump uid can only contain alphanumeric characters, hyphens (-), underscores (_) and dots (.), and cannot start with a dot."),
+            Code::InvalidDumpUid,
+        ));
+    }
+    if !opt.dump_dir.join(format!("{dump_uid}.dump")).is_file() {
+        return Err(index_scheduler::Error::DumpNotFound(dump_uid).into());
+    }
+
+    let task = KindWithContent::DumpImport { dump_uid, import_keys };
+    let uid = get_task_id(&req, &opt)?;
+    let dry_run = is_dry_run(&req, &opt)?;
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+            .await??
+            .into();
+
+    debug!(returns = ?task, "Import dump");
+    Ok(HttpResponse::Accepted().json(task))
+}
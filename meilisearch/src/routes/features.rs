@@ -3,14 +3,15 @@ use actix_web::{HttpRequest, HttpResponse};
 use deserr::actix_web::AwebJson;
 use deserr::Deserr;
 use index_scheduler::IndexScheduler;
+use meilisearch_auth::AuthController;
 use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::ResponseError;
-use meilisearch_types::keys::actions;
+use meilisearch_types::keys::{actions, Action};
 use serde_json::json;
 use tracing::debug;
 
 use crate::analytics::Analytics;
-use crate::extractors::authentication::policies::ActionPolicy;
+use crate::extractors::authentication::policies::{ActionPolicy, ExperimentalFeaturesUpdatePolicy};
 use crate::extractors::authentication::GuardedData;
 use crate::extractors::sequential_extractor::SeqHandler;
 
@@ -52,10 +53,8 @@ pub struct RuntimeTogglableFeatures {
 }
 
 async fn patch_features(
-    index_scheduler: GuardedData<
-        ActionPolicy<{ actions::EXPERIMENTAL_FEATURES_UPDATE }>,
-        Data<IndexScheduler>,
-    >,
+    index_scheduler: GuardedData<ExperimentalFeaturesUpdatePolicy, Data<IndexScheduler>>,
+    auth_controller: Data<AuthController>,
     new_features: AwebJson<RuntimeTogglableFeatures, DeserrJsonError>,
     req: HttpRequest,
     analytics: Data<dyn Analytics>,
@@ -63,6 +62,20 @@ async fn patch_features(
     let features = index_scheduler.features();
     debug!(parameters = ?new_features, "Patch features");
 
+    // the key must be allowed to update each of the features it toggles.
+    let RuntimeTogglableFeatures { vector_store, metrics, logs_route, export_puffin_reports } =
+        &new_features.0;
+    for (toggled, action) in [
+        (vector_store, Action::ExperimentalVectorStoreUpdate),
+        (metrics, Action::ExperimentalMetricsUpdate),
+        (logs_route, Action::ExperimentalLogsRouteUpdate),
+        (export_puffin_reports, Action::ExperimentalExportPuffinReportsUpdate),
+    ] {
+        if toggled.is_some() {
+            index_scheduler.check_action(&auth_controller, action, None)?;
+        }
+    }
+
     let old_features = features.runtime_features();
     let new_features = meilisearch_types::features::RuntimeTogglableFeatures {
         vector_store: new_features.0.vector_store.unwrap_or(old_features.vector_store),
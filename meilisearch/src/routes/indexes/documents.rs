@@ -94,6 +94,8 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 pub struct GetDocument {
     #[deserr(default, error = DeserrQueryParamError<InvalidDocumentFields>)]
     fields: OptionStarOrList<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidDocumentRetrieveVectors>)]
+    retrieve_vectors: Param<bool>,
 }
 
 pub async fn get_document(
@@ -109,11 +111,15 @@ pub async fn get_document(
 
     analytics.get_fetch_documents(&DocumentFetchKind::PerDocumentId, &req);
 
-    let GetDocument { fields } = params.into_inner();
+    let GetDocument { fields, retrieve_vectors } = params.into_inner();
     let attributes_to_retrieve = fields.merge_star_and_none();
+    if retrieve_vectors.0 {
+        index_scheduler.features().check_vector("Passing `retrieveVectors` as a parameter")?;
+    }
 
     let index = index_scheduler.index(&index_uid)?;
-    let document = retrieve_document(&index, &document_id, attributes_to_retrieve)?;
+    let document =
+        retrieve_document(&index, &document_id, attributes_to_retrieve, retrieve_vectors.0)?;
     debug!(returns = ?document, "Get document");
     Ok(HttpResponse::Ok().json(document))
 }
@@ -644,6 +650,7 @@ fn retrieve_document<S: AsRef<str>>(
     index: &Index,
     doc_id: &str,
     attributes_to_retrieve: Option<Vec<S>>,
+    retrieve_vectors: bool,
 ) -> Result<Document, ResponseError> {
     let txn = index.read_txn()?;
 
@@ -663,7 +670,7 @@ fn retrieve_document<S: AsRef<str>>(
         .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(doc_id.to_string()))?;
 
     let document = meilisearch_types::milli::obkv_to_json(&all_fields, &fields_ids_map, document)?;
-    let document = match &attributes_to_retrieve {
+    let mut document = match &attributes_to_retrieve {
         Some(attributes_to_retrieve) => permissive_json_pointer::select_values(
             &document,
             attributes_to_retrieve.iter().map(|s| s.as_ref()),
@@ -671,5 +678,12 @@ fn retrieve_document<S: AsRef<str>>(
         None => document,
     };
 
+    // the embeddings stored in the vector store replace the `_vectors` field of the document,
+    // so that the generated embeddings are returned as well.
+    if retrieve_vectors {
+        let embeddings = index.embeddings(&txn, internal_id)?;
+        document.insert("_vectors".to_string(), serde_json::json!(embeddings));
+    }
+
     Ok(document)
 }
@@ -0,0 +1,128 @@
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::AwebJson;
+use index_scheduler::IndexScheduler;
+use meilisearch_auth::AuthController;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli;
+use meilisearch_types::milli::vector::Embedding;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use super::render::render_stored_document;
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/{embedder_name}/render").route(web::post().to(render)));
+}
+
+#[derive(Deserialize)]
+pub struct EmbedderParam {
+    index_uid: String,
+    embedder_name: String,
+}
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct EmbedderRenderQuery {
+    #[deserr(default, error = DeserrJsonError<InvalidRenderDocumentId>)]
+    pub document_id: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidRenderDocument>)]
+    pub document: Option<Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidRenderRetrieveEmbeddings>)]
+    pub retrieve_embeddings: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedderRenderResult {
+    pub embedder: String,
+    pub rendered: String,
+    /// The embedding of each chunk of the rendered text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<Vec<Embedding>>,
+}
+
+/// Renders the document template of an embedder for a stored or a given document,
+/// and optionally embeds the result as it would be at indexing time.
+///
+/// Rendering a stored document also requires the `documents.get` action, and embedding
+/// the result the `documents.add` action, as it calls the embedder like an indexation does.
+pub async fn render(
+    index_scheduler: GuardedData<
+        ActionPolicy<{ actions::SETTINGS_EMBEDDERS_GET }>,
+        Data<IndexScheduler>,
+    >,
+    auth_controller: Data<AuthController>,
+    path: web::Path<EmbedderParam>,
+    params: AwebJson<EmbedderRenderQuery, DeserrJsonError>,
+) -> Result<HttpResponse, ResponseError> {
+    let EmbedderParam { index_uid, embedder_name } = path.into_inner();
+    let index_uid = IndexUid::try_from(index_uid)?;
+    index_scheduler.features().check_vector("Using the `/embedders/{name}/render` route")?;
+
+    let query = params.into_inner();
+    debug!(parameters = ?query, "Render embedder template");
+    let EmbedderRenderQuery { document_id, document, retrieve_embeddings } = query;
+
+    if document_id.is_some() {
+        index_scheduler.check_action(
+            &auth_controller,
+            Action::DocumentsGet,
+            Some(index_uid.as_str()),
+        )?;
+    }
+    if retrieve_embeddings {
+        index_scheduler.check_action(
+            &auth_controller,
+            Action::DocumentsAdd,
+            Some(index_uid.as_str()),
+        )?;
+    }
+
+    let index = index_scheduler.index(&index_uid)?;
+    let embedding_configs = index.embedding_configs(&index.read_txn()?)?;
+    let embedders = index_scheduler.embedders(embedding_configs)?;
+    let (embedder, prompt) = embedders
+        .get(&embedder_name)
+        .ok_or(milli::UserError::InvalidEmbedder(embedder_name.clone()))
+        .map_err(milli::Error::from)?;
+
+    let rendered = match (document_id, document) {
+        (Some(document_id), None) => render_stored_document(&index, &prompt, &document_id)?,
+        (None, Some(Value::Object(document))) => {
+            prompt.render_object(&document).map_err(|err| match err {
+                milli::Error::UserError(err) => {
+                    ResponseError::from_msg(err.to_string(), Code::InvalidRenderDocument)
+                }
+                err => err.into(),
+            })?
+        }
+        _ => return Err(MeilisearchHttpError::InvalidRenderDocument.into()),
+    };
+
+    let embeddings = if retrieve_embeddings {
+        let chunks: Vec<String> = prompt.chunk(&rendered).into_iter().map(String::from).collect();
+        let embeddings = tokio::task::spawn_blocking(move || embedder.embed(chunks))
+            .await?
+            .map_err(|err| milli::Error::from(milli::vector::Error::from(err)))?;
+        Some(
+            embeddings
+                .iter()
+                .flat_map(|embeddings| embeddings.iter().map(|embedding| embedding.to_vec()))
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    let result = EmbedderRenderResult { embedder: embedder_name, rendered, embeddings };
+    debug!(returns = ?result, "Render embedder template");
+    Ok(HttpResponse::Ok().json(result))
+}
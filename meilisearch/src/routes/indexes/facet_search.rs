@@ -1,6 +1,9 @@
+use std::convert::Infallible;
+
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::AwebJson;
+use deserr::{DeserializeError, ValuePointerRef};
 use index_scheduler::IndexScheduler;
 use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::deserr_codes::*;
@@ -14,9 +17,10 @@ use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::routes::indexes::search::search_kind;
 use crate::search::{
-    add_search_rules, perform_facet_search, HybridQuery, MatchingStrategy, SearchQuery,
-    DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
-    DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET,
+    add_search_rules, check_search_rules_attribute, perform_facet_search, FacetRangeQuery,
+    HybridQuery, MatchingStrategy, SearchQuery, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
+    DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
+    DEFAULT_SEARCH_OFFSET,
 };
 use crate::search_queue::SearchQueue;
 
@@ -28,12 +32,15 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 ///
 /// Intentionally don't use `deny_unknown_fields` to ignore search parameters sent by user
 #[derive(Debug, Clone, Default, PartialEq, deserr::Deserr)]
-#[deserr(error = DeserrJsonError, rename_all = camelCase)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, validate = validate_facet_search_query -> DeserrJsonError<InvalidFacetSearchRanges>)]
 pub struct FacetSearchQuery {
     #[deserr(default, error = DeserrJsonError<InvalidFacetSearchQuery>)]
     pub facet_query: Option<String>,
     #[deserr(error = DeserrJsonError<InvalidFacetSearchFacetName>, missing_field_error = DeserrJsonError::missing_facet_search_facet_name)]
     pub facet_name: String,
+    /// Returns the number of documents in each range of numeric values instead of the facet values.
+    #[deserr(default, error = DeserrJsonError<InvalidFacetSearchRanges>)]
+    pub ranges: Option<Vec<FacetRangeQuery>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchQ>)]
     pub q: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchVector>)]
@@ -48,6 +55,23 @@ pub struct FacetSearchQuery {
     pub attributes_to_search_on: Option<Vec<String>>,
 }
 
+fn validate_facet_search_query<E: DeserializeError>(
+    query: FacetSearchQuery,
+    location: ValuePointerRef,
+) -> Result<FacetSearchQuery, E> {
+    if query.ranges.is_some() && query.facet_query.is_some() {
+        Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            deserr::ErrorKind::Unexpected {
+                msg: "`ranges` cannot be used together with `facetQuery`.".to_string(),
+            },
+            location,
+        )))
+    } else {
+        Ok(query)
+    }
+}
+
 pub async fn search(
     index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
     search_queue: Data<SearchQueue>,
@@ -65,19 +89,21 @@ pub async fn search(
 
     let facet_query = query.facet_query.clone();
     let facet_name = query.facet_name.clone();
+    let ranges = query.ranges.clone();
     let mut search_query = SearchQuery::from(query);
 
     // Tenant token search_rules.
     if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
-        add_search_rules(&mut search_query, search_rules);
+        check_search_rules_attribute(&search_rules, "facetName", &facet_name)?;
+        add_search_rules(&mut search_query, search_rules)?;
     }
 
     let index = index_scheduler.index(&index_uid)?;
     let features = index_scheduler.features();
-    let search_kind = search_kind(&search_query, &index_scheduler, &index, features)?;
+    let search_kind = search_kind(&search_query, &index_scheduler, &index_uid, &index, features)?;
     let _permit = search_queue.try_get_search_permit().await?;
     let search_result = tokio::task::spawn_blocking(move || {
-        perform_facet_search(&index, search_query, facet_query, facet_name, search_kind)
+        perform_facet_search(&index, search_query, facet_query, facet_name, ranges, search_kind)
     })
     .await?;
 
@@ -97,6 +123,7 @@ impl From<FacetSearchQuery> for SearchQuery {
         let FacetSearchQuery {
             facet_query: _,
             facet_name: _,
+            ranges: _,
             q,
             vector,
             filter,
@@ -121,11 +148,15 @@ impl From<FacetSearchQuery> for SearchQuery {
             filter,
             sort: None,
             facets: None,
+            facet_ranges: None,
+            facet_histogram: None,
+            facet_hierarchy_depth: None,
             highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
             highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
             crop_marker: DEFAULT_CROP_MARKER(),
             matching_strategy,
             vector,
+            image: None,
             attributes_to_search_on,
             hybrid,
         }
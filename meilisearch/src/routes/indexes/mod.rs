@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::convert::Infallible;
 
 use actix_web::web::Data;
@@ -26,7 +27,9 @@ use crate::routes::is_dry_run;
 use crate::Opt;
 
 pub mod documents;
+pub mod embedders;
 pub mod facet_search;
+pub mod render;
 pub mod search;
 pub mod settings;
 
@@ -48,6 +51,8 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .service(web::scope("/documents").configure(documents::configure))
             .service(web::scope("/search").configure(search::configure))
             .service(web::scope("/facet-search").configure(facet_search::configure))
+            .service(web::scope("/render").configure(render::configure))
+            .service(web::scope("/embedders").configure(embedders::configure))
             .service(web::scope("/settings").configure(settings::configure)),
     );
 }
@@ -254,6 +259,9 @@ pub struct IndexStats {
     pub is_indexing: bool,
     /// Association of every field name with the number of times it occurs in the documents.
     pub field_distribution: FieldDistribution,
+    /// Association of every embedder name with the size taken by its vectors, in bytes.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub vector_storage_size: BTreeMap<String, u64>,
 }
 
 impl From<index_scheduler::IndexStats> for IndexStats {
@@ -262,6 +270,7 @@ impl From<index_scheduler::IndexStats> for IndexStats {
             number_of_documents: stats.inner_stats.number_of_documents,
             is_indexing: stats.is_indexing,
             field_distribution: stats.inner_stats.field_distribution,
+            vector_storage_size: stats.inner_stats.vector_storage_size,
         }
     }
 }
@@ -0,0 +1,88 @@
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::AwebJson;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::prompt::Prompt;
+use meilisearch_types::milli::{self, Index};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::post().to(render)));
+}
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct RenderQuery {
+    #[deserr(error = DeserrJsonError<InvalidRenderTemplate>)]
+    pub template: String,
+    #[deserr(error = DeserrJsonError<InvalidRenderDocumentId>)]
+    pub document_id: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderResult {
+    pub template: String,
+    pub rendered: String,
+}
+
+/// Renders a document template against a document of the index, without modifying the settings.
+pub async fn render(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebJson<RenderQuery, DeserrJsonError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    index_scheduler.features().check_vector("Using the `/render` route")?;
+
+    let query = params.into_inner();
+    debug!(parameters = ?query, "Render template");
+    let RenderQuery { template, document_id } = query;
+
+    let prompt = Prompt::new(template.clone())
+        .map_err(|err| ResponseError::from_msg(err.to_string(), Code::InvalidRenderTemplate))?;
+
+    let index = index_scheduler.index(&index_uid)?;
+    let rendered = render_stored_document(&index, &prompt, &document_id)?;
+
+    let result = RenderResult { template, rendered };
+    debug!(returns = ?result, "Render template");
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Renders a prompt for a document of the index, identified by its external id.
+pub fn render_stored_document(
+    index: &Index,
+    prompt: &Prompt,
+    document_id: &str,
+) -> Result<String, ResponseError> {
+    let rtxn = index.read_txn()?;
+    let fields_ids_map = index.fields_ids_map(&rtxn)?;
+
+    let internal_id = index
+        .external_documents_ids()
+        .get(&rtxn, document_id)?
+        .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(document_id.to_string()))?;
+
+    let (_, document) = index
+        .documents(&rtxn, std::iter::once(internal_id))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(document_id.to_string()))?;
+
+    prompt.render_document(document, &fields_ids_map).map_err(|err| match err {
+        milli::Error::UserError(err) => {
+            ResponseError::from_msg(err.to_string(), Code::InvalidRenderTemplate)
+        }
+        err => err.into(),
+    })
+}
@@ -1,3 +1,5 @@
+use std::num::NonZeroUsize;
+
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
@@ -19,9 +21,10 @@ use crate::extractors::authentication::GuardedData;
 use crate::extractors::sequential_extractor::SeqHandler;
 use crate::metrics::MEILISEARCH_DEGRADED_SEARCH_REQUESTS;
 use crate::search::{
-    add_search_rules, perform_search, HybridQuery, MatchingStrategy, SearchKind, SearchQuery,
-    SemanticRatio, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
-    DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET, DEFAULT_SEMANTIC_RATIO,
+    add_search_rules, perform_search, Fusion, HybridQuery, MatchingStrategy, SearchKind,
+    SearchQuery, SemanticRatio, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
+    DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
+    DEFAULT_SEARCH_OFFSET, DEFAULT_SEMANTIC_RATIO,
 };
 use crate::search_queue::SearchQueue;
 
@@ -68,6 +71,8 @@ pub struct SearchQueryGet {
     show_ranking_score_details: Param<bool>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchFacets>)]
     facets: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchFacetHierarchyDepth>)]
+    facet_hierarchy_depth: Option<FacetHierarchyDepthGet>,
     #[deserr( default = DEFAULT_HIGHLIGHT_PRE_TAG(), error = DeserrQueryParamError<InvalidSearchHighlightPreTag>)]
     highlight_pre_tag: String,
     #[deserr( default = DEFAULT_HIGHLIGHT_POST_TAG(), error = DeserrQueryParamError<InvalidSearchHighlightPostTag>)]
@@ -82,6 +87,10 @@ pub struct SearchQueryGet {
     pub hybrid_embedder: Option<String>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchSemanticRatio>)]
     pub hybrid_semantic_ratio: Option<SemanticRatioGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchFusion>)]
+    pub hybrid_fusion: Option<Fusion>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchRankConstant>)]
+    pub hybrid_rank_constant: Option<Param<u32>>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, deserr::Deserr)]
@@ -105,6 +114,20 @@ impl std::ops::Deref for SemanticRatioGet {
     }
 }
 
+/// The depth of the facet hierarchy in the query parameters, rejecting `0` with the same error as the body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, deserr::Deserr)]
+#[deserr(try_from(String) = TryFrom::try_from -> InvalidSearchFacetHierarchyDepth)]
+pub struct FacetHierarchyDepthGet(NonZeroUsize);
+
+impl std::convert::TryFrom<String> for FacetHierarchyDepthGet {
+    type Error = InvalidSearchFacetHierarchyDepth;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let depth: usize = s.parse().map_err(|_| InvalidSearchFacetHierarchyDepth)?;
+        NonZeroUsize::new(depth).map(FacetHierarchyDepthGet).ok_or(InvalidSearchFacetHierarchyDepth)
+    }
+}
+
 impl From<SearchQueryGet> for SearchQuery {
     fn from(other: SearchQueryGet) -> Self {
         let filter = match other.filter {
@@ -115,23 +138,26 @@ impl From<SearchQueryGet> for SearchQuery {
             None => None,
         };
 
-        let hybrid = match (other.hybrid_embedder, other.hybrid_semantic_ratio) {
-            (None, None) => None,
-            (None, Some(semantic_ratio)) => {
-                Some(HybridQuery { semantic_ratio: *semantic_ratio, embedder: None })
-            }
-            (Some(embedder), None) => Some(HybridQuery {
-                semantic_ratio: DEFAULT_SEMANTIC_RATIO(),
-                embedder: Some(embedder),
+        let hybrid = match (
+            other.hybrid_embedder,
+            other.hybrid_semantic_ratio,
+            other.hybrid_fusion,
+            other.hybrid_rank_constant,
+        ) {
+            (None, None, None, None) => None,
+            (embedder, semantic_ratio, fusion, rank_constant) => Some(HybridQuery {
+                semantic_ratio: semantic_ratio.map_or_else(DEFAULT_SEMANTIC_RATIO, |ratio| *ratio),
+                embedder,
+                fusion: fusion.unwrap_or_default(),
+                rank_constant: rank_constant.map(|rank_constant| rank_constant.0),
             }),
-            (Some(embedder), Some(semantic_ratio)) => {
-                Some(HybridQuery { semantic_ratio: *semantic_ratio, embedder: Some(embedder) })
-            }
         };
 
         Self {
             q: other.q,
             vector: other.vector.map(CS::into_inner),
+            // images are too large to be passed as query parameters
+            image: None,
             offset: other.offset.0,
             limit: other.limit.0,
             page: other.page.as_deref().copied(),
@@ -146,6 +172,10 @@ impl From<SearchQueryGet> for SearchQuery {
             show_ranking_score: other.show_ranking_score.0,
             show_ranking_score_details: other.show_ranking_score_details.0,
             facets: other.facets.map(|o| o.into_iter().collect()),
+            // the ranges and histograms are too structured to be passed as query parameters
+            facet_ranges: None,
+            facet_histogram: None,
+            facet_hierarchy_depth: other.facet_hierarchy_depth.map(|depth| depth.0),
             highlight_pre_tag: other.highlight_pre_tag,
             highlight_post_tag: other.highlight_post_tag,
             crop_marker: other.crop_marker,
@@ -196,7 +226,7 @@ pub async fn search_with_url_query(
 
     // Tenant token search_rules.
     if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
-        add_search_rules(&mut query, search_rules);
+        add_search_rules(&mut query, search_rules)?;
     }
 
     let mut aggregate = SearchAggregator::from_query(&query, &req);
@@ -204,7 +234,7 @@ pub async fn search_with_url_query(
     let index = index_scheduler.index(&index_uid)?;
     let features = index_scheduler.features();
 
-    let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)?;
+    let search_kind = search_kind(&query, index_scheduler.get_ref(), &index_uid, &index, features)?;
 
     let _permit = search_queue.try_get_search_permit().await?;
     let search_result =
@@ -235,7 +265,7 @@ pub async fn search_with_post(
 
     // Tenant token search_rules.
     if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
-        add_search_rules(&mut query, search_rules);
+        add_search_rules(&mut query, search_rules)?;
     }
 
     let mut aggregate = SearchAggregator::from_query(&query, &req);
@@ -244,7 +274,7 @@ pub async fn search_with_post(
 
     let features = index_scheduler.features();
 
-    let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)?;
+    let search_kind = search_kind(&query, index_scheduler.get_ref(), &index_uid, &index, features)?;
 
     let _permit = search_queue.try_get_search_permit().await?;
     let search_result =
@@ -266,6 +296,7 @@ pub async fn search_with_post(
 pub fn search_kind(
     query: &SearchQuery,
     index_scheduler: &IndexScheduler,
+    index_uid: &str,
     index: &milli::Index,
     features: RoFeatures,
 ) -> Result<SearchKind, ResponseError> {
@@ -277,6 +308,24 @@ pub fn search_kind(
         features.check_vector("Passing `hybrid` as a query parameter")?;
     }
 
+    // an image can only be matched semantically, `q` is embedded along with it
+    if query.image.is_some() {
+        features.check_vector("Passing `image` as a search parameter")?;
+        if query.vector.is_some() {
+            return Err(MeilisearchHttpError::SearchImageWithVector.into());
+        }
+        let embedder = query.hybrid.as_ref().and_then(|hybrid| hybrid.embedder.as_deref());
+        let search_kind = SearchKind::semantic(index_scheduler, index_uid, index, embedder, None)?;
+        if let SearchKind::SemanticOnly { embedder_name, embedder, .. } = &search_kind {
+            if !embedder.accepts_images() {
+                return Err(
+                    MeilisearchHttpError::EmbedderWithoutImages(embedder_name.clone()).into()
+                );
+            }
+        }
+        return Ok(search_kind);
+    }
+
     // regardless of anything, always do a keyword search when we don't have a vector and the query is whitespace or missing
     if query.vector.is_none() {
         match &query.q {
@@ -287,28 +336,32 @@ pub fn search_kind(
     }
 
     match &query.hybrid {
-        Some(HybridQuery { semantic_ratio, embedder }) if **semantic_ratio == 1.0 => {
+        Some(HybridQuery { semantic_ratio, embedder, .. }) if **semantic_ratio == 1.0 => {
             Ok(SearchKind::semantic(
                 index_scheduler,
+                index_uid,
                 index,
                 embedder.as_deref(),
                 query.vector.as_ref().map(Vec::len),
             )?)
         }
-        Some(HybridQuery { semantic_ratio, embedder: _ }) if **semantic_ratio == 0.0 => {
+        Some(HybridQuery { semantic_ratio, .. }) if **semantic_ratio == 0.0 => {
             Ok(SearchKind::KeywordOnly)
         }
-        Some(HybridQuery { semantic_ratio, embedder }) => Ok(SearchKind::hybrid(
+        Some(hybrid) => Ok(SearchKind::hybrid(
             index_scheduler,
+            index_uid,
             index,
-            embedder.as_deref(),
-            **semantic_ratio,
+            hybrid.embedder.as_deref(),
+            *hybrid.semantic_ratio,
+            hybrid.fusion_strategy(),
             query.vector.as_ref().map(Vec::len),
         )?),
         None => match (query.q.as_deref(), query.vector.as_deref()) {
             (_query, None) => Ok(SearchKind::KeywordOnly),
             (None, Some(_vector)) => Ok(SearchKind::semantic(
                 index_scheduler,
+                index_uid,
                 index,
                 None,
                 query.vector.as_ref().map(Vec::len),
@@ -20,7 +20,7 @@ use crate::Opt;
 
 #[macro_export]
 macro_rules! make_setting_route {
-    ($route:literal, $update_verb:ident, $type:ty, $err_ty:ty, $attr:ident, $camelcase_attr:literal, $analytics_var:ident, $analytics:expr) => {
+    ($route:literal, $update_verb:ident, $type:ty, $err_ty:ty, $attr:ident, $camelcase_attr:literal, $get_action:ident, $update_action:ident, $analytics_var:ident, $analytics:expr) => {
         pub mod $attr {
             use actix_web::web::Data;
             use actix_web::{web, HttpRequest, HttpResponse, Resource};
@@ -40,7 +40,7 @@ macro_rules! make_setting_route {
 
             pub async fn delete(
                 index_scheduler: GuardedData<
-                    ActionPolicy<{ actions::SETTINGS_UPDATE }>,
+                    ActionPolicy<{ actions::$update_action }>,
                     Data<IndexScheduler>,
                 >,
                 index_uid: web::Path<String>,
@@ -73,7 +73,7 @@ macro_rules! make_setting_route {
 
             pub async fn update(
                 index_scheduler: GuardedData<
-                    ActionPolicy<{ actions::SETTINGS_UPDATE }>,
+                    ActionPolicy<{ actions::$update_action }>,
                     Data<IndexScheduler>,
                 >,
                 index_uid: actix_web::web::Path<String>,
@@ -125,7 +125,7 @@ macro_rules! make_setting_route {
 
             pub async fn get(
                 index_scheduler: GuardedData<
-                    ActionPolicy<{ actions::SETTINGS_GET }>,
+                    ActionPolicy<{ actions::$get_action }>,
                     Data<IndexScheduler>,
                 >,
                 index_uid: actix_web::web::Path<String>,
@@ -160,6 +160,8 @@ make_setting_route!(
     >,
     filterable_attributes,
     "filterableAttributes",
+    SETTINGS_FILTERABLE_ATTRIBUTES_GET,
+    SETTINGS_FILTERABLE_ATTRIBUTES_UPDATE,
     analytics,
     |setting: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
         use serde_json::json;
@@ -186,6 +188,8 @@ make_setting_route!(
     >,
     sortable_attributes,
     "sortableAttributes",
+    SETTINGS_SORTABLE_ATTRIBUTES_GET,
+    SETTINGS_SORTABLE_ATTRIBUTES_UPDATE,
     analytics,
     |setting: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
         use serde_json::json;
@@ -212,6 +216,8 @@ make_setting_route!(
     >,
     displayed_attributes,
     "displayedAttributes",
+    SETTINGS_DISPLAYED_ATTRIBUTES_GET,
+    SETTINGS_DISPLAYED_ATTRIBUTES_UPDATE,
     analytics,
     |displayed: &Option<Vec<String>>, req: &HttpRequest| {
         use serde_json::json;
@@ -238,6 +244,8 @@ make_setting_route!(
     >,
     typo_tolerance,
     "typoTolerance",
+    SETTINGS_TYPO_TOLERANCE_GET,
+    SETTINGS_TYPO_TOLERANCE_UPDATE,
     analytics,
     |setting: &Option<meilisearch_types::settings::TypoSettings>, req: &HttpRequest| {
         use serde_json::json;
@@ -283,6 +291,8 @@ make_setting_route!(
     >,
     searchable_attributes,
     "searchableAttributes",
+    SETTINGS_SEARCHABLE_ATTRIBUTES_GET,
+    SETTINGS_SEARCHABLE_ATTRIBUTES_UPDATE,
     analytics,
     |setting: &Option<Vec<String>>, req: &HttpRequest| {
         use serde_json::json;
@@ -309,6 +319,8 @@ make_setting_route!(
     >,
     stop_words,
     "stopWords",
+    SETTINGS_STOP_WORDS_GET,
+    SETTINGS_STOP_WORDS_UPDATE,
     analytics,
     |stop_words: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
         use serde_json::json;
@@ -334,6 +346,8 @@ make_setting_route!(
     >,
     non_separator_tokens,
     "nonSeparatorTokens",
+    SETTINGS_NON_SEPARATOR_TOKENS_GET,
+    SETTINGS_NON_SEPARATOR_TOKENS_UPDATE,
     analytics,
     |non_separator_tokens: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
         use serde_json::json;
@@ -359,6 +373,8 @@ make_setting_route!(
     >,
     separator_tokens,
     "separatorTokens",
+    SETTINGS_SEPARATOR_TOKENS_GET,
+    SETTINGS_SEPARATOR_TOKENS_UPDATE,
     analytics,
     |separator_tokens: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
         use serde_json::json;
@@ -384,6 +400,8 @@ make_setting_route!(
     >,
     dictionary,
     "dictionary",
+    SETTINGS_DICTIONARY_GET,
+    SETTINGS_DICTIONARY_UPDATE,
     analytics,
     |dictionary: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
         use serde_json::json;
@@ -409,6 +427,8 @@ make_setting_route!(
     >,
     synonyms,
     "synonyms",
+    SETTINGS_SYNONYMS_GET,
+    SETTINGS_SYNONYMS_UPDATE,
     analytics,
     |synonyms: &Option<std::collections::BTreeMap<String, Vec<String>>>, req: &HttpRequest| {
         use serde_json::json;
@@ -434,6 +454,8 @@ make_setting_route!(
     >,
     distinct_attribute,
     "distinctAttribute",
+    SETTINGS_DISTINCT_ATTRIBUTE_GET,
+    SETTINGS_DISTINCT_ATTRIBUTE_UPDATE,
     analytics,
     |distinct: &Option<String>, req: &HttpRequest| {
         use serde_json::json;
@@ -458,6 +480,8 @@ make_setting_route!(
     >,
     proximity_precision,
     "proximityPrecision",
+    SETTINGS_PROXIMITY_PRECISION_GET,
+    SETTINGS_PROXIMITY_PRECISION_UPDATE,
     analytics,
     |precision: &Option<meilisearch_types::settings::ProximityPrecisionView>, req: &HttpRequest| {
         use serde_json::json;
@@ -483,6 +507,8 @@ make_setting_route!(
     >,
     ranking_rules,
     "rankingRules",
+    SETTINGS_RANKING_RULES_GET,
+    SETTINGS_RANKING_RULES_UPDATE,
     analytics,
     |setting: &Option<Vec<meilisearch_types::settings::RankingRuleView>>, req: &HttpRequest| {
         use serde_json::json;
@@ -514,6 +540,8 @@ make_setting_route!(
     >,
     faceting,
     "faceting",
+    SETTINGS_FACETING_GET,
+    SETTINGS_FACETING_UPDATE,
     analytics,
     |setting: &Option<meilisearch_types::settings::FacetingSettings>, req: &HttpRequest| {
         use serde_json::json;
@@ -528,6 +556,7 @@ make_setting_route!(
                         s.sort_facet_values_by.as_ref().set().map(|s| s.iter().any(|(k, v)| k == "*" && v == &FacetValuesSort::Count))
                     }),
                     "sort_facet_values_by_total": setting.as_ref().and_then(|s| s.sort_facet_values_by.as_ref().set().map(|s| s.len())),
+                    "hierarchical_facets_total": setting.as_ref().and_then(|s| s.hierarchical_facets.as_ref().set().map(|s| s.len())),
                 },
             }),
             Some(req),
@@ -544,6 +573,8 @@ make_setting_route!(
     >,
     pagination,
     "pagination",
+    SETTINGS_PAGINATION_GET,
+    SETTINGS_PAGINATION_UPDATE,
     analytics,
     |setting: &Option<meilisearch_types::settings::PaginationSettings>, req: &HttpRequest| {
         use serde_json::json;
@@ -569,6 +600,8 @@ make_setting_route!(
     >,
     embedders,
     "embedders",
+    SETTINGS_EMBEDDERS_GET,
+    SETTINGS_EMBEDDERS_UPDATE,
     analytics,
     |setting: &Option<std::collections::BTreeMap<String, Setting<meilisearch_types::milli::vector::settings::EmbeddingSettings>>>, req: &HttpRequest| {
 
@@ -604,21 +637,54 @@ fn embedder_analytics(
                 EmbedderSource::UserProvided => sources.insert("userProvided"),
                 EmbedderSource::Ollama => sources.insert("ollama"),
                 EmbedderSource::Rest => sources.insert("rest"),
+                EmbedderSource::Onnx => sources.insert("onnx"),
             };
         }
     };
 
+    let distances: std::collections::HashSet<_> = setting
+        .iter()
+        .flat_map(|map| map.values())
+        .filter_map(|config| config.clone().set())
+        .filter_map(|config| config.distance.set())
+        .map(|distance| distance.to_string())
+        .collect();
+
     let document_template_used = setting.as_ref().map(|map| {
         map.values()
             .filter_map(|config| config.clone().set())
             .any(|config| config.document_template.set().is_some())
     });
 
+    let quantizations: std::collections::HashSet<_> = setting
+        .iter()
+        .flat_map(|map| map.values())
+        .filter_map(|config| config.clone().set())
+        .filter_map(|config| config.quantization.set())
+        .map(|quantization| quantization.to_string())
+        .collect();
+
+    let rescoring_used = setting.as_ref().map(|map| {
+        map.values()
+            .filter_map(|config| config.clone().set())
+            .any(|config| config.rescoring.set() == Some(true))
+    });
+
+    let chunking_used = setting.as_ref().map(|map| {
+        map.values()
+            .filter_map(|config| config.clone().set())
+            .any(|config| config.chunking.set().is_some())
+    });
+
     json!(
         {
             "total": setting.as_ref().map(|s| s.len()),
             "sources": sources,
             "document_template_used": document_template_used,
+            "distances": distances,
+            "quantizations": quantizations,
+            "rescoring_used": rescoring_used,
+            "chunking_used": chunking_used,
         }
     )
 }
@@ -632,6 +698,8 @@ make_setting_route!(
     >,
     search_cutoff_ms,
     "searchCutoffMs",
+    SETTINGS_SEARCH_CUTOFF_MS_GET,
+    SETTINGS_SEARCH_CUTOFF_MS_UPDATE,
     analytics,
     |setting: &Option<u64>, req: &HttpRequest| {
         analytics.publish(
@@ -771,6 +839,10 @@ pub async fn update_all(
                     .as_ref()
                     .set()
                     .and_then(|s| s.sort_facet_values_by.as_ref().set().map(|s| s.len())),
+                "hierarchical_facets_total": new_settings.faceting
+                    .as_ref()
+                    .set()
+                    .and_then(|s| s.hierarchical_facets.as_ref().set().map(|s| s.len())),
             },
             "pagination": {
                 "max_total_hits": new_settings.pagination
@@ -54,6 +54,18 @@ pub async fn get_metrics(
     }
     crate::metrics::MEILISEARCH_IS_INDEXING.set(index_scheduler.is_task_processing()? as i64);
 
+    let query_embedding_stats = index_scheduler.query_embedding_stats();
+    crate::metrics::MEILISEARCH_QUERY_EMBEDDING_CACHE_REQUESTS
+        .with_label_values(&["hit"])
+        .set(query_embedding_stats.hits() as i64);
+    crate::metrics::MEILISEARCH_QUERY_EMBEDDING_CACHE_REQUESTS
+        .with_label_values(&["miss"])
+        .set(query_embedding_stats.misses() as i64);
+    crate::metrics::MEILISEARCH_QUERY_EMBEDDING_REQUESTS
+        .set(query_embedding_stats.requests() as i64);
+    crate::metrics::MEILISEARCH_QUERY_EMBEDDING_DURATION_SECONDS
+        .set(query_embedding_stats.requests_duration().as_secs_f64());
+
     let encoder = TextEncoder::new();
     let mut buffer = vec![];
     encoder.encode(&prometheus::gather(), &mut buffer).expect("Failed to encode metrics");
@@ -7,7 +7,7 @@ use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::keys::actions;
 use serde::Serialize;
-use tracing::debug;
+use tracing::{debug, error};
 
 use crate::analytics::{Analytics, MultiSearchAggregator};
 use crate::extractors::authentication::policies::ActionPolicy;
@@ -15,7 +15,8 @@ use crate::extractors::authentication::{AuthenticationError, GuardedData};
 use crate::extractors::sequential_extractor::SeqHandler;
 use crate::routes::indexes::search::search_kind;
 use crate::search::{
-    add_search_rules, perform_search, SearchQueryWithIndex, SearchResultWithIndex,
+    add_search_rules, embed_queries_in_batch, perform_search, SearchQueryWithIndex,
+    SearchResultWithIndex,
 };
 use crate::search_queue::SearchQueue;
 
@@ -54,7 +55,7 @@ pub async fn multi_search_with_post(
     // so that `?` doesn't work if it doesn't use `with_index`, ensuring that it is not forgotten in case of code
     // changes.
     let search_results: Result<_, (ResponseError, usize)> = async {
-        let mut search_results = Vec::with_capacity(queries.len());
+        let mut searches = Vec::with_capacity(queries.len());
         for (query_index, (index_uid, mut query)) in
             queries.into_iter().map(SearchQueryWithIndex::into_index_query).enumerate()
         {
@@ -67,7 +68,7 @@ pub async fn multi_search_with_post(
             // Apply search rules from tenant token
             if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid)
             {
-                add_search_rules(&mut query, search_rules);
+                add_search_rules(&mut query, search_rules).with_index(query_index)?;
             }
 
             let index = index_scheduler
@@ -81,8 +82,38 @@ pub async fn multi_search_with_post(
                 })
                 .with_index(query_index)?;
 
-            let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)
-                .with_index(query_index)?;
+            let search_kind =
+                search_kind(&query, index_scheduler.get_ref(), &index_uid, &index, features)
+                    .with_index(query_index)?;
+
+            searches.push((query_index, index_uid, index, query, search_kind));
+        }
+
+        // embed the queries of the semantic searches with a single request per embedder
+        // before performing the searches.
+        let queries_to_embed = searches
+            .iter()
+            .map(|(_, _, _, query, search_kind)| search_kind.query_to_embed(query))
+            .collect();
+        let embeddings =
+            match tokio::task::spawn_blocking(move || embed_queries_in_batch(queries_to_embed))
+                .await
+            {
+                Ok(embeddings) => embeddings,
+                // no query is to blame, let each search embed its own query
+                Err(error) => {
+                    error!(%error, "Could not embed the queries of the searches in batch");
+                    searches.iter().map(|_| None).collect()
+                }
+            };
+
+        let mut search_results = Vec::with_capacity(searches.len());
+        for ((query_index, index_uid, index, mut query, search_kind), embedding) in
+            searches.into_iter().zip(embeddings)
+        {
+            if let Some(embedding) = embedding {
+                query.vector = Some(embedding.with_index(query_index)?);
+            }
 
             let search_result =
                 tokio::task::spawn_blocking(move || perform_search(&index, query, search_kind))
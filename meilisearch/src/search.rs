@@ -1,11 +1,13 @@
 use core::fmt;
 use std::cmp::min;
 use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::convert::Infallible;
+use std::num::NonZeroUsize;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use deserr::Deserr;
+use deserr::{DeserializeError, Deserr, ValuePointerRef};
 use either::Either;
 use indexmap::IndexMap;
 use meilisearch_auth::IndexSearchRules;
@@ -14,9 +16,13 @@ use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::heed::RoTxn;
 use meilisearch_types::index_uid::IndexUid;
-use meilisearch_types::milli::score_details::{ScoreDetails, ScoringStrategy};
-use meilisearch_types::milli::vector::Embedder;
-use meilisearch_types::milli::{FacetValueHit, OrderBy, SearchForFacetValues, TimeBudget};
+use meilisearch_types::milli::prompt::Prompt;
+use meilisearch_types::milli::score_details::{self, ScoreDetails, ScoringStrategy};
+use meilisearch_types::milli::vector::{Embedder, EmbedderInput, Embedding, QueryEmbeddingCache};
+use meilisearch_types::milli::{
+    FacetHierarchyNode, FacetRange, FacetRangeHit, FacetValueHit, OrderBy, SearchForFacetValues,
+    TimeBudget,
+};
 use meilisearch_types::settings::DEFAULT_PAGINATION_MAX_TOTAL_HITS;
 use meilisearch_types::{milli, Document};
 use milli::tokenizer::TokenizerBuilder;
@@ -39,6 +45,7 @@ pub const DEFAULT_CROP_MARKER: fn() -> String = || "…".to_string();
 pub const DEFAULT_HIGHLIGHT_PRE_TAG: fn() -> String = || "<em>".to_string();
 pub const DEFAULT_HIGHLIGHT_POST_TAG: fn() -> String = || "</em>".to_string();
 pub const DEFAULT_SEMANTIC_RATIO: fn() -> SemanticRatio = || SemanticRatio(0.5);
+pub const DEFAULT_RANK_CONSTANT: fn() -> u32 = || 60;
 
 #[derive(Clone, Default, PartialEq, Deserr)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
@@ -47,6 +54,8 @@ pub struct SearchQuery {
     pub q: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchVector>)]
     pub vector: Option<Vec<f32>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchImage>)]
+    pub image: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidHybridQuery>)]
     pub hybrid: Option<HybridQuery>,
     #[deserr(default = DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSearchOffset>)]
@@ -77,6 +86,12 @@ pub struct SearchQuery {
     pub sort: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetRanges>)]
+    pub facet_ranges: Option<BTreeMap<String, Vec<FacetRangeQuery>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetHistogram>)]
+    pub facet_histogram: Option<BTreeMap<String, FacetHistogramQuery>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetHierarchyDepth>, try_from(Option<usize>) = deserialize_facet_hierarchy_depth -> InvalidSearchFacetHierarchyDepth)]
+    pub facet_hierarchy_depth: Option<NonZeroUsize>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     pub highlight_pre_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
@@ -97,6 +112,7 @@ impl fmt::Debug for SearchQuery {
         let Self {
             q,
             vector,
+            image,
             hybrid,
             offset,
             limit,
@@ -112,6 +128,9 @@ impl fmt::Debug for SearchQuery {
             filter,
             sort,
             facets,
+            facet_ranges,
+            facet_histogram,
+            facet_hierarchy_depth,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -144,6 +163,14 @@ impl fmt::Debug for SearchQuery {
                 );
             }
         }
+        if let Some(image) = image {
+            // base64 images are too long to be logged
+            if image.len() < 100 {
+                debug.field("image", &image);
+            } else {
+                debug.field("image", &format!("[{} bytes]", image.len()));
+            }
+        }
         if let Some(hybrid) = hybrid {
             debug.field("hybrid", &hybrid);
         }
@@ -159,6 +186,15 @@ impl fmt::Debug for SearchQuery {
         if let Some(facets) = facets {
             debug.field("facets", &facets);
         }
+        if let Some(facet_ranges) = facet_ranges {
+            debug.field("facet_ranges", &facet_ranges);
+        }
+        if let Some(facet_histogram) = facet_histogram {
+            debug.field("facet_histogram", &facet_histogram);
+        }
+        if let Some(facet_hierarchy_depth) = facet_hierarchy_depth {
+            debug.field("facet_hierarchy_depth", &facet_hierarchy_depth);
+        }
         debug.field("matching_strategy", &matching_strategy);
 
         // Then everything related to the formatting
@@ -200,45 +236,157 @@ pub struct HybridQuery {
     pub semantic_ratio: SemanticRatio,
     #[deserr(default, error = DeserrJsonError<InvalidEmbedder>, default)]
     pub embedder: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFusion>, default)]
+    pub fusion: Fusion,
+    /// The `k` constant of the reciprocal rank fusion, defaults to [`DEFAULT_RANK_CONSTANT`].
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankConstant>, default)]
+    pub rank_constant: Option<u32>,
+}
+
+impl HybridQuery {
+    pub fn fusion_strategy(&self) -> milli::HybridFusion {
+        match self.fusion {
+            Fusion::Score => milli::HybridFusion::Score,
+            Fusion::ReciprocalRank => milli::HybridFusion::ReciprocalRank {
+                rank_constant: self.rank_constant.unwrap_or_else(DEFAULT_RANK_CONSTANT),
+            },
+        }
+    }
+}
+
+/// How the keyword and semantic results of a hybrid search are combined.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserr)]
+#[deserr(rename_all = camelCase)]
+pub enum Fusion {
+    /// Compare the ranking scores of both searches, weighted by the semantic ratio
+    Score,
+    /// Sum the weighted reciprocal ranks of the documents in both searches
+    ReciprocalRank,
+}
+
+impl Default for Fusion {
+    fn default() -> Self {
+        Self::Score
+    }
+}
+
+/// A range of numeric facet values, `from` is included and `to` is excluded.
+#[derive(Debug, Clone, Copy, PartialEq, Deserr)]
+#[deserr(rename_all = camelCase, deny_unknown_fields, validate = validate_facet_range -> __Deserr_E)]
+pub struct FacetRangeQuery {
+    #[deserr(default)]
+    pub from: Option<f64>,
+    #[deserr(default)]
+    pub to: Option<f64>,
+}
+
+fn validate_facet_range<E: DeserializeError>(
+    range: FacetRangeQuery,
+    location: ValuePointerRef,
+) -> Result<FacetRangeQuery, E> {
+    let msg = match range {
+        FacetRangeQuery { from: None, to: None } => {
+            "At least one of `from` or `to` must be provided."
+        }
+        FacetRangeQuery { from: Some(from), to: Some(to) } if from >= to => {
+            "`from` must be lower than `to`."
+        }
+        _ => return Ok(range),
+    };
+    Err(deserr::take_cf_content(E::error::<Infallible>(
+        None,
+        deserr::ErrorKind::Unexpected { msg: msg.to_string() },
+        location,
+    )))
+}
+
+fn deserialize_facet_hierarchy_depth(
+    depth: Option<usize>,
+) -> Result<Option<NonZeroUsize>, InvalidSearchFacetHierarchyDepth> {
+    depth.map(|depth| NonZeroUsize::new(depth).ok_or(InvalidSearchFacetHierarchyDepth)).transpose()
+}
+
+impl From<FacetRangeQuery> for FacetRange {
+    fn from(FacetRangeQuery { from, to }: FacetRangeQuery) -> Self {
+        FacetRange { from, to }
+    }
+}
+
+/// Splits the values of a numeric facet into buckets of `interval` width.
+#[derive(Debug, Clone, Copy, PartialEq, Deserr)]
+#[deserr(rename_all = camelCase, deny_unknown_fields, validate = validate_facet_histogram -> __Deserr_E)]
+pub struct FacetHistogramQuery {
+    pub interval: f64,
+}
+
+fn validate_facet_histogram<E: DeserializeError>(
+    histogram: FacetHistogramQuery,
+    location: ValuePointerRef,
+) -> Result<FacetHistogramQuery, E> {
+    if histogram.interval.is_finite() && histogram.interval > 0.0 {
+        Ok(histogram)
+    } else {
+        Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            deserr::ErrorKind::Unexpected {
+                msg: "`interval` must be a number greater than `0`.".to_string(),
+            },
+            location,
+        )))
+    }
 }
 
 pub enum SearchKind {
     KeywordOnly,
-    SemanticOnly { embedder_name: String, embedder: Arc<Embedder> },
-    Hybrid { embedder_name: String, embedder: Arc<Embedder>, semantic_ratio: f32 },
+    SemanticOnly {
+        embedder_name: String,
+        embedder: Arc<Embedder>,
+        query_cache: QueryEmbeddingCache,
+    },
+    Hybrid {
+        embedder_name: String,
+        embedder: Arc<Embedder>,
+        query_cache: QueryEmbeddingCache,
+        semantic_ratio: f32,
+        fusion: milli::HybridFusion,
+    },
 }
 impl SearchKind {
     pub(crate) fn semantic(
         index_scheduler: &index_scheduler::IndexScheduler,
+        index_uid: &str,
         index: &Index,
         embedder_name: Option<&str>,
         vector_len: Option<usize>,
     ) -> Result<Self, ResponseError> {
-        let (embedder_name, embedder) =
-            Self::embedder(index_scheduler, index, embedder_name, vector_len)?;
-        Ok(Self::SemanticOnly { embedder_name, embedder })
+        let (embedder_name, embedder, query_cache) =
+            Self::embedder(index_scheduler, index_uid, index, embedder_name, vector_len)?;
+        Ok(Self::SemanticOnly { embedder_name, embedder, query_cache })
     }
 
     pub(crate) fn hybrid(
         index_scheduler: &index_scheduler::IndexScheduler,
+        index_uid: &str,
         index: &Index,
         embedder_name: Option<&str>,
         semantic_ratio: f32,
+        fusion: milli::HybridFusion,
         vector_len: Option<usize>,
     ) -> Result<Self, ResponseError> {
-        let (embedder_name, embedder) =
-            Self::embedder(index_scheduler, index, embedder_name, vector_len)?;
-        Ok(Self::Hybrid { embedder_name, embedder, semantic_ratio })
+        let (embedder_name, embedder, query_cache) =
+            Self::embedder(index_scheduler, index_uid, index, embedder_name, vector_len)?;
+        Ok(Self::Hybrid { embedder_name, embedder, query_cache, semantic_ratio, fusion })
     }
 
     fn embedder(
         index_scheduler: &index_scheduler::IndexScheduler,
+        index_uid: &str,
         index: &Index,
         embedder_name: Option<&str>,
         vector_len: Option<usize>,
-    ) -> Result<(String, Arc<Embedder>), ResponseError> {
+    ) -> Result<(String, Arc<Embedder>, QueryEmbeddingCache), ResponseError> {
         let embedder_configs = index.embedding_configs(&index.read_txn()?)?;
-        let embedders = index_scheduler.embedders(embedder_configs)?;
+        let embedders = index_scheduler.embedders(embedder_configs.clone())?;
 
         let embedder_name = embedder_name.unwrap_or_else(|| embedders.get_default_embedder_name());
 
@@ -261,10 +409,88 @@ impl SearchKind {
             }
         }
 
-        Ok((embedder_name.to_owned(), embedder))
+        // the embedder was found, and so is its configuration
+        let (_, config) = embedder_configs.iter().find(|(name, _)| name == embedder_name).unwrap();
+        let query_cache = index_scheduler.query_embedding_cache(
+            index_uid,
+            embedder_name,
+            &config.embedder_options,
+        );
+
+        Ok((embedder_name.to_owned(), embedder, query_cache))
     }
 }
 
+/// The text of a semantic search that must be embedded before performing the search.
+pub struct QueryToEmbed {
+    embedder: Arc<Embedder>,
+    query_cache: QueryEmbeddingCache,
+    q: String,
+}
+
+impl SearchKind {
+    /// Returns the text this search must embed before being performed.
+    ///
+    /// Only the semantic searches need it, the hybrid searches only embed their query
+    /// when the keyword results are not good enough.
+    pub fn query_to_embed(&self, query: &SearchQuery) -> Option<QueryToEmbed> {
+        let SearchKind::SemanticOnly { embedder, query_cache, .. } = self else { return None };
+        // the images are embedded when preparing their search
+        if query.vector.is_some() || query.image.is_some() {
+            return None;
+        }
+        let q = query.q.clone()?;
+        Some(QueryToEmbed { embedder: embedder.clone(), query_cache: query_cache.clone(), q })
+    }
+}
+
+/// Embeds the texts of several searches, such as the ones of a multi-search, with as few requests
+/// as their embedders accept.
+///
+/// Returns the embedding of each text, or the error of the request that embedded it.
+pub fn embed_queries_in_batch(
+    queries: Vec<Option<QueryToEmbed>>,
+) -> Vec<Option<Result<Embedding, ResponseError>>> {
+    let mut results: Vec<_> = queries.iter().map(|_| None).collect();
+
+    // group the texts by embedder
+    let mut batches: Vec<(&QueryToEmbed, Vec<usize>)> = Vec::new();
+    for (position, query) in queries.iter().enumerate() {
+        let Some(query) = query else { continue };
+        match batches.iter_mut().find(|(first, _)| first.query_cache.ptr_eq(&query.query_cache)) {
+            Some((_, positions)) => positions.push(position),
+            None => batches.push((query, vec![position])),
+        }
+    }
+
+    for (first, positions) in batches {
+        let texts: Vec<&str> = positions
+            .iter()
+            .filter_map(|&position| queries[position].as_ref())
+            .map(|query| query.q.as_str())
+            .collect();
+
+        match first.query_cache.embed_many(&first.embedder, &texts) {
+            Ok(embeddings) => {
+                for (position, embedding) in positions.into_iter().zip(embeddings) {
+                    results[position] = Some(Ok(embedding));
+                }
+            }
+            // every search of the batch reports the error, without embedding its text again
+            Err(error) => {
+                let error = ResponseError::from(MeilisearchHttpError::from(milli::Error::from(
+                    milli::vector::Error::from(error),
+                )));
+                for position in positions {
+                    results[position] = Some(Err(error.clone()));
+                }
+            }
+        }
+    }
+
+    results
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Deserr)]
 #[deserr(try_from(f32) = TryFrom::try_from -> InvalidSearchSemanticRatio)]
 pub struct SemanticRatio(f32);
@@ -316,6 +542,8 @@ pub struct SearchQueryWithIndex {
     pub q: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchQ>)]
     pub vector: Option<Vec<f32>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchImage>)]
+    pub image: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidHybridQuery>)]
     pub hybrid: Option<HybridQuery>,
     #[deserr(default = DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSearchOffset>)]
@@ -346,6 +574,12 @@ pub struct SearchQueryWithIndex {
     pub sort: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetRanges>)]
+    pub facet_ranges: Option<BTreeMap<String, Vec<FacetRangeQuery>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetHistogram>)]
+    pub facet_histogram: Option<BTreeMap<String, FacetHistogramQuery>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetHierarchyDepth>, try_from(Option<usize>) = deserialize_facet_hierarchy_depth -> InvalidSearchFacetHierarchyDepth)]
+    pub facet_hierarchy_depth: Option<NonZeroUsize>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     pub highlight_pre_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
@@ -364,6 +598,7 @@ impl SearchQueryWithIndex {
             index_uid,
             q,
             vector,
+            image,
             offset,
             limit,
             page,
@@ -378,6 +613,9 @@ impl SearchQueryWithIndex {
             filter,
             sort,
             facets,
+            facet_ranges,
+            facet_histogram,
+            facet_hierarchy_depth,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
@@ -390,6 +628,7 @@ impl SearchQueryWithIndex {
             SearchQuery {
                 q,
                 vector,
+                image,
                 offset,
                 limit,
                 page,
@@ -404,6 +643,9 @@ impl SearchQueryWithIndex {
                 filter,
                 sort,
                 facets,
+                facet_ranges,
+                facet_histogram,
+                facet_hierarchy_depth,
                 highlight_pre_tag,
                 highlight_post_tag,
                 crop_marker,
@@ -473,6 +715,8 @@ pub struct SearchHit {
     pub ranking_score: Option<f64>,
     #[serde(rename = "_rankingScoreDetails", skip_serializing_if = "Option::is_none")]
     pub ranking_score_details: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(rename = "_matchedChunk", skip_serializing_if = "Option::is_none")]
+    pub matched_chunk: Option<String>,
 }
 
 #[derive(Serialize, Clone, PartialEq)]
@@ -487,6 +731,12 @@ pub struct SearchResult {
     pub facet_distribution: Option<BTreeMap<String, IndexMap<String, u64>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub facet_stats: Option<BTreeMap<String, FacetStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_ranges: Option<BTreeMap<String, IndexMap<String, u64>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_histogram: Option<BTreeMap<String, IndexMap<String, u64>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_hierarchy: Option<BTreeMap<String, IndexMap<String, FacetHierarchyNode>>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub semantic_hit_count: Option<u32>,
@@ -507,6 +757,9 @@ impl fmt::Debug for SearchResult {
             hits_info,
             facet_distribution,
             facet_stats,
+            facet_ranges,
+            facet_histogram,
+            facet_hierarchy,
             semantic_hit_count,
             degraded,
             used_negative_operator,
@@ -530,6 +783,15 @@ impl fmt::Debug for SearchResult {
         if let Some(facet_stats) = facet_stats {
             debug.field("facet_stats", &facet_stats);
         }
+        if let Some(facet_ranges) = facet_ranges {
+            debug.field("facet_ranges", &facet_ranges);
+        }
+        if let Some(facet_histogram) = facet_histogram {
+            debug.field("facet_histogram", &facet_histogram);
+        }
+        if let Some(facet_hierarchy) = facet_hierarchy {
+            debug.field("facet_hierarchy", &facet_hierarchy);
+        }
         if let Some(semantic_hit_count) = semantic_hit_count {
             debug.field("semantic_hit_count", &semantic_hit_count);
         }
@@ -564,14 +826,69 @@ pub struct FacetStats {
 #[derive(Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FacetSearchResult {
-    pub facet_hits: Vec<FacetValueHit>,
+    pub facet_hits: FacetHits,
     pub facet_query: Option<String>,
     pub processing_time_ms: u128,
 }
 
+/// The hits of a facet search, the facet values or the number of documents in each range.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum FacetHits {
+    Values(Vec<FacetValueHit>),
+    Ranges(Vec<FacetRangeHit>),
+}
+
 /// Incorporate search rules in search query
-pub fn add_search_rules(query: &mut SearchQuery, rules: IndexSearchRules) {
-    query.filter = match (query.filter.take(), rules.filter) {
+///
+/// The attributes that are not allowed by the rules are silently dropped from the attributes to retrieve,
+/// highlight and crop, but the query is rejected when any other parameter uses them.
+pub fn add_search_rules(
+    query: &mut SearchQuery,
+    rules: IndexSearchRules,
+) -> Result<(), MeilisearchHttpError> {
+    check_search_rules_attributes(query, &rules)?;
+    let IndexSearchRules { filter, attributes_to_retrieve, limit } = rules;
+
+    // the attributes that are not allowed are neither retrieved, nor highlighted or cropped
+    if let Some(allowed) = attributes_to_retrieve.filter(|allowed| !allowed.contains("*")) {
+        let restrict = |attribute: &str, suffix: &str| -> Vec<String> {
+            if attribute == "*" {
+                allowed.iter().map(|allowed| format!("{allowed}{suffix}")).collect()
+            } else if is_allowed_by_search_rules(&allowed, attribute) {
+                vec![format!("{attribute}{suffix}")]
+            } else {
+                Vec::new()
+            }
+        };
+
+        let attributes_to_retrieve =
+            query.attributes_to_retrieve.take().unwrap_or_else(|| BTreeSet::from(["*".into()]));
+        query.attributes_to_retrieve = Some(
+            attributes_to_retrieve.iter().flat_map(|attribute| restrict(attribute, "")).collect(),
+        );
+        query.attributes_to_highlight = query.attributes_to_highlight.take().map(|attributes| {
+            attributes.iter().flat_map(|attribute| restrict(attribute, "")).collect()
+        });
+        query.attributes_to_crop = query.attributes_to_crop.take().map(|attributes| {
+            attributes
+                .iter()
+                .flat_map(|attribute| match attribute.rsplit_once(':') {
+                    Some((attribute, crop_length)) => {
+                        restrict(attribute, &format!(":{crop_length}"))
+                    }
+                    None => restrict(attribute, ""),
+                })
+                .collect()
+        });
+    }
+
+    if let Some(max_limit) = limit {
+        query.limit = query.limit.min(max_limit);
+        query.hits_per_page = query.hits_per_page.map(|hits_per_page| hits_per_page.min(max_limit));
+    }
+
+    query.filter = match (query.filter.take(), filter) {
         (None, rules_filter) => rules_filter,
         (filter, None) => filter,
         (Some(filter), Some(rules_filter)) => {
@@ -586,7 +903,78 @@ pub fn add_search_rules(query: &mut SearchQuery, rules: IndexSearchRules) {
 
             Some(Value::Array([filter, rules_filter].concat()))
         }
+    };
+    Ok(())
+}
+
+/// Whether the attributes retrievable according to the search rules include an attribute,
+/// the attributes nested in an allowed attribute being allowed too.
+fn is_allowed_by_search_rules(allowed: &BTreeSet<String>, attribute: &str) -> bool {
+    allowed.contains("*") || allowed.iter().any(|allowed| milli::is_faceted_by(attribute, allowed))
+}
+
+/// Ensures that an attribute is allowed by the search rules, `parameter` is the parameter naming it.
+pub fn check_search_rules_attribute(
+    rules: &IndexSearchRules,
+    parameter: &'static str,
+    attribute: &str,
+) -> Result<(), MeilisearchHttpError> {
+    let Some(allowed) = rules.attributes_to_retrieve.as_ref() else { return Ok(()) };
+    if is_allowed_by_search_rules(allowed, attribute) {
+        Ok(())
+    } else {
+        Err(MeilisearchHttpError::ForbiddenSearchAttribute {
+            parameter,
+            attribute: attribute.to_owned(),
+        })
+    }
+}
+
+/// Ensures that the parameters of the query only use the attributes allowed by the search rules,
+/// as their results would otherwise reveal the values of the other attributes.
+fn check_search_rules_attributes(
+    query: &SearchQuery,
+    rules: &IndexSearchRules,
+) -> Result<(), MeilisearchHttpError> {
+    let Some(allowed) = rules.attributes_to_retrieve.as_ref() else { return Ok(()) };
+    if allowed.contains("*") {
+        return Ok(());
+    }
+
+    let check =
+        |parameter, attribute: &str| check_search_rules_attribute(rules, parameter, attribute);
+    for facet in query.facets.iter().flatten() {
+        check("facets", facet)?;
+    }
+    for facet in query.facet_ranges.iter().flat_map(BTreeMap::keys) {
+        check("facetRanges", facet)?;
+    }
+    for facet in query.facet_histogram.iter().flat_map(BTreeMap::keys) {
+        check("facetHistogram", facet)?;
+    }
+    for attribute in query.attributes_to_search_on.iter().flatten() {
+        check("attributesToSearchOn", attribute)?;
+    }
+    for sort in query.sort.iter().flatten() {
+        // the invalid sort criteria are reported by the search
+        match AscDesc::from_str(sort) {
+            Ok(sort) => check("sort", sort.field().unwrap_or("_geo"))?,
+            Err(_) => continue,
+        }
+    }
+    if let Some(filter) = &query.filter {
+        // the invalid filters are reported by the search
+        if let Ok(Some(filter)) = parse_filter(filter) {
+            for field in filter.fields() {
+                check("filter", field)?;
+            }
+        }
+    }
+    // the positions of the matches are returned for all the displayed attributes
+    if query.show_matches_position {
+        return Err(MeilisearchHttpError::ForbiddenSearchMatchesPosition);
     }
+    Ok(())
 }
 
 fn prepare_search<'t>(
@@ -605,23 +993,28 @@ fn prepare_search<'t>(
                 search.query(q);
             }
         }
-        SearchKind::SemanticOnly { embedder_name, embedder } => {
-            let vector = match query.vector.clone() {
-                Some(vector) => vector,
-                None => embedder
-                    .embed_one(query.q.clone().unwrap())
-                    .map_err(milli::vector::Error::from)
-                    .map_err(milli::Error::from)?,
-            };
+        SearchKind::SemanticOnly { embedder_name, embedder, query_cache } => {
+            let vector = match (query.vector.clone(), &query.image) {
+                (Some(vector), _) => Ok(vector),
+                // images are not worth caching, they are unlikely to be searched twice
+                (None, Some(image)) => {
+                    let text = query.q.clone().unwrap_or_default();
+                    embedder.embed_one_input(EmbedderInput::new(text, Some(image.clone())))
+                }
+                (None, None) => query_cache.embed(embedder, query.q.as_deref().unwrap()),
+            }
+            .map_err(milli::vector::Error::from)
+            .map_err(milli::Error::from)?;
 
             search.semantic(embedder_name.clone(), embedder.clone(), Some(vector));
         }
-        SearchKind::Hybrid { embedder_name, embedder, semantic_ratio: _ } => {
+        SearchKind::Hybrid { embedder_name, embedder, query_cache, .. } => {
             if let Some(q) = &query.q {
                 search.query(q);
             }
             // will be embedded in hybrid search if necessary
             search.semantic(embedder_name.clone(), embedder.clone(), query.vector.clone());
+            search.query_embedding_cache(query_cache.clone());
         }
     }
 
@@ -716,11 +1109,29 @@ pub fn perform_search(
             let semantic_hit_count = results.document_scores.len() as u32;
             (results, Some(semantic_hit_count))
         }
-        SearchKind::Hybrid { semantic_ratio, .. } => search.execute_hybrid(*semantic_ratio)?,
+        SearchKind::Hybrid { semantic_ratio, fusion, .. } => {
+            search.execute_hybrid(*semantic_ratio, *fusion)?
+        }
     };
 
     let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
 
+    // the prompt of the embedder if its document template is split in chunks,
+    // used to retrieve the chunk of text that matched the semantic search.
+    let chunked_prompt = match &search_kind {
+        SearchKind::KeywordOnly => None,
+        SearchKind::SemanticOnly { embedder_name, .. }
+        | SearchKind::Hybrid { embedder_name, .. } => index
+            .embedding_configs(&rtxn)?
+            .into_iter()
+            .find(|(name, _)| name == embedder_name)
+            .map(|(_, config)| config.prompt)
+            .filter(|prompt| prompt.chunking.is_some())
+            .map(Prompt::try_from)
+            .transpose()
+            .map_err(milli::Error::from)?,
+    };
+
     let displayed_ids = index
         .displayed_fields_ids(&rtxn)?
         .map(|fields| fields.into_iter().collect::<BTreeSet<_>>())
@@ -831,12 +1242,25 @@ pub fn perform_search(
         let ranking_score_details =
             query.show_ranking_score_details.then(|| ScoreDetails::to_json_map(score.iter()));
 
+        let vector_index = score.iter().find_map(|details| match details {
+            ScoreDetails::Vector(score_details::Vector { vector_index, .. }) => *vector_index,
+            _ => None,
+        });
+        let matched_chunk = match (&chunked_prompt, vector_index) {
+            (Some(prompt), Some(vector_index)) => {
+                let text = prompt.render_document(obkv, &fields_ids_map)?;
+                prompt.chunk(&text).get(vector_index as usize).map(|chunk| chunk.to_string())
+            }
+            _ => None,
+        };
+
         let hit = SearchHit {
             document,
             formatted,
             matches_position,
             ranking_score_details,
             ranking_score,
+            matched_chunk,
         };
         documents.push(hit);
     }
@@ -859,7 +1283,46 @@ pub fn perform_search(
         HitsInfo::OffsetLimit { limit: query.limit, offset, estimated_total_hits: number_of_hits }
     };
 
-    let (facet_distribution, facet_stats) = match query.facets {
+    let (facet_ranges, facet_histogram) = match (&query.facet_ranges, &query.facet_histogram) {
+        (None, None) => (None, None),
+        (ranges, histogram) => {
+            let mut facet_distribution = index.facets_distribution(&rtxn);
+            let max_values_by_facet = index
+                .max_values_per_facet(&rtxn)
+                .map_err(milli::Error::from)?
+                .map(|x| x as usize)
+                .unwrap_or(DEFAULT_VALUES_PER_FACET);
+            facet_distribution
+                .max_values_per_facet(max_values_by_facet)
+                .candidates(candidates.clone());
+
+            let ranges = ranges
+                .as_ref()
+                .map(|ranges| {
+                    let ranges = ranges
+                        .iter()
+                        .map(|(name, ranges)| {
+                            (name.clone(), ranges.iter().copied().map(FacetRange::from).collect())
+                        })
+                        .collect();
+                    facet_distribution.compute_ranges(&ranges)
+                })
+                .transpose()?;
+            let histogram = histogram
+                .as_ref()
+                .map(|histogram| {
+                    let intervals = histogram
+                        .iter()
+                        .map(|(name, histogram)| (name.clone(), histogram.interval))
+                        .collect();
+                    facet_distribution.compute_histogram(&intervals)
+                })
+                .transpose()?;
+            (ranges, histogram)
+        }
+    };
+
+    let (facet_distribution, facet_stats, facet_hierarchy) = match query.facets {
         Some(ref fields) => {
             let mut facet_distribution = index.facets_distribution(&rtxn);
 
@@ -884,9 +1347,27 @@ pub fn perform_search(
                 .default_order_by(sort_facet_values_by.get("*"))
                 .execute()?;
             let stats = facet_distribution.compute_stats()?;
-            (Some(distribution), Some(stats))
+
+            // the hierarchical facets are returned as a tree along with their distribution.
+            let filterable_fields = index.filterable_fields(&rtxn).map_err(milli::Error::from)?;
+            let separators: BTreeMap<_, _> = index
+                .hierarchical_facets(&rtxn)
+                .map_err(milli::Error::from)?
+                .into_iter()
+                .filter(|(name, _)| milli::is_faceted(name, &filterable_fields))
+                .filter(|(name, _)| fields.iter().any(|f| f == "*" || f == name))
+                .collect();
+            let hierarchy = if separators.is_empty() {
+                None
+            } else {
+                Some(
+                    facet_distribution
+                        .compute_hierarchy(&separators, query.facet_hierarchy_depth)?,
+                )
+            };
+            (Some(distribution), Some(stats), hierarchy)
         }
-        None => (None, None),
+        None => (None, None, None),
     };
 
     let facet_stats = facet_stats.map(|stats| {
@@ -900,6 +1381,9 @@ pub fn perform_search(
         processing_time_ms: before_search.elapsed().as_millis(),
         facet_distribution,
         facet_stats,
+        facet_ranges,
+        facet_histogram,
+        facet_hierarchy,
         degraded,
         used_negative_operator,
         semantic_hit_count,
@@ -912,6 +1396,7 @@ pub fn perform_facet_search(
     search_query: SearchQuery,
     facet_query: Option<String>,
     facet_name: String,
+    ranges: Option<Vec<FacetRangeQuery>>,
     search_kind: SearchKind,
 ) -> Result<FacetSearchResult, MeilisearchHttpError> {
     let before_search = Instant::now();
@@ -933,9 +1418,16 @@ pub fn perform_facet_search(
     if let Some(max_facets) = index.max_values_per_facet(&rtxn)? {
         facet_search.max_values(max_facets as usize);
     }
+    let facet_hits = match ranges {
+        Some(ranges) => {
+            let ranges: Vec<_> = ranges.into_iter().map(FacetRange::from).collect();
+            FacetHits::Ranges(facet_search.execute_ranges(&ranges)?)
+        }
+        None => FacetHits::Values(facet_search.execute()?),
+    };
 
     Ok(FacetSearchResult {
-        facet_hits: facet_search.execute()?,
+        facet_hits,
         facet_query,
         processing_time_ms: before_search.elapsed().as_millis(),
     })
@@ -421,7 +421,7 @@ async fn error_add_api_key_invalid_parameters_actions() {
     meili_snap::snapshot!(code, @"400 Bad Request");
     meili_snap::snapshot!(meili_snap::json_string!(response, { ".createdAt" => "[ignored]", ".updatedAt" => "[ignored]" }), @r###"
     {
-      "message": "Unknown value `doc.add` at `.actions[0]`: expected one of `*`, `search`, `documents.*`, `documents.add`, `documents.get`, `documents.delete`, `indexes.*`, `indexes.create`, `indexes.get`, `indexes.update`, `indexes.delete`, `indexes.swap`, `tasks.*`, `tasks.cancel`, `tasks.delete`, `tasks.get`, `settings.*`, `settings.get`, `settings.update`, `stats.*`, `stats.get`, `metrics.*`, `metrics.get`, `dumps.*`, `dumps.create`, `snapshots.*`, `snapshots.create`, `version`, `keys.create`, `keys.get`, `keys.update`, `keys.delete`, `experimental.get`, `experimental.update`",
+      "message": "Unknown value `doc.add` at `.actions[0]`: expected one of `*`, `search`, `documents.*`, `documents.add`, `documents.get`, `documents.delete`, `indexes.*`, `indexes.create`, `indexes.get`, `indexes.update`, `indexes.delete`, `indexes.swap`, `tasks.*`, `tasks.cancel`, `tasks.delete`, `tasks.get`, `settings.*`, `settings.get`, `settings.update`, `stats.*`, `stats.get`, `metrics.*`, `metrics.get`, `dumps.*`, `dumps.create`, `snapshots.*`, `snapshots.create`, `version`, `keys.create`, `keys.get`, `keys.update`, `keys.delete`, `experimental.get`, `experimental.update`, `dumps.import`, `settings.filterableAttributes.get`, `settings.filterableAttributes.update`, `settings.sortableAttributes.get`, `settings.sortableAttributes.update`, `settings.displayedAttributes.get`, `settings.displayedAttributes.update`, `settings.typoTolerance.get`, `settings.typoTolerance.update`, `settings.searchableAttributes.get`, `settings.searchableAttributes.update`, `settings.stopWords.get`, `settings.stopWords.update`, `settings.nonSeparatorTokens.get`, `settings.nonSeparatorTokens.update`, `settings.separatorTokens.get`, `settings.separatorTokens.update`, `settings.dictionary.get`, `settings.dictionary.update`, `settings.synonyms.get`, `settings.synonyms.update`, `settings.distinctAttribute.get`, `settings.distinctAttribute.update`, `settings.proximityPrecision.get`, `settings.proximityPrecision.update`, `settings.rankingRules.get`, `settings.rankingRules.update`, `settings.faceting.get`, `settings.faceting.update`, `settings.pagination.get`, `settings.pagination.update`, `settings.embedders.get`, `settings.embedders.update`, `settings.searchCutoffMs.get`, `settings.searchCutoffMs.update`, `auditLog.get`, `experimental.vectorStore.update`, `experimental.metrics.update`, `experimental.logsRoute.update`, `experimental.exportPuffinReports.update`",
       "code": "invalid_api_key_actions",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_api_key_actions"
@@ -1779,3 +1779,72 @@ async fn error_access_api_key_routes_no_master_key_set() {
     "###);
     meili_snap::snapshot!(code, @"401 Unauthorized");
 }
+
+#[actix_rt::test]
+async fn api_key_usage() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+    let (task, _) = server.index("products").create(None).await;
+    server.wait_task(task.uid()).await;
+
+    let content = json!({
+        "uid": "4bc0887a-0e41-4f3b-935d-0c451dcee9c8",
+        "indexes": ["products"],
+        "actions": ["search", "indexes.get"],
+        "expiresAt": null
+    });
+    let (response, code) = server.add_api_key(content).await;
+    meili_snap::snapshot!(code, @"201 Created");
+    let key = response["key"].as_str().unwrap().to_string();
+
+    server.use_api_key(&key);
+    let index = server.index("products");
+    for _ in 0..2 {
+        let (_, code) = index.search_post(json!({})).await;
+        meili_snap::snapshot!(code, @"200 OK");
+    }
+    let (_, code) = index.get().await;
+    meili_snap::snapshot!(code, @"200 OK");
+
+    server.use_api_key("MASTER_KEY");
+    let (response, code) = server.get_api_key("4bc0887a-0e41-4f3b-935d-0c451dcee9c8").await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response, { ".createdAt" => "[ignored]", ".updatedAt" => "[ignored]", ".lastUsedAt" => "[ignored]", ".key" => "[ignored]" }), @r###"
+    {
+      "name": null,
+      "description": null,
+      "key": "[ignored]",
+      "uid": "4bc0887a-0e41-4f3b-935d-0c451dcee9c8",
+      "actions": [
+        "search",
+        "indexes.get"
+      ],
+      "indexes": [
+        "products"
+      ],
+      "expiresAt": null,
+      "createdAt": "[ignored]",
+      "updatedAt": "[ignored]",
+      "lastUsedAt": "[ignored]",
+      "requestCounts": {
+        "search": 2,
+        "indexes.get": 1
+      },
+      "masterKeyGeneration": 0
+    }
+    "###);
+
+    // only the default keys were never used
+    let (response, code) = server.list_api_keys("?unusedSince=2000-01-01").await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["results"].as_array().unwrap().iter().map(|key| &key["name"]).collect::<Vec<_>>()), @r###"
+    [
+      "Default Search API Key",
+      "Default Admin API Key"
+    ]
+    "###);
+
+    let (response, code) = server.list_api_keys("?unusedSince=2999-01-01").await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(response["total"], @"3");
+}
@@ -0,0 +1,196 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn audit_log_records_administrative_requests() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+
+    let (task, code) = server.index("products").create(None).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(task.uid()).await;
+    // the documents are not administrative
+    let (task, code) = server.index("products").add_documents(json!([{ "id": 1 }]), None).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(task.uid()).await;
+
+    let (response, code) = server
+        .add_api_key(json!({
+            "uid": "a1b2c3d4-0000-4000-8000-000000000001",
+            "actions": ["settings.synonyms.update", "search"],
+            "indexes": ["products"],
+            "expiresAt": null,
+        }))
+        .await;
+    snapshot!(code, @"201 Created");
+    server.use_api_key(response["key"].as_str().unwrap());
+
+    let index = server.index("products");
+    let (_, code) = index.update_settings_synonyms(json!({ "tv": ["television"] })).await;
+    snapshot!(code, @"202 Accepted");
+    // the searches are not recorded, the rejected administrative requests are
+    let (_, code) = index.search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+    let (_, code) = index.delete().await;
+    snapshot!(code, @"403 Forbidden");
+
+    server.use_api_key("MASTER_KEY");
+    let (response, code) = server.service.get("/audit-log").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".results[].timestamp" => "[timestamp]" }), @r###"
+    {
+      "results": [
+        {
+          "uid": 3,
+          "keyUid": "a1b2c3d4-0000-4000-8000-000000000001",
+          "action": "indexes.delete",
+          "indexUid": "products",
+          "timestamp": "[timestamp]",
+          "summary": "DELETE /indexes/products 403"
+        },
+        {
+          "uid": 2,
+          "keyUid": "a1b2c3d4-0000-4000-8000-000000000001",
+          "action": "settings.synonyms.update",
+          "indexUid": "products",
+          "timestamp": "[timestamp]",
+          "summary": "PUT /indexes/products/settings/synonyms 202"
+        },
+        {
+          "uid": 1,
+          "keyUid": null,
+          "action": "keys.create",
+          "indexUid": null,
+          "timestamp": "[timestamp]",
+          "summary": "POST /keys 201"
+        },
+        {
+          "uid": 0,
+          "keyUid": null,
+          "action": "indexes.create",
+          "indexUid": null,
+          "timestamp": "[timestamp]",
+          "summary": "POST /indexes 202"
+        }
+      ],
+      "limit": 20,
+      "next": null
+    }
+    "###);
+
+    let (response, code) = server
+        .service
+        .get("/audit-log?keyUid=a1b2c3d4-0000-4000-8000-000000000001&action=settings.synonyms.update")
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["results"].as_array().unwrap().len(), @"1");
+
+    let (response, code) = server.service.get("/audit-log?beforeTimestamp=2000-01-01").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"]), @"[]");
+}
+
+#[actix_rt::test]
+async fn audit_log_pagination() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+
+    for name in ["a", "b", "c", "d"] {
+        let (_, code) = server
+            .add_api_key(
+                json!({ "name": name, "actions": ["search"], "indexes": ["*"], "expiresAt": null }),
+            )
+            .await;
+        snapshot!(code, @"201 Created");
+    }
+
+    let uids = |response: &crate::common::Value| {
+        let uids: Vec<_> = response["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["uid"].as_u64().unwrap())
+            .collect();
+        format!("{uids:?} next: {}", response["next"])
+    };
+
+    let (response, code) = server.service.get("/audit-log?limit=2").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(uids(&response), @"[3, 2] next: 2");
+
+    let (response, code) = server.service.get("/audit-log?limit=2&beforeUid=2").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(uids(&response), @"[1, 0] next: null");
+
+    let (response, code) = server.service.get("/audit-log?afterUid=1").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(uids(&response), @"[3, 2] next: null");
+}
+
+#[actix_rt::test]
+async fn audit_log_restricted_to_the_indexes_of_the_key() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+
+    for uid in ["products", "movies"] {
+        let (task, code) = server.index(uid).update_settings(json!({ "stopWords": ["the"] })).await;
+        snapshot!(code, @"202 Accepted");
+        server.wait_task(task.uid()).await;
+    }
+
+    let (response, code) = server
+        .add_api_key(json!({
+            "actions": ["auditLog.get"],
+            "indexes": ["products"],
+            "expiresAt": null,
+        }))
+        .await;
+    snapshot!(code, @"201 Created");
+    server.use_api_key(response["key"].as_str().unwrap());
+
+    let (response, code) = server.service.get("/audit-log").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"], { "[].timestamp" => "[timestamp]" }), @r###"
+    [
+      {
+        "uid": 0,
+        "keyUid": null,
+        "action": "settings.update",
+        "indexUid": "products",
+        "timestamp": "[timestamp]",
+        "summary": "PATCH /indexes/products/settings 202"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn error_audit_log_bad_parameters() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+
+    let (response, code) = server.service.get("/audit-log?keyUid=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_audit_log_key_uid""###);
+
+    let (response, code) = server.service.get("/audit-log?beforeUid=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_audit_log_before_uid""###);
+
+    let (response, code) = server.service.get("/audit-log?action=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_audit_log_action""###);
+
+    let (response, code) = server.service.get("/audit-log?afterTimestamp=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `afterTimestamp`: `doggo` is an invalid date-time. It should follow the YYYY-MM-DD or RFC 3339 date-time format.",
+      "code": "invalid_audit_log_after_timestamp",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_audit_log_after_timestamp"
+    }
+    "###);
+}
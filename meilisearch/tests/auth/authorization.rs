@@ -34,28 +34,30 @@ pub static AUTHORIZATIONS: Lazy<HashMap<(&'static str, &'static str), HashSet<&'
             ("GET",     "/indexes") =>                                         hashset!{"indexes.get", "indexes.*", "*"},
             ("POST",    "/swap-indexes") =>                                    hashset!{"indexes.swap", "indexes.*", "*"},
             ("GET",     "/indexes/products/settings") =>                       hashset!{"settings.get", "settings.*", "*"},
-            ("GET",     "/indexes/products/settings/displayed-attributes") =>  hashset!{"settings.get", "settings.*", "*"},
-            ("GET",     "/indexes/products/settings/distinct-attribute") =>    hashset!{"settings.get", "settings.*", "*"},
-            ("GET",     "/indexes/products/settings/filterable-attributes") => hashset!{"settings.get", "settings.*", "*"},
-            ("GET",     "/indexes/products/settings/ranking-rules") =>         hashset!{"settings.get", "settings.*", "*"},
-            ("GET",     "/indexes/products/settings/searchable-attributes") => hashset!{"settings.get", "settings.*", "*"},
-            ("GET",     "/indexes/products/settings/sortable-attributes") =>   hashset!{"settings.get", "settings.*", "*"},
-            ("GET",     "/indexes/products/settings/stop-words") =>            hashset!{"settings.get", "settings.*", "*"},
-            ("GET",     "/indexes/products/settings/synonyms") =>              hashset!{"settings.get", "settings.*", "*"},
+            ("GET",     "/indexes/products/settings/displayed-attributes") =>  hashset!{"settings.displayedAttributes.get", "settings.get", "settings.*", "*"},
+            ("GET",     "/indexes/products/settings/distinct-attribute") =>    hashset!{"settings.distinctAttribute.get", "settings.get", "settings.*", "*"},
+            ("GET",     "/indexes/products/settings/filterable-attributes") => hashset!{"settings.filterableAttributes.get", "settings.get", "settings.*", "*"},
+            ("GET",     "/indexes/products/settings/ranking-rules") =>         hashset!{"settings.rankingRules.get", "settings.get", "settings.*", "*"},
+            ("GET",     "/indexes/products/settings/searchable-attributes") => hashset!{"settings.searchableAttributes.get", "settings.get", "settings.*", "*"},
+            ("GET",     "/indexes/products/settings/sortable-attributes") =>   hashset!{"settings.sortableAttributes.get", "settings.get", "settings.*", "*"},
+            ("GET",     "/indexes/products/settings/stop-words") =>            hashset!{"settings.stopWords.get", "settings.get", "settings.*", "*"},
+            ("GET",     "/indexes/products/settings/synonyms") =>              hashset!{"settings.synonyms.get", "settings.get", "settings.*", "*"},
             ("DELETE",  "/indexes/products/settings") =>                       hashset!{"settings.update", "settings.*", "*"},
             ("PATCH",   "/indexes/products/settings") =>                       hashset!{"settings.update", "settings.*", "*"},
-            ("PATCH",   "/indexes/products/settings/typo-tolerance") =>        hashset!{"settings.update", "settings.*", "*"},
-            ("PUT",     "/indexes/products/settings/displayed-attributes") =>  hashset!{"settings.update", "settings.*", "*"},
-            ("PUT",     "/indexes/products/settings/distinct-attribute") =>    hashset!{"settings.update", "settings.*", "*"},
-            ("PUT",     "/indexes/products/settings/filterable-attributes") => hashset!{"settings.update", "settings.*", "*"},
-            ("PUT",     "/indexes/products/settings/ranking-rules") =>         hashset!{"settings.update", "settings.*", "*"},
-            ("PUT",     "/indexes/products/settings/searchable-attributes") => hashset!{"settings.update", "settings.*", "*"},
-            ("PUT",     "/indexes/products/settings/sortable-attributes") =>   hashset!{"settings.update", "settings.*", "*"},
-            ("PUT",     "/indexes/products/settings/stop-words") =>            hashset!{"settings.update", "settings.*", "*"},
-            ("PUT",     "/indexes/products/settings/synonyms") =>              hashset!{"settings.update", "settings.*", "*"},
+            ("PATCH",   "/indexes/products/settings/typo-tolerance") =>        hashset!{"settings.typoTolerance.update", "settings.update", "settings.*", "*"},
+            ("PUT",     "/indexes/products/settings/displayed-attributes") =>  hashset!{"settings.displayedAttributes.update", "settings.update", "settings.*", "*"},
+            ("PUT",     "/indexes/products/settings/distinct-attribute") =>    hashset!{"settings.distinctAttribute.update", "settings.update", "settings.*", "*"},
+            ("PUT",     "/indexes/products/settings/filterable-attributes") => hashset!{"settings.filterableAttributes.update", "settings.update", "settings.*", "*"},
+            ("PUT",     "/indexes/products/settings/ranking-rules") =>         hashset!{"settings.rankingRules.update", "settings.update", "settings.*", "*"},
+            ("PUT",     "/indexes/products/settings/searchable-attributes") => hashset!{"settings.searchableAttributes.update", "settings.update", "settings.*", "*"},
+            ("PUT",     "/indexes/products/settings/sortable-attributes") =>   hashset!{"settings.sortableAttributes.update", "settings.update", "settings.*", "*"},
+            ("PUT",     "/indexes/products/settings/stop-words") =>            hashset!{"settings.stopWords.update", "settings.update", "settings.*", "*"},
+            ("PUT",     "/indexes/products/settings/synonyms") =>              hashset!{"settings.synonyms.update", "settings.update", "settings.*", "*"},
+            ("POST",    "/indexes/products/embedders/default/render") =>       hashset!{"settings.embedders.get", "settings.get", "settings.*", "*"},
             ("GET",     "/indexes/products/stats") =>                          hashset!{"stats.get", "stats.*", "*"},
             ("GET",     "/stats") =>                                           hashset!{"stats.get", "stats.*", "*"},
             ("POST",    "/dumps") =>                                           hashset!{"dumps.create", "dumps.*", "*"},
+            ("POST",    "/dumps/import") =>                                    hashset!{"dumps.import", "dumps.*", "*"},
             ("POST",    "/snapshots") =>                                       hashset!{"snapshots.create", "snapshots.*", "*"},
             ("GET",     "/version") =>                                         hashset!{"version", "*"},
             ("GET",     "/metrics") =>                                         hashset!{"metrics.get", "metrics.*", "*"},
@@ -67,7 +69,8 @@ pub static AUTHORIZATIONS: Lazy<HashMap<(&'static str, &'static str), HashSet<&'
             ("POST",    "/keys") =>                                            hashset!{"keys.create", "*"},
             ("GET",     "/keys") =>                                            hashset!{"keys.get", "*"},
             ("GET",     "/experimental-features") =>                           hashset!{"experimental.get", "*"},
-            ("PATCH",   "/experimental-features") =>                           hashset!{"experimental.update", "*"},
+            ("PATCH",   "/experimental-features") =>                           hashset!{"experimental.update", "experimental.vectorStore.update", "experimental.metrics.update", "experimental.logsRoute.update", "experimental.exportPuffinReports.update", "*"},
+            ("GET",     "/audit-log") =>                                       hashset!{"auditLog.get", "*"},
         };
 
         authorizations
@@ -1035,3 +1038,117 @@ async fn error_creating_index_without_index() {
     let (response, code) = index.create(None).await;
     assert_eq!(403, code, "{:?}", &response);
 }
+
+#[actix_rt::test]
+async fn access_restricted_to_some_settings() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+    let (task, code) = server.index("products").create(None).await;
+    assert_eq!(202, code, "{:?}", &task);
+    server.wait_task(task.uid()).await;
+
+    let content = json!({
+        "indexes": ["products"],
+        "actions": ["settings.synonyms.update", "settings.stopWords.update", "settings.synonyms.get"],
+        "expiresAt": null,
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &response);
+    server.use_api_key(response["key"].as_str().unwrap());
+
+    let index = server.index("products");
+    let (response, code) = index.update_settings_synonyms(json!({ "tv": ["television"] })).await;
+    assert_eq!(202, code, "{:?}", &response);
+    let (response, code) = index.update_settings_stop_words(json!(["the"])).await;
+    assert_eq!(202, code, "{:?}", &response);
+    let (response, code) = server.service.get("/indexes/products/settings/synonyms").await;
+    assert_eq!(200, code, "{:?}", &response);
+
+    // the other settings can't be read nor changed
+    let (response, code) = server.service.get("/indexes/products/settings/stop-words").await;
+    assert_eq!(403, code, "{:?}", &response);
+    let (response, code) = index.update_settings_filterable_attributes(json!(["color"])).await;
+    assert_eq!(403, code, "{:?}", &response);
+    let (response, code) = index.update_settings(json!({ "synonyms": {} })).await;
+    assert_eq!(403, code, "{:?}", &response);
+}
+
+#[actix_rt::test]
+async fn access_restricted_to_some_experimental_features() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+
+    let content = json!({
+        "indexes": ["*"],
+        "actions": ["experimental.metrics.update"],
+        "expiresAt": null,
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &response);
+    server.use_api_key(response["key"].as_str().unwrap());
+
+    let (response, code) = server.set_features(json!({ "metrics": true })).await;
+    assert_eq!(200, code, "{:?}", &response);
+    assert_eq!(response["metrics"], json!(true));
+
+    // the other features can't be changed, even along with an allowed one
+    let (response, code) = server.set_features(json!({ "vectorStore": true })).await;
+    assert_eq!(403, code, "{:?}", &response);
+    let (response, code) =
+        server.set_features(json!({ "metrics": false, "logsRoute": true })).await;
+    assert_eq!(403, code, "{:?}", &response);
+    let (response, code) = server.get_features().await;
+    assert_eq!(403, code, "{:?}", &response);
+}
+
+#[actix_rt::test]
+async fn render_embedder_requires_the_actions_of_its_parameters() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+    let (response, code) = server.set_features(json!({ "vectorStore": true })).await;
+    assert_eq!(200, code, "{:?}", &response);
+
+    let index = server.index("products");
+    let settings =
+        json!({ "embedders": { "manual": { "source": "userProvided", "dimensions": 2 } } });
+    let (task, code) = index.update_settings(settings).await;
+    assert_eq!(202, code, "{:?}", &task);
+    server.wait_task(task.uid()).await;
+    let documents = json!([{ "id": 1, "title": "Shazam!", "_vectors": { "manual": [1.0, 3.0] } }]);
+    let (task, code) = index.add_documents(documents, None).await;
+    assert_eq!(202, code, "{:?}", &task);
+    server.wait_task(task.uid()).await;
+
+    let content =
+        json!({ "indexes": ["products"], "actions": ["settings.get"], "expiresAt": null });
+    let (settings_key, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &settings_key);
+    let content = json!({
+        "indexes": ["products"],
+        "actions": ["settings.get", "documents.get"],
+        "expiresAt": null,
+    });
+    let (documents_key, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &documents_key);
+
+    server.use_api_key(settings_key["key"].as_str().unwrap());
+    let index = server.index("products");
+    let (response, code) = index
+        .render_embedder("manual", json!({ "document": { "id": 1, "title": "Shazam!" } }))
+        .await;
+    assert_eq!(200, code, "{:?}", &response);
+    let (response, code) = index.render_embedder("manual", json!({ "documentId": "1" })).await;
+    assert_eq!(403, code, "{:?}", &response);
+    assert_eq!(response, INVALID_RESPONSE.clone());
+
+    server.use_api_key(documents_key["key"].as_str().unwrap());
+    let index = server.index("products");
+    let (response, code) = index.render_embedder("manual", json!({ "documentId": "1" })).await;
+    assert_eq!(200, code, "{:?}", &response);
+    // embedding the rendered template calls the embedder like an indexation does
+    let (response, code) = index
+        .render_embedder("manual", json!({ "documentId": "1", "retrieveEmbeddings": true }))
+        .await;
+    assert_eq!(403, code, "{:?}", &response);
+    assert_eq!(response, INVALID_RESPONSE.clone());
+}
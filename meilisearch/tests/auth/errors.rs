@@ -90,7 +90,7 @@ async fn create_api_key_bad_actions() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Unknown value `doggo` at `.actions[0]`: expected one of `*`, `search`, `documents.*`, `documents.add`, `documents.get`, `documents.delete`, `indexes.*`, `indexes.create`, `indexes.get`, `indexes.update`, `indexes.delete`, `indexes.swap`, `tasks.*`, `tasks.cancel`, `tasks.delete`, `tasks.get`, `settings.*`, `settings.get`, `settings.update`, `stats.*`, `stats.get`, `metrics.*`, `metrics.get`, `dumps.*`, `dumps.create`, `snapshots.*`, `snapshots.create`, `version`, `keys.create`, `keys.get`, `keys.update`, `keys.delete`, `experimental.get`, `experimental.update`",
+      "message": "Unknown value `doggo` at `.actions[0]`: expected one of `*`, `search`, `documents.*`, `documents.add`, `documents.get`, `documents.delete`, `indexes.*`, `indexes.create`, `indexes.get`, `indexes.update`, `indexes.delete`, `indexes.swap`, `tasks.*`, `tasks.cancel`, `tasks.delete`, `tasks.get`, `settings.*`, `settings.get`, `settings.update`, `stats.*`, `stats.get`, `metrics.*`, `metrics.get`, `dumps.*`, `dumps.create`, `snapshots.*`, `snapshots.create`, `version`, `keys.create`, `keys.get`, `keys.update`, `keys.delete`, `experimental.get`, `experimental.update`, `dumps.import`, `settings.filterableAttributes.get`, `settings.filterableAttributes.update`, `settings.sortableAttributes.get`, `settings.sortableAttributes.update`, `settings.displayedAttributes.get`, `settings.displayedAttributes.update`, `settings.typoTolerance.get`, `settings.typoTolerance.update`, `settings.searchableAttributes.get`, `settings.searchableAttributes.update`, `settings.stopWords.get`, `settings.stopWords.update`, `settings.nonSeparatorTokens.get`, `settings.nonSeparatorTokens.update`, `settings.separatorTokens.get`, `settings.separatorTokens.update`, `settings.dictionary.get`, `settings.dictionary.update`, `settings.synonyms.get`, `settings.synonyms.update`, `settings.distinctAttribute.get`, `settings.distinctAttribute.update`, `settings.proximityPrecision.get`, `settings.proximityPrecision.update`, `settings.rankingRules.get`, `settings.rankingRules.update`, `settings.faceting.get`, `settings.faceting.update`, `settings.pagination.get`, `settings.pagination.update`, `settings.embedders.get`, `settings.embedders.update`, `settings.searchCutoffMs.get`, `settings.searchCutoffMs.update`, `auditLog.get`, `experimental.vectorStore.update`, `experimental.metrics.update`, `experimental.logsRoute.update`, `experimental.exportPuffinReports.update`",
       "code": "invalid_api_key_actions",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_api_key_actions"
@@ -138,7 +138,7 @@ async fn create_api_key_bad_expires_at() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Unknown field `expires_at`: did you mean `expiresAt`? expected one of `description`, `name`, `uid`, `actions`, `indexes`, `expiresAt`",
+      "message": "Unknown field `expires_at`: did you mean `expiresAt`? expected one of `description`, `name`, `uid`, `actions`, `indexes`, `expiresAt`, `searchRules`, `rateLimit`, `dailyQuota`, `tenantTokenKeys`",
       "code": "bad_request",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#bad_request"
@@ -150,7 +150,7 @@ async fn create_api_key_bad_expires_at() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Unknown field `expires_at`: did you mean `expiresAt`? expected one of `description`, `name`, `uid`, `actions`, `indexes`, `expiresAt`",
+      "message": "Unknown field `expires_at`: did you mean `expiresAt`? expected one of `description`, `name`, `uid`, `actions`, `indexes`, `expiresAt`, `searchRules`, `rateLimit`, `dailyQuota`, `tenantTokenKeys`",
       "code": "bad_request",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#bad_request"
@@ -225,7 +225,7 @@ async fn create_api_key_unexpected_field() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Unknown field `doggo`: expected one of `description`, `name`, `uid`, `actions`, `indexes`, `expiresAt`",
+      "message": "Unknown field `doggo`: expected one of `description`, `name`, `uid`, `actions`, `indexes`, `expiresAt`, `searchRules`, `rateLimit`, `dailyQuota`, `tenantTokenKeys`",
       "code": "bad_request",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#bad_request"
@@ -267,6 +267,23 @@ async fn list_api_keys_bad_limit() {
     "###);
 }
 
+#[actix_rt::test]
+async fn list_api_keys_bad_unused_since() {
+    let mut server = Server::new_auth().await;
+    server.use_admin_key("MASTER_KEY").await;
+
+    let (response, code) = server.list_api_keys("?unusedSince=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `unusedSince`: `doggo` is an invalid date-time. It should follow the YYYY-MM-DD or RFC 3339 date-time format.",
+      "code": "invalid_api_key_unused_since",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_api_key_unused_since"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn list_api_keys_unexpected_field() {
     let mut server = Server::new_auth().await;
@@ -276,7 +293,7 @@ async fn list_api_keys_unexpected_field() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Unknown parameter `doggo`: expected one of `offset`, `limit`",
+      "message": "Unknown parameter `doggo`: expected one of `offset`, `limit`, `unusedSince`",
       "code": "bad_request",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#bad_request"
@@ -386,6 +403,24 @@ async fn patch_api_keys_immutable_expires_at() {
     "###);
 }
 
+#[actix_rt::test]
+async fn patch_api_keys_immutable_search_rules() {
+    let mut server = Server::new_auth().await;
+    server.use_admin_key("MASTER_KEY").await;
+
+    let (response, code) =
+        server.patch_api_key("doggo", json!({ "searchRules": { "*": { "limit": 1 } } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Immutable field `searchRules`: expected one of `description`, `name`",
+      "code": "immutable_api_key_search_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#immutable_api_key_search_rules"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn patch_api_keys_immutable_created_at() {
     let mut server = Server::new_auth().await;
@@ -0,0 +1,111 @@
+use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+use tempfile::TempDir;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::common::{default_settings, Server};
+use crate::json;
+
+/// Starts a server whose master key replaced `OLD_MASTER_KEY`.
+async fn rotated_server(previous_master_keys_expire_at: Option<OffsetDateTime>) -> Server {
+    let dir = TempDir::new().unwrap();
+    let options = Opt {
+        previous_master_keys: vec![String::from("OLD_MASTER_KEY")],
+        previous_master_keys_expire_at,
+        ..default_settings(dir.path())
+    };
+    let mut server = Server::new_auth_with_options(options, dir).await;
+    server.use_api_key("MASTER_KEY");
+    let (task, code) = server.index("test").create(None).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(task.uid()).await;
+    server
+}
+
+/// Creates a search key and returns its uid and its value derived from `OLD_MASTER_KEY`.
+async fn key_derived_from_old_master_key(server: &mut Server) -> (String, String) {
+    let uid = "4bc0887a-0e41-4f3b-935d-0c451dcee9c0";
+    let (response, code) = server
+        .add_api_key(json!({
+            "uid": uid,
+            "actions": ["search"],
+            "indexes": ["test"],
+            "expiresAt": null,
+        }))
+        .await;
+    snapshot!(code, @"201 Created");
+    let current_key = response["key"].as_str().unwrap();
+
+    let mut keys = server.service.auth.generate_keys(Uuid::parse_str(uid).unwrap());
+    let (_, old_key) = keys.pop().unwrap();
+    assert_eq!(keys, vec![(0, current_key.to_string())]);
+    (uid.to_string(), old_key)
+}
+
+#[actix_rt::test]
+async fn previous_master_key_is_accepted() {
+    let mut server = rotated_server(None).await;
+    let (uid, old_key) = key_derived_from_old_master_key(&mut server).await;
+
+    server.use_api_key(&old_key);
+    let (_, code) = server.index("test").search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+
+    // the previous master key doesn't give access to every route
+    server.use_api_key("OLD_MASTER_KEY");
+    let (_, code) = server.list_api_keys("").await;
+    snapshot!(code, @"403 Forbidden");
+
+    server.use_api_key("MASTER_KEY");
+    let (response, code) = server.get_api_key(&uid).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["masterKeyGeneration"]), @"1");
+
+    // once used with the value derived from the current master key, the key is on the last generation
+    let (response, _) = server.get_api_key(&uid).await;
+    server.use_api_key(response["key"].as_str().unwrap());
+    let (_, code) = server.index("test").search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+
+    server.use_api_key("MASTER_KEY");
+    let (response, code) = server.get_api_key(&uid).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["masterKeyGeneration"]), @"0");
+}
+
+#[actix_rt::test]
+async fn previous_master_key_expires() {
+    let expire_at = OffsetDateTime::now_utc() - Duration::hours(1);
+    let mut server = rotated_server(Some(expire_at)).await;
+    let uid = "4bc0887a-0e41-4f3b-935d-0c451dcee9c0";
+    let (_, code) = server
+        .add_api_key(json!({
+            "uid": uid,
+            "actions": ["search"],
+            "indexes": ["test"],
+            "expiresAt": null,
+        }))
+        .await;
+    snapshot!(code, @"201 Created");
+
+    // the grace period is over, only the current master key is accepted
+    let keys = server.service.auth.generate_keys(Uuid::parse_str(uid).unwrap());
+    snapshot!(keys.len(), @"1");
+
+    let old_key = meilisearch_auth::generate_key_as_hexa(
+        Uuid::parse_str(uid).unwrap(),
+        "OLD_MASTER_KEY".as_bytes(),
+    );
+    server.use_api_key(old_key);
+    let (response, code) = server.index("test").search_post(json!({})).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The provided API key is invalid.",
+      "code": "invalid_api_key",
+      "type": "auth",
+      "link": "https://docs.meilisearch.com/errors#invalid_api_key"
+    }
+    "###);
+}
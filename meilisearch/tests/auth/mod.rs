@@ -1,8 +1,13 @@
 mod api_keys;
+mod audit_log;
 mod authorization;
 mod errors;
+mod master_key_rotation;
 mod payload;
+mod rate_limit;
+mod search_rules;
 mod tenant_token;
+mod tenant_token_public_keys;
 
 mod tenant_token_multi_search;
 
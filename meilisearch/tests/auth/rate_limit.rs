@@ -0,0 +1,150 @@
+use actix_web::test;
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{Server, Value};
+use crate::json;
+
+/// Creates a search key with the given limits and returns it.
+async fn limited_key(server: &mut Server, limits: Value) -> String {
+    server.use_api_key("MASTER_KEY");
+    let (task, code) = server.index("test").create(None).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(task.uid()).await;
+
+    let mut content = json!({ "actions": ["search"], "indexes": ["test"], "expiresAt": null });
+    content.as_object_mut().unwrap().extend(limits.as_object().unwrap().clone());
+    let (response, code) = server.add_api_key(content).await;
+    snapshot!(code, @"201 Created");
+    response["key"].as_str().unwrap().to_string()
+}
+
+#[actix_rt::test]
+async fn rate_limited_key() {
+    let mut server = Server::new_auth().await;
+    let key =
+        limited_key(&mut server, json!({ "rateLimit": { "requestsPerSecond": 1, "burst": 2 } }))
+            .await;
+    let app = server.init_web_app().await;
+
+    let search = || {
+        test::TestRequest::get()
+            .uri("/indexes/test/search")
+            .insert_header(("Authorization", format!("Bearer {key}")))
+            .to_request()
+    };
+
+    for _ in 0..2 {
+        let res = test::call_service(&app, search()).await;
+        snapshot!(res.status(), @"200 OK");
+    }
+
+    let res = test::call_service(&app, search()).await;
+    snapshot!(res.status(), @"429 Too Many Requests");
+    snapshot!(res.headers().get("retry-after").unwrap().to_str().unwrap(), @"1");
+    let body = test::read_body(res).await;
+    let response: Value = serde_json::from_slice(&body).unwrap();
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Too many requests were made with this API key. Retry after 1s.",
+      "code": "api_key_rate_limit_exceeded",
+      "type": "auth",
+      "link": "https://docs.meilisearch.com/errors#api_key_rate_limit_exceeded"
+    }
+    "###);
+
+    // the master key is never limited
+    let req = test::TestRequest::get()
+        .uri("/indexes/test/search")
+        .insert_header(("Authorization", "Bearer MASTER_KEY"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    snapshot!(res.status(), @"200 OK");
+}
+
+#[actix_rt::test]
+async fn key_with_daily_quota() {
+    let mut server = Server::new_auth().await;
+    let key = limited_key(&mut server, json!({ "dailyQuota": 2 })).await;
+    server.use_api_key(&key);
+
+    let index = server.index("test");
+    for _ in 0..2 {
+        let (_, code) = index.search_post(json!({})).await;
+        snapshot!(code, @"200 OK");
+    }
+
+    let (response, code) = index.search_post(json!({})).await;
+    snapshot!(code, @"429 Too Many Requests");
+    snapshot!(json_string!(response, { ".message" => "[message]" }), @r###"
+    {
+      "message": "[message]",
+      "code": "api_key_daily_quota_exceeded",
+      "type": "auth",
+      "link": "https://docs.meilisearch.com/errors#api_key_daily_quota_exceeded"
+    }
+    "###);
+
+    // the limits are shown with the key
+    server.use_api_key("MASTER_KEY");
+    let (response, code) = server.get_api_key(&key).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["dailyQuota"], @"2");
+}
+
+#[actix_rt::test]
+async fn keys_created_later_are_limited() {
+    let mut server = Server::new_auth().await;
+    let unlimited_key = limited_key(&mut server, json!({})).await;
+    server.use_api_key(&unlimited_key);
+    for _ in 0..3 {
+        let (_, code) = server.index("test").search_post(json!({})).await;
+        snapshot!(code, @"200 OK");
+    }
+
+    let key = limited_key(&mut server, json!({ "dailyQuota": 1 })).await;
+    server.use_api_key(&key);
+    let (_, code) = server.index("test").search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+    let (_, code) = server.index("test").search_post(json!({})).await;
+    snapshot!(code, @"429 Too Many Requests");
+
+    // the unlimited key is still not counted
+    server.use_api_key(&unlimited_key);
+    let (_, code) = server.index("test").search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+}
+
+#[actix_rt::test]
+async fn error_create_key_invalid_rate_limit() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+
+    let (response, code) = server
+        .add_api_key(json!({
+            "actions": ["search"],
+            "indexes": ["*"],
+            "expiresAt": null,
+            "rateLimit": { "requestsPerSecond": 0 },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rateLimit`: `requestsPerSecond` and `burst` must be greater than `0`.",
+      "code": "invalid_api_key_rate_limit",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_api_key_rate_limit"
+    }
+    "###);
+
+    let (response, code) = server
+        .add_api_key(json!({
+            "actions": ["search"],
+            "indexes": ["*"],
+            "expiresAt": null,
+            "dailyQuota": -1,
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_api_key_daily_quota""###);
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{Server, Value};
+use crate::json;
+
+fn generate_tenant_token(parent_uid: &str, parent_key: &str, search_rules: Value) -> String {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    let body: HashMap<&str, Value> = HashMap::from([
+        ("apiKeyUid", json!(parent_uid)),
+        ("searchRules", search_rules),
+        ("exp", json!(null)),
+    ]);
+    encode(&Header::default(), &body, &EncodingKey::from_secret(parent_key.as_bytes())).unwrap()
+}
+
+/// Creates the `sales` index and a search key restricted by search rules, returns the key and its uid.
+async fn restricted_key(server: &mut Server) -> (String, String) {
+    server.use_api_key("MASTER_KEY");
+
+    let index = server.index("sales");
+    let documents = json!([
+        { "id": "287947", "title": "Shazam!", "color": ["green", "blue"] },
+        { "id": "299537", "title": "Captain Marvel", "color": ["yellow", "blue"] },
+        { "id": "522681", "title": "Escape Room", "color": ["yellow", "red"] },
+        { "id": "166428", "title": "How to Train Your Dragon", "color": ["green", "red"] },
+        { "id": "450465", "title": "Glass", "color": ["blue", "red"] },
+    ]);
+    let (task, _) = index.add_documents(documents, Some("id")).await;
+    index.wait_task(task.uid()).await;
+    let (task, _) = index.update_settings_filterable_attributes(json!(["color"])).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = server
+        .add_api_key(json!({
+            "actions": ["search"],
+            "indexes": ["sales"],
+            "expiresAt": null,
+            "searchRules": {
+                "sales": {
+                    "filter": "color = blue",
+                    "attributesToRetrieve": ["id", "title"],
+                    "limit": 2,
+                }
+            }
+        }))
+        .await;
+    snapshot!(code, @"201 Created");
+    snapshot!(json_string!(response["searchRules"]), @r###"
+    {
+      "sales": {
+        "filter": "color = blue",
+        "attributesToRetrieve": [
+          "id",
+          "title"
+        ],
+        "limit": 2
+      }
+    }
+    "###);
+
+    (response["key"].as_str().unwrap().to_string(), response["uid"].as_str().unwrap().to_string())
+}
+
+#[actix_rt::test]
+async fn search_with_restricted_key() {
+    let mut server = Server::new_auth().await;
+    let (key, _) = restricted_key(&mut server).await;
+    server.use_api_key(key);
+
+    let index = server.index("sales");
+    let (response, code) = index
+        .search_post(json!({
+            "limit": 20,
+            "attributesToRetrieve": ["*"],
+            "attributesToHighlight": ["*", "color"],
+            "attributesToCrop": ["title:2"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "287947",
+        "title": "Shazam!",
+        "_formatted": {
+          "id": "287947",
+          "title": "Shazam!"
+        }
+      },
+      {
+        "id": "299537",
+        "title": "Captain Marvel",
+        "_formatted": {
+          "id": "299537",
+          "title": "Captain Marvel"
+        }
+      }
+    ]
+    "###);
+    snapshot!(response["limit"], @"2");
+    snapshot!(response["estimatedTotalHits"], @"3");
+
+    // an attribute that isn't allowed is never retrieved
+    let (response, code) = index.search_post(json!({ "attributesToRetrieve": ["color"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {},
+      {}
+    ]
+    "###);
+
+    let (response, code) = index.search_post(json!({ "hitsPerPage": 10, "page": 2 })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hitsPerPage"], @"2");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "450465",
+        "title": "Glass"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn restricted_key_allows_nested_attributes() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+
+    let index = server.index("books");
+    let documents = json!([
+        { "id": 1, "title": "Dune", "author": { "name": "Frank Herbert", "born": 1920 } },
+        { "id": 2, "title": "Hyperion", "author": { "name": "Dan Simmons", "born": 1948 } },
+    ]);
+    let (task, _) = index.add_documents(documents, Some("id")).await;
+    index.wait_task(task.uid()).await;
+    let (task, _) = index.update_settings_filterable_attributes(json!(["author.name"])).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = server
+        .add_api_key(json!({
+            "actions": ["search"],
+            "indexes": ["books"],
+            "expiresAt": null,
+            "searchRules": { "books": { "attributesToRetrieve": ["author"] } }
+        }))
+        .await;
+    snapshot!(code, @"201 Created");
+    server.use_api_key(response["key"].as_str().unwrap());
+
+    // the attributes nested in an allowed attribute are retrieved, highlighted and cropped
+    let (response, code) = index
+        .search_post(json!({
+            "q": "dune",
+            "attributesToRetrieve": ["author.name", "title"],
+            "attributesToHighlight": ["author.name"],
+            "attributesToCrop": ["author.name:1"],
+            "facets": ["author.name"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "author": {
+          "name": "Frank Herbert"
+        },
+        "_formatted": {
+          "author": {
+            "name": "Frank…"
+          }
+        }
+      }
+    ]
+    "###);
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "author.name": {
+        "Frank Herbert": 1
+      }
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn tenant_token_cannot_lift_key_search_rules() {
+    let mut server = Server::new_auth().await;
+    let (key, uid) = restricted_key(&mut server).await;
+
+    let token = generate_tenant_token(
+        &uid,
+        &key,
+        json!({ "sales": { "filter": "color = red", "attributesToRetrieve": ["*"], "limit": 10 } }),
+    );
+    server.use_api_key(token);
+
+    let index = server.index("sales");
+    let (response, code) = index.search_post(json!({ "limit": 20 })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "450465",
+        "title": "Glass"
+      }
+    ]
+    "###);
+    snapshot!(response["limit"], @"2");
+}
+
+#[actix_rt::test]
+async fn restricted_key_cannot_use_other_attributes() {
+    let mut server = Server::new_auth().await;
+    let (key, _) = restricted_key(&mut server).await;
+    server.use_api_key(key);
+    let index = server.index("sales");
+
+    let (response, code) = index.search_post(json!({ "facets": ["color"] })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The search rules of the API key do not allow the `color` attribute in `facets`.",
+      "code": "invalid_api_key",
+      "type": "auth",
+      "link": "https://docs.meilisearch.com/errors#invalid_api_key"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({ "facets": ["*"] })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(response["message"], @r###""The search rules of the API key do not allow the `*` attribute in `facets`.""###);
+
+    let (response, code) =
+        index.search_post(json!({ "facetRanges": { "color": [{ "from": 0 }] } })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(response["message"], @r###""The search rules of the API key do not allow the `color` attribute in `facetRanges`.""###);
+
+    let (response, code) =
+        index.search_post(json!({ "facetHistogram": { "color": { "interval": 1 } } })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(response["message"], @r###""The search rules of the API key do not allow the `color` attribute in `facetHistogram`.""###);
+
+    let (response, code) = index.search_post(json!({ "attributesToSearchOn": ["color"] })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(response["message"], @r###""The search rules of the API key do not allow the `color` attribute in `attributesToSearchOn`.""###);
+
+    let (response, code) = index.search_post(json!({ "sort": ["color:asc"] })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(response["message"], @r###""The search rules of the API key do not allow the `color` attribute in `sort`.""###);
+
+    let (response, code) =
+        index.search_post(json!({ "filter": ["title = Glass", ["color = red"]] })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(response["message"], @r###""The search rules of the API key do not allow the `color` attribute in `filter`.""###);
+
+    let (response, code) = index.search_post(json!({ "showMatchesPosition": true })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(response["message"], @r###""The search rules of the API key restrict the retrievable attributes, `showMatchesPosition` cannot be used.""###);
+
+    let (response, code) = index.facet_search(json!({ "facetName": "color" })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(response["message"], @r###""The search rules of the API key do not allow the `color` attribute in `facetName`.""###);
+
+    // the allowed attributes can still be used
+    let (response, code) =
+        index.search_post(json!({ "q": "glass", "attributesToSearchOn": ["title"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "450465",
+        "title": "Glass"
+      }
+    ]
+    "###);
+}
@@ -385,6 +385,20 @@ impl Index<'_> {
         self.service.post_encoded(url, query, self.encoder).await
     }
 
+    pub async fn render(&self, query: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/render", urlencode(self.uid.as_ref()));
+        self.service.post_encoded(url, query, self.encoder).await
+    }
+
+    pub async fn render_embedder(&self, embedder: &str, query: Value) -> (Value, StatusCode) {
+        let url = format!(
+            "/indexes/{}/embedders/{}/render",
+            urlencode(self.uid.as_ref()),
+            urlencode(embedder)
+        );
+        self.service.post_encoded(url, query, self.encoder).await
+    }
+
     pub async fn update_distinct_attribute(&self, value: Value) -> (Value, StatusCode) {
         let url =
             format!("/indexes/{}/settings/{}", urlencode(self.uid.as_ref()), "distinct-attribute");
@@ -173,6 +173,10 @@ impl Server {
         self.service.post("/dumps", json!(null)).await
     }
 
+    pub async fn import_dump(&self, value: Value) -> (Value, StatusCode) {
+        self.service.post("/dumps/import", value).await
+    }
+
     pub async fn create_snapshot(&self) -> (Value, StatusCode) {
         self.service.post("/snapshots", json!(null)).await
     }
@@ -0,0 +1,111 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+/// Spawns a local embedding server that counts the texts it embeds.
+async fn counting_embedding_server() -> (String, Arc<AtomicUsize>) {
+    async fn embed(counter: web::Data<Arc<AtomicUsize>>) -> HttpResponse {
+        counter.fetch_add(1, Ordering::Relaxed);
+        HttpResponse::Ok().json(serde_json::json!({ "data": [{ "embedding": [1.0, 0.0, 0.0] }] }))
+    }
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let data = web::Data::new(counter.clone());
+    let server = HttpServer::new(move || {
+        App::new().app_data(data.clone()).route("/embed", web::post().to(embed))
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let address = server.addrs()[0];
+    actix_rt::spawn(server.run());
+    (format!("http://{address}/embed"), counter)
+}
+
+fn embedder(url: &str) -> serde_json::Value {
+    serde_json::json!({
+        "source": "rest",
+        "url": url,
+        "query": {},
+        "dimensions": 3,
+        "documentTemplate": "{{doc.text}}",
+    })
+}
+
+#[actix_rt::test]
+async fn cached_embeddings_are_not_requested_again() {
+    let server = Server::new().await;
+    let (url, counter) = counting_embedding_server().await;
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let index = server.index("test");
+    let (response, code) =
+        index.update_settings(json!({ "embedders": { "default": embedder(&url) } })).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index
+        .add_documents(
+            json!([
+                { "id": 1, "text": "ocean waves" },
+                { "id": 2, "text": "desert dunes" },
+            ]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "receivedDocuments": 2,
+      "indexedDocuments": 2,
+      "embeddingCache": {
+        "hits": 0,
+        "misses": 2
+      }
+    }
+    "###);
+    snapshot!(counter.load(Ordering::Relaxed), @"2");
+
+    // adding an embedder reindexes the vectors of every embedder, but only the new one calls the model.
+    let (response, code) = index
+        .update_settings(
+            json!({ "embedders": { "default": embedder(&url), "other": embedder(&url) } }),
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]["embeddingCache"]), @r###"
+    {
+      "hits": 2,
+      "misses": 2
+    }
+    "###);
+    snapshot!(counter.load(Ordering::Relaxed), @"4");
+
+    // the embeddings of a deleted document are purged from the cache.
+    let (response, code) = index.delete_document(1).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) =
+        index.add_documents(json!([{ "id": 1, "text": "ocean waves" }]), None).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]["embeddingCache"]), @r###"
+    {
+      "hits": 0,
+      "misses": 2
+    }
+    "###);
+    snapshot!(counter.load(Ordering::Relaxed), @"6");
+}
@@ -540,3 +540,50 @@ async fn get_document_by_filter() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn get_document_with_vectors() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(
+            json!({ "embedders": { "manual": { "source": "userProvided", "dimensions": 2 } } }),
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index
+        .add_documents(
+            json!([{ "id": 0, "name": "kefir", "_vectors": { "manual": [0.5, 1.5] } }]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) =
+        index.service.get("/indexes/test/documents/0?retrieveVectors=true&fields=name").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "name": "kefir",
+      "_vectors": {
+        "manual": [
+          [
+            0.5,
+            1.5
+          ]
+        ]
+      }
+    }
+    "###);
+
+    let (response, code) =
+        index.service.get("/indexes/test/documents/0?retrieveVectors=maybe").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_document_retrieve_vectors""###);
+}
@@ -1,5 +1,7 @@
 mod add_documents;
 mod delete_documents;
+mod embedding_cache;
 mod errors;
 mod get_documents;
+mod render;
 mod update_documents;
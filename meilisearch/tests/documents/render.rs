@@ -0,0 +1,131 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn render_requires_vector_store() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.render(json!({ "template": "{{doc.title}}", "documentId": "1" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Using the `/render` route requires enabling the `vector store` experimental feature. See https://github.com/meilisearch/product/discussions/677",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn render_document_with_filters() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .add_documents(
+            json!([{
+                "id": 1,
+                "title": "  ",
+                "overview": "<p>A <em>computer hacker</em> learns about the true nature of reality</p>",
+                "genres": ["Action", "", "Science Fiction"],
+                "cast": [{ "name": "Keanu" }]
+            }]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index
+        .render(json!({
+            "template": "{{doc.title | default_if_blank: \"Untitled\"}} ({{doc.genres | join_with: \", \", \" and \"}}): {{doc.overview | truncatewords_html: 4}} {{doc.cast | json}}",
+            "documentId": "1"
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "template": "{{doc.title | default_if_blank: \"Untitled\"}} ({{doc.genres | join_with: \", \", \" and \"}}): {{doc.overview | truncatewords_html: 4}} {{doc.cast | json}}",
+      "rendered": "Untitled (Action and Science Fiction): A computer hacker learns... [{\"name\":\"Keanu\"}]"
+    }
+    "###);
+
+    let (response, code) =
+        index.render(json!({ "template": "{{doc.title}}", "documentId": "2" })).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response["code"], @r###""document_not_found""###);
+
+    let (response, code) = index
+        .render(json!({ "template": "{{doc.overview | truncatewords_html: \"many\"}}", "documentId": "1" }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_render_template""###);
+
+    let (response, code) = index
+        .render(json!({ "template": "{{doc.title | unknown_filter}}", "documentId": "1" }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_render_template""###);
+}
+
+#[actix_rt::test]
+async fn render_embedder_template() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index
+        .update_settings(
+            json!({ "embedders": { "manual": { "source": "userProvided", "dimensions": 2 } } }),
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index
+        .add_documents(
+            json!([{ "id": 1, "title": "Shazam!", "_vectors": { "manual": [1.0, 3.0] } }]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (stored, code) = index.render_embedder("manual", json!({ "documentId": "1" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(stored["embedder"], @r###""manual""###);
+
+    assert!(stored["rendered"].as_str().unwrap().contains("Shazam!"), "{stored}");
+
+    let (given, code) = index
+        .render_embedder("manual", json!({ "document": { "id": 1, "title": "Shazam!" } }))
+        .await;
+    snapshot!(code, @"200 OK");
+    assert!(given["rendered"].as_str().unwrap().contains("Shazam!"), "{given}");
+    assert!(given.get("embeddings").is_none());
+
+    let (response, code) = index
+        .render_embedder("manual", json!({ "documentId": "1", "document": { "id": 1 } }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: expected either a `documentId` or a `document` object, but not both.",
+      "code": "invalid_render_document",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_render_document"
+    }
+    "###);
+
+    let (response, code) = index.render_embedder("unknown", json!({ "documentId": "1" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_embedder""###);
+}
@@ -1,5 +1,6 @@
 mod data;
 
+use dump::DumpCompression;
 use meili_snap::{json_string, snapshot};
 use meilisearch::Opt;
 
@@ -73,7 +74,8 @@ async fn import_dump_v1_movie_raw() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -235,7 +237,8 @@ async fn import_dump_v1_movie_with_settings() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -383,7 +386,8 @@ async fn import_dump_v1_rubygems_with_settings() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -517,7 +521,8 @@ async fn import_dump_v2_movie_raw() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -663,7 +668,8 @@ async fn import_dump_v2_movie_with_settings() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -808,7 +814,8 @@ async fn import_dump_v2_rubygems_with_settings() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -942,7 +949,8 @@ async fn import_dump_v3_movie_raw() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1088,7 +1096,8 @@ async fn import_dump_v3_movie_with_settings() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1233,7 +1242,8 @@ async fn import_dump_v3_rubygems_with_settings() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1367,7 +1377,8 @@ async fn import_dump_v4_movie_raw() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1513,7 +1524,8 @@ async fn import_dump_v4_movie_with_settings() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1658,7 +1670,8 @@ async fn import_dump_v4_rubygems_with_settings() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1903,7 +1916,8 @@ async fn import_dump_v6_containing_experimental_features() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1939,3 +1953,119 @@ async fn import_dump_v6_containing_experimental_features() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn import_dump_v5_in_running_instance() {
+    let temp = tempfile::tempdir().unwrap();
+    let options = default_settings(temp.path());
+    std::fs::create_dir_all(&options.dump_dir).unwrap();
+    std::fs::copy(GetDump::TestV5.path(), options.dump_dir.join("test-v5.dump")).unwrap();
+    let mut server = Server::new_auth_with_options(options, temp).await;
+    server.use_api_key("MASTER_KEY");
+
+    let (response, code) = server.import_dump(json!({ "dumpUid": "test-v5" })).await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response["taskUid"].as_u64().unwrap()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+    snapshot!(task["type"], @r###""dumpImport""###);
+    snapshot!(json_string!(task["details"]), @r###"
+    {
+      "dumpUid": "test-v5",
+      "importedIndexes": 2
+    }
+    "###);
+
+    let (indexes, code) = server.list_indexes(None, None).await;
+    assert_eq!(code, 200, "{indexes}");
+    assert_eq!(indexes["results"].as_array().unwrap().len(), 2);
+    assert_eq!(indexes["results"][0]["uid"], json!("test"));
+    assert_eq!(indexes["results"][1]["uid"], json!("test2"));
+
+    let (docs, code) =
+        server.index("test").get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(docs["results"].as_array().unwrap().len(), 10);
+
+    // The keys were not requested, only the default ones must exist.
+    let (keys, code) = server.list_api_keys("").await;
+    snapshot!(code, @"200 OK");
+    assert!(keys["results"].as_array().unwrap().iter().all(|key| key["name"] != "my key"));
+
+    // Importing the same dump again must fail without touching the existing indexes.
+    let (response, code) =
+        server.import_dump(json!({ "dumpUid": "test-v5", "importKeys": true })).await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response["taskUid"].as_u64().unwrap()).await;
+    snapshot!(task["status"], @r###""failed""###);
+    snapshot!(json_string!(task["error"]), @r###"
+    {
+      "message": "Index `test` already exists.",
+      "code": "index_already_exists",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_already_exists"
+    }
+    "###);
+
+    let (keys, code) = server.list_api_keys("").await;
+    snapshot!(code, @"200 OK");
+    assert!(keys["results"].as_array().unwrap().iter().all(|key| key["name"] != "my key"));
+
+    let (response, code) = server.import_dump(json!({ "dumpUid": "unknown" })).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Dump `unknown` not found.",
+      "code": "dump_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#dump_not_found"
+    }
+    "###);
+
+    let (response, code) = server.import_dump(json!({ "dumpUid": "../test-v5" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_dump_uid""###);
+}
+
+#[actix_rt::test]
+async fn create_and_import_zstd_dump() {
+    let temp = tempfile::tempdir().unwrap();
+    let options = Opt { dump_compression: DumpCompression::Zstd, ..default_settings(temp.path()) };
+    let server = Server::new_with_options(options).await.unwrap();
+
+    let index = server.index("test");
+    let (task, _) = index
+        .add_documents(json!([{ "id": 1, "name": "paul" }, { "id": 2, "name": "tamo" }]), None)
+        .await;
+    index.wait_task(task["taskUid"].as_u64().unwrap()).await;
+
+    let (response, code) = server.create_dump().await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response["taskUid"].as_u64().unwrap()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+
+    let dump_uid = task["details"]["dumpUid"].as_str().unwrap();
+    let dump_path = temp.path().join("dumps").join(format!("{dump_uid}.dump"));
+    let content = std::fs::read(&dump_path).unwrap();
+    assert!(content.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]), "The dump is not compressed with zstd");
+
+    // The compression is detected when importing the dump, whatever the configuration.
+    let temp = tempfile::tempdir().unwrap();
+    let options = Opt { import_dump: Some(dump_path), ..default_settings(temp.path()) };
+    let server = Server::new_with_options(options).await.unwrap();
+
+    let (docs, code) =
+        server.index("test").get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(docs["results"]), @r###"
+    [
+      {
+        "id": 1,
+        "name": "paul"
+      },
+      {
+        "id": 2,
+        "name": "tamo"
+      }
+    ]
+    "###);
+}
@@ -45,6 +45,38 @@ async fn stats() {
     assert_eq!(response["fieldDistribution"]["age"], 1);
 }
 
+#[actix_rt::test]
+async fn stats_vector_storage_size() {
+    let server = Server::new().await;
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    assert_eq!(code, 200);
+
+    let index = server.index("test");
+    let (response, code) = index
+        .update_settings(
+            json!({ "embedders": { "default": { "source": "userProvided", "dimensions": 2 } } }),
+        )
+        .await;
+    assert_eq!(code, 202);
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index.stats().await;
+    assert_eq!(code, 200);
+    assert_eq!(response["vectorStorageSize"]["default"], 0);
+
+    let documents = json!([
+        { "id": 1, "_vectors": { "default": [1.0, 2.0] } },
+        { "id": 2, "_vectors": { "default": [2.0, 3.0] } },
+    ]);
+    let (response, code) = index.add_documents(documents, None).await;
+    assert_eq!(code, 202);
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index.stats().await;
+    assert_eq!(code, 200);
+    assert!(response["vectorStorageSize"]["default"].as_u64().unwrap() > 0);
+}
+
 #[actix_rt::test]
 async fn error_get_stats_unexisting_index() {
     let server = Server::new().await;
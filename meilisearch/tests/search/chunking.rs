@@ -0,0 +1,107 @@
+use actix_web::{web, App, HttpResponse, HttpServer};
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+/// Spawns a local embedding server, the embedding of a text only depends on the landscape it mentions.
+async fn mock_embedding_server() -> String {
+    async fn embed(body: web::Json<serde_json::Value>) -> HttpResponse {
+        let text = body["input"].as_str().unwrap_or_default();
+        let embedding = if text.contains("ocean") {
+            [1.0, 0.0, 0.0]
+        } else if text.contains("mountain") {
+            [0.0, 1.0, 0.0]
+        } else {
+            [0.0, 0.0, 1.0]
+        };
+        HttpResponse::Ok().json(serde_json::json!({ "data": [{ "embedding": embedding }] }))
+    }
+
+    let server = HttpServer::new(|| App::new().route("/embed", web::post().to(embed)))
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+    let address = server.addrs()[0];
+    actix_rt::spawn(server.run());
+    format!("http://{address}/embed")
+}
+
+#[actix_rt::test]
+async fn search_returns_the_matched_chunk() {
+    let server = Server::new().await;
+    let url = mock_embedding_server().await;
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let index = server.index("test");
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "default": {
+                    "source": "rest",
+                    "url": url,
+                    "query": {},
+                    "dimensions": 3,
+                    "documentTemplate": "{{doc.text}}",
+                    "chunking": { "unit": "tokens", "size": 4 },
+                }
+            }
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let (response, code) = index
+        .add_documents(
+            json!([
+                { "id": 1, "text": "ocean waves and sand mountain peaks and snow" },
+                { "id": 2, "text": "desert dunes" },
+            ]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "mountain",
+            "hybrid": { "semanticRatio": 1.0 },
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "_matchedChunk": "mountain peaks and snow"
+      },
+      {
+        "id": 2,
+        "_matchedChunk": "desert dunes"
+      }
+    ]
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "ocean",
+            "hybrid": { "semanticRatio": 1.0 },
+            "attributesToRetrieve": ["id"],
+            "limit": 1,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "_matchedChunk": "ocean waves and sand"
+      }
+    ]
+    "###);
+}
@@ -321,6 +321,69 @@ async fn search_bad_facets() {
     // Can't make the `attributes_to_highlight` fail with a get search since it'll accept anything as an array of strings.
 }
 
+#[actix_rt::test]
+async fn search_bad_facet_ranges_and_histogram() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.search_post(json!({"facetRanges": {"price": [{ "from": 10, "to": 10 }]}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.facetRanges.price[0]`: `from` must be lower than `to`.",
+      "code": "invalid_search_facet_ranges",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_ranges"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({"facetHistogram": {"price": { "interval": 0 }}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.facetHistogram.price`: `interval` must be a number greater than `0`.",
+      "code": "invalid_search_facet_histogram",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_histogram"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({"facetHierarchyDepth": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.facetHierarchyDepth`: expected a positive integer, but found a string: `\"doggo\"`",
+      "code": "invalid_search_facet_hierarchy_depth",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_hierarchy_depth"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({"facetHierarchyDepth": 0})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.facetHierarchyDepth`: the value of `facetHierarchyDepth` is invalid, expected an integer greater than `0`.",
+      "code": "invalid_search_facet_hierarchy_depth",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_hierarchy_depth"
+    }
+    "###);
+
+    let (response, code) = index.search_get("facetHierarchyDepth=0").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `facetHierarchyDepth`: the value of `facetHierarchyDepth` is invalid, expected an integer greater than `0`.",
+      "code": "invalid_search_facet_hierarchy_depth",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_facet_hierarchy_depth"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn search_non_filterable_facets() {
     let server = Server::new().await;
@@ -1,4 +1,4 @@
-use meili_snap::snapshot;
+use meili_snap::{json_string, snapshot};
 use once_cell::sync::Lazy;
 
 use crate::common::{Server, Value};
@@ -200,3 +200,123 @@ async fn simple_facet_search_with_sort_by_count() {
     assert_eq!(hits[0], json!({ "value": "Action", "count": 3 }));
     assert_eq!(hits[1], json!({ "value": "Adventure", "count": 2 }));
 }
+
+#[actix_rt::test]
+async fn facet_search_on_numeric_facets() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "price": 5 },
+        { "id": 2, "price": 12.5 },
+        { "id": 3, "price": 12.5 },
+        { "id": 4, "price": 120 },
+        { "id": 5, "price": [42, 1200] },
+    ]);
+    index.update_settings_filterable_attributes(json!(["price"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "price", "facetQuery": "12"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"12.5","count":2},{"value":"120","count":1},{"value":"1200","count":1}]"###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "price", "filter": "id != 5"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"5","count":1},{"value":"12.5","count":2},{"value":"120","count":1}]"###);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_ranges() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "price": 5 },
+        { "id": 2, "price": 10 },
+        { "id": 3, "price": 12.5 },
+        { "id": 4, "price": 120 },
+        { "id": 5, "price": "free" },
+    ]);
+    index.update_settings_filterable_attributes(json!(["price"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let ranges = json!([{ "to": 10 }, { "from": 10, "to": 50 }, { "from": 50 }]);
+    let (response, code) =
+        index.facet_search(json!({"facetName": "price", "ranges": ranges})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"from":null,"to":10.0,"count":1},{"from":10.0,"to":50.0,"count":2},{"from":50.0,"to":null,"count":1}]"###);
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "price", "ranges": ranges, "filter": "id > 2"}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"from":null,"to":10.0,"count":0},{"from":10.0,"to":50.0,"count":1},{"from":50.0,"to":null,"count":1}]"###);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_ranges_on_a_field_without_values() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings_filterable_attributes(json!(["price", "rating"])).await;
+    index.add_documents(json!([{ "id": 1, "price": 5 }]), None).await;
+    index.wait_task(1).await;
+
+    let ranges = json!([{ "to": 3 }, { "from": 3 }]);
+    let (response, code) =
+        index.facet_search(json!({"facetName": "rating", "ranges": ranges})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"from":null,"to":3.0,"count":0},{"from":3.0,"to":null,"count":0}]"###);
+}
+
+#[actix_rt::test]
+async fn facet_search_bad_ranges() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings_filterable_attributes(json!(["price"])).await;
+    index.wait_task(0).await;
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "price", "ranges": [{ "from": 50, "to": 10 }]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.ranges[0]`: `from` must be lower than `to`.",
+      "code": "invalid_facet_search_ranges",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_facet_search_ranges"
+    }
+    "###);
+
+    let (response, code) = index.facet_search(json!({"facetName": "price", "ranges": [{}]})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["message"], @r###""Invalid value at `.ranges[0]`: At least one of `from` or `to` must be provided.""###);
+
+    let (response, code) = index
+        .facet_search(json!({"facetName": "price", "facetQuery": "1", "ranges": [{ "to": 10 }]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["message"], @r###""Invalid value: `ranges` cannot be used together with `facetQuery`.""###);
+
+    index.update_settings_faceting(json!({ "maxValuesPerFacet": 2 })).await;
+    index.wait_task(1).await;
+
+    let ranges = json!([{ "to": 10 }, { "from": 10, "to": 50 }, { "from": 50 }]);
+    let (response, code) =
+        index.facet_search(json!({"facetName": "price", "ranges": ranges})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Too many ranges in the facet search: found 3, but limited to 2 by the `maxValuesPerFacet` setting.",
+      "code": "invalid_facet_search_ranges",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_facet_search_ranges"
+    }
+    "###);
+}
@@ -1,4 +1,4 @@
-use meili_snap::snapshot;
+use meili_snap::{json_string, snapshot};
 use once_cell::sync::Lazy;
 
 use crate::common::index::Index;
@@ -130,6 +130,109 @@ async fn distribution_shift() {
     snapshot!(response["hits"], @r###"[{"title":"Captain Marvel","desc":"a Shazam ersatz","id":"3","_vectors":{"default":[2.0,3.0]},"_rankingScore":0.19161224365234375},{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2","_vectors":{"default":[1.0,2.0]},"_rankingScore":1.1920928955078125e-7},{"title":"Shazam!","desc":"a Captain Marvel ersatz","id":"1","_vectors":{"default":[1.0,3.0]},"_rankingScore":1.1920928955078125e-7}]"###);
 }
 
+#[actix_rt::test]
+async fn euclidean_distance() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "default": {
+                    "distance": "euclidean"
+                }
+            }
+        }))
+        .await;
+
+    snapshot!(code, @"202 Accepted");
+    let response = server.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(response["details"], @r###"{"embedders":{"default":{"distance":"euclidean"}}}"###);
+
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"semanticRatio": 1.0}}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"], @r###"[{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2","_vectors":{"default":[1.0,2.0]}},{"title":"Shazam!","desc":"a Captain Marvel ersatz","id":"1","_vectors":{"default":[1.0,3.0]}},{"title":"Captain Marvel","desc":"a Shazam ersatz","id":"3","_vectors":{"default":[2.0,3.0]}}]"###);
+}
+
+#[actix_rt::test]
+async fn binary_quantization_with_rescoring() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "default": {
+                    "distance": "euclidean",
+                    "quantization": "binary",
+                    "rescoring": true
+                }
+            }
+        }))
+        .await;
+
+    snapshot!(code, @"202 Accepted");
+    let response = server.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(response["details"], @r###"{"embedders":{"default":{"distance":"euclidean","quantization":"binary","rescoring":true}}}"###);
+
+    // the neighbors are rescored with their full precision embeddings, so the order is the same as without quantization
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"semanticRatio": 1.0}}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"], @r###"[{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2","_vectors":{"default":[1.0,2.0]}},{"title":"Shazam!","desc":"a Captain Marvel ersatz","id":"1","_vectors":{"default":[1.0,3.0]}},{"title":"Captain Marvel","desc":"a Shazam ersatz","id":"3","_vectors":{"default":[2.0,3.0]}}]"###);
+}
+
+#[actix_rt::test]
+async fn invalid_quantization() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "default": {
+                    "distance": "dot",
+                    "quantization": "binary"
+                }
+            }
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = server.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "`.embedders.default.quantization`: the embeddings compared with the `dot` distance cannot be quantized",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "default": {
+                    "rescoring": true
+                }
+            }
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = server.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(json_string!(response["error"]["message"]), @r###""`.embedders.default.rescoring`: `rescoring` can only be enabled with a `quantization`""###);
+}
+
 #[actix_rt::test]
 async fn highlighter() {
     let server = Server::new().await;
@@ -377,3 +480,77 @@ async fn query_combination() {
     snapshot!(response["hits"], @r###"[{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2","_vectors":{"default":[1.0,2.0]},"_rankingScore":0.9848484848484848}]"###);
     snapshot!(response["semanticHitCount"], @"0");
 }
+
+#[actix_rt::test]
+async fn multi_search_without_vector() {
+    let server = Server::new().await;
+    index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    // the queries can't be embedded in batch by a user-provided embedder, each search reports it on its own
+    let (response, code) = server
+        .multi_search(json!({"queries": [
+            {"indexUid": "test", "q": "Planet", "hybrid": {"semanticRatio": 0.99}, "attributesToRetrieve": ["id"]},
+            {"indexUid": "test", "q": "Planet", "hybrid": {"semanticRatio": 0.5}, "attributesToRetrieve": ["id"]},
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["results"][0]["hits"], @r###"[{"id":"2"}]"###);
+    snapshot!(response["results"][0]["semanticHitCount"], @"0");
+    snapshot!(response["results"][1]["hits"], @r###"[{"id":"2"}]"###);
+    snapshot!(response["results"][1]["semanticHitCount"], @"0");
+
+    let (response, code) = server
+        .multi_search(json!({"queries": [
+            {"indexUid": "test", "q": "Planet", "hybrid": {"semanticRatio": 0.5}},
+            {"indexUid": "test", "q": "Captain", "hybrid": {"semanticRatio": 1.0}},
+        ]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Inside `.queries[1]`: Error while generating embeddings: user error: attempt to embed the following text in a configuration where embeddings must be user provided: \"Captain\"",
+      "code": "vector_embedding_error",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#vector_embedding_error"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn reciprocal_rank_fusion() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    // keyword ranks: 2, 3, 1 and semantic ranks: 3, 2, 1
+    let (response, code) = index
+        .search_post(json!({
+            "q": "Captain",
+            "vector": [1.0, 1.0],
+            "hybrid": {"semanticRatio": 0.5, "fusion": "reciprocalRank", "rankConstant": 1},
+            "attributesToRetrieve": ["id"],
+            "showRankingScoreDetails": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    let hits = response["hits"].as_array().unwrap();
+    let ranks: Vec<_> = hits
+        .iter()
+        .map(|hit| {
+            let fusion = &hit["_rankingScoreDetails"]["reciprocalRankFusion"];
+            serde_json::json!([hit["id"], fusion["keywordRank"], fusion["semanticRank"]])
+        })
+        .collect();
+    // documents 2 and 3 tie, they stay in the order of the keyword search
+    snapshot!(serde_json::json!(ranks), @r###"[["2",1,2],["3",2,1],["1",3,3]]"###);
+    snapshot!(response["semanticHitCount"], @"1");
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "Captain",
+            "vector": [1.0, 1.0],
+            "hybrid": {"semanticRatio": 0.5, "fusion": "reciprocal"},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_search_fusion""###);
+}
@@ -1,6 +1,7 @@
 // This modules contains all the test concerning search. Each particular feature of the search
 // should be tested in its own module to isolate tests and keep the tests readable.
 
+mod chunking;
 mod distinct;
 mod errors;
 mod facet_search;
@@ -8,7 +9,9 @@ mod formatted;
 mod geo;
 mod hybrid;
 mod multi;
+mod multimodal;
 mod pagination;
+mod query_embedding_cache;
 mod restrict_searchable;
 mod search_queue;
 
@@ -682,6 +685,147 @@ async fn search_facet_distribution() {
         .await;
 }
 
+#[actix_rt::test]
+async fn search_facet_ranges_and_histogram() {
+    let server = Server::new().await;
+    let index = server.index("nested");
+
+    index.update_settings(json!({"filterableAttributes": ["doggos.age"]})).await;
+
+    let documents = NESTED_DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "facetRanges": { "doggos.age": [{ "to": 5 }, { "from": 5 }] },
+            "facetHistogram": { "doggos.age": { "interval": 3 } },
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetRanges"]), @r###"
+    {
+      "doggos.age": {
+        "*-5": 1,
+        "5-*": 2
+      }
+    }
+    "###);
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetHistogram"]), @r###"
+    {
+      "doggos.age": {
+        "0-3": 1,
+        "3-6": 2,
+        "6-9": 2
+      }
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "doggos.age > 5",
+            "facetHistogram": { "doggos.age": { "interval": 3 } },
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetHistogram"]), @r###"
+    {
+      "doggos.age": {
+        "6-9": 2
+      }
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_facet_hierarchy() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index
+        .update_settings(json!({
+            "filterableAttributes": ["category"],
+            "faceting": { "hierarchicalFacets": { "category": " > " } },
+        }))
+        .await;
+
+    let documents = json!([
+        { "id": 1, "category": "Electronics > Phones > Android" },
+        { "id": 2, "category": "Electronics > Phones > iOS" },
+        { "id": 3, "category": "Electronics > Laptops" },
+        { "id": 4, "category": "Books" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index.search_post(json!({ "facets": ["category"] })).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetHierarchy"]), @r###"
+    {
+      "category": {
+        "Books": {
+          "count": 1
+        },
+        "Electronics": {
+          "count": 3,
+          "children": {
+            "Laptops": {
+              "count": 1
+            },
+            "Phones": {
+              "count": 2,
+              "children": {
+                "Android": {
+                  "count": 1
+                },
+                "iOS": {
+                  "count": 1
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+    "###);
+
+    // filtering on a node matches all its descendants
+    let (response, code) = index
+        .search_post(json!({
+            "filter": "category = \"Electronics > Phones\"",
+            "facets": ["*"],
+            "facetHierarchyDepth": 2,
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "category": "Electronics > Phones > Android"
+      },
+      {
+        "id": 2,
+        "category": "Electronics > Phones > iOS"
+      }
+    ]
+    "###);
+    meili_snap::snapshot!(meili_snap::json_string!(response["facetHierarchy"]), @r###"
+    {
+      "category": {
+        "Electronics": {
+          "count": 2,
+          "children": {
+            "Phones": {
+              "count": 2
+            }
+          }
+        }
+      }
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn displayed_attributes() {
     let server = Server::new().await;
@@ -0,0 +1,253 @@
+use actix_web::{web, App, HttpResponse, HttpServer};
+use meili_snap::{json_string, snapshot};
+
+use crate::common::index::Index;
+use crate::common::Server;
+use crate::json;
+
+/// Spawns a local embedding server, the embedding of an input only depends on its image.
+async fn mock_embedding_server() -> String {
+    async fn embed(body: web::Json<serde_json::Value>) -> HttpResponse {
+        let embedding = match body["input"]["image"].as_str() {
+            Some("https://example.com/red.png") => [1.0, 0.0, 0.0],
+            Some("https://example.com/blue.png") => [0.0, 1.0, 0.0],
+            Some(image) if image.starts_with("data:image/png;base64,") => [0.1, 0.9, 0.0],
+            _ => [0.0, 0.2, 1.0],
+        };
+        HttpResponse::Ok().json(serde_json::json!({ "data": [{ "embedding": embedding }] }))
+    }
+
+    let server = HttpServer::new(|| App::new().route("/embed", web::post().to(embed)))
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+    let address = server.addrs()[0];
+    actix_rt::spawn(server.run());
+    format!("http://{address}/embed")
+}
+
+async fn index_with_products<'a>(server: &'a Server, url: &str) -> Index<'a> {
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let index = server.index("test");
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "default": {
+                    "source": "rest",
+                    "url": url,
+                    "query": {},
+                    "dimensions": 3,
+                    "documentTemplate": "{{doc.name}}",
+                    "inputType": "textAndImage",
+                    "imageField": "image",
+                }
+            }
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let (response, code) = index
+        .add_documents(
+            json!([
+                { "id": 1, "name": "Red shoe", "image": "https://example.com/red.png" },
+                { "id": 2, "name": "Blue shoe", "image": "https://example.com/blue.png" },
+                { "id": 3, "name": "Shoe without picture" },
+            ]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    index
+}
+
+#[actix_rt::test]
+async fn search_with_image() {
+    let server = Server::new().await;
+    let url = mock_embedding_server().await;
+    let index = index_with_products(&server, &url).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "shoe",
+            "image": "https://example.com/blue.png",
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 2
+      },
+      {
+        "id": 3
+      },
+      {
+        "id": 1
+      }
+    ]
+    "###);
+    snapshot!(response["semanticHitCount"], @"3");
+
+    let (response, code) = index
+        .search_post(json!({
+            "image": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==",
+            "limit": 1,
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 2
+      }
+    ]
+    "###);
+
+    // the document without image is embedded from its text only
+    let (response, code) =
+        index.search_post(json!({ "image": "", "limit": 1, "attributesToRetrieve": ["id"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 3
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_with_image_errors() {
+    let server = Server::new().await;
+    let url = mock_embedding_server().await;
+    let index = index_with_products(&server, &url).await;
+
+    let (response, code) = index
+        .search_post(json!({ "image": "https://example.com/red.png", "vector": [1.0, 0.0, 0.0] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: `image` and `vector` cannot be passed together, the image is embedded as the query vector.",
+      "code": "invalid_search_image",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_image"
+    }
+    "###);
+
+    // the embeddings of a user-provided embedder can't be computed from an image
+    let index = server.index("manual");
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": { "manual": { "source": "userProvided", "dimensions": 3 } }
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "image": "https://example.com/red.png",
+            "hybrid": { "embedder": "manual" },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: the embedder `manual` does not accept images. Only `rest` embedders with the `textAndImage` input type can embed the `image` search parameter.",
+      "code": "invalid_search_image",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_image"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn texts_are_never_sent_as_images() {
+    let server = Server::new().await;
+    let url = mock_embedding_server().await;
+    let index = index_with_products(&server, &url).await;
+    let smuggled = r#"{"text":"shoe","image":"https://example.com/red.png"}"#;
+
+    let (response, code) = index.add_documents(json!([{ "id": 4, "name": smuggled }]), None).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    // only the document with the red image is embedded as it
+    let (response, code) = index
+        .search_post(json!({
+            "vector": [1.0, 0.0, 0.0],
+            "hybrid": { "semanticRatio": 1.0 },
+            "limit": 2,
+            "showRankingScore": true,
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"][0]["id"], @"1");
+    assert!(response["hits"][1]["_rankingScore"].as_f64().unwrap() < 0.9, "{response}");
+
+    // a query is embedded from its text only
+    let (response, code) = index
+        .search_post(json!({
+            "q": smuggled,
+            "hybrid": { "semanticRatio": 1.0 },
+            "limit": 1,
+            "showRankingScore": true,
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    assert_ne!(response["hits"][0]["id"], json!(1), "{response}");
+}
+
+#[actix_rt::test]
+async fn image_field_requires_the_text_and_image_input_type() {
+    let server = Server::new().await;
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+    let index = server.index("test");
+
+    for embedder in [
+        json!({ "inputType": "text", "imageField": "image" }),
+        json!({ "inputType": "textAndImage" }),
+        json!({ "inputType": "textAndImage", "imageField": "image", "chunking": { "size": 10 } }),
+    ] {
+        let mut embedder = embedder;
+        embedder["source"] = json!("rest");
+        embedder["url"] = json!("https://localhost:7777");
+        embedder["dimensions"] = json!(3);
+        let (response, code) =
+            index.update_settings(json!({ "embedders": { "default": embedder } })).await;
+        snapshot!(code, @"202 Accepted");
+        let response = index.wait_task(response.uid()).await;
+        assert_eq!(response["status"], json!("failed"), "{embedder}: {response}");
+        assert_eq!(response["error"]["code"], json!("invalid_settings_embedders"), "{response}");
+    }
+
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "default": {
+                    "source": "rest",
+                    "url": "https://localhost:7777",
+                    "dimensions": 3,
+                    "inputType": "text",
+                    "imageField": "image",
+                }
+            }
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["error"]["message"], @r###""`.embedders.default.imageField`: `imageField` must be set with the `textAndImage` input type, and only with it""###);
+}
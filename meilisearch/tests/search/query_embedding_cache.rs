@@ -0,0 +1,102 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use meili_snap::snapshot;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+/// Spawns a local embedding server that counts the texts it embeds.
+async fn counting_embedding_server() -> (String, Arc<AtomicUsize>) {
+    async fn embed(counter: web::Data<Arc<AtomicUsize>>) -> HttpResponse {
+        counter.fetch_add(1, Ordering::Relaxed);
+        HttpResponse::Ok().json(serde_json::json!({ "data": [{ "embedding": [1.0, 0.0, 0.0] }] }))
+    }
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let data = web::Data::new(counter.clone());
+    let server = HttpServer::new(move || {
+        App::new().app_data(data.clone()).route("/embed", web::post().to(embed))
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let address = server.addrs()[0];
+    actix_rt::spawn(server.run());
+    (format!("http://{address}/embed"), counter)
+}
+
+fn search(q: &str, semantic_ratio: f32) -> Value {
+    json!({ "q": q, "hybrid": { "semanticRatio": semantic_ratio }, "limit": 1 })
+}
+
+#[actix_rt::test]
+async fn query_embeddings_are_cached() {
+    let server = Server::new().await;
+    let (url, counter) = counting_embedding_server().await;
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let index = server.index("test");
+    let (response, code) = index
+        .update_settings(json!({
+            "searchableAttributes": ["text"],
+            "embedders": {
+                "default": {
+                    "source": "rest",
+                    "url": url,
+                    "query": {},
+                    "dimensions": 3,
+                    "documentTemplate": "{{doc.text}}",
+                }
+            }
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index
+        .add_documents(
+            json!([{ "id": 1, "text": "ocean" }, { "id": 2, "text": "desert dunes" }]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(counter.load(Ordering::Relaxed), @"2");
+
+    // a semantic search embeds its query once
+    for _ in 0..2 {
+        let (_, code) = index.search_post(search("shoes", 1.0)).await;
+        snapshot!(code, @"200 OK");
+    }
+    snapshot!(counter.load(Ordering::Relaxed), @"3");
+
+    // a hybrid search whose keyword results are good enough doesn't embed its query
+    let (response, code) = index.search_post(search("ocean", 0.1)).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["semanticHitCount"], @"0");
+    snapshot!(counter.load(Ordering::Relaxed), @"3");
+
+    // otherwise it embeds it once
+    for _ in 0..2 {
+        let (_, code) = index.search_post(search("mountain", 0.5)).await;
+        snapshot!(code, @"200 OK");
+    }
+    snapshot!(counter.load(Ordering::Relaxed), @"4");
+
+    // a multi-search only embeds the queries that are not cached
+    let (_, code) = server
+        .multi_search(json!({"queries": [
+            { "indexUid": "test", "q": "shoes", "hybrid": { "semanticRatio": 1.0 } },
+            { "indexUid": "test", "q": "boots", "hybrid": { "semanticRatio": 1.0 } },
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(counter.load(Ordering::Relaxed), @"5");
+
+    let stats = server.service.index_scheduler.query_embedding_stats();
+    snapshot!(format!("hits: {}, misses: {}, requests: {}", stats.hits(), stats.misses(), stats.requests()), @"hits: 3, misses: 3, requests: 3");
+}
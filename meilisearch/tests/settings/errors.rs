@@ -308,6 +308,18 @@ async fn settings_bad_faceting() {
       "link": "https://docs.meilisearch.com/errors#invalid_settings_faceting"
     }
     "###);
+
+    let (response, code) =
+        index.update_settings_faceting(json!({ "hierarchicalFacets": { "category": " " } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value: `hierarchicalFacets` setting is invalid. The separator of `category` must contain at least one non-whitespace character.",
+      "code": "invalid_settings_faceting",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_faceting"
+    }
+    "###);
 }
 
 #[actix_rt::test]
@@ -26,7 +26,8 @@ static DEFAULT_SETTINGS_VALUES: Lazy<HashMap<&'static str, Value>> = Lazy::new(|
             "maxValuesPerFacet": json!(100),
             "sortFacetValuesBy": {
                 "*": "alpha"
-            }
+            },
+            "hierarchicalFacets": {}
         }),
     );
     map.insert(
@@ -75,7 +76,8 @@ async fn get_settings() {
             "maxValuesPerFacet": 100,
             "sortFacetValuesBy": {
                 "*": "alpha"
-            }
+            },
+            "hierarchicalFacets": {}
         })
     );
     assert_eq!(
@@ -176,7 +178,8 @@ async fn secrets_are_hidden_in_settings() {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchicalFacets": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -198,7 +201,8 @@ async fn secrets_are_hidden_in_settings() {
           "embeddingObject": [
             "embedding"
           ],
-          "inputType": "text"
+          "inputType": "text",
+          "distance": "cosine"
         }
       },
       "searchCutoffMs": null
@@ -221,6 +225,114 @@ async fn secrets_are_hidden_in_settings() {
     "###);
 }
 
+#[actix_rt::test]
+async fn embedder_chunking_settings() {
+    let server = Server::new().await;
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    meili_snap::snapshot!(code, @"200 OK");
+
+    let index = server.index("test");
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "default": {
+                    "source": "rest",
+                    "url": "https://localhost:7777",
+                    "dimensions": 4,
+                    "chunking": { "unit": "tokens", "size": 200, "overlap": 20 },
+                }
+            }
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    meili_snap::snapshot!(response["status"], @r###""succeeded""###);
+
+    let (response, code) = index.settings().await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["embedders"]["default"]["chunking"]), @r###"
+    {
+      "unit": "tokens",
+      "size": 200,
+      "overlap": 20
+    }
+    "###);
+
+    // the overlap must be lower than the size
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": { "default": { "chunking": { "size": 10, "overlap": 10 } } }
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    meili_snap::snapshot!(response["status"], @r###""failed""###);
+    meili_snap::snapshot!(response["error"]["code"], @r###""invalid_settings_embedders""###);
+
+    // the embeddings of a user-provided embedder can't be chunked
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "manual": { "source": "userProvided", "dimensions": 2, "chunking": { "size": 10 } }
+            }
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    meili_snap::snapshot!(response["status"], @r###""failed""###);
+    meili_snap::snapshot!(response["error"]["code"], @r###""invalid_settings_embedders""###);
+}
+
+#[actix_rt::test]
+async fn onnx_embedder_settings() {
+    let server = Server::new().await;
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    meili_snap::snapshot!(code, @"200 OK");
+
+    let index = server.index("test");
+
+    // the path of the model is required
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": { "default": { "source": "onnx", "pooling": "cls" } }
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    meili_snap::snapshot!(response["status"], @r###""failed""###);
+    meili_snap::snapshot!(response["error"]["code"], @r###""invalid_settings_embedders""###);
+
+    // the pooling is specific to the onnx source
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "default": {
+                    "source": "rest",
+                    "url": "https://localhost:7777",
+                    "dimensions": 4,
+                    "pooling": "mean",
+                }
+            }
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    meili_snap::snapshot!(response["status"], @r###""failed""###);
+    meili_snap::snapshot!(response["error"]["code"], @r###""invalid_settings_embedders""###);
+
+    // the model is loaded from disk when the settings are applied
+    let (response, code) = index
+        .update_settings(json!({
+            "embedders": {
+                "default": { "source": "onnx", "model": "/this/directory/does/not/exist" }
+            }
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    meili_snap::snapshot!(response["status"], @r###""failed""###);
+}
+
 #[actix_rt::test]
 async fn error_update_settings_unknown_field() {
     let server = Server::new().await;
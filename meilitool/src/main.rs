@@ -1,23 +1,29 @@
-use std::fs::{read_dir, read_to_string, remove_file, File};
-use std::io::BufWriter;
-use std::path::PathBuf;
+use std::fs::{self, read_dir, read_to_string, remove_file, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use clap::{Parser, Subcommand};
-use dump::{DumpWriter, IndexMetadata};
+use dump::{DumpCompression, DumpWriter, IndexMetadata};
 use file_store::FileStore;
 use meilisearch_auth::AuthController;
-use meilisearch_types::heed::types::{SerdeJson, Str};
-use meilisearch_types::heed::{Database, Env, EnvOpenOptions, RoTxn, RwTxn, Unspecified};
+use meilisearch_types::heed::byteorder::BE;
+use meilisearch_types::heed::types::{SerdeBincode, SerdeJson, Str, I128};
+use meilisearch_types::heed::{
+    CompactionOption, Database, Env, EnvOpenOptions, RoTxn, RwTxn, Unspecified,
+};
 use meilisearch_types::milli::documents::{obkv_to_object, DocumentsBatchReader};
-use meilisearch_types::milli::{obkv_to_json, BEU32};
+use meilisearch_types::milli::{obkv_to_json, CboRoaringBitmapCodec, RoaringBitmapCodec, BEU32};
+use meilisearch_types::settings::SecretPolicy;
 use meilisearch_types::tasks::{Status, Task};
 use meilisearch_types::versioning::check_version_file;
 use meilisearch_types::Index;
 use time::macros::format_description;
 use time::OffsetDateTime;
+use upgrade::OfflineUpgrade;
 use uuid_codec::UuidCodec;
 
+mod upgrade;
 mod uuid_codec;
 
 #[derive(Parser)]
@@ -61,18 +67,102 @@ enum Command {
         /// the processed ones is not particularly interesting.
         #[arg(long)]
         skip_enqueued_tasks: bool,
+
+        /// The compression algorithm used to write the dump, either `gzip` or `zstd`.
+        #[arg(long, default_value_t)]
+        compression: DumpCompression,
+    },
+
+    /// Lists the indexes along with their number of documents and their size on disk.
+    ListIndexes,
+
+    /// Prints the settings of an index in JSON.
+    ///
+    /// The API keys of the embedders are hidden.
+    IndexSettings {
+        /// The uid of the index.
+        #[arg(long)]
+        index_uid: String,
+    },
+
+    /// Exports the documents of an index as NDJSON.
+    ExportDocuments {
+        /// The uid of the index.
+        #[arg(long)]
+        index_uid: String,
+
+        /// The file in which the documents are written, the standard output is used by default.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Compacts the LMDB env of an index to reclaim the space used by its free pages.
+    ///
+    /// Make sure to run this command when Meilisearch is not running. The index is first copied
+    /// and compacted next to the original file which is then replaced by the compacted copy.
+    CompactIndex {
+        /// The uid of the index.
+        #[arg(long)]
+        index_uid: String,
+    },
+
+    /// Cancels an enqueued task, typically a task that never finishes processing.
+    ///
+    /// The processing tasks are stored as enqueued tasks and are processed again on every launch.
+    /// Make sure to run this command when Meilisearch is not running, the task is then marked as
+    /// canceled and its content file is deleted.
+    CancelTask {
+        /// The uid of the task.
+        #[arg(long)]
+        task_uid: u32,
+    },
+
+    /// Upgrades the database in place from the previous minor version of Meilisearch.
+    ///
+    /// The databases added by the new version are created in the indexes and the auth store, the
+    /// size of the vectors of the indexes is computed and all the API keys are written again in the
+    /// new format. The task queue keeps its format and is only checked.
+    ///
+    /// Make sure to run this command when Meilisearch is not running. Unless told otherwise,
+    /// the database is first copied in the backup directory.
+    Upgrade {
+        /// Only checks that the database can be upgraded, without modifying it.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// The directory in which the database is copied before being upgraded.
+        #[arg(long, default_value = "backups/")]
+        backup_dir: PathBuf,
+
+        /// Skips the copy of the database, use it only if you already have a backup.
+        #[arg(long)]
+        skip_backup: bool,
     },
 }
 
 fn main() -> anyhow::Result<()> {
     let Cli { db_path, command } = Cli::parse();
 
-    check_version_file(&db_path).context("While checking the version file")?;
+    // The upgrade is the only command that works on a database of another version.
+    if !matches!(command, Command::Upgrade { .. }) {
+        check_version_file(&db_path).context("While checking the version file")?;
+    }
 
     match command {
         Command::ClearTaskQueue => clear_task_queue(db_path),
-        Command::ExportADump { dump_dir, skip_enqueued_tasks } => {
-            export_a_dump(db_path, dump_dir, skip_enqueued_tasks)
+        Command::ExportADump { dump_dir, skip_enqueued_tasks, compression } => {
+            export_a_dump(db_path, dump_dir, skip_enqueued_tasks, compression)
+        }
+        Command::ListIndexes => list_indexes(db_path),
+        Command::IndexSettings { index_uid } => index_settings(db_path, index_uid),
+        Command::ExportDocuments { index_uid, output } => {
+            export_documents(db_path, index_uid, output)
+        }
+        Command::CompactIndex { index_uid } => compact_index(db_path, index_uid),
+        Command::CancelTask { task_uid } => cancel_task(db_path, task_uid),
+        Command::Upgrade { dry_run, backup_dir, skip_backup } => {
+            let backup_dir = if skip_backup { None } else { Some(backup_dir) };
+            OfflineUpgrade { db_path, dry_run, backup_dir }.upgrade()
         }
     }
 }
@@ -169,6 +259,7 @@ fn export_a_dump(
     db_path: PathBuf,
     dump_dir: PathBuf,
     skip_enqueued_tasks: bool,
+    compression: DumpCompression,
 ) -> Result<(), anyhow::Error> {
     let started_at = OffsetDateTime::now_utc();
 
@@ -188,7 +279,15 @@ fn export_a_dump(
         }
     };
 
-    let dump = DumpWriter::new(instance_uid).context("While creating a new dump")?;
+    let dump_uid = started_at.format(format_description!(
+        "[year repr:full][month repr:numerical][day padding:zero]-[hour padding:zero][minute padding:zero][second padding:zero][subsecond digits:3]"
+    )).unwrap();
+
+    let path = dump_dir.join(format!("{}.dump", dump_uid));
+    let file = File::create(&path)
+        .with_context(|| format!("While creating the dump file at {}", path.display()))?;
+    let dump = DumpWriter::new(instance_uid, BufWriter::new(file), compression)
+        .context("While creating a new dump")?;
     let file_store =
         FileStore::new(db_path.join("update_files")).context("While opening the FileStore")?;
 
@@ -304,15 +403,187 @@ fn export_a_dump(
     // We will not dump experimental feature settings
     eprintln!("The tool is not dumping experimental features, please set them by hand afterward");
 
-    let dump_uid = started_at.format(format_description!(
-        "[year repr:full][month repr:numerical][day padding:zero]-[hour padding:zero][minute padding:zero][second padding:zero][subsecond digits:3]"
-    )).unwrap();
-
-    let path = dump_dir.join(format!("{}.dump", dump_uid));
-    let file = File::create(&path)?;
-    dump.persist_to(BufWriter::new(file))?;
+    dump.finish()?;
 
     eprintln!("Dump exported at path {:?}", path.display());
 
     Ok(())
 }
+
+/// Opens the env of the index-scheduler located at `db_path`.
+fn open_index_scheduler_env(db_path: &Path) -> anyhow::Result<Env> {
+    let path = db_path.join("tasks");
+    EnvOpenOptions::new()
+        .max_dbs(100)
+        .open(&path)
+        .with_context(|| format!("While trying to open {:?}", path.display()))
+}
+
+/// Returns the path of the index named `index_uid` according to the index mapping.
+fn find_index_path(db_path: &Path, index_uid: &str) -> anyhow::Result<PathBuf> {
+    let env = open_index_scheduler_env(db_path)?;
+    let rtxn = env.read_txn()?;
+    let index_mapping: Database<Str, UuidCodec> =
+        try_opening_database(&env, &rtxn, "index-mapping")?;
+    let uuid = index_mapping
+        .get(&rtxn, index_uid)?
+        .with_context(|| format!("Index {index_uid:?} not found"))?;
+    Ok(db_path.join("indexes").join(uuid.to_string()))
+}
+
+fn open_index(index_path: &Path) -> anyhow::Result<Index> {
+    Index::new(EnvOpenOptions::new(), index_path).with_context(|| {
+        format!("While trying to open the index at path {:?}", index_path.display())
+    })
+}
+
+/// Lists the indexes with their number of documents and size on disk.
+fn list_indexes(db_path: PathBuf) -> anyhow::Result<()> {
+    let env = open_index_scheduler_env(&db_path)?;
+    let rtxn = env.read_txn()?;
+    let index_mapping: Database<Str, UuidCodec> =
+        try_opening_database(&env, &rtxn, "index-mapping")?;
+
+    let mut count = 0;
+    for result in index_mapping.iter(&rtxn)? {
+        let (uid, uuid) = result?;
+        let index = open_index(&db_path.join("indexes").join(uuid.to_string()))?;
+        let index_rtxn = index.read_txn()?;
+        let number_of_documents = index.number_of_documents(&index_rtxn)?;
+        let size = index.on_disk_size()?;
+        println!("{uid}\t{uuid}\t{number_of_documents} documents\t{size} bytes");
+        count += 1;
+    }
+
+    eprintln!("Found {count} indexes.");
+
+    Ok(())
+}
+
+/// Prints the settings of an index on the standard output.
+fn index_settings(db_path: PathBuf, index_uid: String) -> anyhow::Result<()> {
+    let index = open_index(&find_index_path(&db_path, &index_uid)?)?;
+    let rtxn = index.read_txn()?;
+    let settings = meilisearch_types::settings::settings(&index, &rtxn, SecretPolicy::HideSecrets)?;
+
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &settings)?;
+    writeln!(stdout)?;
+
+    Ok(())
+}
+
+/// Exports the documents of an index as NDJSON, in a file or on the standard output.
+fn export_documents(
+    db_path: PathBuf,
+    index_uid: String,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let index = open_index(&find_index_path(&db_path, &index_uid)?)?;
+    let rtxn = index.read_txn()?;
+
+    let writer: Box<dyn Write> = match &output {
+        Some(path) => Box::new(
+            File::create(path)
+                .with_context(|| format!("While creating the file at {}", path.display()))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut writer = BufWriter::new(writer);
+
+    let fields_ids_map = index.fields_ids_map(&rtxn)?;
+    let all_fields: Vec<_> = fields_ids_map.iter().map(|(id, _)| id).collect();
+
+    let mut count = 0;
+    for ret in index.all_documents(&rtxn)? {
+        let (_id, doc) = ret?;
+        let document = obkv_to_json(&all_fields, &fields_ids_map, doc)?;
+        serde_json::to_writer(&mut writer, &document)?;
+        writer.write_all(b"\n")?;
+        count += 1;
+    }
+    writer.flush()?;
+
+    eprintln!("Successfully exported {count} documents!");
+
+    Ok(())
+}
+
+/// Compacts the LMDB env of an index by replacing it with a compacted copy.
+fn compact_index(db_path: PathBuf, index_uid: String) -> anyhow::Result<()> {
+    let index_path = find_index_path(&db_path, &index_uid)?;
+    let index = open_index(&index_path)?;
+    let size_before = index.on_disk_size()?;
+
+    eprintln!("Compacting the {index_uid:?} index...");
+
+    let data_path = index_path.join("data.mdb");
+    let compacted_path = index_path.join("data.mdb.cpy");
+    index
+        .copy_to_file(&compacted_path, CompactionOption::Enabled)
+        .with_context(|| format!("While compacting the index into {}", compacted_path.display()))?;
+
+    // The env must be closed before we replace its file with the compacted one.
+    index.prepare_for_closing().wait();
+    fs::rename(&compacted_path, &data_path)
+        .with_context(|| format!("While replacing {}", data_path.display()))?;
+
+    let size_after = fs::metadata(&data_path)?.len();
+    eprintln!(
+        "Successfully compacted the {index_uid:?} index from {size_before} to {size_after} bytes!"
+    );
+
+    Ok(())
+}
+
+/// Marks an enqueued task as canceled and deletes its content file.
+fn cancel_task(db_path: PathBuf, task_uid: u32) -> anyhow::Result<()> {
+    let env = open_index_scheduler_env(&db_path)?;
+    let mut wtxn = env.write_txn()?;
+    let all_tasks: Database<BEU32, SerdeJson<Task>> =
+        try_opening_database(&env, &wtxn, "all-tasks")?;
+    let status: Database<SerdeBincode<Status>, RoaringBitmapCodec> =
+        try_opening_database(&env, &wtxn, "status")?;
+    let finished_at: Database<I128<BE>, CboRoaringBitmapCodec> =
+        try_opening_database(&env, &wtxn, "finished-at")?;
+
+    let mut task =
+        all_tasks.get(&wtxn, &task_uid)?.with_context(|| format!("Task {task_uid} not found"))?;
+    if task.status != Status::Enqueued {
+        bail!(
+            "Task {task_uid} is {}, only an enqueued or processing task can be canceled",
+            task.status
+        );
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let content_file = task.content_uuid();
+    task.status = Status::Canceled;
+    task.finished_at = Some(now);
+    task.details = task.details.map(|d| d.to_failed());
+    all_tasks.put(&mut wtxn, &task_uid, &task)?;
+
+    let mut enqueued = status.get(&wtxn, &Status::Enqueued)?.unwrap_or_default();
+    enqueued.remove(task_uid);
+    status.put(&mut wtxn, &Status::Enqueued, &enqueued)?;
+    let mut canceled = status.get(&wtxn, &Status::Canceled)?.unwrap_or_default();
+    canceled.insert(task_uid);
+    status.put(&mut wtxn, &Status::Canceled, &canceled)?;
+
+    let timestamp = now.unix_timestamp_nanos();
+    let mut task_ids = finished_at.get(&wtxn, &timestamp)?.unwrap_or_default();
+    task_ids.insert(task_uid);
+    finished_at.put(&mut wtxn, &timestamp, &task_ids)?;
+
+    wtxn.commit().context("While committing the transaction")?;
+
+    if let Some(content_file) = content_file {
+        let file_store =
+            FileStore::new(db_path.join("update_files")).context("While opening the FileStore")?;
+        file_store.delete(content_file)?;
+    }
+
+    eprintln!("Successfully canceled the task {task_uid}!");
+
+    Ok(())
+}
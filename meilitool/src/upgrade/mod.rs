@@ -0,0 +1,91 @@
+mod v1_7;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use meilisearch_types::versioning::{create_version_file, get_version};
+
+use self::v1_7::v1_7_to_v1_8;
+
+/// Upgrades a database to the version of this tool, one minor version after the other.
+pub struct OfflineUpgrade {
+    pub db_path: PathBuf,
+    /// Only reports what would be done, nothing is written on disk.
+    pub dry_run: bool,
+    /// The directory in which the database is copied before being modified.
+    pub backup_dir: Option<PathBuf>,
+}
+
+impl OfflineUpgrade {
+    pub fn upgrade(self) -> anyhow::Result<()> {
+        // The versions we know how to upgrade from, each function upgrades to the next minor version.
+        let upgrade_list: [(&str, fn(&Path, bool) -> anyhow::Result<()>); 1] =
+            [("1.7", v1_7_to_v1_8)];
+
+        let (major, minor, patch) =
+            get_version(&self.db_path).context("While reading the version of the database")?;
+        let current_version = format!("{major}.{minor}");
+        let target_version =
+            format!("{}.{}", env!("CARGO_PKG_VERSION_MAJOR"), env!("CARGO_PKG_VERSION_MINOR"));
+
+        if current_version == target_version {
+            eprintln!(
+                "The database is already in v{major}.{minor}.{patch}, there is nothing to upgrade."
+            );
+            return Ok(());
+        }
+
+        let start_at = match upgrade_list.iter().position(|(from, _)| *from == current_version) {
+            Some(start_at) => start_at,
+            None => bail!(
+                "Upgrading from v{major}.{minor}.{patch} is not supported, only the databases in v{} can be upgraded to v{target_version}.",
+                upgrade_list[0].0
+            ),
+        };
+
+        if self.dry_run {
+            eprintln!("Dry run, the database will not be modified.");
+        } else if let Some(backup_dir) = &self.backup_dir {
+            let db_name =
+                self.db_path.file_name().and_then(|name| name.to_str()).unwrap_or("data.ms");
+            let backup_path = backup_dir.join(format!("{db_name}-v{major}.{minor}.{patch}"));
+            if backup_path.exists() {
+                bail!("A backup already exists at {}.", backup_path.display());
+            }
+
+            eprintln!("Copying the database in {}...", backup_path.display());
+            copy_dir_all(&self.db_path, &backup_path).with_context(|| {
+                format!("While copying the database in {}", backup_path.display())
+            })?;
+        }
+
+        for (_, upgrade) in &upgrade_list[start_at..] {
+            upgrade(&self.db_path, self.dry_run)?;
+        }
+
+        if self.dry_run {
+            eprintln!("The database can be upgraded to v{}.", env!("CARGO_PKG_VERSION"));
+        } else {
+            create_version_file(&self.db_path).context("While writing the version file")?;
+            eprintln!("Successfully upgraded the database to v{}!", env!("CARGO_PKG_VERSION"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Recursively copies the content of the `src` directory into the `dst` directory.
+fn copy_dir_all(src: &Path, dst: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let path = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &path)?;
+        } else {
+            fs::copy(entry.path(), path)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,213 @@
+//! Upgrades a database from v1.7 to v1.8.
+//!
+//! - The task queue is only read: the new task kinds and details are new variants and optional
+//!   fields that v1.8 reads from the v1.7 tasks as they are.
+//! - The indexes get the `embedding-cache` and `vector-full-precision` databases, and the size of
+//!   the vectors of their embedders is computed and stored in their main database.
+//! - The auth store gets the `api-key-usages` and `audit-log` databases, and every API key is
+//!   written again in the v1.8 format with its actions indexed again, for the actions that `*`
+//!   and the `*` of their category now grant, like `dumps.import` and `auditLog.get`.
+
+use std::path::Path;
+
+use anyhow::Context;
+use meilisearch_auth::{open_auth_store_env, HeedAuthStore};
+use meilisearch_types::heed::types::{Bytes, DecodeIgnore, SerdeJson, Str};
+use meilisearch_types::heed::{Database, Env, EnvOpenOptions, RoTxn, Unspecified};
+use meilisearch_types::keys::Key;
+use meilisearch_types::milli::index::{db_name, main_key};
+use meilisearch_types::milli::BEU32;
+use meilisearch_types::settings::SecretPolicy;
+use meilisearch_types::tasks::Task;
+
+use crate::uuid_codec::UuidCodec;
+use crate::{open_index, open_index_scheduler_env, try_opening_database};
+
+/// The databases of an index that don't exist in v1.7.
+const NEW_INDEX_DATABASES: [&str; 2] = [db_name::EMBEDDING_CACHE, db_name::VECTOR_FULL_PRECISION];
+/// The databases of the auth store that don't exist in v1.7.
+const NEW_AUTH_DATABASES: [&str; 2] = ["api-key-usages", "audit-log"];
+
+pub fn v1_7_to_v1_8(db_path: &Path, dry_run: bool) -> anyhow::Result<()> {
+    eprintln!("Upgrading from v1.7 to v1.8...");
+
+    check_task_queue(db_path)?;
+    upgrade_indexes(db_path, dry_run)?;
+    upgrade_api_keys(db_path, dry_run)?;
+
+    Ok(())
+}
+
+/// Reads all the tasks of the task queue.
+fn check_task_queue(db_path: &Path) -> anyhow::Result<()> {
+    let env = open_index_scheduler_env(db_path)?;
+    let rtxn = env.read_txn()?;
+    let all_tasks: Database<BEU32, SerdeJson<Task>> =
+        try_opening_database(&env, &rtxn, "all-tasks")?;
+
+    let mut count = 0;
+    for result in all_tasks.iter(&rtxn)? {
+        result.with_context(|| format!("While reading the task following the task {count}"))?;
+        count += 1;
+    }
+
+    eprintln!("Checked {count} tasks.");
+
+    Ok(())
+}
+
+/// Creates the new databases of all the indexes and stores the size of their vectors.
+///
+/// During a dry run the indexes are only opened in read-only transactions, opening them as
+/// an [`Index`](meilisearch_types::Index) would create the databases they are missing.
+fn upgrade_indexes(db_path: &Path, dry_run: bool) -> anyhow::Result<()> {
+    let env = open_index_scheduler_env(db_path)?;
+    let rtxn = env.read_txn()?;
+    let index_mapping: Database<Str, UuidCodec> =
+        try_opening_database(&env, &rtxn, "index-mapping")?;
+
+    let mut count = 0;
+    for result in index_mapping.iter(&rtxn)? {
+        let (uid, uuid) = result?;
+        let index_path = db_path.join("indexes").join(uuid.to_string());
+        if dry_run {
+            check_index_read_only(uid, &index_path)?;
+        } else {
+            upgrade_index(uid, &index_path)?;
+        }
+        count += 1;
+    }
+
+    if dry_run {
+        eprintln!("Checked {count} indexes.");
+    } else {
+        eprintln!("Successfully upgraded {count} indexes!");
+    }
+
+    Ok(())
+}
+
+/// Opens an index, which creates its missing databases, and stores the size of the vectors
+/// of the embedders that don't have one yet.
+fn upgrade_index(uid: &str, index_path: &Path) -> anyhow::Result<()> {
+    let index = open_index(index_path)?;
+    let mut wtxn = index.write_txn()?;
+    index
+        .number_of_documents(&wtxn)
+        .with_context(|| format!("While reading the documents of the {uid:?} index"))?;
+    meilisearch_types::settings::settings(&index, &wtxn, SecretPolicy::HideSecrets)
+        .with_context(|| format!("While reading the settings of the {uid:?} index"))?;
+
+    let sizes = index.vector_storage_size(&wtxn)?;
+    let embedders = index
+        .embedder_category_id
+        .iter(&wtxn)?
+        .map(|result| result.map(|(name, embedder_index)| (name.to_owned(), embedder_index)))
+        .collect::<Result<Vec<_>, _>>()?;
+    for (name, embedder_index) in embedders {
+        if !sizes.contains_key(&name) {
+            index.update_vector_storage_size(&mut wtxn, &name, embedder_index).with_context(
+                || format!("While computing the size of the {name:?} vectors of the {uid:?} index"),
+            )?;
+        }
+    }
+
+    wtxn.commit()?;
+    Ok(())
+}
+
+/// Reads the main database and the documents of an index without writing anything,
+/// and reports what the upgrade would change.
+fn check_index_read_only(uid: &str, index_path: &Path) -> anyhow::Result<()> {
+    let env = EnvOpenOptions::new()
+        .max_dbs(100)
+        .open(index_path)
+        .with_context(|| format!("While trying to open {:?}", index_path.display()))?;
+    let rtxn = env.read_txn()?;
+    let main: Database<Bytes, DecodeIgnore> = try_opening_database(&env, &rtxn, "main")?;
+    main.iter(&rtxn)?
+        .try_for_each(|result| result.map(drop))
+        .with_context(|| format!("While reading the main database of the {uid:?} index"))?;
+    let documents: Database<BEU32, DecodeIgnore> = try_opening_database(&env, &rtxn, "documents")?;
+    documents
+        .iter(&rtxn)?
+        .try_for_each(|result| result.map(drop))
+        .with_context(|| format!("While reading the documents of the {uid:?} index"))?;
+
+    for db_name in missing_databases(&env, &rtxn, &NEW_INDEX_DATABASES)? {
+        eprintln!("The {db_name:?} database of the {uid:?} index must be created.");
+    }
+
+    let embedders: Database<Str, DecodeIgnore> =
+        try_opening_database(&env, &rtxn, db_name::VECTOR_EMBEDDER_CATEGORY_ID)?;
+    let has_sizes =
+        main.remap_key_type::<Str>().get(&rtxn, main_key::VECTOR_STORAGE_SIZES)?.is_some();
+    if !has_sizes && !embedders.is_empty(&rtxn)? {
+        eprintln!("The size of the vectors of the {uid:?} index must be computed.");
+    }
+
+    Ok(())
+}
+
+/// Creates the new databases of the auth store and writes every API key again.
+///
+/// During a dry run the keys are only read, `HeedAuthStore::new` would create the missing databases.
+fn upgrade_api_keys(db_path: &Path, dry_run: bool) -> anyhow::Result<()> {
+    let auth_path = db_path.join("auth");
+    if !auth_path.exists() {
+        eprintln!("No auth store found, skipping the API keys.");
+        return Ok(());
+    }
+
+    if dry_run {
+        let env = open_auth_store_env(&auth_path)
+            .with_context(|| format!("While opening the auth store at {}", auth_path.display()))?;
+        let rtxn = env.read_txn()?;
+        for db_name in missing_databases(&env, &rtxn, &NEW_AUTH_DATABASES)? {
+            eprintln!("The {db_name:?} database of the auth store must be created.");
+        }
+        let keys: Database<Bytes, SerdeJson<Key>> = try_opening_database(&env, &rtxn, "api-keys")?;
+        let mut count = 0;
+        for result in keys.iter(&rtxn)? {
+            result
+                .with_context(|| format!("While reading the API key following the key {count}"))?;
+            count += 1;
+        }
+        eprintln!("{count} API keys must be written again.");
+        return Ok(());
+    }
+
+    let auth_store = HeedAuthStore::new(db_path)
+        .with_context(|| format!("While opening the auth store at {}", db_path.display()))?;
+
+    let mut count = 0;
+    for key in auth_store.list_api_keys()? {
+        // writing the key again also indexes its actions again.
+        auth_store.put_api_key(key)?;
+        count += 1;
+    }
+
+    eprintln!("Successfully updated {count} API keys!");
+
+    Ok(())
+}
+
+/// Returns the names of the databases that don't exist in the env.
+fn missing_databases<'a>(
+    env: &Env,
+    rtxn: &RoTxn,
+    db_names: &[&'a str],
+) -> anyhow::Result<Vec<&'a str>> {
+    let mut missing = Vec::new();
+    for &db_name in db_names {
+        let database: Option<Database<Unspecified, Unspecified>> = env
+            .database_options()
+            .name(db_name)
+            .open(rtxn)
+            .with_context(|| format!("While opening the {db_name:?} database"))?;
+        if database.is_none() {
+            missing.push(db_name);
+        }
+    }
+    Ok(missing)
+}
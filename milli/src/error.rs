@@ -203,6 +203,8 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     InvalidEmbedder(String),
     #[error("Too many vectors for document with id {0}: found {1}, but limited to 256.")]
     TooManyVectors(String, usize),
+    #[error("Too many ranges in the facet search: found {count}, but limited to {max} by the `maxValuesPerFacet` setting.")]
+    TooManyFacetSearchRanges { count: usize, max: usize },
     #[error("`.embedders.{embedder_name}`: Field `{field}` unavailable for source `{source_}` (only available for sources: {}). Available fields: {}",
         allowed_sources_for_field
          .iter()
@@ -246,8 +248,18 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     },
     #[error("`.embedders.{embedder_name}.dimensions`: `dimensions` cannot be zero")]
     InvalidSettingsDimensions { embedder_name: String },
+    #[error("`.embedders.{embedder_name}.chunking`: `size` must be greater than zero and `overlap` must be lower than `size`. Found a size of {size} and an overlap of {overlap}")]
+    InvalidSettingsChunking { embedder_name: String, size: usize, overlap: usize },
     #[error("`.embedders.{embedder_name}.url`: could not parse `{url}`: {inner_error}")]
     InvalidUrl { embedder_name: String, inner_error: url::ParseError, url: String },
+    #[error("`.embedders.{embedder_name}.imageField`: `imageField` must be set with the `textAndImage` input type, and only with it")]
+    InvalidSettingsImageField { embedder_name: String },
+    #[error("`.embedders.{embedder_name}.chunking`: the inputs of the `textAndImage` input type cannot be chunked")]
+    InvalidSettingsChunkingWithImages { embedder_name: String },
+    #[error("`.embedders.{embedder_name}.quantization`: the embeddings compared with the `{distance}` distance cannot be quantized")]
+    InvalidSettingsQuantization { embedder_name: String, distance: crate::vector::Distance },
+    #[error("`.embedders.{embedder_name}.rescoring`: `rescoring` can only be enabled with a `quantization`")]
+    InvalidSettingsRescoring { embedder_name: String },
 }
 
 impl From<crate::vector::Error> for Error {
@@ -22,7 +22,7 @@ use crate::heed_codec::{
 };
 use crate::order_by_map::OrderByMap;
 use crate::proximity::ProximityPrecision;
-use crate::vector::EmbeddingConfig;
+use crate::vector::{ArroyReader, Embedding, EmbeddingConfig, VectorStorage};
 use crate::{
     default_criteria, CboRoaringBitmapCodec, Criterion, DocumentId, ExternalDocumentsIds,
     FacetDistribution, FieldDistribution, FieldId, FieldIdWordCountCodec, GeoPoint, ObkvCodec,
@@ -64,9 +64,11 @@ pub mod main_key {
     pub const EXACT_ATTRIBUTES: &str = "exact-attributes";
     pub const MAX_VALUES_PER_FACET: &str = "max-values-per-facet";
     pub const SORT_FACET_VALUES_BY: &str = "sort-facet-values-by";
+    pub const HIERARCHICAL_FACETS: &str = "hierarchical-facets";
     pub const PAGINATION_MAX_TOTAL_HITS: &str = "pagination-max-total-hits";
     pub const PROXIMITY_PRECISION: &str = "proximity-precision";
     pub const EMBEDDING_CONFIGS: &str = "embedding_configs";
+    pub const VECTOR_STORAGE_SIZES: &str = "vector-storage-sizes";
     pub const SEARCH_CUTOFF: &str = "search_cutoff";
 }
 
@@ -95,6 +97,8 @@ pub mod db_name {
     pub const FIELD_ID_DOCID_FACET_STRINGS: &str = "field-id-docid-facet-strings";
     pub const VECTOR_EMBEDDER_CATEGORY_ID: &str = "vector-embedder-category-id";
     pub const VECTOR_ARROY: &str = "vector-arroy";
+    pub const VECTOR_FULL_PRECISION: &str = "vector-full-precision";
+    pub const EMBEDDING_CACHE: &str = "embedding-cache";
     pub const DOCUMENTS: &str = "documents";
     pub const SCRIPT_LANGUAGE_DOCIDS: &str = "script_language_docids";
 }
@@ -164,7 +168,15 @@ pub struct Index {
     /// Maps an embedder name to its id in the arroy store.
     pub embedder_category_id: Database<Str, U8>,
     /// Vector store based on arroy™.
-    pub vector_arroy: arroy::Database<arroy::distances::Angular>,
+    ///
+    /// The data type of the trees depends on the distance and the quantization of their embedder,
+    /// see [`Index::embedder_vector_storage`].
+    pub vector_arroy: arroy::Database<Unspecified>,
+    /// Maps an arroy index and a document id to the full precision embedding of the document,
+    /// only filled for the quantized embedders that rescore their neighbors.
+    pub vector_full_precision: Database<Bytes, Bytes>,
+    /// Maps a hash of an embedder and of a text to the embedding of this text, see [`crate::vector::EmbeddingCache`].
+    pub embedding_cache: Database<Bytes, Bytes>,
 
     /// Maps the document id to the document as an obkv store.
     pub(crate) documents: Database<BEU32, ObkvCodec>,
@@ -179,7 +191,7 @@ impl Index {
     ) -> Result<Index> {
         use db_name::*;
 
-        options.max_dbs(25);
+        options.max_dbs(27);
 
         let env = options.open(path)?;
         let mut wtxn = env.write_txn()?;
@@ -223,6 +235,8 @@ impl Index {
         let embedder_category_id =
             env.create_database(&mut wtxn, Some(VECTOR_EMBEDDER_CATEGORY_ID))?;
         let vector_arroy = env.create_database(&mut wtxn, Some(VECTOR_ARROY))?;
+        let vector_full_precision = env.create_database(&mut wtxn, Some(VECTOR_FULL_PRECISION))?;
+        let embedding_cache = env.create_database(&mut wtxn, Some(EMBEDDING_CACHE))?;
 
         let documents = env.create_database(&mut wtxn, Some(DOCUMENTS))?;
         wtxn.commit()?;
@@ -254,7 +268,9 @@ impl Index {
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
+            vector_full_precision,
             embedder_category_id,
+            embedding_cache,
             documents,
         })
     }
@@ -1412,6 +1428,28 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(txn, main_key::SORT_FACET_VALUES_BY)
     }
 
+    /// Returns the separator of the path-delimited values of each hierarchical facet.
+    pub fn hierarchical_facets(&self, txn: &RoTxn) -> heed::Result<BTreeMap<String, String>> {
+        let separators = self
+            .main
+            .remap_types::<Str, SerdeJson<BTreeMap<String, String>>>()
+            .get(txn, main_key::HIERARCHICAL_FACETS)?
+            .unwrap_or_default();
+        Ok(separators)
+    }
+
+    pub(crate) fn put_hierarchical_facets(
+        &self,
+        txn: &mut RwTxn,
+        val: &BTreeMap<String, String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(txn, main_key::HIERARCHICAL_FACETS, &val)
+    }
+
+    pub(crate) fn delete_hierarchical_facets(&self, txn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::HIERARCHICAL_FACETS)
+    }
+
     pub fn pagination_max_total_hits(&self, txn: &RoTxn) -> heed::Result<Option<u64>> {
         self.main.remap_types::<Str, BEU64>().get(txn, main_key::PAGINATION_MAX_TOTAL_HITS)
     }
@@ -1516,6 +1554,107 @@ impl Index {
             .unwrap_or_default())
     }
 
+    /// Returns the size taken by the embeddings and the arroy trees of each embedder, in bytes.
+    ///
+    /// The sizes are computed when the arroy trees are built, see [`Self::update_vector_storage_size`].
+    pub fn vector_storage_size(&self, rtxn: &RoTxn<'_>) -> heed::Result<BTreeMap<String, u64>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::VECTOR_STORAGE_SIZES)?
+            .unwrap_or_default())
+    }
+
+    /// Computes and stores the size taken by the embeddings and the arroy trees of an embedder.
+    ///
+    /// Only the size of the keys and values is counted, not the overhead of the database pages.
+    /// It scans all the vectors of the embedder and must only be called after building its trees.
+    pub fn update_vector_storage_size(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        embedder_name: &str,
+        embedder_index: u8,
+    ) -> Result<()> {
+        // the arroy indexes of an embedder all start with the embedder index,
+        // see the `writer_index` computation in the vector sort.
+        let mut size = 0;
+        for result in
+            self.vector_arroy.remap_types::<Bytes, Bytes>().prefix_iter(wtxn, &[embedder_index])?
+        {
+            let (key, value) = result?;
+            size += (key.len() + value.len()) as u64;
+        }
+        // the full precision embeddings are keyed by the same arroy indexes.
+        for result in self.vector_full_precision.prefix_iter(wtxn, &[embedder_index])? {
+            let (key, value) = result?;
+            size += (key.len() + value.len()) as u64;
+        }
+
+        let mut sizes = self.vector_storage_size(wtxn)?;
+        sizes.insert(embedder_name.to_owned(), size);
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::VECTOR_STORAGE_SIZES,
+            &sizes,
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn delete_vector_storage_sizes(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::VECTOR_STORAGE_SIZES)
+    }
+
+    /// Returns the embeddings of a document stored in the arroy trees, for each embedder.
+    ///
+    /// A document has one embedding per vector it was indexed with, in the order of the arroy trees.
+    pub fn embeddings(
+        &self,
+        rtxn: &RoTxn<'_>,
+        docid: DocumentId,
+    ) -> Result<BTreeMap<String, Vec<Embedding>>> {
+        let mut res = BTreeMap::new();
+        for result in self.embedder_category_id.iter(rtxn)? {
+            let (embedder_name, embedder_index) = result?;
+            let storage = self.embedder_vector_storage(rtxn, embedder_name)?;
+            let writer_index = (embedder_index as u16) << 8;
+            let mut embeddings = Vec::new();
+            for k in 0..=u8::MAX {
+                let reader = match ArroyReader::open(
+                    rtxn,
+                    self.vector_arroy,
+                    self.vector_full_precision,
+                    writer_index | (k as u16),
+                    storage,
+                ) {
+                    Ok(reader) => reader,
+                    Err(arroy::Error::MissingMetadata) => break,
+                    Err(err) => return Err(err.into()),
+                };
+                if let Some(embedding) = reader.item_vector(rtxn, docid)? {
+                    embeddings.push(embedding);
+                }
+            }
+            res.insert(embedder_name.to_owned(), embeddings);
+        }
+        Ok(res)
+    }
+
+    /// The distance and the quantization used by the arroy trees of an embedder.
+    ///
+    /// Defaults to the unquantized cosine distance if the embedder doesn't exist.
+    pub fn embedder_vector_storage(
+        &self,
+        rtxn: &RoTxn<'_>,
+        embedder_name: &str,
+    ) -> Result<VectorStorage> {
+        Ok(self
+            .embedding_configs(rtxn)?
+            .into_iter()
+            .find(|(name, _)| name == embedder_name)
+            .map(|(_, config)| config.vector_storage())
+            .unwrap_or_default())
+    }
+
     pub(crate) fn put_search_cutoff(&self, wtxn: &mut RwTxn<'_>, cutoff: u64) -> heed::Result<()> {
         self.main.remap_types::<Str, BEU64>().put(wtxn, main_key::SEARCH_CUTOFF, &cutoff)
     }
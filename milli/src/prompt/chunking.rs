@@ -0,0 +1,109 @@
+use charabia::Tokenize;
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of chunks a text is split into, as a document can't have more vectors.
+const MAX_CHUNKS: usize = u8::MAX as usize;
+
+/// Describes how a rendered document template is split in overlapping chunks,
+/// each chunk being embedded as one of the vectors of the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct Chunking {
+    /// The unit in which the size and overlap are expressed.
+    #[serde(default)]
+    #[deserr(default)]
+    pub unit: ChunkUnit,
+    /// The maximum size of a chunk.
+    pub size: usize,
+    /// How much of the end of a chunk is repeated at the beginning of the next one.
+    #[serde(default)]
+    #[deserr(default)]
+    pub overlap: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub enum ChunkUnit {
+    /// Unicode characters.
+    #[default]
+    Characters,
+    /// Words, as segmented by the tokenizer of Meilisearch.
+    ///
+    /// This is not the tokenizer of the embedding model, and is only an approximation of it.
+    Tokens,
+}
+
+impl Chunking {
+    /// Whether the size is not zero and the overlap is smaller than the size.
+    pub fn is_valid(&self) -> bool {
+        self.size > 0 && self.overlap < self.size
+    }
+
+    /// Splits the text in at most 255 chunks of at most `size` units.
+    ///
+    /// Always returns at least one chunk, the text itself if it doesn't need to be split.
+    pub fn split<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        // byte offsets of the start and end of each unit of the text
+        let units: Vec<(usize, usize)> = match self.unit {
+            ChunkUnit::Characters => {
+                text.char_indices().map(|(start, c)| (start, start + c.len_utf8())).collect()
+            }
+            ChunkUnit::Tokens => text
+                .tokenize()
+                .filter(|token| token.is_word())
+                .map(|token| (token.byte_start, token.byte_end))
+                .collect(),
+        };
+
+        if !self.is_valid() || units.len() <= self.size {
+            return vec![text];
+        }
+
+        let step = self.size - self.overlap;
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        while chunks.len() < MAX_CHUNKS {
+            let end = (start + self.size).min(units.len());
+            chunks.push(&text[units[start].0..units[end - 1].1]);
+            if end == units.len() {
+                break;
+            }
+            start += step;
+        }
+        chunks
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChunkUnit, Chunking};
+
+    #[test]
+    fn split_characters() {
+        let chunking = Chunking { unit: ChunkUnit::Characters, size: 4, overlap: 1 };
+        assert_eq!(chunking.split("abcdefghij"), ["abcd", "defg", "ghij"]);
+        assert_eq!(chunking.split("abc"), ["abc"]);
+        assert_eq!(chunking.split(""), [""]);
+        // multi-bytes characters are never cut
+        assert_eq!(chunking.split("éèàùç"), ["éèàù", "ùç"]);
+    }
+
+    #[test]
+    fn split_tokens() {
+        let chunking = Chunking { unit: ChunkUnit::Tokens, size: 2, overlap: 0 };
+        assert_eq!(
+            chunking.split("the quick, brown fox jumps"),
+            ["the quick", "brown fox", "jumps"]
+        );
+    }
+
+    #[test]
+    fn split_is_bounded() {
+        let chunking = Chunking { unit: ChunkUnit::Characters, size: 1, overlap: 0 };
+        let text = "a".repeat(1000);
+        assert_eq!(chunking.split(&text).len(), 255);
+    }
+}
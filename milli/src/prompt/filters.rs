@@ -0,0 +1,322 @@
+//! Meilisearch-specific liquid filters, available in the document templates of the embedders
+//! on top of the liquid standard library.
+
+use liquid::model::{State, Value as LiquidValue};
+use liquid::ValueView;
+use liquid_core::{
+    Display_filter, Error, Expression, Filter, FilterParameters, FilterReflection,
+    FromFilterParameters, ParseFilter, Result, Runtime,
+};
+
+/// Registers the Meilisearch filters on a parser builder.
+///
+/// Their names differ from the ones of the standard library, whose filters keep their behavior
+/// so that the existing templates render the same texts and their embeddings stay valid.
+pub fn register(builder: liquid::ParserBuilder) -> liquid::ParserBuilder {
+    builder
+        .filter(TruncateWordsHtml)
+        .filter(StripHtmlTags)
+        .filter(JoinWith)
+        .filter(DefaultIfBlank)
+        .filter(Json)
+}
+
+#[derive(Debug, FilterParameters)]
+struct TruncateWordsHtmlArgs {
+    #[parameter(description = "The maximum number of words to keep.", arg_type = "integer")]
+    words: Option<Expression>,
+    #[parameter(
+        description = "The text appended to the end of the text if it is truncated.",
+        arg_type = "str"
+    )]
+    ellipsis: Option<Expression>,
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "truncatewords_html",
+    description = "Removes the HTML tags of a text and truncates it to a number of words.",
+    parameters(TruncateWordsHtmlArgs),
+    parsed(TruncateWordsHtmlFilter)
+)]
+pub struct TruncateWordsHtml;
+
+#[derive(Debug, FromFilterParameters, Display_filter)]
+#[name = "truncatewords_html"]
+struct TruncateWordsHtmlFilter {
+    #[parameters]
+    args: TruncateWordsHtmlArgs,
+}
+
+impl Filter for TruncateWordsHtmlFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<LiquidValue> {
+        let args = self.args.evaluate(runtime)?;
+        let words = args.words.unwrap_or(15);
+        let words = usize::try_from(words).map_err(|_| {
+            Error::with_msg("`truncatewords_html` expects a positive number of words")
+        })?;
+        let ellipsis = args.ellipsis.map(|ellipsis| ellipsis.to_string());
+
+        if input.is_nil() {
+            return Ok(LiquidValue::Nil);
+        }
+
+        let text = strip_html(&input.to_kstr());
+        let mut split = text.split_whitespace();
+        let truncated: Vec<&str> = split.by_ref().take(words).collect();
+        let is_truncated = split.next().is_some();
+        let mut truncated = truncated.join(" ");
+        if is_truncated {
+            truncated.push_str(ellipsis.as_deref().unwrap_or("..."));
+        }
+
+        Ok(LiquidValue::scalar(truncated))
+    }
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "strip_html_tags",
+    description = "Removes the HTML tags, comments, scripts and styles of a text, keeping the `<` that don't start a tag.",
+    parsed(StripHtmlTagsFilter)
+)]
+pub struct StripHtmlTags;
+
+#[derive(Debug, Default, Display_filter)]
+#[name = "strip_html_tags"]
+struct StripHtmlTagsFilter;
+
+impl Filter for StripHtmlTagsFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<LiquidValue> {
+        if input.is_nil() {
+            return Ok(LiquidValue::Nil);
+        }
+        Ok(LiquidValue::scalar(strip_html(&input.to_kstr())))
+    }
+}
+
+#[derive(Debug, FilterParameters)]
+struct JoinWithArgs {
+    #[parameter(description = "The separator between the elements.", arg_type = "str")]
+    separator: Expression,
+    #[parameter(
+        description = "The separator between the last two elements, defaults to the separator.",
+        arg_type = "str"
+    )]
+    last_separator: Option<Expression>,
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "join_with",
+    description = "Joins the non-empty elements of an array, with a distinct separator before the last one.",
+    parameters(JoinWithArgs),
+    parsed(JoinWithFilter)
+)]
+pub struct JoinWith;
+
+#[derive(Debug, FromFilterParameters, Display_filter)]
+#[name = "join_with"]
+struct JoinWithFilter {
+    #[parameters]
+    args: JoinWithArgs,
+}
+
+impl Filter for JoinWithFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<LiquidValue> {
+        let args = self.args.evaluate(runtime)?;
+
+        // a single value is joined with nothing
+        let Some(array) = input.as_array() else { return Ok(input.to_value()) };
+
+        let elements: Vec<_> = array
+            .values()
+            .filter(|element| !element.query_state(State::Blank))
+            .map(|element| element.to_kstr().into_owned())
+            .collect();
+
+        let separator = args.separator.as_str();
+        let last_separator = args.last_separator.as_ref().map_or(separator, |s| s.as_str());
+
+        let joined = match elements.split_last() {
+            Some((last, [])) => last.to_string(),
+            Some((last, rest)) => format!("{}{last_separator}{last}", rest.join(separator)),
+            None => String::new(),
+        };
+
+        Ok(LiquidValue::scalar(joined))
+    }
+}
+
+#[derive(Debug, FilterParameters)]
+struct DefaultArgs {
+    #[parameter(description = "The value to use when the input is blank.")]
+    default: Expression,
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "default_if_blank",
+    description = "Replaces a missing, empty or whitespace-only value.",
+    parameters(DefaultArgs),
+    parsed(DefaultIfBlankFilter)
+)]
+pub struct DefaultIfBlank;
+
+#[derive(Debug, FromFilterParameters, Display_filter)]
+#[name = "default_if_blank"]
+struct DefaultIfBlankFilter {
+    #[parameters]
+    args: DefaultArgs,
+}
+
+impl Filter for DefaultIfBlankFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<LiquidValue> {
+        let args = self.args.evaluate(runtime)?;
+
+        // unlike the `default` of the standard library, `false` is kept and whitespace-only strings are replaced
+        let is_blank = match input.as_scalar().and_then(|scalar| scalar.to_bool()) {
+            Some(_) => false,
+            None => input.query_state(State::Blank),
+        };
+
+        if is_blank {
+            Ok(args.default.to_value())
+        } else {
+            Ok(input.to_value())
+        }
+    }
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(name = "json", description = "Serializes a value as JSON.", parsed(JsonFilter))]
+pub struct Json;
+
+#[derive(Debug, Default, Display_filter)]
+#[name = "json"]
+struct JsonFilter;
+
+impl Filter for JsonFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<LiquidValue> {
+        let json = serde_json::to_string(&input.to_value())
+            .map_err(|error| Error::with_msg(format!("cannot serialize value as JSON: {error}")))?;
+        Ok(LiquidValue::scalar(json))
+    }
+}
+
+/// Removes the tags, comments, scripts and styles of an HTML text.
+///
+/// A `<` that doesn't start a tag is kept, so plain texts are left untouched.
+fn strip_html(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let lowercase_start = rest.get(..8).unwrap_or(rest).to_ascii_lowercase();
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + "-->".len())
+        } else if lowercase_start.starts_with("<script") || lowercase_start.starts_with("<style") {
+            let closing =
+                if lowercase_start.starts_with("<script") { "</script>" } else { "</style>" };
+            rest.to_ascii_lowercase().find(closing).map(|end| end + closing.len())
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!') {
+            rest.find('>').map(|end| end + 1)
+        } else {
+            output.push('<');
+            rest = &rest[1..];
+            continue;
+        };
+
+        // an unclosed tag runs until the end of the text
+        rest = end.map_or("", |end| &rest[end..]);
+    }
+    output.push_str(rest);
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::strip_html;
+    use crate::prompt::new_template;
+
+    fn render(template: &str, doc: serde_json::Value) -> String {
+        let globals = liquid::object!({ "doc": liquid::model::to_value(&doc).unwrap() });
+        new_template(template).unwrap().render(&globals).unwrap()
+    }
+
+    #[test]
+    fn strip_html_tags() {
+        assert_eq!(strip_html("<p>Hello <b>world</b></p>"), "Hello world");
+        assert_eq!(strip_html("a <!-- comment --> b"), "a  b");
+        assert_eq!(strip_html("<script>let a = 1 < 2;</script>text"), "text");
+        assert_eq!(strip_html("1 < 2 and 3 > 2"), "1 < 2 and 3 > 2");
+        assert_eq!(strip_html("unclosed <a href="), "unclosed ");
+    }
+
+    #[test]
+    fn truncatewords_html() {
+        let doc = serde_json::json!({ "overview": "<p>A <em>long</em> overview of the movie</p>" });
+        assert_eq!(
+            render("{{ doc.overview | truncatewords_html: 3 }}", doc.clone()),
+            "A long overview..."
+        );
+        assert_eq!(
+            render("{{ doc.overview | truncatewords_html: 3, \" [...]\" }}", doc.clone()),
+            "A long overview [...]"
+        );
+        assert_eq!(
+            render("{{ doc.overview | truncatewords_html: 10 }}", doc),
+            "A long overview of the movie"
+        );
+    }
+
+    #[test]
+    fn join_with() {
+        let doc = serde_json::json!({ "genres": ["Drama", "", "Comedy", null, "Action"], "genre": "Drama" });
+        assert_eq!(
+            render("{{ doc.genres | join_with: \", \" }}", doc.clone()),
+            "Drama, Comedy, Action"
+        );
+        assert_eq!(
+            render("{{ doc.genres | join_with: \", \", \" and \" }}", doc.clone()),
+            "Drama, Comedy and Action"
+        );
+        assert_eq!(render("{{ doc.genre | join_with: \", \" }}", doc), "Drama");
+    }
+
+    #[test]
+    fn default_if_blank() {
+        let doc = serde_json::json!({ "title": "  ", "adult": false, "year": 1999 });
+        assert_eq!(
+            render("{{ doc.title | default_if_blank: \"Untitled\" }}", doc.clone()),
+            "Untitled"
+        );
+        assert_eq!(
+            render("{{ doc.missing | default_if_blank: \"Unknown\" }}", doc.clone()),
+            "Unknown"
+        );
+        assert_eq!(render("{{ doc.adult | default_if_blank: true }}", doc.clone()), "false");
+        assert_eq!(render("{{ doc.year | default_if_blank: 2000 }}", doc), "1999");
+    }
+
+    #[test]
+    fn standard_filters_are_kept() {
+        let doc =
+            serde_json::json!({ "title": "  ", "adult": false, "bio": "1 <b>&lt;</b> 2 <br>" });
+        // the `default` of the standard library only replaces nil, false and empty values
+        assert_eq!(render("{{ doc.title | default: \"Untitled\" }}", doc.clone()), "  ");
+        assert_eq!(render("{{ doc.adult | default: true }}", doc.clone()), "true");
+        assert_eq!(render("{{ doc.bio | strip_html }}", doc), "1 &lt; 2 ");
+    }
+
+    #[test]
+    fn json() {
+        let doc = serde_json::json!({ "cast": [{ "name": "Keanu" }], "title": "The \"Matrix\"" });
+        assert_eq!(render("{{ doc.cast | json }}", doc.clone()), r#"[{"name":"Keanu"}]"#);
+        assert_eq!(render("{{ doc.title | json }}", doc), r#""The \"Matrix\"""#);
+    }
+}
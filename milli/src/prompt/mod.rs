@@ -1,31 +1,37 @@
+mod chunking;
 mod context;
 mod document;
 pub(crate) mod error;
 mod fields;
+mod filters;
 mod template_checker;
 
 use std::convert::TryFrom;
 
 use error::{NewPromptError, RenderPromptError};
 
+pub use self::chunking::{ChunkUnit, Chunking};
 use self::context::Context;
 use self::document::Document;
-use crate::update::del_add::DelAdd;
-use crate::FieldsIdsMap;
+use crate::update::del_add::{into_del_add_obkv, DelAdd, DelAddOperation};
+use crate::{FieldsIdsMap, InternalError, UserError};
 
 pub struct Prompt {
     template: liquid::Template,
     template_text: String,
+    chunking: Option<Chunking>,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct PromptData {
     pub template: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunking: Option<Chunking>,
 }
 
 impl From<Prompt> for PromptData {
     fn from(value: Prompt) -> Self {
-        Self { template: value.template_text }
+        Self { template: value.template_text, chunking: value.chunking }
     }
 }
 
@@ -33,19 +39,23 @@ impl TryFrom<PromptData> for Prompt {
     type Error = NewPromptError;
 
     fn try_from(value: PromptData) -> Result<Self, Self::Error> {
-        Prompt::new(value.template)
+        Ok(Prompt::new(value.template)?.with_chunking(value.chunking))
     }
 }
 
 impl Clone for Prompt {
     fn clone(&self) -> Self {
         let template_text = self.template_text.clone();
-        Self { template: new_template(&template_text).unwrap(), template_text }
+        Self {
+            template: new_template(&template_text).unwrap(),
+            template_text,
+            chunking: self.chunking,
+        }
     }
 }
 
 fn new_template(text: &str) -> Result<liquid::Template, liquid::Error> {
-    liquid::ParserBuilder::with_stdlib().build().unwrap().parse(text)
+    filters::register(liquid::ParserBuilder::with_stdlib()).build().unwrap().parse(text)
 }
 
 fn default_template() -> liquid::Template {
@@ -60,25 +70,26 @@ fn default_template_text() -> &'static str {
 
 impl Default for Prompt {
     fn default() -> Self {
-        Self { template: default_template(), template_text: default_template_text().into() }
+        Self {
+            template: default_template(),
+            template_text: default_template_text().into(),
+            chunking: None,
+        }
     }
 }
 
 impl Default for PromptData {
     fn default() -> Self {
-        Self { template: default_template_text().into() }
+        Self { template: default_template_text().into(), chunking: None }
     }
 }
 
 impl Prompt {
     pub fn new(template: String) -> Result<Self, NewPromptError> {
         let this = Self {
-            template: liquid::ParserBuilder::with_stdlib()
-                .build()
-                .unwrap()
-                .parse(&template)
-                .map_err(NewPromptError::cannot_parse_template)?,
+            template: new_template(&template).map_err(NewPromptError::cannot_parse_template)?,
             template_text: template,
+            chunking: None,
         };
 
         // render template with special object that's OK with `doc.*` and `fields.*`
@@ -100,6 +111,51 @@ impl Prompt {
 
         self.template.render(&context).map_err(RenderPromptError::missing_context)
     }
+
+    /// Renders a document as stored in the index, outside of an indexing operation.
+    pub fn render_document(
+        &self,
+        document: obkv::KvReaderU16<'_>,
+        field_id_map: &FieldsIdsMap,
+    ) -> crate::Result<String> {
+        let mut buffer = Vec::new();
+        into_del_add_obkv(document, DelAddOperation::Addition, &mut buffer)?;
+        Ok(self.render(obkv::KvReaderU16::new(&buffer), DelAdd::Addition, field_id_map)?)
+    }
+
+    /// Renders a document given as a JSON object, that doesn't need to be stored in the index.
+    pub fn render_object(
+        &self,
+        object: &serde_json::Map<String, serde_json::Value>,
+    ) -> crate::Result<String> {
+        let mut fields_ids_map = FieldsIdsMap::new();
+        let mut writer = obkv::KvWriterU16::memory();
+        // the fields are inserted in the map in order, so their ids are increasing as the obkv expects
+        for (name, value) in object {
+            let fid = fields_ids_map.insert(name).ok_or(UserError::AttributeLimitReached)?;
+            writer.insert(fid, serde_json::to_vec(value).map_err(InternalError::SerdeJson)?)?;
+        }
+        let document = writer.into_inner()?;
+        self.render_document(obkv::KvReaderU16::new(&document), &fields_ids_map)
+    }
+
+    /// Splits the text in the chunks to embed as the vectors of a document.
+    ///
+    /// Without chunking, the text is embedded as a single vector.
+    pub fn chunk<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        match &self.chunking {
+            Some(chunking) => chunking.split(text),
+            None => vec![text],
+        }
+    }
+
+    pub fn chunking(&self) -> Option<Chunking> {
+        self.chunking
+    }
+
+    pub fn with_chunking(self, chunking: Option<Chunking>) -> Self {
+        Self { chunking, ..self }
+    }
 }
 
 #[cfg(test)]
@@ -173,4 +229,33 @@ mod test {
             })
         ));
     }
+
+    #[test]
+    fn template_filters_ok() {
+        Prompt::new(
+            "{{doc.title | default_if_blank: \"Untitled\"}}: {{doc.overview | truncatewords_html: 50}} \
+             {{doc.genres | join_with: \", \", \" and \"}} {{doc.cast | json}} {{doc.bio | strip_html_tags}}"
+                .into(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn template_filters_invalid() {
+        assert!(matches!(
+            Prompt::new("{{doc.overview | truncatewords_html: \"many\"}}".into()),
+            Err(NewPromptError {
+                kind: NewPromptErrorKind::InvalidFieldsInTemplate(_),
+                fault: FaultSource::User
+            })
+        ));
+        assert!(matches!(
+            // missing separator
+            Prompt::new("{{doc.genres | join_with}}".into()),
+            Err(NewPromptError {
+                kind: NewPromptErrorKind::CannotParseTemplate(_),
+                fault: FaultSource::User
+            })
+        ));
+    }
 }
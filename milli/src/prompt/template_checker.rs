@@ -3,6 +3,11 @@ use liquid::model::{
 };
 use liquid::{Object, ObjectView, ValueView};
 
+/// A rendering context where every `doc.*` and `fields.*` value is `nil`.
+///
+/// Rendering a template against it also evaluates the arguments of its filters,
+/// so that invalid arguments are reported when the template is set rather than when indexing.
+/// The Meilisearch filters accept `nil` inputs for this reason.
 #[derive(Debug)]
 pub struct TemplateChecker;
 
@@ -17,6 +17,8 @@ pub enum ScoreDetails {
     Sort(Sort),
     Vector(Vector),
     GeoSort(GeoSort),
+    /// The reciprocal rank fusion of the keyword and semantic searches of a hybrid search.
+    Fusion(Fusion),
 
     /// Returned when we don't have the time to finish applying all the subsequent ranking-rules
     Skipped,
@@ -53,6 +55,7 @@ impl ScoreDetails {
             ScoreDetails::Sort(_) => None,
             ScoreDetails::GeoSort(_) => None,
             ScoreDetails::Vector(_) => None,
+            ScoreDetails::Fusion(_) => None,
             ScoreDetails::Skipped => Some(Rank { rank: 0, max_rank: 1 }),
         }
     }
@@ -101,6 +104,7 @@ impl ScoreDetails {
             ScoreDetails::Vector(vector) => {
                 RankOrValue::Score(vector.similarity.as_ref().map(|s| *s as f64).unwrap_or(0.0f64))
             }
+            ScoreDetails::Fusion(fusion) => RankOrValue::Score(fusion.score),
             ScoreDetails::Skipped => RankOrValue::Rank(Rank { rank: 0, max_rank: 1 }),
         }
     }
@@ -258,6 +262,18 @@ impl ScoreDetails {
                     details_map.insert("vectorSort".into(), details);
                     order += 1;
                 }
+                ScoreDetails::Fusion(fusion) => {
+                    let details = serde_json::json!({
+                        "order": order,
+                        "keywordRank": fusion.keyword_rank,
+                        "semanticRank": fusion.semantic_rank,
+                        "keywordContribution": fusion.keyword_contribution,
+                        "semanticContribution": fusion.semantic_contribution,
+                        "score": fusion.score,
+                    });
+                    details_map.insert("reciprocalRankFusion".into(), details);
+                    order += 1;
+                }
                 ScoreDetails::Skipped => {
                     details_map
                         .insert("skipped".to_string(), serde_json::json!({ "order": order }));
@@ -492,6 +508,22 @@ impl PartialOrd for GeoSort {
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Vector {
     pub similarity: Option<f32>,
+    /// Index of the vector of the document that was the most similar.
+    ///
+    /// When the document template of the embedder is chunked, this is the index of the chunk.
+    pub vector_index: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fusion {
+    /// Rank of the document in the keyword search, starting at 1, if it was returned by it.
+    pub keyword_rank: Option<u32>,
+    /// Rank of the document in the semantic search, starting at 1, if it was returned by it.
+    pub semantic_rank: Option<u32>,
+    pub keyword_contribution: f64,
+    pub semantic_contribution: f64,
+    /// The sum of the contributions, scaled so that a document ranked first in both searches scores 1.
+    pub score: f64,
 }
 
 impl GeoSort {
@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
+use std::num::NonZeroUsize;
 use std::ops::ControlFlow;
 use std::{fmt, mem};
 
@@ -17,6 +18,7 @@ use crate::heed_codec::{BytesRefCodec, StrRefCodec};
 use crate::search::facet::facet_distribution_iter::{
     count_iterate_over_facet_distribution, lexicographically_iterate_over_facet_distribution,
 };
+use crate::search::facet::FacetRange;
 use crate::{FieldId, Index, Result};
 
 /// The default number of values by facets that will
@@ -37,6 +39,35 @@ pub enum OrderBy {
     Count,
 }
 
+/// A value of a hierarchical facet and the values under it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct FacetHierarchyNode {
+    /// The number of candidates with this value or one of its descendants.
+    pub count: u64,
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub children: IndexMap<String, FacetHierarchyNode>,
+}
+
+/// A node of a hierarchy being built, its children are indexed by their normalized value.
+struct HierarchyBuilder {
+    name: String,
+    count: u64,
+    children: IndexMap<String, HierarchyBuilder>,
+}
+
+impl HierarchyBuilder {
+    fn build_all(
+        nodes: IndexMap<String, HierarchyBuilder>,
+    ) -> IndexMap<String, FacetHierarchyNode> {
+        nodes
+            .into_values()
+            .map(|HierarchyBuilder { name, count, children }| {
+                (name, FacetHierarchyNode { count, children: Self::build_all(children) })
+            })
+            .collect()
+    }
+}
+
 pub struct FacetDistribution<'a> {
     facets: Option<HashMap<String, OrderBy>>,
     candidates: Option<RoaringBitmap>,
@@ -332,6 +363,158 @@ impl<'a> FacetDistribution<'a> {
         Ok(distribution)
     }
 
+    /// Returns the number of candidates in each range of values of the given numeric facets.
+    pub fn compute_ranges(
+        &self,
+        ranges: &BTreeMap<String, Vec<FacetRange>>,
+    ) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
+        self.check_faceted(ranges.keys())?;
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let candidates = match &self.candidates {
+            Some(candidates) => candidates,
+            None => return Ok(Default::default()),
+        };
+
+        let mut distribution = BTreeMap::new();
+        for (name, ranges) in ranges {
+            let Some(fid) = fields_ids_map.id(name) else { continue };
+            let mut values = IndexMap::new();
+            for range in ranges {
+                let docids = range.docids(self.index, self.rtxn, fid)?;
+                values.insert(range.name(), candidates.intersection_len(&docids));
+            }
+            distribution.insert(name.clone(), values);
+        }
+
+        Ok(distribution)
+    }
+
+    /// Returns the number of candidates in the buckets of the given interval of the numeric facets,
+    /// from the bucket of the lowest value of the candidates to the one of the highest.
+    ///
+    /// At most `max_values_per_facet` buckets are returned by facet.
+    pub fn compute_histogram(
+        &self,
+        intervals: &BTreeMap<String, f64>,
+    ) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
+        self.check_faceted(intervals.keys())?;
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let candidates = match &self.candidates {
+            Some(candidates) => candidates,
+            None => return Ok(Default::default()),
+        };
+
+        let mut distribution = BTreeMap::new();
+        for (name, &interval) in intervals {
+            let Some(fid) = fields_ids_map.id(name) else { continue };
+            let min = super::facet_min_value(self.index, self.rtxn, fid, candidates.clone())?;
+            let max = super::facet_max_value(self.index, self.rtxn, fid, candidates.clone())?;
+            let (Some(min), Some(max)) = (min, max) else { continue };
+
+            let first = (min / interval).floor();
+            let mut values = IndexMap::new();
+            for bucket in 0..self.max_values_per_facet {
+                // computing the bounds from the first bucket avoids accumulating rounding errors.
+                let from = (first + bucket as f64) * interval;
+                if from > max {
+                    break;
+                }
+                let range = FacetRange { from: Some(from), to: Some(from + interval) };
+                let docids = range.docids(self.index, self.rtxn, fid)?;
+                values.insert(range.name(), candidates.intersection_len(&docids));
+            }
+            distribution.insert(name.clone(), values);
+        }
+
+        Ok(distribution)
+    }
+
+    /// Returns the tree of the values of the given hierarchical facets, split on their separator,
+    /// with the number of candidates under each node, down to `max_depth` levels.
+    ///
+    /// The nodes are sorted lexicographically and at most `max_values_per_facet` children are
+    /// returned by node.
+    pub fn compute_hierarchy(
+        &self,
+        separators: &BTreeMap<String, String>,
+        max_depth: Option<NonZeroUsize>,
+    ) -> Result<BTreeMap<String, IndexMap<String, FacetHierarchyNode>>> {
+        self.check_faceted(separators.keys())?;
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let candidates = match &self.candidates {
+            Some(candidates) => candidates,
+            None => return Ok(Default::default()),
+        };
+
+        let mut distribution = BTreeMap::new();
+        for (name, separator) in separators {
+            let Some(fid) = fields_ids_map.id(name) else { continue };
+            // the ancestors of a value are indexed with it and come first in the lexicographic
+            // order, we can always attach a value to the node of its parent.
+            let mut roots: IndexMap<String, HierarchyBuilder> = IndexMap::new();
+            lexicographically_iterate_over_facet_distribution(
+                self.rtxn,
+                self.index
+                    .facet_id_string_docids
+                    .remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+                fid,
+                candidates,
+                |facet_key, nbr_docids, any_docid| {
+                    let facet_key = StrRefCodec::bytes_decode(facet_key).unwrap();
+
+                    let key: (FieldId, _, &str) = (fid, any_docid, facet_key);
+                    let original_string =
+                        self.index.field_id_docid_facet_strings.get(self.rtxn, &key)?.unwrap();
+
+                    let segments: Vec<_> = original_string.split(separator.as_str()).collect();
+                    if max_depth.map_or(false, |max_depth| segments.len() > max_depth.get()) {
+                        return Ok(ControlFlow::Continue(()));
+                    }
+
+                    let (last, ancestors) = segments.split_last().unwrap();
+                    let mut children = &mut roots;
+                    for ancestor in ancestors {
+                        match children.get_mut(&crate::normalize_facet(ancestor)) {
+                            Some(node) => children = &mut node.children,
+                            None => return Ok(ControlFlow::Continue(())),
+                        }
+                    }
+
+                    if children.len() < self.max_values_per_facet {
+                        children.insert(
+                            crate::normalize_facet(last),
+                            HierarchyBuilder {
+                                name: last.trim().to_string(),
+                                count: nbr_docids,
+                                children: IndexMap::new(),
+                            },
+                        );
+                    }
+                    Ok(ControlFlow::Continue(()))
+                },
+            )?;
+            distribution.insert(name.clone(), HierarchyBuilder::build_all(roots));
+        }
+
+        Ok(distribution)
+    }
+
+    /// Returns an error if one of the given fields is not filterable.
+    fn check_faceted<'n>(&self, names: impl Iterator<Item = &'n String>) -> Result<()> {
+        let filterable_fields = self.index.filterable_fields(self.rtxn)?;
+        let invalid_fields: HashSet<_> =
+            names.filter(|facet| !crate::is_faceted(facet, &filterable_fields)).collect();
+        if invalid_fields.is_empty() {
+            Ok(())
+        } else {
+            Err(UserError::InvalidFacetsDistribution {
+                invalid_facets_name: invalid_fields.into_iter().cloned().collect(),
+                valid_facets_name: filterable_fields.into_iter().collect(),
+            }
+            .into())
+        }
+    }
+
     pub fn execute(&self) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
         let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
         let filterable_fields = self.index.filterable_fields(self.rtxn)?;
@@ -398,11 +581,11 @@ mod tests {
     use std::iter;
 
     use big_s::S;
-    use maplit::hashset;
+    use maplit::{btreemap, hashset};
 
     use crate::documents::documents_batch_reader_from_objects;
     use crate::index::tests::TempIndex;
-    use crate::{milli_snap, FacetDistribution, OrderBy};
+    use crate::{milli_snap, FacetDistribution, FacetRange, OrderBy};
 
     #[test]
     fn few_candidates_few_facet_values() {
@@ -677,6 +860,92 @@ mod tests {
         milli_snap!(format!("{map:?}"), "candidates_217_777", @r###"{"colour": (217.0, 776.0)}"###);
     }
 
+    #[test]
+    fn facet_ranges_and_histogram() {
+        let mut index = TempIndex::new_with_map_size(4096 * 10_000);
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("colour") }))
+            .unwrap();
+
+        let documents: Vec<_> = (0..1000)
+            .map(|i| serde_json::json!({ "colour": i }).as_object().unwrap().clone())
+            .collect();
+        let documents = documents_batch_reader_from_objects(documents);
+
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let ranges = btreemap! { S("colour") => vec![
+            FacetRange { from: None, to: Some(250.0) },
+            FacetRange { from: Some(250.0), to: Some(500.0) },
+            FacetRange { from: Some(500.0), to: None },
+        ]};
+        let map = FacetDistribution::new(&txn, &index)
+            .candidates((217..777).collect())
+            .compute_ranges(&ranges)
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "ranges", @r###"{"colour": {"*-250": 33, "250-500": 250, "500-*": 277}}"###);
+
+        let intervals = btreemap! { S("colour") => 200.0 };
+        let map = FacetDistribution::new(&txn, &index)
+            .candidates((217..777).collect())
+            .compute_histogram(&intervals)
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "histogram", @r###"{"colour": {"200-400": 183, "400-600": 200, "600-800": 177}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .candidates((0..1000).collect())
+            .max_values_per_facet(2)
+            .compute_histogram(&intervals)
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "histogram_with_max_2", @r###"{"colour": {"0-200": 200, "200-400": 200}}"###);
+    }
+
+    #[test]
+    fn facet_hierarchy() {
+        let mut index = TempIndex::new();
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("category") });
+                settings.set_hierarchical_facets(btreemap! { S("category") => S(" > ") });
+            })
+            .unwrap();
+
+        let documents = documents!([
+            { "category": "Electronics > Phones > Android" },
+            { "category": "electronics > Phones > iOS" },
+            { "category": "Electronics > Laptops" },
+            { "category": ["Books", "Electronics"] },
+        ]);
+
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let separators = btreemap! { S("category") => S(" > ") };
+
+        let map = FacetDistribution::new(&txn, &index)
+            .candidates((0..4).collect())
+            .compute_hierarchy(&separators, None)
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "all", @r###"{"category": {"Books": FacetHierarchyNode { count: 1, children: {} }, "Electronics": FacetHierarchyNode { count: 4, children: {"Laptops": FacetHierarchyNode { count: 1, children: {} }, "Phones": FacetHierarchyNode { count: 2, children: {"Android": FacetHierarchyNode { count: 1, children: {} }, "iOS": FacetHierarchyNode { count: 1, children: {} }} }} }}}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .candidates((0..2).collect())
+            .compute_hierarchy(&separators, NonZeroUsize::new(2))
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "depth_2", @r###"{"category": {"Electronics": FacetHierarchyNode { count: 2, children: {"Phones": FacetHierarchyNode { count: 2, children: {} }} }}}"###);
+    }
+
     #[test]
     fn facet_stats_array() {
         let mut index = TempIndex::new_with_map_size(4096 * 10_000);
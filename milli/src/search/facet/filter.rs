@@ -221,6 +221,28 @@ impl<'a> Filter<'a> {
 }
 
 impl<'a> Filter<'a> {
+    /// The names of the fields used by the filter, the geo filters use the `_geo` field.
+    pub fn fields(&self) -> Vec<&str> {
+        fn collect_fields<'c>(condition: &'c FilterCondition, fields: &mut Vec<&'c str>) {
+            match condition {
+                FilterCondition::Not(condition) => collect_fields(condition, fields),
+                FilterCondition::Condition { fid, .. } | FilterCondition::In { fid, .. } => {
+                    fields.push(fid.value())
+                }
+                FilterCondition::Or(conditions) | FilterCondition::And(conditions) => {
+                    conditions.iter().for_each(|condition| collect_fields(condition, fields))
+                }
+                FilterCondition::GeoLowerThan { .. } | FilterCondition::GeoBoundingBox { .. } => {
+                    fields.push("_geo")
+                }
+            }
+        }
+
+        let mut fields = Vec::new();
+        collect_fields(&self.condition, &mut fields);
+        fields
+    }
+
     pub fn evaluate(&self, rtxn: &heed::RoTxn, index: &Index) -> Result<RoaringBitmap> {
         // to avoid doing this for each recursive call we're going to do it ONCE ahead of time
         let filterable_fields = index.filterable_fields(rtxn)?;
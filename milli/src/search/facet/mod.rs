@@ -1,15 +1,19 @@
+use std::ops::Bound;
+
 pub use facet_sort_ascending::ascending_facet_sort;
 pub use facet_sort_descending::descending_facet_sort;
 use heed::types::{Bytes, DecodeIgnore};
 use heed::{BytesDecode, RoTxn};
 use roaring::RoaringBitmap;
 
-pub use self::facet_distribution::{FacetDistribution, OrderBy, DEFAULT_VALUES_PER_FACET};
+pub use self::facet_distribution::{
+    FacetDistribution, FacetHierarchyNode, OrderBy, DEFAULT_VALUES_PER_FACET,
+};
 pub use self::filter::{BadGeoError, Filter};
-pub use self::search::{FacetValueHit, SearchForFacetValues};
+pub use self::search::{FacetRangeHit, FacetValueHit, SearchForFacetValues};
 use crate::heed_codec::facet::{FacetGroupKeyCodec, FacetGroupValueCodec, OrderedF64Codec};
 use crate::heed_codec::BytesRefCodec;
-use crate::{Index, Result};
+use crate::{FieldId, Index, Result};
 
 mod facet_distribution;
 mod facet_distribution_iter;
@@ -53,6 +57,38 @@ pub fn facet_max_value<'t>(
     facet_extreme_value(it)
 }
 
+/// A range of numeric facet values, the lower bound is included and the upper one excluded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FacetRange {
+    pub from: Option<f64>,
+    pub to: Option<f64>,
+}
+
+impl FacetRange {
+    /// The name of the range, e.g. `0-10`, the missing bounds are written `*`.
+    pub fn name(&self) -> String {
+        let bound = |bound: Option<f64>| bound.map_or_else(|| String::from("*"), |b| b.to_string());
+        format!("{}-{}", bound(self.from), bound(self.to))
+    }
+
+    /// Returns the documents with a value of the field in the range,
+    /// computed from the groups of the facet level trees.
+    pub fn docids(&self, index: &Index, rtxn: &RoTxn, field_id: FieldId) -> Result<RoaringBitmap> {
+        let left = self.from.map_or(Bound::Unbounded, Bound::Included);
+        let right = self.to.map_or(Bound::Unbounded, Bound::Excluded);
+        let mut docids = RoaringBitmap::new();
+        facet_range_search::find_docids_of_facet_within_bounds::<OrderedF64Codec>(
+            rtxn,
+            index.facet_id_f64_docids,
+            field_id,
+            &left,
+            &right,
+            &mut docids,
+        )?;
+        Ok(docids)
+    }
+}
+
 /// Get the first facet value in the facet database
 pub(crate) fn get_first_facet_value<'t, BoundCodec>(
     txn: &'t RoTxn,
@@ -6,11 +6,17 @@ use charabia::normalizer::NormalizerOption;
 use charabia::Normalize;
 use fst::automaton::{Automaton, Str};
 use fst::{IntoStreamer, Streamer};
+use heed::BytesDecode;
 use roaring::RoaringBitmap;
 use tracing::error;
 
+use super::facet_distribution_iter::lexicographically_iterate_over_facet_distribution;
+use super::{get_first_facet_value, FacetRange};
 use crate::error::UserError;
-use crate::heed_codec::facet::{FacetGroupKey, FacetGroupValue};
+use crate::heed_codec::facet::{
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, OrderedF64Codec,
+};
+use crate::heed_codec::BytesRefCodec;
 use crate::search::build_dfa;
 use crate::{DocumentId, FieldId, OrderBy, Result, Search};
 
@@ -62,7 +68,9 @@ impl<'a> SearchForFacetValues<'a> {
         Ok(index.field_id_docid_facet_strings.get(rtxn, &key)?.map(|v| v.to_owned()))
     }
 
-    pub fn execute(&self) -> Result<Vec<FacetValueHit>> {
+    /// Returns the field id of the facet, `None` if no document contains it (yet),
+    /// or an error if the facet is not filterable.
+    fn facet_field_id(&self) -> Result<Option<FieldId>> {
         let index = self.search_query.index;
         let rtxn = self.search_query.rtxn;
 
@@ -79,20 +87,11 @@ impl<'a> SearchForFacetValues<'a> {
             .into());
         }
 
-        let fields_ids_map = index.fields_ids_map(rtxn)?;
-        let fid = match fields_ids_map.id(&self.facet) {
-            Some(fid) => fid,
-            // we return an empty list of results when the attribute has been
-            // set as filterable but no document contains this field (yet).
-            None => return Ok(Vec::new()),
-        };
-
-        let fst = match self.search_query.index.facet_id_string_fst.get(rtxn, &fid)? {
-            Some(fst) => fst,
-            None => return Ok(Vec::new()),
-        };
+        Ok(index.fields_ids_map(rtxn)?.id(&self.facet))
+    }
 
-        let search_candidates = self.search_query.execute_for_candidates(
+    fn search_candidates(&self) -> Result<RoaringBitmap> {
+        self.search_query.execute_for_candidates(
             self.is_hybrid
                 || self
                     .search_query
@@ -100,13 +99,89 @@ impl<'a> SearchForFacetValues<'a> {
                     .as_ref()
                     .and_then(|semantic| semantic.vector.as_ref())
                     .is_some(),
-        )?;
+        )
+    }
+
+    /// Returns the number of documents in each range of numeric values, in the order of the ranges.
+    ///
+    /// There can't be more ranges than the maximum number of values returned by the facet search.
+    pub fn execute_ranges(&self, ranges: &[FacetRange]) -> Result<Vec<FacetRangeHit>> {
+        if ranges.len() > self.max_values {
+            return Err(UserError::TooManyFacetSearchRanges {
+                count: ranges.len(),
+                max: self.max_values,
+            }
+            .into());
+        }
+
+        let empty_hit =
+            |range: &FacetRange| FacetRangeHit { from: range.from, to: range.to, count: 0 };
+        // the ranges are empty when no document contains this field (yet).
+        let Some(fid) = self.facet_field_id()? else {
+            return Ok(ranges.iter().map(empty_hit).collect());
+        };
+
+        let index = self.search_query.index;
+        let rtxn = self.search_query.rtxn;
+        let search_candidates = self.search_candidates()?;
+        ranges
+            .iter()
+            .map(|range| {
+                let docids = range.docids(index, rtxn, fid)?;
+                Ok(FacetRangeHit {
+                    count: search_candidates.intersection_len(&docids),
+                    ..empty_hit(range)
+                })
+            })
+            .collect()
+    }
+
+    pub fn execute(&self) -> Result<Vec<FacetValueHit>> {
+        let index = self.search_query.index;
+        let rtxn = self.search_query.rtxn;
+
+        let fid = match self.facet_field_id()? {
+            Some(fid) => fid,
+            // we return an empty list of results when the attribute has been
+            // set as filterable but no document contains this field (yet).
+            None => return Ok(Vec::new()),
+        };
+
+        let fst = index.facet_id_string_fst.get(rtxn, &fid)?;
+        let numbers_db =
+            index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let has_numbers = get_first_facet_value::<BytesRefCodec>(rtxn, numbers_db, fid)?.is_some();
+        if fst.is_none() && !has_numbers {
+            return Ok(Vec::new());
+        }
+
+        let search_candidates = self.search_candidates()?;
 
         let mut results = match index.sort_facet_values_by(rtxn)?.get(&self.facet) {
             OrderBy::Lexicographic => ValuesCollection::by_lexicographic(self.max_values),
             OrderBy::Count => ValuesCollection::by_count(self.max_values),
         };
 
+        if let Some(fst) = fst {
+            self.fetch_string_values(fid, fst, &search_candidates, &mut results)?;
+        }
+        if has_numbers {
+            self.fetch_numeric_values(fid, &search_candidates, &mut results)?;
+        }
+
+        Ok(results.into_sorted_vec())
+    }
+
+    fn fetch_string_values(
+        &self,
+        fid: FieldId,
+        fst: fst::Set<&[u8]>,
+        search_candidates: &RoaringBitmap,
+        results: &mut ValuesCollection,
+    ) -> Result<()> {
+        let index = self.search_query.index;
+        let rtxn = self.search_query.rtxn;
+
         match self.query.as_ref() {
             Some(query) => {
                 let options = NormalizerOption { lossy: true, ..Default::default() };
@@ -125,8 +200,8 @@ impl<'a> SearchForFacetValues<'a> {
                                 fid,
                                 query,
                                 query,
-                                &search_candidates,
-                                &mut results,
+                                search_candidates,
+                                results,
                             )?;
                         }
                     } else {
@@ -150,8 +225,8 @@ impl<'a> SearchForFacetValues<'a> {
                                     fid,
                                     value,
                                     query,
-                                    &search_candidates,
-                                    &mut results,
+                                    search_candidates,
+                                    results,
                                 )?
                                 .is_break()
                             {
@@ -169,8 +244,8 @@ impl<'a> SearchForFacetValues<'a> {
                                 fid,
                                 value,
                                 query,
-                                &search_candidates,
-                                &mut results,
+                                search_candidates,
+                                results,
                             )?
                             .is_break()
                         {
@@ -197,7 +272,39 @@ impl<'a> SearchForFacetValues<'a> {
             }
         }
 
-        Ok(results.into_sorted_vec())
+        Ok(())
+    }
+
+    /// Collects the numeric facet values starting with the query,
+    /// once formatted like in the facet distribution.
+    fn fetch_numeric_values(
+        &self,
+        fid: FieldId,
+        search_candidates: &RoaringBitmap,
+        results: &mut ValuesCollection,
+    ) -> Result<()> {
+        let index = self.search_query.index;
+        let rtxn = self.search_query.rtxn;
+        let query = self.query.as_deref().map(str::trim).unwrap_or_default();
+
+        lexicographically_iterate_over_facet_distribution(
+            rtxn,
+            index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+            fid,
+            search_candidates,
+            |facet_key, count, _| {
+                let value = OrderedF64Codec::bytes_decode(facet_key)
+                    .map_err(heed::Error::Decoding)?
+                    .to_string();
+                if value.starts_with(query) {
+                    Ok(results.insert(FacetValueHit { value, count }))
+                } else {
+                    Ok(ControlFlow::Continue(()))
+                }
+            },
+        )?;
+
+        Ok(())
     }
 
     fn fetch_original_facets_using_normalized(
@@ -252,6 +359,17 @@ pub struct FacetValueHit {
     pub count: u64,
 }
 
+/// The number of documents in a range of numeric facet values.
+#[derive(Debug, Clone, serde::Serialize, PartialEq)]
+pub struct FacetRangeHit {
+    /// The included lower bound of the range
+    pub from: Option<f64>,
+    /// The excluded upper bound of the range
+    pub to: Option<f64>,
+    /// The number of documents with a value in this range
+    pub count: u64,
+}
+
 impl PartialOrd for FacetValueHit {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -1,12 +1,26 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
 use itertools::Itertools;
 use roaring::RoaringBitmap;
 
-use crate::score_details::{ScoreDetails, ScoreValue, ScoringStrategy};
+use crate::score_details::{self, ScoreDetails, ScoreValue, ScoringStrategy};
 use crate::search::SemanticSearch;
 use crate::{MatchingWords, Result, Search, SearchResult};
 
+/// How the results of the keyword and semantic searches are combined in a hybrid search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HybridFusion {
+    /// Compares the scores of the documents in both searches, weighted by the semantic ratio.
+    #[default]
+    Score,
+    /// Reciprocal rank fusion: sums `ratio / (rank_constant + rank)` over the searches returning a document,
+    /// with the semantic ratio for the semantic search and its complement for the keyword search.
+    ///
+    /// Only the ranks of the documents are used, so the fusion doesn't depend on the distribution of the scores.
+    ReciprocalRank { rank_constant: u32 },
+}
+
 struct ScoreWithRatioResult {
     matching_words: MatchingWords,
     candidates: RoaringBitmap,
@@ -149,8 +163,104 @@ impl ScoreWithRatioResult {
     }
 }
 
+/// The ranks of a document in the keyword and semantic searches, starting at 1, with its score details.
+#[derive(Default)]
+struct RankedDocument {
+    keyword: Option<(u32, Vec<ScoreDetails>)>,
+    semantic: Option<(u32, Vec<ScoreDetails>)>,
+}
+
+fn merge_reciprocal_rank(
+    vector_results: SearchResult,
+    keyword_results: SearchResult,
+    semantic_ratio: f32,
+    rank_constant: u32,
+    from: usize,
+    length: usize,
+) -> (SearchResult, u32) {
+    let mut documents: Vec<(u32, RankedDocument)> = Vec::new();
+    let mut positions = HashMap::new();
+    for (rank, (docid, scores)) in
+        keyword_results.documents_ids.iter().zip(keyword_results.document_scores).enumerate()
+    {
+        positions.insert(*docid, documents.len());
+        let document = RankedDocument { keyword: Some((rank as u32 + 1, scores)), semantic: None };
+        documents.push((*docid, document));
+    }
+    for (rank, (docid, scores)) in
+        vector_results.documents_ids.iter().zip(vector_results.document_scores).enumerate()
+    {
+        let position = *positions.entry(*docid).or_insert_with(|| {
+            documents.push((*docid, RankedDocument::default()));
+            documents.len() - 1
+        });
+        documents[position].1.semantic = Some((rank as u32 + 1, scores));
+    }
+
+    let rank_constant = rank_constant as f64;
+    let contribution = |ratio: f32, rank: Option<u32>| {
+        rank.map_or(0.0, |rank| ratio as f64 / (rank_constant + rank as f64))
+    };
+    let mut fused: Vec<_> = documents
+        .into_iter()
+        .map(|(docid, RankedDocument { keyword, semantic })| {
+            let keyword_rank = keyword.as_ref().map(|(rank, _)| *rank);
+            let semantic_rank = semantic.as_ref().map(|(rank, _)| *rank);
+            let keyword_contribution = contribution(1.0 - semantic_ratio, keyword_rank);
+            let semantic_contribution = contribution(semantic_ratio, semantic_rank);
+            let fusion = score_details::Fusion {
+                keyword_rank,
+                semantic_rank,
+                keyword_contribution,
+                semantic_contribution,
+                // a document ranked first in both searches gets `1 / (rank_constant + 1)`
+                score: (keyword_contribution + semantic_contribution) * (rank_constant + 1.0),
+            };
+
+            let mut scores = vec![ScoreDetails::Fusion(fusion)];
+            let has_keyword_scores = keyword.is_some();
+            scores.extend(keyword.into_iter().flat_map(|(_, scores)| scores));
+            // keep the vector details of the semantic search for the matched chunk of the document
+            scores.extend(semantic.into_iter().flat_map(|(_, scores)| scores).filter(|details| {
+                !has_keyword_scores || matches!(details, ScoreDetails::Vector(_))
+            }));
+            (docid, fusion, scores)
+        })
+        .collect();
+    // stable sort, the documents with the same score stay in the order of the keyword search
+    fused.sort_by(|(_, left, _), (_, right, _)| right.score.total_cmp(&left.score));
+
+    let mut semantic_hit_count = 0;
+    let mut documents_ids = Vec::with_capacity(length);
+    let mut document_scores = Vec::with_capacity(length);
+    for (docid, fusion, scores) in fused.into_iter().skip(from).take(length) {
+        if fusion.semantic_contribution > fusion.keyword_contribution {
+            semantic_hit_count += 1;
+        }
+        documents_ids.push(docid);
+        document_scores.push(scores);
+    }
+
+    (
+        SearchResult {
+            matching_words: keyword_results.matching_words,
+            candidates: vector_results.candidates | keyword_results.candidates,
+            documents_ids,
+            document_scores,
+            degraded: vector_results.degraded | keyword_results.degraded,
+            used_negative_operator: vector_results.used_negative_operator
+                | keyword_results.used_negative_operator,
+        },
+        semantic_hit_count,
+    )
+}
+
 impl<'a> Search<'a> {
-    pub fn execute_hybrid(&self, semantic_ratio: f32) -> Result<(SearchResult, Option<u32>)> {
+    pub fn execute_hybrid(
+        &self,
+        semantic_ratio: f32,
+        fusion: HybridFusion,
+    ) -> Result<(SearchResult, Option<u32>)> {
         // TODO: find classier way to achieve that than to reset vector and query params
         // create separate keyword and semantic searches
         let mut search = Search {
@@ -168,6 +278,7 @@ impl<'a> Search<'a> {
             rtxn: self.rtxn,
             index: self.index,
             semantic: self.semantic.clone(),
+            query_embedding_cache: self.query_embedding_cache.clone(),
             time_budget: self.time_budget.clone(),
         };
 
@@ -191,8 +302,12 @@ impl<'a> Search<'a> {
         let vector_query = match vector {
             Some(vector_query) => vector_query,
             None => {
-                // attempt to embed the vector
-                match embedder.embed_one(query) {
+                // attempt to embed the vector, only now that we know the semantic search is needed
+                let embedding = match &self.query_embedding_cache {
+                    Some(cache) => cache.embed(&embedder, &query),
+                    None => embedder.embed_one(query),
+                };
+                match embedding {
                     Ok(embedding) => embedding,
                     Err(error) => {
                         tracing::error!(error=%error, "Embedding failed");
@@ -208,11 +323,27 @@ impl<'a> Search<'a> {
         // TODO: would be better to have two distinct functions at this point
         let vector_results = search.execute()?;
 
-        let keyword_results = ScoreWithRatioResult::new(keyword_results, 1.0 - semantic_ratio);
-        let vector_results = ScoreWithRatioResult::new(vector_results, semantic_ratio);
-
-        let (merge_results, semantic_hit_count) =
-            ScoreWithRatioResult::merge(vector_results, keyword_results, self.offset, self.limit);
+        let (merge_results, semantic_hit_count) = match fusion {
+            HybridFusion::Score => {
+                let keyword_results =
+                    ScoreWithRatioResult::new(keyword_results, 1.0 - semantic_ratio);
+                let vector_results = ScoreWithRatioResult::new(vector_results, semantic_ratio);
+                ScoreWithRatioResult::merge(
+                    vector_results,
+                    keyword_results,
+                    self.offset,
+                    self.limit,
+                )
+            }
+            HybridFusion::ReciprocalRank { rank_constant } => merge_reciprocal_rank(
+                vector_results,
+                keyword_results,
+                semantic_ratio,
+                rank_constant,
+                self.offset,
+                self.limit,
+            ),
+        };
         assert!(merge_results.documents_ids.len() <= self.limit);
         Ok((merge_results, Some(semantic_hit_count)))
     }
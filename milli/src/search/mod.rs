@@ -9,7 +9,7 @@ pub use self::facet::{FacetDistribution, Filter, OrderBy, DEFAULT_VALUES_PER_FAC
 pub use self::new::matches::{FormatOptions, MatchBounds, MatcherBuilder, MatchingWords};
 use self::new::{execute_vector_search, PartialSearchResult};
 use crate::score_details::{ScoreDetails, ScoringStrategy};
-use crate::vector::Embedder;
+use crate::vector::{Embedder, QueryEmbeddingCache};
 use crate::{
     execute_search, filtered_universe, AscDesc, DefaultSearchLogger, DocumentId, Index, Result,
     SearchContext, TimeBudget,
@@ -48,6 +48,7 @@ pub struct Search<'a> {
     rtxn: &'a heed::RoTxn<'a>,
     index: &'a Index,
     semantic: Option<SemanticSearch>,
+    query_embedding_cache: Option<QueryEmbeddingCache>,
     time_budget: TimeBudget,
 }
 
@@ -68,6 +69,7 @@ impl<'a> Search<'a> {
             rtxn,
             index,
             semantic: None,
+            query_embedding_cache: None,
             time_budget: TimeBudget::max(),
         }
     }
@@ -87,6 +89,12 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Sets the cache the query is looked up in before being embedded by a hybrid search.
+    pub fn query_embedding_cache(&mut self, cache: QueryEmbeddingCache) -> &mut Search<'a> {
+        self.query_embedding_cache = Some(cache);
+        self
+    }
+
     pub fn offset(&mut self, offset: usize) -> &mut Search<'a> {
         self.offset = offset;
         self
@@ -237,6 +245,7 @@ impl fmt::Debug for Search<'_> {
             rtxn: _,
             index: _,
             semantic,
+            query_embedding_cache: _,
             time_budget,
         } = self;
         f.debug_struct("Search")
@@ -5,16 +5,22 @@ use roaring::RoaringBitmap;
 
 use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
 use crate::score_details::{self, ScoreDetails};
-use crate::vector::{DistributionShift, Embedder};
+use crate::vector::{ArroyReader, DistributionShift, Embedder, VectorStorage};
 use crate::{DocumentId, Result, SearchContext, SearchLogger};
 
+/// How many more neighbors are fetched from the quantized trees than the limit,
+/// before being rescored with their full precision embeddings.
+const RESCORING_OVERSAMPLING: usize = 4;
+
 pub struct VectorSort<Q: RankingRuleQueryTrait> {
     query: Option<Q>,
     target: Vec<f32>,
     vector_candidates: RoaringBitmap,
-    cached_sorted_docids: std::vec::IntoIter<(DocumentId, f32)>,
+    // docid, distance, index of the vector of the document
+    cached_sorted_docids: std::vec::IntoIter<(DocumentId, f32, u8)>,
     limit: usize,
     distribution_shift: Option<DistributionShift>,
+    storage: VectorStorage,
     embedder_index: u8,
 }
 
@@ -32,6 +38,7 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
             .embedder_category_id
             .get(ctx.txn, embedder_name)?
             .ok_or_else(|| crate::UserError::InvalidEmbedder(embedder_name.to_owned()))?;
+        let storage = ctx.index.embedder_vector_storage(ctx.txn, embedder_name)?;
 
         Ok(Self {
             query: None,
@@ -40,6 +47,7 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
             cached_sorted_docids: Default::default(),
             limit,
             distribution_shift: embedder.distribution(),
+            storage,
             embedder_index,
         })
     }
@@ -52,13 +60,19 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
         let writer_index = (self.embedder_index as u16) << 8;
         let readers: std::result::Result<Vec<_>, _> = (0..=u8::MAX)
             .map_while(|k| {
-                arroy::Reader::open(ctx.txn, writer_index | (k as u16), ctx.index.vector_arroy)
-                    .map(Some)
-                    .or_else(|e| match e {
-                        arroy::Error::MissingMetadata => Ok(None),
-                        e => Err(e),
-                    })
-                    .transpose()
+                ArroyReader::open(
+                    ctx.txn,
+                    ctx.index.vector_arroy,
+                    ctx.index.vector_full_precision,
+                    writer_index | (k as u16),
+                    self.storage,
+                )
+                .map(Some)
+                .or_else(|e| match e {
+                    arroy::Error::MissingMetadata => Ok(None),
+                    e => Err(e),
+                })
+                .transpose()
             })
             .collect();
 
@@ -67,12 +81,33 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
         let target = &self.target;
         let mut results = Vec::new();
 
-        for reader in readers.iter() {
-            let nns_by_vector =
-                reader.nns_by_vector(ctx.txn, target, self.limit, None, Some(vector_candidates))?;
-            results.extend(nns_by_vector.into_iter());
+        for (vector_index, reader) in readers.iter().enumerate() {
+            // there are at most 256 readers
+            let vector_index = vector_index as u8;
+            if reader.can_rescore() {
+                // the quantized trees only give approximate distances, so we fetch more neighbors
+                // and compute their exact distance with the full precision embeddings.
+                let count = self.limit.saturating_mul(RESCORING_OVERSAMPLING);
+                let nns_by_vector =
+                    reader.nns_by_vector(ctx.txn, target, count, Some(vector_candidates))?;
+                for (docid, distance) in nns_by_vector {
+                    let distance = match reader.full_precision_vector(ctx.txn, docid)? {
+                        Some(embedding) => self.storage.distance.distance(target, &embedding),
+                        None => distance,
+                    };
+                    results.push((docid, distance, vector_index));
+                }
+            } else {
+                let nns_by_vector =
+                    reader.nns_by_vector(ctx.txn, target, self.limit, Some(vector_candidates))?;
+                results.extend(
+                    nns_by_vector
+                        .into_iter()
+                        .map(|(docid, distance)| (docid, distance, vector_index)),
+                );
+            }
         }
-        results.sort_unstable_by_key(|(_, distance)| OrderedFloat(*distance));
+        results.sort_unstable_by_key(|(_, distance, _)| OrderedFloat(*distance));
         self.cached_sorted_docids = results.into_iter();
 
         Ok(())
@@ -113,13 +148,16 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
             return Ok(Some(RankingRuleOutput {
                 query,
                 candidates: universe.clone(),
-                score: ScoreDetails::Vector(score_details::Vector { similarity: None }),
+                score: ScoreDetails::Vector(score_details::Vector {
+                    similarity: None,
+                    vector_index: None,
+                }),
             }));
         }
 
-        for (docid, distance) in self.cached_sorted_docids.by_ref() {
+        for (docid, distance, vector_index) in self.cached_sorted_docids.by_ref() {
             if vector_candidates.contains(docid) {
-                let score = 1.0 - distance;
+                let score = self.storage.distance.similarity(distance);
                 let score = self
                     .distribution_shift
                     .map(|distribution| distribution.shift(score))
@@ -127,7 +165,10 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
                 return Ok(Some(RankingRuleOutput {
                     query,
                     candidates: RoaringBitmap::from_iter([docid]),
-                    score: ScoreDetails::Vector(score_details::Vector { similarity: Some(score) }),
+                    score: ScoreDetails::Vector(score_details::Vector {
+                        similarity: Some(score),
+                        vector_index: Some(vector_index),
+                    }),
                 }));
             }
         }
@@ -143,7 +184,10 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
             return Ok(Some(RankingRuleOutput {
                 query,
                 candidates: universe.clone(),
-                score: ScoreDetails::Vector(score_details::Vector { similarity: None }),
+                score: ScoreDetails::Vector(score_details::Vector {
+                    similarity: None,
+                    vector_index: None,
+                }),
             }));
         }
 
@@ -49,7 +49,9 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
+            vector_full_precision,
             embedder_category_id: _,
+            embedding_cache,
             documents,
         } = self.index;
 
@@ -90,6 +92,9 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         field_id_docid_facet_strings.clear(self.wtxn)?;
         // vector
         vector_arroy.clear(self.wtxn)?;
+        vector_full_precision.clear(self.wtxn)?;
+        self.index.delete_vector_storage_sizes(self.wtxn)?;
+        embedding_cache.clear(self.wtxn)?;
 
         documents.clear(self.wtxn)?;
 
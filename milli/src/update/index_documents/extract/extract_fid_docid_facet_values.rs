@@ -129,10 +129,12 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
 
                 let geo_support =
                     geo_fields_ids.map_or(false, |(lat, lng)| field_id == lat || field_id == lng);
+                let del_separator = settings_diff.old.hierarchical_facet_separator(field_id);
+                let add_separator = settings_diff.new.hierarchical_facet_separator(field_id);
                 let del_filterable_values =
-                    del_value.map(|value| extract_facet_values(&value, geo_support));
+                    del_value.map(|value| extract_facet_values(&value, geo_support, del_separator));
                 let add_filterable_values =
-                    add_value.map(|value| extract_facet_values(&value, geo_support));
+                    add_value.map(|value| extract_facet_values(&value, geo_support, add_separator));
 
                 // Those closures are just here to simplify things a bit.
                 let mut insert_numbers_diff = |del_numbers, add_numbers| {
@@ -412,13 +414,21 @@ enum FilterableValues {
 }
 
 /// Extracts the facet values of a JSON field.
-fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
+///
+/// The strings of a hierarchical facet are extracted with all their ancestors,
+/// e.g. `A > B > C` gives `A`, `A > B` and `A > B > C`, for a filter on a node to match its descendants.
+fn extract_facet_values(
+    value: &Value,
+    geo_field: bool,
+    hierarchy_separator: Option<&str>,
+) -> FilterableValues {
     fn inner_extract_facet_values(
         value: &Value,
         can_recurse: bool,
         output_numbers: &mut Vec<f64>,
         output_strings: &mut Vec<(String, String)>,
         geo_field: bool,
+        hierarchy_separator: Option<&str>,
     ) {
         match value {
             Value::Null => (),
@@ -440,8 +450,19 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
                         )
                     }
                 }
-                let normalized = crate::normalize_facet(original);
-                output_strings.push((normalized, original.clone()));
+                match hierarchy_separator {
+                    Some(separator) => {
+                        let segments: Vec<_> = original.split(separator).collect();
+                        for depth in 1..=segments.len() {
+                            let path = segments[..depth].join(separator);
+                            output_strings.push((crate::normalize_facet(&path), path));
+                        }
+                    }
+                    None => {
+                        let normalized = crate::normalize_facet(original);
+                        output_strings.push((normalized, original.clone()));
+                    }
+                }
             }
             Value::Array(values) => {
                 if can_recurse {
@@ -452,6 +473,7 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
                             output_numbers,
                             output_strings,
                             geo_field,
+                            hierarchy_separator,
                         );
                     }
                 }
@@ -468,7 +490,14 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
         otherwise => {
             let mut numbers = Vec::new();
             let mut strings = Vec::new();
-            inner_extract_facet_values(otherwise, true, &mut numbers, &mut strings, geo_field);
+            inner_extract_facet_values(
+                otherwise,
+                true,
+                &mut numbers,
+                &mut strings,
+                geo_field,
+                hierarchy_separator,
+            );
             FilterableValues::Values { numbers, strings }
         }
     }
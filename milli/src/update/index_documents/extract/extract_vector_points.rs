@@ -12,14 +12,21 @@ use itertools::EitherOrBoth;
 use ordered_float::OrderedFloat;
 use serde_json::{from_slice, Value};
 
-use super::helpers::{create_writer, writer_into_reader, GrenadParameters};
+use super::helpers::{
+    create_sorter, create_writer, keep_first, sorter_into_reader, writer_into_reader,
+    GrenadParameters, MergeFn,
+};
 use crate::error::UserError;
 use crate::prompt::Prompt;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::index_documents::helpers::try_split_at;
 use crate::update::settings::InnerIndexSettingsDiff;
-use crate::vector::Embedder;
-use crate::{DocumentId, InternalError, Result, ThreadPoolNoAbort, VectorOrArrayOfVectors};
+use crate::vector::{
+    CacheKeys, Embedder, EmbedderInput, Embedding, EmbeddingCache, EmbeddingCacheStats, Embeddings,
+};
+use crate::{
+    DocumentId, FieldsIdsMap, InternalError, Result, ThreadPoolNoAbort, VectorOrArrayOfVectors,
+};
 
 /// The length of the elements that are always in the buffer when inserting new values.
 const TRUNCATE_SIZE: usize = size_of::<DocumentId>();
@@ -31,6 +38,8 @@ pub struct ExtractedVectorPoints {
     pub remove_vectors: grenad::Reader<BufReader<File>>,
     // docid -> prompt
     pub prompts: grenad::Reader<BufReader<File>>,
+    // cache key -> (), the texts that are not embedded by their document anymore
+    pub stale_cache_keys: grenad::Reader<BufReader<File>>,
 }
 
 enum VectorStateDelta {
@@ -74,10 +83,20 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
     indexer: GrenadParameters,
     settings_diff: &InnerIndexSettingsDiff,
     prompt: &Prompt,
+    embedder: &Embedder,
     embedder_name: &str,
+    cache_keys: Option<&CacheKeys>,
 ) -> Result<ExtractedVectorPoints> {
     puffin::profile_function!();
 
+    // an input is sent whole along with its image, the settings validation already rejects this configuration
+    if embedder.accepts_images() && prompt.chunking().is_some() {
+        return Err(UserError::InvalidSettingsChunkingWithImages {
+            embedder_name: embedder_name.to_owned(),
+        }
+        .into());
+    }
+
     let old_fields_ids_map = &settings_diff.old.fields_ids_map;
     let new_fields_ids_map = &settings_diff.new.fields_ids_map;
 
@@ -102,6 +121,16 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
         tempfile::tempfile()?,
     );
 
+    // (cache key) -> ()
+    let mut stale_cache_keys_sorter = create_sorter(
+        grenad::SortAlgorithm::Unstable,
+        keep_first,
+        indexer.chunk_compression_type,
+        indexer.chunk_compression_level,
+        indexer.max_nb_chunks,
+        indexer.max_memory,
+    );
+
     let mut key_buffer = Vec::new();
     let mut cursor = obkv_documents.into_cursor()?;
     while let Some((key, value)) = cursor.move_on_next()? {
@@ -145,6 +174,23 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
         let del_value = del_map.and_then(|mut map| map.remove(embedder_name));
         let add_value = add_map.and_then(|mut map| map.remove(embedder_name));
 
+        // the previous text of the document, if it was embedded and may not be anymore.
+        // It is only needed to purge the embedding cache, and all the embeddings are regenerated
+        // when the settings of the embedders change.
+        let document_existed = obkv
+            .iter()
+            .map(|(_, deladd)| KvReaderDelAdd::new(deladd))
+            .any(|deladd| deladd.get(DelAdd::Deletion).is_some());
+        let old_input = || {
+            Some(prompt)
+                .filter(|_| cache_keys.is_some() && document_existed)
+                .filter(|_| !settings_diff.reindex_vectors())
+                .and_then(|p| {
+                    render_input(p, embedder, obkv, DelAdd::Deletion, old_fields_ids_map).ok()
+                })
+        };
+        let mut stale_input = None;
+
         let delta = match (del_value, add_value) {
             (Some(old), Some(new)) => {
                 // no autogeneration
@@ -168,7 +214,9 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                     .any(|deladd| deladd.get(DelAdd::Addition).is_some());
                 if document_is_kept {
                     // becomes autogenerated
-                    VectorStateDelta::NowGenerated(prompt.render(
+                    VectorStateDelta::NowGenerated(render_input(
+                        prompt,
+                        embedder,
                         obkv,
                         DelAdd::Addition,
                         new_fields_ids_map,
@@ -179,6 +227,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
             }
             (None, Some(new)) => {
                 // was possibly autogenerated, remove all vectors for that document
+                stale_input = old_input();
                 let add_vectors = extract_vectors(new, document_id, embedder_name)?;
                 if add_vectors.len() > usize::from(u8::MAX) {
                     return Err(crate::Error::UserError(crate::UserError::TooManyVectors(
@@ -203,10 +252,13 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                         // When vector pipeline will be optimized, this should be removed.
                         .filter(|_| !settings_diff.reindex_vectors())
                         .map(|p| {
-                            p.render(obkv, DelAdd::Deletion, old_fields_ids_map).unwrap_or_default()
+                            render_input(p, embedder, obkv, DelAdd::Deletion, old_fields_ids_map)
+                                .unwrap_or_default()
                         });
-                    let new_prompt = prompt.render(obkv, DelAdd::Addition, new_fields_ids_map)?;
+                    let new_prompt =
+                        render_input(prompt, embedder, obkv, DelAdd::Addition, new_fields_ids_map)?;
                     if old_prompt.as_ref() != Some(&new_prompt) {
+                        stale_input = old_input();
                         let old_prompt = old_prompt.unwrap_or_default();
                         tracing::trace!(
                             "🚀 Changing prompt from\n{old_prompt}\n===to===\n{new_prompt}"
@@ -217,6 +269,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                         VectorStateDelta::NoChange
                     }
                 } else {
+                    stale_input = old_input();
                     VectorStateDelta::NowRemoved
                 }
             }
@@ -231,6 +284,12 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
             delta,
             settings_diff,
         )?;
+
+        if let (Some(cache_keys), Some(stale_input)) = (cache_keys, stale_input) {
+            for text in embedded_texts(prompt, embedder, &stale_input) {
+                stale_cache_keys_sorter.insert(cache_keys.key(text), [])?;
+            }
+        }
     }
 
     Ok(ExtractedVectorPoints {
@@ -240,9 +299,63 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
         remove_vectors: writer_into_reader(remove_vectors_writer)?,
         // docid -> prompt
         prompts: writer_into_reader(prompts_writer)?,
+        // cache key -> ()
+        stale_cache_keys: sorter_into_reader(stale_cache_keys_sorter, indexer)?,
     })
 }
 
+/// Splits the input of a document in the texts that are embedded as its vectors.
+///
+/// A long text can be split in several parts, each of them embedded as one of the vectors of the document.
+/// The inputs of the embedders accepting images are never chunked.
+fn embedded_texts<'t>(prompt: &Prompt, embedder: &Embedder, input: &'t str) -> Vec<&'t str> {
+    if embedder.accepts_images() {
+        vec![input]
+    } else {
+        prompt.chunk(input)
+    }
+}
+
+/// Returns the input sent to the embedder from a text stored by [`render_input`].
+fn stored_input(embedder: &Embedder, text: &str) -> Result<EmbedderInput> {
+    if embedder.accepts_images() {
+        Ok(serde_json::from_str(text).map_err(InternalError::SerdeJson)?)
+    } else {
+        Ok(EmbedderInput::Text(text.to_owned()))
+    }
+}
+
+/// Renders the text sent to the embedder for one side of a document.
+///
+/// The embedders accepting images receive the rendered template along with the value of the image field
+/// of the document, stored as a JSON [`EmbedderInput`] so that a text is never mistaken for an image.
+fn render_input(
+    prompt: &Prompt,
+    embedder: &Embedder,
+    obkv: obkv::KvReaderU16<'_>,
+    side: DelAdd,
+    fields_ids_map: &FieldsIdsMap,
+) -> Result<String> {
+    let text = prompt.render(obkv, side, fields_ids_map)?;
+    if !embedder.accepts_images() {
+        return Ok(text);
+    }
+
+    // the image is ignored when it is not a string, like the fields of a template that can't be rendered.
+    let image = embedder
+        .image_field()
+        .and_then(|image_field| fields_ids_map.id(image_field))
+        .and_then(|image_fid| obkv.get(image_fid))
+        .and_then(|deladd| KvReaderDelAdd::new(deladd).get(side))
+        .and_then(|value| match from_slice(value) {
+            Ok(Value::String(image)) => Some(image),
+            _ => None,
+        });
+
+    // unwrap: serializing an enum of strings can't fail
+    Ok(serde_json::to_string(&EmbedderInput::new(text, image)).unwrap())
+}
+
 fn to_vector_map(
     obkv: KvReaderDelAdd,
     side: DelAdd,
@@ -356,95 +469,192 @@ fn extract_vectors(
     }
 }
 
+pub struct ExtractedEmbeddings {
+    // docid -> embeddings
+    pub embeddings: grenad::Reader<BufReader<File>>,
+    // cache key -> embedding, for the texts that were not found in the embedding cache
+    pub cache_entries: grenad::Reader<BufReader<File>>,
+    pub stats: EmbeddingCacheStats,
+}
+
+/// A text to embed, or its embedding if it was found in the embedding cache.
+enum PendingEmbedding {
+    Cached(Embedding),
+    ToEmbed(Option<Vec<u8>>),
+}
+
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::extract")]
 pub fn extract_embeddings<R: io::Read + io::Seek>(
     // docid, prompt
     prompt_reader: grenad::Reader<R>,
     indexer: GrenadParameters,
     embedder: Arc<Embedder>,
+    prompt: &Prompt,
+    cache: Option<(&EmbeddingCache, &CacheKeys)>,
     request_threads: &ThreadPoolNoAbort,
-) -> Result<grenad::Reader<BufReader<File>>> {
+) -> Result<ExtractedEmbeddings> {
     puffin::profile_function!();
     let n_chunks = embedder.chunk_count_hint(); // chunk level parallelism
     let n_vectors_per_chunk = embedder.prompt_count_in_chunk_hint(); // number of vectors in a single chunk
 
     // docid, state with embedding
-    let mut state_writer = create_writer(
+    let mut state_writer = EmbeddingsWriter::new(create_writer(
         indexer.chunk_compression_type,
         indexer.chunk_compression_level,
         tempfile::tempfile()?,
+    ));
+    let mut cache_entries_sorter = create_sorter(
+        grenad::SortAlgorithm::Unstable,
+        keep_first,
+        indexer.chunk_compression_type,
+        indexer.chunk_compression_level,
+        indexer.max_nb_chunks,
+        indexer.max_memory,
     );
+    let mut stats = EmbeddingCacheStats::default();
+    let rtxn = cache.map(|(cache, _)| cache.read_txn()).transpose()?;
 
+    // the texts that are waiting to be embedded, along with the cached embeddings in between them
+    // so that the embeddings are written in the order of the documents.
+    let mut pending: Vec<(DocumentId, PendingEmbedding)> = Vec::new();
     let mut chunks = Vec::with_capacity(n_chunks);
     let mut current_chunk = Vec::with_capacity(n_vectors_per_chunk);
-    let mut current_chunk_ids = Vec::with_capacity(n_vectors_per_chunk);
-    let mut chunks_ids = Vec::with_capacity(n_chunks);
     let mut cursor = prompt_reader.into_cursor()?;
 
     while let Some((key, value)) = cursor.move_on_next()? {
         let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
         // SAFETY: precondition, the grenad value was saved from a string
-        let prompt = unsafe { std::str::from_utf8_unchecked(value) };
-        if current_chunk.len() == current_chunk.capacity() {
-            chunks.push(std::mem::replace(
-                &mut current_chunk,
-                Vec::with_capacity(n_vectors_per_chunk),
-            ));
-            chunks_ids.push(std::mem::replace(
-                &mut current_chunk_ids,
-                Vec::with_capacity(n_vectors_per_chunk),
-            ));
-        };
-        current_chunk.push(prompt.to_owned());
-        current_chunk_ids.push(docid);
-
-        if chunks.len() == chunks.capacity() {
-            let chunked_embeds = embedder
-                .embed_chunks(
-                    std::mem::replace(&mut chunks, Vec::with_capacity(n_chunks)),
-                    request_threads,
-                )
-                .map_err(crate::vector::Error::from)
-                .map_err(crate::Error::from)?;
-
-            for (docid, embeddings) in chunks_ids
-                .iter()
-                .flat_map(|docids| docids.iter())
-                .zip(chunked_embeds.iter().flat_map(|embeds| embeds.iter()))
-            {
-                state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
+        let text = unsafe { std::str::from_utf8_unchecked(value) };
+        for text in embedded_texts(prompt, &embedder, text) {
+            let cache_key = match (cache, &rtxn) {
+                (Some((cache, keys)), Some(rtxn)) => {
+                    let cache_key = keys.key(text);
+                    if let Some(embedding) = cache.get(rtxn, &cache_key)? {
+                        stats.hits += 1;
+                        pending.push((docid, PendingEmbedding::Cached(embedding)));
+                        continue;
+                    }
+                    Some(cache_key)
+                }
+                _ => None,
+            };
+            stats.misses += 1;
+
+            if current_chunk.len() == current_chunk.capacity() {
+                chunks.push(std::mem::replace(
+                    &mut current_chunk,
+                    Vec::with_capacity(n_vectors_per_chunk),
+                ));
+            };
+            current_chunk.push(stored_input(&embedder, text)?);
+            pending.push((docid, PendingEmbedding::ToEmbed(cache_key)));
+
+            if chunks.len() == chunks.capacity() {
+                let chunked_embeds = embedder
+                    .embed_input_chunks(
+                        std::mem::replace(&mut chunks, Vec::with_capacity(n_chunks)),
+                        request_threads,
+                    )
+                    .map_err(crate::vector::Error::from)
+                    .map_err(crate::Error::from)?;
+
+                // the texts of the current chunk are not embedded yet, they stay pending.
+                write_pending(
+                    &mut pending,
+                    chunked_embeds.iter().flat_map(|embeds| embeds.iter()),
+                    &mut state_writer,
+                    &mut cache_entries_sorter,
+                )?;
             }
-            chunks_ids.clear();
         }
     }
 
-    // send last chunk
-    if !chunks.is_empty() {
-        let chunked_embeds = embedder
-            .embed_chunks(std::mem::take(&mut chunks), request_threads)
+    // send the last chunks
+    if !current_chunk.is_empty() {
+        chunks.push(std::mem::take(&mut current_chunk));
+    }
+    let chunked_embeds = if chunks.is_empty() {
+        Vec::new()
+    } else {
+        embedder
+            .embed_input_chunks(chunks, request_threads)
             .map_err(crate::vector::Error::from)
-            .map_err(crate::Error::from)?;
-        for (docid, embeddings) in chunks_ids
-            .iter()
-            .flat_map(|docids| docids.iter())
-            .zip(chunked_embeds.iter().flat_map(|embeds| embeds.iter()))
-        {
-            state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
+            .map_err(crate::Error::from)?
+    };
+    write_pending(
+        &mut pending,
+        chunked_embeds.iter().flat_map(|embeds| embeds.iter()),
+        &mut state_writer,
+        &mut cache_entries_sorter,
+    )?;
+
+    Ok(ExtractedEmbeddings {
+        embeddings: writer_into_reader(state_writer.finish()?)?,
+        cache_entries: sorter_into_reader(cache_entries_sorter, indexer)?,
+        stats,
+    })
+}
+
+/// Writes the pending embeddings in order, taking the embeddings of the texts that were not cached
+/// from the output of the embedder, and keeps the ones that come after the last embedded text.
+fn write_pending<'e>(
+    pending: &mut Vec<(DocumentId, PendingEmbedding)>,
+    mut embedded: impl Iterator<Item = &'e Embeddings<f32>>,
+    state_writer: &mut EmbeddingsWriter,
+    cache_entries_sorter: &mut grenad::Sorter<MergeFn>,
+) -> Result<()> {
+    let mut written = 0;
+    for (docid, pending) in pending.iter() {
+        match pending {
+            PendingEmbedding::Cached(embedding) => state_writer.push(*docid, embedding)?,
+            PendingEmbedding::ToEmbed(cache_key) => {
+                let Some(embeddings) = embedded.next() else { break };
+                if let Some(cache_key) = cache_key {
+                    cache_entries_sorter.insert(cache_key, cast_slice(embeddings.as_inner()))?;
+                }
+                state_writer.push(*docid, embeddings.as_inner())?;
+            }
         }
+        written += 1;
     }
+    pending.drain(..written);
+    Ok(())
+}
 
-    if !current_chunk.is_empty() {
-        let embeds = embedder
-            .embed_chunks(vec![std::mem::take(&mut current_chunk)], request_threads)
-            .map_err(crate::vector::Error::from)
-            .map_err(crate::Error::from)?;
+/// Writes the embeddings of each document in a grenad, concatenating the embeddings of
+/// the consecutive chunks of text of a document.
+struct EmbeddingsWriter {
+    writer: Writer<BufWriter<File>>,
+    current: Option<(DocumentId, Vec<f32>)>,
+}
 
-        if let Some(embeds) = embeds.first() {
-            for (docid, embeddings) in current_chunk_ids.iter().zip(embeds.iter()) {
-                state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
+impl EmbeddingsWriter {
+    fn new(writer: Writer<BufWriter<File>>) -> Self {
+        Self { writer, current: None }
+    }
+
+    fn push(&mut self, docid: DocumentId, embeddings: &[f32]) -> Result<()> {
+        match &mut self.current {
+            Some((current_docid, current)) if *current_docid == docid => {
+                current.extend_from_slice(embeddings);
+            }
+            _ => {
+                self.flush()?;
+                self.current = Some((docid, embeddings.to_vec()));
             }
         }
+        Ok(())
     }
 
-    writer_into_reader(state_writer)
+    fn flush(&mut self) -> Result<()> {
+        if let Some((docid, embeddings)) = self.current.take() {
+            self.writer.insert(docid.to_be_bytes(), cast_slice(&embeddings))?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Writer<BufWriter<File>>> {
+        self.flush()?;
+        Ok(self.writer)
+    }
 }
@@ -23,7 +23,7 @@ use self::extract_fid_docid_facet_values::{extract_fid_docid_facet_values, Extra
 use self::extract_fid_word_count_docids::extract_fid_word_count_docids;
 use self::extract_geo_points::extract_geo_points;
 use self::extract_vector_points::{
-    extract_embeddings, extract_vector_points, ExtractedVectorPoints,
+    extract_embeddings, extract_vector_points, ExtractedEmbeddings, ExtractedVectorPoints,
 };
 use self::extract_word_docids::extract_word_docids;
 use self::extract_word_pair_proximity_docids::extract_word_pair_proximity_docids;
@@ -31,6 +31,7 @@ use self::extract_word_position_docids::extract_word_position_docids;
 use super::helpers::{as_cloneable_grenad, CursorClonableMmap, GrenadParameters};
 use super::{helpers, TypedChunk};
 use crate::update::settings::InnerIndexSettingsDiff;
+use crate::vector::{CacheKeys, EmbeddingCache};
 use crate::{FieldId, Result, ThreadPoolNoAbortBuilder};
 
 /// Extract data for each databases from obkv documents in parallel.
@@ -46,6 +47,7 @@ pub(crate) fn data_from_obkv_documents(
     geo_fields_ids: Option<(FieldId, FieldId)>,
     settings_diff: Arc<InnerIndexSettingsDiff>,
     max_positions_per_attributes: Option<u32>,
+    embedding_cache: EmbeddingCache,
 ) -> Result<()> {
     puffin::profile_function!();
 
@@ -59,6 +61,7 @@ pub(crate) fn data_from_obkv_documents(
                         indexer,
                         lmdb_writer_sx.clone(),
                         settings_diff.clone(),
+                        embedding_cache.clone(),
                     )
                 })
                 .collect::<Result<()>>()
@@ -222,6 +225,7 @@ fn send_original_documents_data(
     indexer: GrenadParameters,
     lmdb_writer_sx: Sender<Result<TypedChunk>>,
     settings_diff: Arc<InnerIndexSettingsDiff>,
+    embedding_cache: EmbeddingCache,
 ) -> Result<()> {
     let original_documents_chunk =
         original_documents_chunk.and_then(|c| unsafe { as_cloneable_grenad(&c) })?;
@@ -238,19 +242,34 @@ fn send_original_documents_data(
         let settings_diff = settings_diff.clone();
         rayon::spawn(move || {
             for (name, (embedder, prompt)) in settings_diff.new.embedding_configs.clone() {
+                let cache_keys = settings_diff
+                    .new
+                    .embedder_options
+                    .get(&name)
+                    .filter(|_| embedding_cache.is_enabled())
+                    .map(|options| CacheKeys::new(&name, options));
                 let result = extract_vector_points(
                     documents_chunk_cloned.clone(),
                     indexer,
                     &settings_diff,
                     &prompt,
+                    &embedder,
                     &name,
+                    cache_keys.as_ref(),
                 );
                 match result {
-                    Ok(ExtractedVectorPoints { manual_vectors, remove_vectors, prompts }) => {
-                        let embeddings = match extract_embeddings(
+                    Ok(ExtractedVectorPoints {
+                        manual_vectors,
+                        remove_vectors,
+                        prompts,
+                        stale_cache_keys,
+                    }) => {
+                        let extracted = match extract_embeddings(
                             prompts,
                             indexer,
                             embedder.clone(),
+                            &prompt,
+                            cache_keys.as_ref().map(|keys| (&embedding_cache, keys)),
                             &request_threads,
                         ) {
                             Ok(results) => Some(results),
@@ -259,6 +278,13 @@ fn send_original_documents_data(
                                 None
                             }
                         };
+                        let (embeddings, embedding_cache_entries, embedding_cache_stats) =
+                            match extracted {
+                                Some(ExtractedEmbeddings { embeddings, cache_entries, stats }) => {
+                                    (Some(embeddings), Some(cache_entries), stats)
+                                }
+                                None => (None, None, Default::default()),
+                            };
 
                         if !(remove_vectors.is_empty()
                             && manual_vectors.is_empty()
@@ -270,6 +296,9 @@ fn send_original_documents_data(
                                 expected_dimension: embedder.dimensions(),
                                 manual_vectors,
                                 embedder_name: name,
+                                embedding_cache_entries,
+                                stale_embedding_cache_keys: stale_cache_keys,
+                                embedding_cache: embedding_cache_stats,
                             }));
                         }
                     }
@@ -38,7 +38,7 @@ pub use crate::update::index_documents::helpers::CursorClonableMmap;
 use crate::update::{
     IndexerConfig, UpdateIndexingStep, WordPrefixDocids, WordPrefixIntegerDocids, WordsPrefixesFst,
 };
-use crate::vector::EmbeddingConfigs;
+use crate::vector::{ArroyWriter, EmbeddingCache, EmbeddingCacheStats, EmbeddingConfigs};
 use crate::{CboRoaringBitmapCodec, Index, Result};
 
 static MERGED_DATABASE_COUNT: usize = 7;
@@ -51,6 +51,8 @@ pub struct DocumentAdditionResult {
     pub indexed_documents: u64,
     /// The total number of documents in the index after the update
     pub number_of_documents: u64,
+    /// How many of the texts to embed were found in the embedding cache
+    pub embedding_cache: EmbeddingCacheStats,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -252,7 +254,11 @@ where
 
         if self.added_documents == 0 && self.deleted_documents == 0 {
             let number_of_documents = self.index.number_of_documents(self.wtxn)?;
-            return Ok(DocumentAdditionResult { indexed_documents: 0, number_of_documents });
+            return Ok(DocumentAdditionResult {
+                indexed_documents: 0,
+                number_of_documents,
+                embedding_cache: EmbeddingCacheStats::default(),
+            });
         }
         let output = self
             .transform
@@ -260,20 +266,17 @@ where
             .expect("Invalid document addition state")
             .output_from_sorter(self.wtxn, &self.progress)?;
 
-        let indexed_documents = output.documents_count as u64;
-        let number_of_documents = self.execute_raw(output)?;
-
-        Ok(DocumentAdditionResult { indexed_documents, number_of_documents })
+        self.execute_raw(output)
     }
 
-    /// Returns the total number of documents in the index after the update.
+    /// Indexes the documents of the transform output, and returns the result of the addition.
     #[tracing::instrument(
         level = "trace",
         skip_all,
         target = "indexing::details",
         name = "index_documents_raw"
     )]
-    pub fn execute_raw(self, output: TransformOutput) -> Result<u64>
+    pub fn execute_raw(self, output: TransformOutput) -> Result<DocumentAdditionResult>
     where
         FP: Fn(UpdateIndexingStep) + Sync,
         FA: Fn() -> bool + Sync,
@@ -386,6 +389,10 @@ where
         let mut exact_word_docids = None;
         let mut chunk_accumulator = ChunkAccumulator::default();
         let mut dimension = HashMap::new();
+        let mut embedding_cache_stats = EmbeddingCacheStats::default();
+        let embedding_cache =
+            EmbeddingCache::new(self.index, self.indexer_config.embedding_cache_size);
+        let extractor_embedding_cache = embedding_cache.clone();
 
         let current_span = tracing::Span::current();
 
@@ -415,6 +422,7 @@ where
                         geo_fields_ids,
                         settings_diff.clone(),
                         max_positions_per_attributes,
+                        extractor_embedding_cache,
                     )
                 });
 
@@ -440,7 +448,12 @@ where
                     Err(status) => {
                         if let Some(typed_chunks) = chunk_accumulator.pop_longest() {
                             let (docids, is_merged_database) =
-                                write_typed_chunk_into_index(typed_chunks, self.index, self.wtxn)?;
+                                write_typed_chunk_into_index(
+                                    typed_chunks,
+                                    self.index,
+                                    self.wtxn,
+                                    &embedding_cache,
+                                )?;
                             if !docids.is_empty() {
                                 final_documents_ids |= docids;
                                 let documents_seen_count = final_documents_ids.len();
@@ -515,14 +528,21 @@ where
                                 embeddings,
                                 manual_vectors,
                                 embedder_name,
+                                embedding_cache_entries,
+                                stale_embedding_cache_keys,
+                                embedding_cache: chunk_embedding_cache_stats,
                             } => {
                                 dimension.insert(embedder_name.clone(), expected_dimension);
+                                embedding_cache_stats += chunk_embedding_cache_stats;
                                 TypedChunk::VectorPoints {
                                     remove_vectors,
                                     embeddings,
                                     expected_dimension,
                                     manual_vectors,
                                     embedder_name,
+                                    embedding_cache_entries,
+                                    stale_embedding_cache_keys,
+                                    embedding_cache: chunk_embedding_cache_stats,
                                 }
                             }
                             otherwise => otherwise,
@@ -547,16 +567,23 @@ where
         for (embedder_name, dimension) in dimension {
             let wtxn = &mut *self.wtxn;
             let vector_arroy = self.index.vector_arroy;
+            let vector_full_precision = self.index.vector_full_precision;
 
             let embedder_index = self.index.embedder_category_id.get(wtxn, &embedder_name)?.ok_or(
                 InternalError::DatabaseMissingEntry { db_name: "embedder_category_id", key: None },
             )?;
+            let storage = self.index.embedder_vector_storage(wtxn, &embedder_name)?;
 
             pool.install(|| {
                 let writer_index = (embedder_index as u16) << 8;
                 for k in 0..=u8::MAX {
-                    let writer =
-                        arroy::Writer::new(vector_arroy, writer_index | (k as u16), dimension)?;
+                    let writer = ArroyWriter::new(
+                        vector_arroy,
+                        vector_full_precision,
+                        writer_index | (k as u16),
+                        dimension,
+                        storage,
+                    )?;
                     if writer.is_empty(wtxn)? {
                         break;
                     }
@@ -565,6 +592,8 @@ where
                 Result::Ok(())
             })
             .map_err(InternalError::from)??;
+
+            self.index.update_vector_storage_size(wtxn, &embedder_name, embedder_index)?;
         }
 
         self.execute_prefix_databases(
@@ -574,7 +603,11 @@ where
             word_fid_docids.map(MergerBuilder::build),
         )?;
 
-        Ok(number_of_documents)
+        Ok(DocumentAdditionResult {
+            indexed_documents: documents_count as u64,
+            number_of_documents,
+            embedding_cache: embedding_cache_stats,
+        })
     }
 
     #[tracing::instrument(
@@ -2252,6 +2285,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 3,
             number_of_documents: 2,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
@@ -2301,6 +2338,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 5,
             number_of_documents: 1,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
@@ -2339,6 +2380,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 3,
             number_of_documents: 3,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
@@ -2377,6 +2422,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 2,
             number_of_documents: 1,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
@@ -2417,6 +2466,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 2,
             number_of_documents: 2,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
@@ -2464,6 +2517,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 3,
             number_of_documents: 1,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
@@ -2500,6 +2557,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
@@ -2535,6 +2596,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
@@ -2616,7 +2681,14 @@ mod tests {
                         path_to_embeddings: Setting::NotSet,
                         embedding_object: Setting::NotSet,
                         input_type: Setting::NotSet,
+                        image_field: Setting::NotSet,
                         distribution: Setting::NotSet,
+                        distance: Setting::NotSet,
+                        quantization: Setting::NotSet,
+                        rescoring: Setting::NotSet,
+                        chunking: Setting::NotSet,
+                        pooling: Setting::NotSet,
+                        normalize: Setting::NotSet,
                     }),
                 );
                 settings.set_embedder_settings(embedders);
@@ -2711,6 +2783,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
@@ -2755,6 +2831,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
@@ -2795,6 +2875,10 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 2,
+            embedding_cache: EmbeddingCacheStats {
+                hits: 0,
+                misses: 0,
+            },
         }
         "###);
         wtxn.commit().unwrap();
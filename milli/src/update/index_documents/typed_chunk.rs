@@ -25,6 +25,7 @@ use crate::update::facet::FacetsUpdate;
 use crate::update::index_documents::helpers::{
     as_cloneable_grenad, keep_latest_obkv, try_split_array_at,
 };
+use crate::vector::{ArroyWriter, EmbeddingCache, EmbeddingCacheStats};
 use crate::{
     lat_lng_to_xyz, DocumentId, FieldId, GeoPoint, Index, InternalError, Result, SerializationError,
 };
@@ -87,6 +88,11 @@ pub(crate) enum TypedChunk {
         expected_dimension: usize,
         manual_vectors: grenad::Reader<BufReader<File>>,
         embedder_name: String,
+        // cache key -> embedding, to store in the embedding cache
+        embedding_cache_entries: Option<grenad::Reader<BufReader<File>>>,
+        // cache key -> (), the texts that are not embedded by their document anymore
+        stale_embedding_cache_keys: grenad::Reader<BufReader<File>>,
+        embedding_cache: EmbeddingCacheStats,
     },
     ScriptLanguageDocids(HashMap<(Script, Language), (RoaringBitmap, RoaringBitmap)>),
 }
@@ -167,8 +173,8 @@ impl TypedChunk {
             TypedChunk::GeoPoints(grenad) => {
                 format!("GeoPoints {{ number_of_entries: {} }}", grenad.len())
             }
-            TypedChunk::VectorPoints{ remove_vectors, manual_vectors, embeddings, expected_dimension, embedder_name } => {
-                format!("VectorPoints {{ remove_vectors: {}, manual_vectors: {}, embeddings: {}, dimension: {}, embedder_name: {} }}", remove_vectors.len(), manual_vectors.len(), embeddings.as_ref().map(|e| e.len()).unwrap_or_default(), expected_dimension, embedder_name)
+            TypedChunk::VectorPoints{ remove_vectors, manual_vectors, embeddings, expected_dimension, embedder_name, embedding_cache_entries, stale_embedding_cache_keys, embedding_cache: _ } => {
+                format!("VectorPoints {{ remove_vectors: {}, manual_vectors: {}, embeddings: {}, dimension: {}, embedder_name: {}, embedding_cache_entries: {}, stale_embedding_cache_keys: {} }}", remove_vectors.len(), manual_vectors.len(), embeddings.as_ref().map(|e| e.len()).unwrap_or_default(), expected_dimension, embedder_name, embedding_cache_entries.as_ref().map(|e| e.len()).unwrap_or_default(), stale_embedding_cache_keys.len())
             }
             TypedChunk::ScriptLanguageDocids(sl_map) => {
                 format!("ScriptLanguageDocids {{ number_of_entries: {} }}", sl_map.len())
@@ -184,6 +190,7 @@ pub(crate) fn write_typed_chunk_into_index(
     typed_chunks: Vec<TypedChunk>,
     index: &Index,
     wtxn: &mut RwTxn,
+    embedding_cache: &EmbeddingCache,
 ) -> Result<(RoaringBitmap, bool)> {
     puffin::profile_function!(typed_chunks[0].to_debug_string());
 
@@ -631,6 +638,8 @@ pub(crate) fn write_typed_chunk_into_index(
             let mut remove_vectors_builder = MergerBuilder::new(keep_first as MergeFn);
             let mut manual_vectors_builder = MergerBuilder::new(keep_first as MergeFn);
             let mut embeddings_builder = MergerBuilder::new(keep_first as MergeFn);
+            let mut embedding_cache_builder = MergerBuilder::new(keep_first as MergeFn);
+            let mut stale_embedding_cache_keys_builder = MergerBuilder::new(keep_first as MergeFn);
             let mut params = None;
             for typed_chunk in typed_chunks {
                 let TypedChunk::VectorPoints {
@@ -639,6 +648,9 @@ pub(crate) fn write_typed_chunk_into_index(
                     embeddings,
                     expected_dimension,
                     embedder_name,
+                    embedding_cache_entries,
+                    stale_embedding_cache_keys,
+                    embedding_cache: _,
                 } = typed_chunk
                 else {
                     unreachable!();
@@ -651,6 +663,10 @@ pub(crate) fn write_typed_chunk_into_index(
                 if let Some(embeddings) = embeddings {
                     embeddings_builder.push(embeddings.into_cursor()?);
                 }
+                if let Some(embedding_cache_entries) = embedding_cache_entries {
+                    embedding_cache_builder.push(embedding_cache_entries.into_cursor()?);
+                }
+                stale_embedding_cache_keys_builder.push(stale_embedding_cache_keys.into_cursor()?);
             }
 
             // typed chunks has always at least 1 chunk.
@@ -659,14 +675,16 @@ pub(crate) fn write_typed_chunk_into_index(
             let embedder_index = index.embedder_category_id.get(wtxn, &embedder_name)?.ok_or(
                 InternalError::DatabaseMissingEntry { db_name: "embedder_category_id", key: None },
             )?;
+            let storage = index.embedder_vector_storage(wtxn, &embedder_name)?;
             let writer_index = (embedder_index as u16) << 8;
-            // FIXME: allow customizing distance
             let writers: std::result::Result<Vec<_>, _> = (0..=u8::MAX)
                 .map(|k| {
-                    arroy::Writer::new(
+                    ArroyWriter::new(
                         index.vector_arroy,
+                        index.vector_full_precision,
                         writer_index | (k as u16),
                         expected_dimension,
+                        storage,
                     )
                 })
                 .collect();
@@ -716,6 +734,20 @@ pub(crate) fn write_typed_chunk_into_index(
                 }
             }
 
+            // forget the texts that are not embedded anymore, before storing the newly generated embeddings
+            // in the embedding cache, as another document may now use one of these texts.
+            let merger = stale_embedding_cache_keys_builder.build();
+            let mut iter = merger.into_stream_merger_iter()?;
+            while let Some((key, _)) = iter.next()? {
+                embedding_cache.delete(wtxn, key)?;
+            }
+            let merger = embedding_cache_builder.build();
+            let mut iter = merger.into_stream_merger_iter()?;
+            while let Some((key, value)) = iter.next()? {
+                embedding_cache.put(wtxn, key, value)?;
+            }
+            embedding_cache.evict(wtxn)?;
+
             // perform the manual diff
             let merger = manual_vectors_builder.build();
             let mut iter = merger.into_stream_merger_iter()?;
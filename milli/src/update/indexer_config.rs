@@ -1,6 +1,7 @@
 use grenad::CompressionType;
 
 use crate::thread_pool_no_abort::ThreadPoolNoAbort;
+use crate::vector::DEFAULT_EMBEDDING_CACHE_SIZE;
 
 #[derive(Debug)]
 pub struct IndexerConfig {
@@ -13,6 +14,8 @@ pub struct IndexerConfig {
     pub thread_pool: Option<ThreadPoolNoAbort>,
     pub max_positions_per_attributes: Option<u32>,
     pub skip_index_budget: bool,
+    /// The maximum number of embeddings kept in the embedding cache of an index, `0` disables the cache.
+    pub embedding_cache_size: usize,
 }
 
 impl Default for IndexerConfig {
@@ -27,6 +30,7 @@ impl Default for IndexerConfig {
             thread_pool: None,
             max_positions_per_attributes: None,
             skip_index_budget: false,
+            embedding_cache_size: DEFAULT_EMBEDDING_CACHE_SIZE,
         }
     }
 }
@@ -15,11 +15,16 @@ use crate::criterion::Criterion;
 use crate::error::UserError;
 use crate::index::{DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS};
 use crate::order_by_map::OrderByMap;
+use crate::prompt::PromptData;
 use crate::proximity::ProximityPrecision;
 use crate::update::index_documents::IndexDocumentsMethod;
 use crate::update::{IndexDocuments, UpdateIndexingStep};
+use crate::vector::rest::InputType;
 use crate::vector::settings::{check_set, check_unset, EmbedderSource, EmbeddingSettings};
-use crate::vector::{Embedder, EmbeddingConfig, EmbeddingConfigs};
+use crate::vector::{
+    Distance, Embedder, EmbedderOptions, EmbeddingCache, EmbeddingCacheStats, EmbeddingConfig,
+    EmbeddingConfigs,
+};
 use crate::{FieldId, FieldsIdsMap, Index, Result};
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
@@ -147,6 +152,8 @@ pub struct Settings<'a, 't, 'i> {
     exact_attributes: Setting<HashSet<String>>,
     max_values_per_facet: Setting<usize>,
     sort_facet_values_by: Setting<OrderByMap>,
+    /// The separator of the path-delimited values of each hierarchical facet.
+    hierarchical_facets: Setting<BTreeMap<String, String>>,
     pagination_max_total_hits: Setting<usize>,
     proximity_precision: Setting<ProximityPrecision>,
     embedder_settings: Setting<BTreeMap<String, Setting<EmbeddingSettings>>>,
@@ -181,6 +188,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             exact_attributes: Setting::NotSet,
             max_values_per_facet: Setting::NotSet,
             sort_facet_values_by: Setting::NotSet,
+            hierarchical_facets: Setting::NotSet,
             pagination_max_total_hits: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             embedder_settings: Setting::NotSet,
@@ -351,6 +359,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.sort_facet_values_by = Setting::Reset;
     }
 
+    pub fn set_hierarchical_facets(&mut self, value: BTreeMap<String, String>) {
+        self.hierarchical_facets = Setting::Set(value);
+    }
+
+    pub fn reset_hierarchical_facets(&mut self) {
+        self.hierarchical_facets = Setting::Reset;
+    }
+
     pub fn set_pagination_max_total_hits(&mut self, value: usize) {
         self.pagination_max_total_hits = Setting::Set(value);
     }
@@ -393,7 +409,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         progress_callback: &FP,
         should_abort: &FA,
         settings_diff: InnerIndexSettingsDiff,
-    ) -> Result<()>
+    ) -> Result<EmbeddingCacheStats>
     where
         FP: Fn(UpdateIndexingStep) + Sync,
         FA: Fn() -> bool + Sync,
@@ -403,7 +419,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         // if the settings are set before any document update, we don't need to do anything, and
         // will set the primary key during the first document addition.
         if self.index.number_of_documents(self.wtxn)? == 0 {
-            return Ok(());
+            return Ok(EmbeddingCacheStats::default());
         }
 
         let transform = Transform::new(
@@ -428,9 +444,9 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             &should_abort,
         )?;
 
-        indexing_builder.execute_raw(output)?;
+        let addition = indexing_builder.execute_raw(output)?;
 
-        Ok(())
+        Ok(addition.embedding_cache)
     }
 
     fn update_displayed(&mut self) -> Result<bool> {
@@ -899,6 +915,20 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(())
     }
 
+    fn update_hierarchical_facets(&mut self) -> Result<()> {
+        match self.hierarchical_facets.as_ref() {
+            Setting::Set(value) => {
+                self.index.put_hierarchical_facets(self.wtxn, value)?;
+            }
+            Setting::Reset => {
+                self.index.delete_hierarchical_facets(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+
+        Ok(())
+    }
+
     fn update_pagination_max_total_hits(&mut self) -> Result<()> {
         match self.pagination_max_total_hits {
             Setting::Set(max) => {
@@ -936,6 +966,12 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             Setting::Set(configs) => {
                 let mut changed = false;
                 let old_configs = self.index.embedding_configs(self.wtxn)?;
+                let old_embedders: Vec<(String, EmbedderOptions, PromptData)> = old_configs
+                    .iter()
+                    .map(|(name, config)| {
+                        (name.clone(), config.embedder_options.clone(), config.prompt.clone())
+                    })
+                    .collect();
                 let old_configs: BTreeMap<String, Setting<EmbeddingSettings>> =
                     old_configs.into_iter().map(|(k, v)| (k, Setting::Set(v.into()))).collect();
 
@@ -984,6 +1020,21 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                     })
                     .collect();
 
+                // the cached embeddings of an embedder can't be reused once its model changed,
+                // and the texts rendered from its previous template won't be embedded anymore.
+                let embedding_cache =
+                    EmbeddingCache::new(self.index, self.indexer_config.embedding_cache_size);
+                for (name, options, prompt) in old_embedders {
+                    let unchanged = new_configs.iter().any(|(new_name, config)| {
+                        *new_name == name
+                            && config.embedder_options == options
+                            && config.prompt == prompt
+                    });
+                    if !unchanged {
+                        embedding_cache.delete_embedder(self.wtxn, &name)?;
+                    }
+                }
+
                 self.index.embedder_category_id.clear(self.wtxn)?;
                 for (index, (embedder_name, _)) in new_configs.iter().enumerate() {
                     self.index.embedder_category_id.put_with_flags(
@@ -1005,6 +1056,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             }
             Setting::Reset => {
                 self.index.delete_embedding_configs(self.wtxn)?;
+                self.index.embedding_cache.clear(self.wtxn)?;
                 true
             }
             Setting::NotSet => false,
@@ -1014,6 +1066,8 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         // clear the vector database.
         if update {
             self.index.vector_arroy.clear(self.wtxn)?;
+            self.index.vector_full_precision.clear(self.wtxn)?;
+            self.index.delete_vector_storage_sizes(self.wtxn)?;
         }
 
         Ok(update)
@@ -1037,7 +1091,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(changed)
     }
 
-    pub fn execute<FP, FA>(mut self, progress_callback: FP, should_abort: FA) -> Result<()>
+    /// Applies the settings, reindexing the documents if needed.
+    ///
+    /// Returns how many of the texts to embed during the reindexing were found in the embedding cache.
+    pub fn execute<FP, FA>(
+        mut self,
+        progress_callback: FP,
+        should_abort: FA,
+    ) -> Result<EmbeddingCacheStats>
     where
         FP: Fn(UpdateIndexingStep) + Sync,
         FA: Fn() -> bool + Sync,
@@ -1062,6 +1123,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         // could trigger re-indexing
         self.update_filterable()?;
         self.update_sortable()?;
+        self.update_hierarchical_facets()?;
         self.update_stop_words()?;
         self.update_non_separator_tokens()?;
         self.update_separator_tokens()?;
@@ -1087,10 +1149,10 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         };
 
         if inner_settings_diff.any_reindexing_needed() {
-            self.reindex(&progress_callback, &should_abort, inner_settings_diff)?;
+            self.reindex(&progress_callback, &should_abort, inner_settings_diff)
+        } else {
+            Ok(EmbeddingCacheStats::default())
         }
-
-        Ok(())
     }
 }
 
@@ -1152,6 +1214,7 @@ impl InnerIndexSettingsDiff {
             .zip(self.new.fields_ids_map.iter())
             .any(|(old, new)| old != new)
             || faceted_updated
+            || self.old.hierarchical_facets != self.new.hierarchical_facets
     }
 
     pub fn reindex_vectors(&self) -> bool {
@@ -1176,7 +1239,9 @@ pub(crate) struct InnerIndexSettings {
     pub exact_attributes: HashSet<FieldId>,
     pub proximity_precision: ProximityPrecision,
     pub embedding_configs: EmbeddingConfigs,
+    pub embedder_options: HashMap<String, EmbedderOptions>,
     pub existing_fields: HashSet<String>,
+    pub hierarchical_facets: BTreeMap<String, String>,
 }
 
 impl InnerIndexSettings {
@@ -1194,12 +1259,18 @@ impl InnerIndexSettings {
         let faceted_fields_ids = index.faceted_fields_ids(rtxn)?;
         let exact_attributes = index.exact_attributes_ids(rtxn)?;
         let proximity_precision = index.proximity_precision(rtxn)?.unwrap_or_default();
-        let embedding_configs = embedders(index.embedding_configs(rtxn)?)?;
+        let embedding_configs = index.embedding_configs(rtxn)?;
+        let embedder_options = embedding_configs
+            .iter()
+            .map(|(name, config)| (name.clone(), config.embedder_options.clone()))
+            .collect();
+        let embedding_configs = embedders(embedding_configs)?;
         let existing_fields: HashSet<_> = index
             .field_distribution(rtxn)?
             .into_iter()
             .filter_map(|(field, count)| (count != 0).then_some(field))
             .collect();
+        let hierarchical_facets = index.hierarchical_facets(rtxn)?;
 
         Ok(Self {
             stop_words,
@@ -1213,10 +1284,18 @@ impl InnerIndexSettings {
             exact_attributes,
             proximity_precision,
             embedding_configs,
+            embedder_options,
             existing_fields,
+            hierarchical_facets,
         })
     }
 
+    /// Returns the separator of the values of the field if it is a hierarchical facet.
+    pub fn hierarchical_facet_separator(&self, field_id: FieldId) -> Option<&str> {
+        let name = self.fields_ids_map.name(field_id)?;
+        self.hierarchical_facets.get(name).map(String::as_str)
+    }
+
     // find and insert the new field ids
     pub fn recompute_facets(&mut self, wtxn: &mut heed::RwTxn, index: &Index) -> Result<()> {
         let new_facets = self
@@ -1253,7 +1332,7 @@ impl InnerIndexSettings {
 fn embedders(embedding_configs: Vec<(String, EmbeddingConfig)>) -> Result<EmbeddingConfigs> {
     let res: Result<_> = embedding_configs
         .into_iter()
-        .map(|(name, EmbeddingConfig { embedder_options, prompt })| {
+        .map(|(name, EmbeddingConfig { embedder_options, prompt, .. })| {
             let prompt = Arc::new(prompt.try_into().map_err(crate::Error::from)?);
 
             let embedder = Arc::new(
@@ -1285,7 +1364,14 @@ fn validate_prompt(
             path_to_embeddings,
             embedding_object,
             input_type,
+            image_field,
             distribution,
+            distance,
+            quantization,
+            rescoring,
+            chunking,
+            pooling,
+            normalize,
         }) => {
             // validate
             let template = crate::prompt::Prompt::new(template)
@@ -1305,7 +1391,14 @@ fn validate_prompt(
                 path_to_embeddings,
                 embedding_object,
                 input_type,
+                image_field,
                 distribution,
+                distance,
+                quantization,
+                rescoring,
+                chunking,
+                pooling,
+                normalize,
             }))
         }
         new => Ok(new),
@@ -1331,7 +1424,14 @@ pub fn validate_embedding_settings(
         path_to_embeddings,
         embedding_object,
         input_type,
+        image_field,
         distribution,
+        distance,
+        quantization,
+        rescoring,
+        chunking,
+        pooling,
+        normalize,
     } = settings;
 
     if let Some(0) = dimensions.set() {
@@ -1341,6 +1441,32 @@ pub fn validate_embedding_settings(
         .into());
     }
 
+    if let Some(chunking) = chunking.as_ref().set() {
+        if !chunking.is_valid() {
+            return Err(crate::error::UserError::InvalidSettingsChunking {
+                embedder_name: name.to_owned(),
+                size: chunking.size,
+                overlap: chunking.overlap,
+            }
+            .into());
+        }
+    }
+
+    if quantization.set().is_some() && distance.set() == Some(Distance::Dot) {
+        return Err(crate::error::UserError::InvalidSettingsQuantization {
+            embedder_name: name.to_owned(),
+            distance: Distance::Dot,
+        }
+        .into());
+    }
+
+    if rescoring.set() == Some(true) && quantization.set().is_none() {
+        return Err(crate::error::UserError::InvalidSettingsRescoring {
+            embedder_name: name.to_owned(),
+        }
+        .into());
+    }
+
     if let Some(url) = url.as_ref().set() {
         url::Url::parse(url).map_err(|error| crate::error::UserError::InvalidUrl {
             embedder_name: name.to_owned(),
@@ -1363,11 +1489,20 @@ pub fn validate_embedding_settings(
             path_to_embeddings,
             embedding_object,
             input_type,
+            image_field,
             distribution,
+            distance,
+            quantization,
+            rescoring,
+            chunking,
+            pooling,
+            normalize,
         }));
     };
     match inferred_source {
         EmbedderSource::OpenAi => {
+            check_unset(&pooling, EmbeddingSettings::POOLING, inferred_source, name)?;
+            check_unset(&normalize, EmbeddingSettings::NORMALIZE, inferred_source, name)?;
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
 
             check_unset(&url, EmbeddingSettings::URL, inferred_source, name)?;
@@ -1386,6 +1521,7 @@ pub fn validate_embedding_settings(
                 name,
             )?;
             check_unset(&input_type, EmbeddingSettings::INPUT_TYPE, inferred_source, name)?;
+            check_unset(&image_field, EmbeddingSettings::IMAGE_FIELD, inferred_source, name)?;
 
             if let Setting::Set(model) = &model {
                 let model = crate::vector::openai::EmbeddingModel::from_name(model.as_str())
@@ -1418,6 +1554,8 @@ pub fn validate_embedding_settings(
             }
         }
         EmbedderSource::Ollama => {
+            check_unset(&pooling, EmbeddingSettings::POOLING, inferred_source, name)?;
+            check_unset(&normalize, EmbeddingSettings::NORMALIZE, inferred_source, name)?;
             // Dimensions get inferred, only model name is required
             check_unset(&dimensions, EmbeddingSettings::DIMENSIONS, inferred_source, name)?;
             check_set(&model, EmbeddingSettings::MODEL, inferred_source, name)?;
@@ -1438,8 +1576,11 @@ pub fn validate_embedding_settings(
                 name,
             )?;
             check_unset(&input_type, EmbeddingSettings::INPUT_TYPE, inferred_source, name)?;
+            check_unset(&image_field, EmbeddingSettings::IMAGE_FIELD, inferred_source, name)?;
         }
         EmbedderSource::HuggingFace => {
+            check_unset(&pooling, EmbeddingSettings::POOLING, inferred_source, name)?;
+            check_unset(&normalize, EmbeddingSettings::NORMALIZE, inferred_source, name)?;
             check_unset(&api_key, EmbeddingSettings::API_KEY, inferred_source, name)?;
             check_unset(&dimensions, EmbeddingSettings::DIMENSIONS, inferred_source, name)?;
 
@@ -1459,8 +1600,11 @@ pub fn validate_embedding_settings(
                 name,
             )?;
             check_unset(&input_type, EmbeddingSettings::INPUT_TYPE, inferred_source, name)?;
+            check_unset(&image_field, EmbeddingSettings::IMAGE_FIELD, inferred_source, name)?;
         }
         EmbedderSource::UserProvided => {
+            check_unset(&pooling, EmbeddingSettings::POOLING, inferred_source, name)?;
+            check_unset(&normalize, EmbeddingSettings::NORMALIZE, inferred_source, name)?;
             check_unset(&model, EmbeddingSettings::MODEL, inferred_source, name)?;
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
             check_unset(&api_key, EmbeddingSettings::API_KEY, inferred_source, name)?;
@@ -1470,6 +1614,7 @@ pub fn validate_embedding_settings(
                 inferred_source,
                 name,
             )?;
+            check_unset(&chunking, EmbeddingSettings::CHUNKING, inferred_source, name)?;
             check_set(&dimensions, EmbeddingSettings::DIMENSIONS, inferred_source, name)?;
 
             check_unset(&url, EmbeddingSettings::URL, inferred_source, name)?;
@@ -1488,11 +1633,54 @@ pub fn validate_embedding_settings(
                 name,
             )?;
             check_unset(&input_type, EmbeddingSettings::INPUT_TYPE, inferred_source, name)?;
+            check_unset(&image_field, EmbeddingSettings::IMAGE_FIELD, inferred_source, name)?;
         }
         EmbedderSource::Rest => {
+            check_unset(&pooling, EmbeddingSettings::POOLING, inferred_source, name)?;
+            check_unset(&normalize, EmbeddingSettings::NORMALIZE, inferred_source, name)?;
             check_unset(&model, EmbeddingSettings::MODEL, inferred_source, name)?;
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
             check_set(&url, EmbeddingSettings::URL, inferred_source, name)?;
+
+            let accepts_images = matches!(input_type, Setting::Set(InputType::TextAndImage));
+            if accepts_images != matches!(image_field, Setting::Set(_)) {
+                return Err(crate::error::UserError::InvalidSettingsImageField {
+                    embedder_name: name.to_owned(),
+                }
+                .into());
+            }
+            // an input is sent whole along with its image
+            if accepts_images && matches!(chunking, Setting::Set(_)) {
+                return Err(crate::error::UserError::InvalidSettingsChunkingWithImages {
+                    embedder_name: name.to_owned(),
+                }
+                .into());
+            }
+        }
+        EmbedderSource::Onnx => {
+            // Dimensions get inferred, only the path of the model is required
+            check_set(&model, EmbeddingSettings::MODEL, inferred_source, name)?;
+            check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
+            check_unset(&api_key, EmbeddingSettings::API_KEY, inferred_source, name)?;
+            check_unset(&dimensions, EmbeddingSettings::DIMENSIONS, inferred_source, name)?;
+
+            check_unset(&url, EmbeddingSettings::URL, inferred_source, name)?;
+            check_unset(&query, EmbeddingSettings::QUERY, inferred_source, name)?;
+            check_unset(&input_field, EmbeddingSettings::INPUT_FIELD, inferred_source, name)?;
+            check_unset(
+                &path_to_embeddings,
+                EmbeddingSettings::PATH_TO_EMBEDDINGS,
+                inferred_source,
+                name,
+            )?;
+            check_unset(
+                &embedding_object,
+                EmbeddingSettings::EMBEDDING_OBJECT,
+                inferred_source,
+                name,
+            )?;
+            check_unset(&input_type, EmbeddingSettings::INPUT_TYPE, inferred_source, name)?;
+            check_unset(&image_field, EmbeddingSettings::IMAGE_FIELD, inferred_source, name)?;
         }
     }
     Ok(Setting::Set(EmbeddingSettings {
@@ -1508,7 +1696,14 @@ pub fn validate_embedding_settings(
         path_to_embeddings,
         embedding_object,
         input_type,
+        image_field,
         distribution,
+        distance,
+        quantization,
+        rescoring,
+        chunking,
+        pooling,
+        normalize,
     }))
 }
 
@@ -2392,6 +2587,7 @@ mod tests {
                     exact_attributes,
                     max_values_per_facet,
                     sort_facet_values_by,
+                    hierarchical_facets,
                     pagination_max_total_hits,
                     proximity_precision,
                     embedder_settings,
@@ -2416,6 +2612,7 @@ mod tests {
                 assert!(matches!(exact_attributes, Setting::NotSet));
                 assert!(matches!(max_values_per_facet, Setting::NotSet));
                 assert!(matches!(sort_facet_values_by, Setting::NotSet));
+                assert!(matches!(hierarchical_facets, Setting::NotSet));
                 assert!(matches!(pagination_max_total_hits, Setting::NotSet));
                 assert!(matches!(proximity_precision, Setting::NotSet));
                 assert!(matches!(embedder_settings, Setting::NotSet));
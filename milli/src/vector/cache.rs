@@ -0,0 +1,155 @@
+use std::ops::Bound;
+
+use heed::types::Bytes;
+use heed::{Database, RoTxn, RwTxn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{EmbedderOptions, Embedding};
+use crate::Index;
+
+/// The default maximum number of embeddings kept in the [`EmbeddingCache`] of an index.
+pub const DEFAULT_EMBEDDING_CACHE_SIZE: usize = 100_000;
+
+/// A persistent cache of the embeddings computed by the embedders of an index.
+///
+/// Each entry maps a hash of the name of an embedder, of its options and of an embedded text
+/// to the embedding of this text, so that unchanged texts are never sent twice to the same model.
+///
+/// The cache holds at most `max_entries` embeddings, a maximum of `0` disables it.
+#[derive(Clone)]
+pub struct EmbeddingCache {
+    env: heed::Env,
+    database: Database<Bytes, Bytes>,
+    max_entries: usize,
+}
+
+impl EmbeddingCache {
+    pub fn new(index: &Index, max_entries: usize) -> Self {
+        Self { env: index.env.clone(), database: index.embedding_cache, max_entries }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_entries > 0
+    }
+
+    /// Opens a read transaction on the index.
+    ///
+    /// The cache is consulted by the extractors while the indexing write transaction is still running,
+    /// so they only see the embeddings that were committed by the previous updates.
+    pub fn read_txn(&self) -> heed::Result<RoTxn> {
+        self.env.read_txn()
+    }
+
+    pub fn get(&self, rtxn: &RoTxn, key: &[u8]) -> heed::Result<Option<Embedding>> {
+        Ok(self.database.get(rtxn, key)?.map(bytemuck::pod_collect_to_vec))
+    }
+
+    /// Stores an embedding, in its native-endian byte representation.
+    pub fn put(&self, wtxn: &mut RwTxn, key: &[u8], embedding: &[u8]) -> heed::Result<()> {
+        self.database.put(wtxn, key, embedding)
+    }
+
+    pub fn delete(&self, wtxn: &mut RwTxn, key: &[u8]) -> heed::Result<bool> {
+        self.database.delete(wtxn, key)
+    }
+
+    /// Removes random entries until the cache holds at most its maximum number of embeddings.
+    pub fn evict(&self, wtxn: &mut RwTxn) -> heed::Result<()> {
+        let len = self.database.len(wtxn)? as usize;
+        let mut to_evict = len.saturating_sub(self.max_entries);
+        if to_evict == 0 {
+            return Ok(());
+        }
+
+        // the keys are hashes, so the entries following a random key are random entries,
+        // evicted from each embedder in proportion of the space it takes in the cache.
+        let start: [u8; 32] = rand::random();
+        let range = (Bound::Included(&start[..]), Bound::Unbounded);
+        let mut iter = self.database.range_mut(wtxn, &range)?;
+        while to_evict > 0 && iter.next().transpose()?.is_some() {
+            // safety: we don't keep references from inside the LMDB database.
+            unsafe { iter.del_current()? };
+            to_evict -= 1;
+        }
+        drop(iter);
+
+        // wrap around to the first keys
+        let mut iter = self.database.iter_mut(wtxn)?;
+        while to_evict > 0 && iter.next().transpose()?.is_some() {
+            // safety: we don't keep references from inside the LMDB database.
+            unsafe { iter.del_current()? };
+            to_evict -= 1;
+        }
+        Ok(())
+    }
+
+    /// Removes all the embeddings cached for an embedder.
+    pub fn delete_embedder(&self, wtxn: &mut RwTxn, embedder_name: &str) -> heed::Result<()> {
+        let prefix = embedder_prefix(embedder_name);
+        let mut iter = self.database.prefix_iter_mut(wtxn, &prefix[..])?;
+        while iter.next().transpose()?.is_some() {
+            // safety: we don't keep references from inside the LMDB database.
+            unsafe { iter.del_current()? };
+        }
+        Ok(())
+    }
+
+    pub fn clear(&self, wtxn: &mut RwTxn) -> heed::Result<()> {
+        self.database.clear(wtxn)
+    }
+}
+
+/// Computes the keys of the texts embedded by one embedder in the [`EmbeddingCache`].
+pub struct CacheKeys {
+    prefix: [u8; 8],
+    /// Hasher already fed with the options of the embedder.
+    config_hasher: Sha256,
+}
+
+impl CacheKeys {
+    pub fn new(embedder_name: &str, options: &EmbedderOptions) -> Self {
+        let mut config_hasher = Sha256::new();
+        // the options are serialized field by field, in the order of their declaration.
+        config_hasher.update(serde_json::to_vec(options).unwrap_or_default());
+        Self { prefix: embedder_prefix(embedder_name), config_hasher }
+    }
+
+    pub fn key(&self, text: &str) -> Vec<u8> {
+        let digest = self.config_hasher.clone().chain_update(text.as_bytes()).finalize();
+        let mut key = Vec::with_capacity(self.prefix.len() + digest.len());
+        key.extend_from_slice(&self.prefix);
+        key.extend_from_slice(&digest);
+        key
+    }
+}
+
+/// The prefix of all the keys of an embedder, a hash of its name that fits in a LMDB key.
+fn embedder_prefix(embedder_name: &str) -> [u8; 8] {
+    let digest = Sha256::digest(embedder_name.as_bytes());
+    let mut prefix = [0; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    prefix
+}
+
+/// How many texts were found in the [`EmbeddingCache`] while indexing, and how many had to be embedded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl EmbeddingCacheStats {
+    /// Whether no text was embedded at all.
+    pub fn is_empty(&self) -> bool {
+        self.hits == 0 && self.misses == 0
+    }
+}
+
+impl std::ops::AddAssign for EmbeddingCacheStats {
+    fn add_assign(&mut self, other: Self) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+    }
+}
@@ -81,6 +81,14 @@ pub enum EmbedErrorKind {
     OpenAiUnexpectedDimension(usize, usize),
     #[error("no embedding was produced")]
     MissingEmbedding,
+    #[error("attempt to embed an image with an embedder that only accepts texts")]
+    ImageNotSupported,
+    #[cfg(feature = "onnx")]
+    #[error("could not run ONNX model: {0}")]
+    OnnxRun(ort::Error),
+    #[cfg(feature = "onnx")]
+    #[error("unexpected shape of the ONNX model output: {0}")]
+    OnnxOutputShape(ndarray::ShapeError),
     #[error(transparent)]
     PanicInThreadPool(#[from] PanicCatched),
 }
@@ -198,6 +206,20 @@ impl EmbedError {
     pub(crate) fn missing_embedding() -> EmbedError {
         Self { kind: EmbedErrorKind::MissingEmbedding, fault: FaultSource::Undecided }
     }
+
+    pub(crate) fn image_not_supported() -> EmbedError {
+        Self { kind: EmbedErrorKind::ImageNotSupported, fault: FaultSource::User }
+    }
+
+    #[cfg(feature = "onnx")]
+    pub(crate) fn onnx_run(inner: ort::Error) -> EmbedError {
+        Self { kind: EmbedErrorKind::OnnxRun(inner), fault: FaultSource::Runtime }
+    }
+
+    #[cfg(feature = "onnx")]
+    pub(crate) fn onnx_output_shape(inner: ndarray::ShapeError) -> EmbedError {
+        Self { kind: EmbedErrorKind::OnnxOutputShape(inner), fault: FaultSource::User }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -257,6 +279,20 @@ impl NewEmbedderError {
         Self { kind: NewEmbedderErrorKind::LoadModel(inner), fault: FaultSource::Runtime }
     }
 
+    #[cfg(feature = "onnx")]
+    pub fn onnx_load_model(model_filename: PathBuf, inner: ort::Error) -> Self {
+        let onnx_load_model = OnnxLoadModel { filename: model_filename, inner };
+        Self {
+            kind: NewEmbedderErrorKind::OnnxLoadModel(onnx_load_model),
+            fault: FaultSource::User,
+        }
+    }
+
+    #[cfg(not(feature = "onnx"))]
+    pub fn onnx_disabled() -> Self {
+        Self { kind: NewEmbedderErrorKind::OnnxDisabled, fault: FaultSource::User }
+    }
+
     pub fn could_not_determine_dimension(inner: EmbedError) -> NewEmbedderError {
         Self {
             kind: NewEmbedderErrorKind::CouldNotDetermineDimension(inner),
@@ -288,6 +324,15 @@ pub struct OpenTokenizer {
     pub inner: Box<dyn std::error::Error + Send + Sync>,
 }
 
+#[cfg(feature = "onnx")]
+#[derive(Debug, thiserror::Error)]
+#[error("could not load ONNX model at {filename}: {inner}")]
+pub struct OnnxLoadModel {
+    pub filename: PathBuf,
+    #[source]
+    pub inner: ort::Error,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum NewEmbedderErrorKind {
     // hf
@@ -309,4 +354,13 @@ pub enum NewEmbedderErrorKind {
     CouldNotDetermineDimension(EmbedError),
     #[error("loading model failed: {0}")]
     LoadModel(candle_core::Error),
+    // onnx
+    #[cfg(feature = "onnx")]
+    #[error(transparent)]
+    OnnxLoadModel(OnnxLoadModel),
+    #[cfg(not(feature = "onnx"))]
+    #[error(
+        "the `onnx` source is not available, Meilisearch was compiled without the `onnx` feature"
+    )]
+    OnnxDisabled,
 }
@@ -9,16 +9,25 @@ use self::error::{EmbedError, NewEmbedderError};
 use crate::prompt::{Prompt, PromptData};
 use crate::ThreadPoolNoAbort;
 
+pub mod cache;
 pub mod error;
 pub mod hf;
 pub mod manual;
+pub mod onnx;
 pub mod openai;
+pub mod query_cache;
 pub mod settings;
+pub mod store;
 
 pub mod ollama;
 pub mod rest;
 
+pub use self::cache::{
+    CacheKeys, EmbeddingCache, EmbeddingCacheStats, DEFAULT_EMBEDDING_CACHE_SIZE,
+};
 pub use self::error::Error;
+pub use self::query_cache::{QueryEmbeddingCache, QueryEmbeddingStats};
+pub use self::store::{ArroyReader, ArroyWriter, Distance, Quantization, VectorStorage};
 
 pub type Embedding = Vec<f32>;
 
@@ -113,6 +122,40 @@ pub enum Embedder {
     Ollama(ollama::Embedder),
     /// An embedder based on making embedding queries against a generic JSON/REST embedding server.
     Rest(rest::Embedder),
+    /// An embedder based on running a local ONNX model, loaded from disk.
+    Onnx(onnx::Embedder),
+}
+
+/// An input sent to an embedder.
+///
+/// Only the embedders accepting images can embed the [`EmbedderInput::TextAndImage`] inputs.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EmbedderInput {
+    Text(String),
+    /// A text along with the URL or the base64 data of an image.
+    TextAndImage {
+        text: String,
+        image: String,
+    },
+}
+
+impl EmbedderInput {
+    /// The input made of this text and of this image, if any.
+    pub fn new(text: String, image: Option<String>) -> Self {
+        match image {
+            Some(image) => Self::TextAndImage { text, image },
+            None => Self::Text(text),
+        }
+    }
+
+    /// The text of this input, if it has no image.
+    pub(crate) fn into_text(self) -> Result<String, EmbedError> {
+        match self {
+            Self::Text(text) => Ok(text),
+            Self::TextAndImage { .. } => Err(EmbedError::image_not_supported()),
+        }
+    }
 }
 
 /// Configuration for an embedder.
@@ -122,7 +165,26 @@ pub struct EmbeddingConfig {
     pub embedder_options: EmbedderOptions,
     /// Document template
     pub prompt: PromptData,
-    // TODO: add metrics and anything needed
+    /// Distance used to compare the embeddings
+    #[serde(default)]
+    pub distance: Distance,
+    /// Quantization of the embeddings stored in the arroy trees
+    #[serde(default)]
+    pub quantization: Option<Quantization>,
+    /// Whether the full precision embeddings are kept to rescore the neighbors found in the quantized trees
+    #[serde(default)]
+    pub rescoring: bool,
+}
+
+impl EmbeddingConfig {
+    /// How the embeddings of this embedder are stored in arroy.
+    pub fn vector_storage(&self) -> VectorStorage {
+        VectorStorage {
+            distance: self.distance,
+            quantization: self.quantization,
+            rescoring: self.rescoring,
+        }
+    }
 }
 
 /// Map of embedder configurations.
@@ -184,6 +246,7 @@ pub enum EmbedderOptions {
     Ollama(ollama::EmbedderOptions),
     UserProvided(manual::EmbedderOptions),
     Rest(rest::EmbedderOptions),
+    Onnx(onnx::EmbedderOptions),
 }
 
 impl Default for EmbedderOptions {
@@ -219,6 +282,7 @@ impl Embedder {
                 Self::UserProvided(manual::Embedder::new(options))
             }
             EmbedderOptions::Rest(options) => Self::Rest(rest::Embedder::new(options)?),
+            EmbedderOptions::Onnx(options) => Self::Onnx(onnx::Embedder::new(options)?),
         })
     }
 
@@ -235,6 +299,7 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.embed(texts),
             Embedder::UserProvided(embedder) => embedder.embed(texts),
             Embedder::Rest(embedder) => embedder.embed(texts),
+            Embedder::Onnx(embedder) => embedder.embed(texts),
         }
     }
 
@@ -249,6 +314,51 @@ impl Embedder {
         })
     }
 
+    /// Embed one or multiple inputs, that can contain images when the embedder [accepts them](Self::accepts_images).
+    pub fn embed_inputs(
+        &self,
+        inputs: Vec<EmbedderInput>,
+    ) -> std::result::Result<Vec<Embeddings<f32>>, EmbedError> {
+        match self {
+            Embedder::Rest(embedder) => embedder.embed_inputs(inputs),
+            _ => self
+                .embed(inputs.into_iter().map(EmbedderInput::into_text).collect::<Result<_, _>>()?),
+        }
+    }
+
+    pub fn embed_one_input(
+        &self,
+        input: EmbedderInput,
+    ) -> std::result::Result<Embedding, EmbedError> {
+        let mut embeddings = self.embed_inputs(vec![input])?;
+        let embeddings = embeddings.pop().ok_or_else(EmbedError::missing_embedding)?;
+        // an input is embedded as a single embedding when it is not a long text
+        embeddings.iter().next().map(<[f32]>::to_vec).ok_or_else(EmbedError::missing_embedding)
+    }
+
+    /// Embed multiple chunks of inputs, that can contain images when the embedder [accepts them](Self::accepts_images).
+    pub fn embed_input_chunks(
+        &self,
+        input_chunks: Vec<Vec<EmbedderInput>>,
+        threads: &ThreadPoolNoAbort,
+    ) -> std::result::Result<Vec<Vec<Embeddings<f32>>>, EmbedError> {
+        match self {
+            Embedder::Rest(embedder) => embedder.embed_input_chunks(input_chunks, threads),
+            _ => {
+                let text_chunks = input_chunks
+                    .into_iter()
+                    .map(|chunk| {
+                        chunk
+                            .into_iter()
+                            .map(EmbedderInput::into_text)
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                    .collect::<Result<_, _>>()?;
+                self.embed_chunks(text_chunks, threads)
+            }
+        }
+    }
+
     /// Embed multiple chunks of texts.
     ///
     /// Each chunk is composed of one or multiple texts.
@@ -263,6 +373,7 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.embed_chunks(text_chunks, threads),
             Embedder::UserProvided(embedder) => embedder.embed_chunks(text_chunks),
             Embedder::Rest(embedder) => embedder.embed_chunks(text_chunks, threads),
+            Embedder::Onnx(embedder) => embedder.embed_chunks(text_chunks),
         }
     }
 
@@ -274,6 +385,7 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.chunk_count_hint(),
             Embedder::UserProvided(_) => 1,
             Embedder::Rest(embedder) => embedder.chunk_count_hint(),
+            Embedder::Onnx(embedder) => embedder.chunk_count_hint(),
         }
     }
 
@@ -285,6 +397,7 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.prompt_count_in_chunk_hint(),
             Embedder::UserProvided(_) => 1,
             Embedder::Rest(embedder) => embedder.prompt_count_in_chunk_hint(),
+            Embedder::Onnx(embedder) => embedder.prompt_count_in_chunk_hint(),
         }
     }
 
@@ -296,6 +409,33 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.dimensions(),
             Embedder::UserProvided(embedder) => embedder.dimensions(),
             Embedder::Rest(embedder) => embedder.dimensions(),
+            Embedder::Onnx(embedder) => embedder.dimensions(),
+        }
+    }
+
+    /// The field of the documents containing an image embedded along with their rendered template.
+    ///
+    /// Only multimodal REST embedders read images from the documents.
+    pub fn image_field(&self) -> Option<&str> {
+        match self {
+            Embedder::Rest(embedder) => embedder.image_field(),
+            Embedder::HuggingFace(_)
+            | Embedder::OpenAi(_)
+            | Embedder::Ollama(_)
+            | Embedder::UserProvided(_)
+            | Embedder::Onnx(_) => None,
+        }
+    }
+
+    /// Whether the embedder can embed the [`EmbedderInput::TextAndImage`] inputs.
+    pub fn accepts_images(&self) -> bool {
+        match self {
+            Embedder::Rest(embedder) => embedder.accepts_images(),
+            Embedder::HuggingFace(_)
+            | Embedder::OpenAi(_)
+            | Embedder::Ollama(_)
+            | Embedder::UserProvided(_)
+            | Embedder::Onnx(_) => false,
         }
     }
 
@@ -307,6 +447,7 @@ impl Embedder {
             Embedder::Ollama(embedder) => embedder.distribution(),
             Embedder::UserProvided(embedder) => embedder.distribution(),
             Embedder::Rest(embedder) => embedder.distribution(),
+            Embedder::Onnx(embedder) => embedder.distribution(),
         }
     }
 }
@@ -40,6 +40,7 @@ impl Embedder {
             path_to_embeddings: Default::default(),
             embedding_object: vec!["embedding".to_owned()],
             input_type: super::rest::InputType::Text,
+            image_field: None,
         }) {
             Ok(embedder) => embedder,
             Err(NewEmbedderError {
@@ -0,0 +1,316 @@
+pub use super::error::{EmbedError, Error, NewEmbedderError};
+use super::DistributionShift;
+#[cfg(not(feature = "onnx"))]
+use super::Embeddings;
+#[cfg(feature = "onnx")]
+pub use runtime::Embedder;
+
+/// How the embeddings of the tokens of a text are combined into the embedding of the text.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+    deserr::Deserr,
+)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub enum Pooling {
+    /// The mean of the embeddings of the tokens, ignoring padding.
+    #[default]
+    Mean,
+    /// The embedding of the first token of the text, the `[CLS]` token of BERT-like models.
+    Cls,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct EmbedderOptions {
+    /// Path to a local directory containing a `model.onnx` and a `tokenizer.json` file.
+    pub path: String,
+    pub pooling: Pooling,
+    /// Whether the embeddings are normalized to unit length.
+    pub normalize: bool,
+    pub distribution: Option<DistributionShift>,
+}
+
+#[cfg(feature = "onnx")]
+mod runtime {
+    use std::path::Path;
+
+    use ndarray::{s, Array2, ArrayView1, ArrayView2, Axis, Ix3};
+    use ort::{GraphOptimizationLevel, Session};
+    use tokenizers::{PaddingParams, Tokenizer};
+
+    use super::{EmbedError, EmbedderOptions, NewEmbedderError, Pooling};
+    use crate::vector::{DistributionShift, Embeddings};
+
+    /// Maximum number of tokens of a text passed to the model, longer texts are truncated.
+    const MAX_TOKENS: usize = 512;
+
+    /// Perform embedding of documents and queries with a local ONNX model, on the CPU
+    pub struct Embedder {
+        session: Session,
+        tokenizer: Tokenizer,
+        /// Whether the model expects a `token_type_ids` input, not all BERT-like models do.
+        needs_token_type_ids: bool,
+        options: EmbedderOptions,
+        dimensions: usize,
+    }
+
+    impl std::fmt::Debug for Embedder {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Embedder")
+                .field("path", &self.options.path)
+                .field("tokenizer", &self.tokenizer)
+                .field("options", &self.options)
+                .finish()
+        }
+    }
+
+    impl Embedder {
+        pub fn new(options: EmbedderOptions) -> std::result::Result<Self, NewEmbedderError> {
+            let directory = Path::new(&options.path);
+            let model_filename = directory.join("model.onnx");
+            let tokenizer_filename = directory.join("tokenizer.json");
+
+            let mut tokenizer = Tokenizer::from_file(&tokenizer_filename)
+                .map_err(|inner| NewEmbedderError::open_tokenizer(tokenizer_filename, inner))?;
+
+            if let Some(pp) = tokenizer.get_padding_mut() {
+                pp.strategy = tokenizers::PaddingStrategy::BatchLongest
+            } else {
+                let pp = PaddingParams {
+                    strategy: tokenizers::PaddingStrategy::BatchLongest,
+                    ..Default::default()
+                };
+                tokenizer.with_padding(Some(pp));
+            }
+
+            let threads = std::thread::available_parallelism().map(|x| x.get()).unwrap_or(8);
+            let session = Session::builder()
+                .and_then(|builder| builder.with_optimization_level(GraphOptimizationLevel::Level3))
+                .and_then(|builder| builder.with_intra_threads(threads))
+                .and_then(|builder| builder.commit_from_file(&model_filename))
+                .map_err(|inner| NewEmbedderError::onnx_load_model(model_filename, inner))?;
+
+            let needs_token_type_ids =
+                session.inputs.iter().any(|input| input.name == "token_type_ids");
+
+            let mut this =
+                Self { session, tokenizer, needs_token_type_ids, options, dimensions: 0 };
+
+            let embeddings = this
+                .embed(vec!["test".into()])
+                .map_err(NewEmbedderError::could_not_determine_dimension)?;
+            this.dimensions = embeddings.first().unwrap().dimension();
+
+            Ok(this)
+        }
+
+        pub fn embed(
+            &self,
+            texts: Vec<String>,
+        ) -> std::result::Result<Vec<Embeddings<f32>>, EmbedError> {
+            let encodings =
+                self.tokenizer.encode_batch(texts, true).map_err(EmbedError::tokenize)?;
+
+            let n_texts = encodings.len();
+            let n_tokens =
+                encodings.iter().map(|encoding| encoding.len()).max().unwrap_or(0).min(MAX_TOKENS);
+
+            let mut input_ids = Array2::<i64>::zeros((n_texts, n_tokens));
+            let mut attention_mask = Array2::<i64>::zeros((n_texts, n_tokens));
+            let mut token_type_ids = Array2::<i64>::zeros((n_texts, n_tokens));
+            for (i, encoding) in encodings.iter().enumerate() {
+                let ids = encoding.get_ids().iter();
+                let mask = encoding.get_attention_mask().iter();
+                let type_ids = encoding.get_type_ids().iter();
+                for (j, ((id, mask), type_id)) in
+                    ids.zip(mask).zip(type_ids).take(n_tokens).enumerate()
+                {
+                    input_ids[[i, j]] = *id as i64;
+                    attention_mask[[i, j]] = *mask as i64;
+                    token_type_ids[[i, j]] = *type_id as i64;
+                }
+            }
+
+            let inputs = if self.needs_token_type_ids {
+                ort::inputs![
+                    "input_ids" => input_ids,
+                    "attention_mask" => attention_mask.clone(),
+                    "token_type_ids" => token_type_ids,
+                ]
+            } else {
+                ort::inputs![
+                    "input_ids" => input_ids,
+                    "attention_mask" => attention_mask.clone(),
+                ]
+            }
+            .map_err(EmbedError::onnx_run)?;
+
+            let outputs = self.session.run(inputs).map_err(EmbedError::onnx_run)?;
+            // the first output of the model is the embedding of each token, `[n_texts, n_tokens, hidden_size]`
+            let output_name = self.session.outputs[0].name.as_str();
+            let hidden_states =
+                outputs[output_name].try_extract_tensor::<f32>().map_err(EmbedError::onnx_run)?;
+            let hidden_states = hidden_states
+                .into_dimensionality::<Ix3>()
+                .map_err(EmbedError::onnx_output_shape)?;
+
+            let embeddings = hidden_states
+                .axis_iter(Axis(0))
+                .zip(attention_mask.axis_iter(Axis(0)))
+                .map(|(token_embeddings, mask)| {
+                    let embedding =
+                        pool(token_embeddings, mask, self.options.pooling, self.options.normalize);
+                    Embeddings::from_single_embedding(embedding)
+                })
+                .collect();
+
+            Ok(embeddings)
+        }
+
+        pub fn embed_chunks(
+            &self,
+            text_chunks: Vec<Vec<String>>,
+        ) -> std::result::Result<Vec<Vec<Embeddings<f32>>>, EmbedError> {
+            text_chunks.into_iter().map(|prompts| self.embed(prompts)).collect()
+        }
+
+        pub fn chunk_count_hint(&self) -> usize {
+            1
+        }
+
+        pub fn prompt_count_in_chunk_hint(&self) -> usize {
+            std::thread::available_parallelism().map(|x| x.get()).unwrap_or(8)
+        }
+
+        pub fn dimensions(&self) -> usize {
+            self.dimensions
+        }
+
+        pub fn distribution(&self) -> Option<DistributionShift> {
+            self.options.distribution
+        }
+    }
+
+    /// Combines the embeddings of the tokens of a text, `[n_tokens, hidden_size]`, into the embedding of the text.
+    ///
+    /// The `mask` tells which tokens are padding and must be ignored by the mean pooling.
+    fn pool(
+        token_embeddings: ArrayView2<f32>,
+        mask: ArrayView1<i64>,
+        pooling: Pooling,
+        normalize: bool,
+    ) -> Vec<f32> {
+        let mut embedding = match pooling {
+            Pooling::Cls => token_embeddings.slice(s![0, ..]).to_vec(),
+            Pooling::Mean => {
+                let mut sum = vec![0.0; token_embeddings.len_of(Axis(1))];
+                let mut count = 0.0;
+                for (token_embedding, mask) in token_embeddings.axis_iter(Axis(0)).zip(mask) {
+                    if *mask == 0 {
+                        continue;
+                    }
+                    count += 1.0;
+                    sum.iter_mut().zip(token_embedding).for_each(|(sum, x)| *sum += x);
+                }
+                sum.iter_mut().for_each(|x| *x /= f32::max(count, 1.0));
+                sum
+            }
+        };
+
+        if normalize {
+            let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                embedding.iter_mut().for_each(|x| *x /= norm);
+            }
+        }
+
+        embedding
+    }
+
+    #[cfg(test)]
+    mod test {
+        use ndarray::array;
+
+        use super::*;
+
+        #[test]
+        fn mean_pooling_ignores_padding() {
+            let token_embeddings = array![[1.0, 2.0], [3.0, 4.0], [100.0, 100.0]];
+            let mask = array![1, 1, 0];
+            let embedding = pool(token_embeddings.view(), mask.view(), Pooling::Mean, false);
+            assert_eq!(embedding, vec![2.0, 3.0]);
+        }
+
+        #[test]
+        fn cls_pooling_takes_the_first_token() {
+            let token_embeddings = array![[1.0, 2.0], [3.0, 4.0]];
+            let mask = array![1, 1];
+            let embedding = pool(token_embeddings.view(), mask.view(), Pooling::Cls, false);
+            assert_eq!(embedding, vec![1.0, 2.0]);
+        }
+
+        #[test]
+        fn normalized_embeddings_have_unit_length() {
+            let token_embeddings = array![[3.0, 4.0], [0.0, 0.0]];
+            let mask = array![1, 0];
+            let embedding = pool(token_embeddings.view(), mask.view(), Pooling::Mean, true);
+            assert_eq!(embedding, vec![0.6, 0.8]);
+
+            // an embedding of zeros can't be normalized and is kept as is
+            let token_embeddings = array![[0.0, 0.0]];
+            let mask = array![1];
+            let embedding = pool(token_embeddings.view(), mask.view(), Pooling::Mean, true);
+            assert_eq!(embedding, vec![0.0, 0.0]);
+        }
+    }
+}
+
+/// The `onnx` source is not available when Meilisearch is compiled without the `onnx` feature.
+#[cfg(not(feature = "onnx"))]
+#[derive(Debug)]
+pub enum Embedder {}
+
+#[cfg(not(feature = "onnx"))]
+impl Embedder {
+    pub fn new(_options: EmbedderOptions) -> std::result::Result<Self, NewEmbedderError> {
+        Err(NewEmbedderError::onnx_disabled())
+    }
+
+    pub fn embed(
+        &self,
+        _texts: Vec<String>,
+    ) -> std::result::Result<Vec<Embeddings<f32>>, EmbedError> {
+        match *self {}
+    }
+
+    pub fn embed_chunks(
+        &self,
+        _text_chunks: Vec<Vec<String>>,
+    ) -> std::result::Result<Vec<Vec<Embeddings<f32>>>, EmbedError> {
+        match *self {}
+    }
+
+    pub fn chunk_count_hint(&self) -> usize {
+        match *self {}
+    }
+
+    pub fn prompt_count_in_chunk_hint(&self) -> usize {
+        match *self {}
+    }
+
+    pub fn dimensions(&self) -> usize {
+        match *self {}
+    }
+
+    pub fn distribution(&self) -> Option<DistributionShift> {
+        match *self {}
+    }
+}
@@ -183,6 +183,7 @@ impl Embedder {
             query: options.query(),
             input_field: vec!["input".to_owned()],
             input_type: crate::vector::rest::InputType::TextArray,
+            image_field: None,
             path_to_embeddings: vec!["data".to_owned()],
             embedding_object: vec!["embedding".to_owned()],
         })?;
@@ -0,0 +1,203 @@
+//! In-memory cache of the embeddings of the search queries.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::error::EmbedError;
+use super::{Embedder, Embedding};
+
+/// The embeddings of the last search queries embedded by one embedder.
+///
+/// Cloning this structure gives another handle on the same cache.
+/// A cache with a capacity of `0` keeps no embedding but still records the time spent embedding the queries.
+#[derive(Clone)]
+pub struct QueryEmbeddingCache {
+    entries: Arc<Mutex<LruEntries>>,
+    stats: Arc<QueryEmbeddingStats>,
+}
+
+impl QueryEmbeddingCache {
+    pub fn new(capacity: usize, stats: Arc<QueryEmbeddingStats>) -> Self {
+        let entries = LruEntries { capacity, ..Default::default() };
+        Self { entries: Arc::new(Mutex::new(entries)), stats }
+    }
+
+    /// Whether both handles are on the same cache.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.entries, &other.entries)
+    }
+
+    /// Returns the embedding of this query, from the cache or else computed by the embedder.
+    pub fn embed(&self, embedder: &Embedder, query: &str) -> Result<Embedding, EmbedError> {
+        if let Some(embedding) = self.get(query) {
+            return Ok(embedding);
+        }
+
+        let start = Instant::now();
+        let embedding = embedder.embed_one(query.to_owned());
+        self.stats.record_request(start.elapsed());
+        let embedding = embedding?;
+
+        self.insert(query.to_owned(), embedding.clone());
+        Ok(embedding)
+    }
+
+    /// Returns the embeddings of several queries, sending the ones that are not in the cache
+    /// to the embedder in as few requests as it accepts.
+    pub fn embed_many(
+        &self,
+        embedder: &Embedder,
+        queries: &[&str],
+    ) -> Result<Vec<Embedding>, EmbedError> {
+        let mut embeddings: Vec<_> = queries.iter().map(|query| self.get(query)).collect();
+        let missing: Vec<usize> = (0..queries.len()).filter(|&i| embeddings[i].is_none()).collect();
+
+        for chunk in missing.chunks(embedder.prompt_count_in_chunk_hint().max(1)) {
+            let texts = chunk.iter().map(|&i| queries[i].to_owned()).collect();
+            let start = Instant::now();
+            let computed = embedder.embed(texts);
+            self.stats.record_request(start.elapsed());
+
+            for (&i, computed) in chunk.iter().zip(computed?) {
+                // long queries can be embedded in several vectors, only the first one is used
+                let Some(embedding) = computed.iter().next().map(<[f32]>::to_vec) else { continue };
+                self.insert(queries[i].to_owned(), embedding.clone());
+                embeddings[i] = Some(embedding);
+            }
+        }
+
+        embeddings
+            .into_iter()
+            .map(|embedding| embedding.ok_or_else(EmbedError::missing_embedding))
+            .collect()
+    }
+
+    /// Returns the embedding of this query, if it is in the cache.
+    pub fn get(&self, query: &str) -> Option<Embedding> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.capacity == 0 {
+            return None;
+        }
+        let embedding = entries.get(query);
+        self.stats.record_lookup(embedding.is_some());
+        embedding
+    }
+
+    /// Puts the embedding of this query in the cache, evicting the least recently used query if the cache is full.
+    pub fn insert(&self, query: String, embedding: Embedding) {
+        self.entries.lock().unwrap().insert(query, embedding);
+    }
+}
+
+/// How the embeddings of the search queries were obtained, for all the embedders.
+#[derive(Debug, Default)]
+pub struct QueryEmbeddingStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    requests: AtomicU64,
+    request_micros: AtomicU64,
+}
+
+impl QueryEmbeddingStats {
+    /// The number of queries found in a cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// The number of queries looked up in a cache but not found.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// The number of requests sent to the embedders to embed queries.
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// The total time spent waiting for the embedders to embed queries.
+    pub fn requests_duration(&self) -> Duration {
+        Duration::from_micros(self.request_micros.load(Ordering::Relaxed))
+    }
+
+    fn record_lookup(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_request(&self, duration: Duration) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.request_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+/// A least recently used map from the queries to their embeddings.
+#[derive(Default)]
+struct LruEntries {
+    capacity: usize,
+    /// query -> (last use, embedding)
+    entries: HashMap<String, (u64, Embedding)>,
+    /// last use -> query, ordered from the least recently used query.
+    uses: BTreeMap<u64, String>,
+    generation: u64,
+}
+
+impl LruEntries {
+    fn get(&mut self, query: &str) -> Option<Embedding> {
+        let (last_use, embedding) = self.entries.get_mut(query)?;
+        self.generation += 1;
+        let query = self.uses.remove(last_use).unwrap();
+        self.uses.insert(self.generation, query);
+        *last_use = self.generation;
+        Some(embedding.clone())
+    }
+
+    fn insert(&mut self, query: String, embedding: Embedding) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.generation += 1;
+        if let Some((last_use, _)) = self.entries.remove(&query) {
+            self.uses.remove(&last_use);
+        } else if self.entries.len() >= self.capacity {
+            if let Some((_, evicted)) = self.uses.pop_first() {
+                self.entries.remove(&evicted);
+            }
+        }
+        self.uses.insert(self.generation, query.clone());
+        self.entries.insert(query, (self.generation, embedding));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_query_is_evicted() {
+        let stats = Arc::new(QueryEmbeddingStats::default());
+        let cache = QueryEmbeddingCache::new(2, stats.clone());
+        cache.insert("shoes".into(), vec![1.0]);
+        cache.insert("socks".into(), vec![2.0]);
+
+        // "shoes" becomes the most recently used query
+        assert_eq!(cache.get("shoes"), Some(vec![1.0]));
+        cache.insert("boots".into(), vec![3.0]);
+
+        assert_eq!(cache.get("socks"), None);
+        assert_eq!(cache.get("shoes"), Some(vec![1.0]));
+        assert_eq!(cache.get("boots"), Some(vec![3.0]));
+        assert_eq!((stats.hits(), stats.misses()), (3, 1));
+    }
+
+    #[test]
+    fn empty_cache_keeps_nothing() {
+        let stats = Arc::new(QueryEmbeddingStats::default());
+        let cache = QueryEmbeddingCache::new(0, stats.clone());
+        cache.insert("shoes".into(), vec![1.0]);
+
+        assert_eq!(cache.get("shoes"), None);
+        assert_eq!((stats.hits(), stats.misses()), (0, 0));
+    }
+}
@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use super::error::EmbedErrorKind;
 use super::{
-    DistributionShift, EmbedError, Embedding, Embeddings, NewEmbedderError, REQUEST_PARALLELISM,
+    DistributionShift, EmbedError, EmbedderInput, Embedding, Embeddings, NewEmbedderError,
+    REQUEST_PARALLELISM,
 };
 use crate::error::FaultSource;
 use crate::ThreadPoolNoAbort;
@@ -81,6 +82,9 @@ pub struct EmbedderOptions {
     // shape of a single embedding
     pub embedding_object: Vec<String>,
     pub input_type: InputType,
+    // field of the documents containing the URL or base64 data of an image, sent with the `textAndImage` input type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_field: Option<String>,
 }
 
 impl Default for EmbedderOptions {
@@ -92,6 +96,7 @@ impl Default for EmbedderOptions {
             path_to_embeddings: vec!["data".into()],
             embedding_object: vec!["embedding".into()],
             input_type: InputType::Text,
+            image_field: None,
             api_key: None,
             distribution: None,
             dimensions: None,
@@ -112,6 +117,7 @@ impl std::hash::Hash for EmbedderOptions {
         self.path_to_embeddings.hash(state);
         self.embedding_object.hash(state);
         self.input_type.hash(state);
+        self.image_field.hash(state);
     }
 }
 
@@ -121,6 +127,34 @@ impl std::hash::Hash for EmbedderOptions {
 pub enum InputType {
     Text,
     TextArray,
+    /// A single `{ "text": .., "image": .. }` object, for multimodal models.
+    ///
+    /// The image is only sent along with the inputs that have one, see [`EmbedderInput`].
+    TextAndImage,
+}
+
+/// The input sent to an embedder with the [`InputType::TextAndImage`] input type.
+#[derive(Debug, Serialize)]
+struct MultimodalInput<'a> {
+    text: &'a str,
+    /// The URL or the base64 data of an image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<&'a str>,
+}
+
+impl<'a> MultimodalInput<'a> {
+    fn text(text: &'a str) -> Self {
+        Self { text, image: None }
+    }
+
+    fn from_input(input: &'a EmbedderInput) -> Self {
+        match input {
+            EmbedderInput::Text(text) => Self::text(text),
+            EmbedderInput::TextAndImage { text, image } => {
+                Self { text: text.as_str(), image: Some(image.as_str()) }
+            }
+        }
+    }
 }
 
 impl Embedder {
@@ -142,14 +176,57 @@ impl Embedder {
     }
 
     pub fn embed(&self, texts: Vec<String>) -> Result<Vec<Embeddings<f32>>, EmbedError> {
-        embed(&self.client, &self.options, self.bearer.as_deref(), texts.as_slice(), texts.len())
+        self.embed_ref(&texts)
     }
 
     pub fn embed_ref<S>(&self, texts: &[S]) -> Result<Vec<Embeddings<f32>>, EmbedError>
     where
         S: AsRef<str> + Serialize,
     {
-        embed(&self.client, &self.options, self.bearer.as_deref(), texts, texts.len())
+        if self.accepts_images() {
+            let inputs: Vec<_> =
+                texts.iter().map(|text| MultimodalInput::text(text.as_ref())).collect();
+            embed(
+                &self.client,
+                &self.options,
+                self.bearer.as_deref(),
+                inputs.as_slice(),
+                inputs.len(),
+            )
+        } else {
+            embed(&self.client, &self.options, self.bearer.as_deref(), texts, texts.len())
+        }
+    }
+
+    /// Embeds inputs that can contain images, the texts are sent without image.
+    ///
+    /// Only the embedders with the [`InputType::TextAndImage`] input type accept images.
+    pub fn embed_inputs(
+        &self,
+        inputs: Vec<EmbedderInput>,
+    ) -> Result<Vec<Embeddings<f32>>, EmbedError> {
+        if !self.accepts_images() {
+            let texts =
+                inputs.into_iter().map(EmbedderInput::into_text).collect::<Result<_, _>>()?;
+            return self.embed(texts);
+        }
+        let inputs: Vec<_> = inputs.iter().map(MultimodalInput::from_input).collect();
+        embed(&self.client, &self.options, self.bearer.as_deref(), inputs.as_slice(), inputs.len())
+    }
+
+    pub fn embed_input_chunks(
+        &self,
+        input_chunks: Vec<Vec<EmbedderInput>>,
+        threads: &ThreadPoolNoAbort,
+    ) -> Result<Vec<Vec<Embeddings<f32>>>, EmbedError> {
+        threads
+            .install(move || {
+                input_chunks.into_par_iter().map(move |chunk| self.embed_inputs(chunk)).collect()
+            })
+            .map_err(|error| EmbedError {
+                kind: EmbedErrorKind::PanicInThreadPool(error),
+                fault: FaultSource::Bug,
+            })?
     }
 
     pub fn embed_tokens(&self, tokens: &[usize]) -> Result<Embeddings<f32>, EmbedError> {
@@ -179,11 +256,24 @@ impl Embedder {
 
     pub fn prompt_count_in_chunk_hint(&self) -> usize {
         match self.options.input_type {
-            InputType::Text => 1,
+            InputType::Text | InputType::TextAndImage => 1,
             InputType::TextArray => 10,
         }
     }
 
+    /// The field of the documents containing the image to embed along with the rendered template, if any.
+    pub fn image_field(&self) -> Option<&str> {
+        match self.options.input_type {
+            InputType::TextAndImage => self.options.image_field.as_deref(),
+            InputType::Text | InputType::TextArray => None,
+        }
+    }
+
+    /// Whether the embedder accepts the [`EmbedderInput::TextAndImage`] inputs.
+    pub fn accepts_images(&self) -> bool {
+        matches!(self.options.input_type, InputType::TextAndImage)
+    }
+
     pub fn dimensions(&self) -> usize {
         self.dimensions
     }
@@ -198,8 +288,15 @@ fn infer_dimensions(
     options: &EmbedderOptions,
     bearer: Option<&str>,
 ) -> Result<usize, NewEmbedderError> {
-    let v = embed(client, options, bearer, ["test"].as_slice(), 1)
-        .map_err(NewEmbedderError::could_not_determine_dimension)?;
+    let v = match options.input_type {
+        InputType::TextAndImage => {
+            embed(client, options, bearer, [MultimodalInput::text("test")].as_slice(), 1)
+        }
+        InputType::Text | InputType::TextArray => {
+            embed(client, options, bearer, ["test"].as_slice(), 1)
+        }
+    }
+    .map_err(NewEmbedderError::could_not_determine_dimension)?;
     // unwrap: guaranteed that v.len() == 1, otherwise the previous line terminated in error
     Ok(v.first().unwrap().dimension())
 }
@@ -219,8 +316,9 @@ where
         if let Some(bearer) = bearer { request.set("Authorization", bearer) } else { request };
     let request = request.set("Content-Type", "application/json");
 
+    // the inputs of the `textAndImage` input type are `MultimodalInput` objects
     let input_value = match options.input_type {
-        InputType::Text => serde_json::json!(inputs.first()),
+        InputType::Text | InputType::TextAndImage => serde_json::json!(inputs.first()),
         InputType::TextArray => serde_json::json!(inputs),
     };
 
@@ -333,7 +431,7 @@ fn response_to_embedding(
     }
 
     let embeddings = match options.input_type {
-        InputType::Text => {
+        InputType::Text | InputType::TextAndImage => {
             for component in &options.embedding_object {
                 current_value = current_value.get(component).ok_or_else(|| {
                     EmbedError::rest_response_missing_embeddings(
@@ -1,9 +1,10 @@
 use deserr::Deserr;
 use serde::{Deserialize, Serialize};
 
+use super::onnx::Pooling;
 use super::rest::InputType;
-use super::{ollama, openai, DistributionShift};
-use crate::prompt::PromptData;
+use super::{ollama, openai, Distance, DistributionShift, Quantization};
+use crate::prompt::{Chunking, PromptData};
 use crate::update::Setting;
 use crate::vector::EmbeddingConfig;
 use crate::UserError;
@@ -50,7 +51,28 @@ pub struct EmbeddingSettings {
     pub input_type: Setting<InputType>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
+    pub image_field: Setting<String>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
     pub distribution: Setting<DistributionShift>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub distance: Setting<Distance>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub quantization: Setting<Quantization>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub rescoring: Setting<bool>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub chunking: Setting<Chunking>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub pooling: Setting<Pooling>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub normalize: Setting<bool>,
 }
 
 pub fn check_unset<T>(
@@ -103,8 +125,16 @@ impl EmbeddingSettings {
     pub const PATH_TO_EMBEDDINGS: &'static str = "pathToEmbeddings";
     pub const EMBEDDING_OBJECT: &'static str = "embeddingObject";
     pub const INPUT_TYPE: &'static str = "inputType";
+    pub const IMAGE_FIELD: &'static str = "imageField";
 
     pub const DISTRIBUTION: &'static str = "distribution";
+    pub const DISTANCE: &'static str = "distance";
+    pub const QUANTIZATION: &'static str = "quantization";
+    pub const RESCORING: &'static str = "rescoring";
+    pub const CHUNKING: &'static str = "chunking";
+
+    pub const POOLING: &'static str = "pooling";
+    pub const NORMALIZE: &'static str = "normalize";
 
     pub fn allowed_sources_for_field(field: &'static str) -> &'static [EmbedderSource] {
         match field {
@@ -114,10 +144,14 @@ impl EmbeddingSettings {
                 EmbedderSource::UserProvided,
                 EmbedderSource::Rest,
                 EmbedderSource::Ollama,
+                EmbedderSource::Onnx,
+            ],
+            Self::MODEL => &[
+                EmbedderSource::HuggingFace,
+                EmbedderSource::OpenAi,
+                EmbedderSource::Ollama,
+                EmbedderSource::Onnx,
             ],
-            Self::MODEL => {
-                &[EmbedderSource::HuggingFace, EmbedderSource::OpenAi, EmbedderSource::Ollama]
-            }
             Self::REVISION => &[EmbedderSource::HuggingFace],
             Self::API_KEY => {
                 &[EmbedderSource::OpenAi, EmbedderSource::Ollama, EmbedderSource::Rest]
@@ -125,11 +159,12 @@ impl EmbeddingSettings {
             Self::DIMENSIONS => {
                 &[EmbedderSource::OpenAi, EmbedderSource::UserProvided, EmbedderSource::Rest]
             }
-            Self::DOCUMENT_TEMPLATE => &[
+            Self::DOCUMENT_TEMPLATE | Self::CHUNKING => &[
                 EmbedderSource::HuggingFace,
                 EmbedderSource::OpenAi,
                 EmbedderSource::Ollama,
                 EmbedderSource::Rest,
+                EmbedderSource::Onnx,
             ],
             Self::URL => &[EmbedderSource::Ollama, EmbedderSource::Rest],
             Self::QUERY => &[EmbedderSource::Rest],
@@ -137,13 +172,16 @@ impl EmbeddingSettings {
             Self::PATH_TO_EMBEDDINGS => &[EmbedderSource::Rest],
             Self::EMBEDDING_OBJECT => &[EmbedderSource::Rest],
             Self::INPUT_TYPE => &[EmbedderSource::Rest],
-            Self::DISTRIBUTION => &[
+            Self::IMAGE_FIELD => &[EmbedderSource::Rest],
+            Self::DISTRIBUTION | Self::DISTANCE | Self::QUANTIZATION | Self::RESCORING => &[
                 EmbedderSource::HuggingFace,
                 EmbedderSource::Ollama,
                 EmbedderSource::OpenAi,
                 EmbedderSource::Rest,
                 EmbedderSource::UserProvided,
+                EmbedderSource::Onnx,
             ],
+            Self::POOLING | Self::NORMALIZE => &[EmbedderSource::Onnx],
             _other => unreachable!("unknown field"),
         }
     }
@@ -155,37 +193,73 @@ impl EmbeddingSettings {
                 Self::MODEL,
                 Self::API_KEY,
                 Self::DOCUMENT_TEMPLATE,
+                Self::CHUNKING,
                 Self::DIMENSIONS,
                 Self::DISTRIBUTION,
+                Self::DISTANCE,
+                Self::QUANTIZATION,
+                Self::RESCORING,
             ],
             EmbedderSource::HuggingFace => &[
                 Self::SOURCE,
                 Self::MODEL,
                 Self::REVISION,
                 Self::DOCUMENT_TEMPLATE,
+                Self::CHUNKING,
                 Self::DISTRIBUTION,
+                Self::DISTANCE,
+                Self::QUANTIZATION,
+                Self::RESCORING,
             ],
             EmbedderSource::Ollama => &[
                 Self::SOURCE,
                 Self::MODEL,
                 Self::DOCUMENT_TEMPLATE,
+                Self::CHUNKING,
                 Self::URL,
                 Self::API_KEY,
                 Self::DISTRIBUTION,
+                Self::DISTANCE,
+                Self::QUANTIZATION,
+                Self::RESCORING,
+            ],
+            EmbedderSource::UserProvided => &[
+                Self::SOURCE,
+                Self::DIMENSIONS,
+                Self::DISTRIBUTION,
+                Self::DISTANCE,
+                Self::QUANTIZATION,
+                Self::RESCORING,
             ],
-            EmbedderSource::UserProvided => &[Self::SOURCE, Self::DIMENSIONS, Self::DISTRIBUTION],
             EmbedderSource::Rest => &[
                 Self::SOURCE,
                 Self::API_KEY,
                 Self::DIMENSIONS,
                 Self::DOCUMENT_TEMPLATE,
+                Self::CHUNKING,
                 Self::URL,
                 Self::QUERY,
                 Self::INPUT_FIELD,
                 Self::PATH_TO_EMBEDDINGS,
                 Self::EMBEDDING_OBJECT,
                 Self::INPUT_TYPE,
+                Self::IMAGE_FIELD,
                 Self::DISTRIBUTION,
+                Self::DISTANCE,
+                Self::QUANTIZATION,
+                Self::RESCORING,
+            ],
+            EmbedderSource::Onnx => &[
+                Self::SOURCE,
+                Self::MODEL,
+                Self::DOCUMENT_TEMPLATE,
+                Self::CHUNKING,
+                Self::POOLING,
+                Self::NORMALIZE,
+                Self::DISTRIBUTION,
+                Self::DISTANCE,
+                Self::QUANTIZATION,
+                Self::RESCORING,
             ],
         }
     }
@@ -230,7 +304,14 @@ impl EmbeddingSettings {
                     path_to_embeddings: old_path_to_embeddings,
                     embedding_object: old_embedding_object,
                     input_type: old_input_type,
+                    image_field: old_image_field,
                     distribution: old_distribution,
+                    distance: old_distance,
+                    quantization: old_quantization,
+                    rescoring: old_rescoring,
+                    chunking: old_chunking,
+                    pooling: old_pooling,
+                    normalize: old_normalize,
                 }),
                 Setting::Set(EmbeddingSettings {
                     source: new_source,
@@ -245,7 +326,14 @@ impl EmbeddingSettings {
                     path_to_embeddings: new_path_to_embeddings,
                     embedding_object: new_embedding_object,
                     input_type: new_input_type,
+                    image_field: new_image_field,
                     distribution: new_distribution,
+                    distance: new_distance,
+                    quantization: new_quantization,
+                    rescoring: new_rescoring,
+                    chunking: new_chunking,
+                    pooling: new_pooling,
+                    normalize: new_normalize,
                 }),
             ) => {
                 let mut needs_reindex = false;
@@ -261,6 +349,15 @@ impl EmbeddingSettings {
                 needs_reindex |= old_path_to_embeddings.apply(new_path_to_embeddings);
                 needs_reindex |= old_embedding_object.apply(new_embedding_object);
                 needs_reindex |= old_input_type.apply(new_input_type);
+                needs_reindex |= old_image_field.apply(new_image_field);
+                // the arroy trees must be rebuilt with the new distance
+                needs_reindex |= old_distance.apply(new_distance);
+                // the embeddings must be quantized, and their full precision kept or dropped
+                needs_reindex |= old_quantization.apply(new_quantization);
+                needs_reindex |= old_rescoring.apply(new_rescoring);
+                needs_reindex |= old_chunking.apply(new_chunking);
+                needs_reindex |= old_pooling.apply(new_pooling);
+                needs_reindex |= old_normalize.apply(new_normalize);
 
                 old_distribution.apply(new_distribution);
                 old_api_key.apply(new_api_key);
@@ -282,6 +379,7 @@ pub enum EmbedderSource {
     Ollama,
     UserProvided,
     Rest,
+    Onnx,
 }
 
 impl std::fmt::Display for EmbedderSource {
@@ -292,6 +390,7 @@ impl std::fmt::Display for EmbedderSource {
             EmbedderSource::UserProvided => "userProvided",
             EmbedderSource::Ollama => "ollama",
             EmbedderSource::Rest => "rest",
+            EmbedderSource::Onnx => "onnx",
         };
         f.write_str(s)
     }
@@ -299,7 +398,11 @@ impl std::fmt::Display for EmbedderSource {
 
 impl From<EmbeddingConfig> for EmbeddingSettings {
     fn from(value: EmbeddingConfig) -> Self {
-        let EmbeddingConfig { embedder_options, prompt } = value;
+        let EmbeddingConfig { embedder_options, prompt, distance, quantization, rescoring } = value;
+        // the rescoring is only meaningful for the quantized embeddings.
+        let rescoring =
+            if quantization.is_some() { Setting::Set(rescoring) } else { Setting::NotSet };
+        let quantization = quantization.map(Setting::Set).unwrap_or_default();
         match embedder_options {
             super::EmbedderOptions::HuggingFace(super::hf::EmbedderOptions {
                 model,
@@ -318,7 +421,14 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings: Setting::NotSet,
                 embedding_object: Setting::NotSet,
                 input_type: Setting::NotSet,
+                image_field: Setting::NotSet,
                 distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                distance: Setting::Set(distance),
+                quantization,
+                rescoring,
+                chunking: prompt.chunking.map(Setting::Set).unwrap_or_default(),
+                pooling: Setting::NotSet,
+                normalize: Setting::NotSet,
             },
             super::EmbedderOptions::OpenAi(super::openai::EmbedderOptions {
                 api_key,
@@ -338,7 +448,14 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings: Setting::NotSet,
                 embedding_object: Setting::NotSet,
                 input_type: Setting::NotSet,
+                image_field: Setting::NotSet,
                 distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                distance: Setting::Set(distance),
+                quantization,
+                rescoring,
+                chunking: prompt.chunking.map(Setting::Set).unwrap_or_default(),
+                pooling: Setting::NotSet,
+                normalize: Setting::NotSet,
             },
             super::EmbedderOptions::Ollama(super::ollama::EmbedderOptions {
                 embedding_model,
@@ -358,7 +475,14 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings: Setting::NotSet,
                 embedding_object: Setting::NotSet,
                 input_type: Setting::NotSet,
+                image_field: Setting::NotSet,
                 distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                distance: Setting::Set(distance),
+                quantization,
+                rescoring,
+                chunking: prompt.chunking.map(Setting::Set).unwrap_or_default(),
+                pooling: Setting::NotSet,
+                normalize: Setting::NotSet,
             },
             super::EmbedderOptions::UserProvided(super::manual::EmbedderOptions {
                 dimensions,
@@ -376,7 +500,14 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings: Setting::NotSet,
                 embedding_object: Setting::NotSet,
                 input_type: Setting::NotSet,
+                image_field: Setting::NotSet,
                 distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                distance: Setting::Set(distance),
+                quantization,
+                rescoring,
+                chunking: Setting::NotSet,
+                pooling: Setting::NotSet,
+                normalize: Setting::NotSet,
             },
             super::EmbedderOptions::Rest(super::rest::EmbedderOptions {
                 api_key,
@@ -387,6 +518,7 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings,
                 embedding_object,
                 input_type,
+                image_field,
                 distribution,
             }) => Self {
                 source: Setting::Set(EmbedderSource::Rest),
@@ -401,7 +533,41 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 path_to_embeddings: Setting::Set(path_to_embeddings),
                 embedding_object: Setting::Set(embedding_object),
                 input_type: Setting::Set(input_type),
+                image_field: image_field.map(Setting::Set).unwrap_or_default(),
+                distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                distance: Setting::Set(distance),
+                quantization,
+                rescoring,
+                chunking: prompt.chunking.map(Setting::Set).unwrap_or_default(),
+                pooling: Setting::NotSet,
+                normalize: Setting::NotSet,
+            },
+            super::EmbedderOptions::Onnx(super::onnx::EmbedderOptions {
+                path,
+                pooling,
+                normalize,
+                distribution,
+            }) => Self {
+                source: Setting::Set(EmbedderSource::Onnx),
+                model: Setting::Set(path),
+                revision: Setting::NotSet,
+                api_key: Setting::NotSet,
+                dimensions: Setting::NotSet,
+                document_template: Setting::Set(prompt.template),
+                url: Setting::NotSet,
+                query: Setting::NotSet,
+                input_field: Setting::NotSet,
+                path_to_embeddings: Setting::NotSet,
+                embedding_object: Setting::NotSet,
+                input_type: Setting::NotSet,
+                image_field: Setting::NotSet,
                 distribution: distribution.map(Setting::Set).unwrap_or_default(),
+                distance: Setting::Set(distance),
+                quantization,
+                rescoring,
+                chunking: prompt.chunking.map(Setting::Set).unwrap_or_default(),
+                pooling: Setting::Set(pooling),
+                normalize: Setting::Set(normalize),
             },
         }
     }
@@ -423,7 +589,14 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
             path_to_embeddings,
             embedding_object,
             input_type,
+            image_field,
             distribution,
+            distance,
+            quantization,
+            rescoring,
+            chunking,
+            pooling,
+            normalize,
         } = value;
 
         if let Some(source) = source.set() {
@@ -498,6 +671,16 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
                                 .set()
                                 .unwrap_or(embedder_options.embedding_object),
                             input_type: input_type.set().unwrap_or(embedder_options.input_type),
+                            image_field: image_field.set(),
+                            distribution: distribution.set(),
+                        })
+                }
+                EmbedderSource::Onnx => {
+                    this.embedder_options =
+                        super::EmbedderOptions::Onnx(super::onnx::EmbedderOptions {
+                            path: model.set().unwrap(),
+                            pooling: pooling.set().unwrap_or_default(),
+                            normalize: normalize.set().unwrap_or_default(),
                             distribution: distribution.set(),
                         })
                 }
@@ -505,8 +688,15 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
         }
 
         if let Setting::Set(template) = document_template {
-            this.prompt = PromptData { template }
+            this.prompt = PromptData { template, chunking: None }
+        }
+        this.prompt.chunking = chunking.set();
+
+        if let Some(distance) = distance.set() {
+            this.distance = distance;
         }
+        this.quantization = quantization.set();
+        this.rescoring = rescoring.set().unwrap_or_default();
 
         this
     }
@@ -0,0 +1,333 @@
+use arroy::distances::{
+    Angular, BinaryQuantizedAngular, BinaryQuantizedEuclidean, BinaryQuantizedManhattan,
+    DotProduct, Euclidean, Manhattan,
+};
+use arroy::ItemId;
+use bytemuck::allocation::pod_collect_to_vec;
+use deserr::Deserr;
+use heed::types::Bytes;
+use heed::{RoTxn, RwTxn, Unspecified};
+use rand::{Rng, SeedableRng};
+use roaring::RoaringBitmap;
+use serde::{Deserialize, Serialize};
+
+/// The distance used to compare the embeddings of an embedder.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub enum Distance {
+    /// The cosine of the angle between the embeddings, insensitive to their norm.
+    #[default]
+    Cosine,
+    /// The dot product of the embeddings.
+    Dot,
+    /// The euclidean (L2) distance between the embeddings.
+    Euclidean,
+    /// The manhattan (L1) distance between the embeddings.
+    Manhattan,
+}
+
+impl Distance {
+    /// Converts a distance returned by arroy to a similarity score, the higher the more similar.
+    ///
+    /// The similarity is in `[-1, 1]` for the cosine distance, and in `]0, 1]` for the other distances.
+    pub fn similarity(&self, distance: f32) -> f32 {
+        match self {
+            // arroy returns `1 - cos`
+            Distance::Cosine => 1.0 - distance,
+            // arroy returns the opposite of the dot product, which is unbounded.
+            // We squash it in ]0, 1[ with a sigmoid.
+            Distance::Dot => 1.0 / (1.0 + distance.exp()),
+            Distance::Euclidean | Distance::Manhattan => 1.0 / (1.0 + distance),
+        }
+    }
+}
+
+impl Distance {
+    /// Computes the distance between two embeddings the way arroy does,
+    /// to rescore the neighbors found in quantized trees.
+    pub fn distance(&self, left: &[f32], right: &[f32]) -> f32 {
+        let dot = || left.iter().zip(right).map(|(l, r)| l * r).sum::<f32>();
+        match self {
+            Distance::Cosine => {
+                let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+                let norms = norm(left) * norm(right);
+                if norms == 0.0 {
+                    1.0
+                } else {
+                    1.0 - dot() / norms
+                }
+            }
+            Distance::Dot => -dot(),
+            Distance::Euclidean => {
+                left.iter().zip(right).map(|(l, r)| (l - r) * (l - r)).sum::<f32>().sqrt()
+            }
+            Distance::Manhattan => left.iter().zip(right).map(|(l, r)| (l - r).abs()).sum(),
+        }
+    }
+}
+
+/// The quantization of the embeddings stored in the arroy trees.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub enum Quantization {
+    /// Each dimension is stored as a single bit, its sign.
+    ///
+    /// The trees are 32 times smaller but the distances between the quantized embeddings are approximate.
+    /// It isn't available for the dot product.
+    Binary,
+}
+
+impl std::fmt::Display for Quantization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Quantization::Binary => f.write_str("binary"),
+        }
+    }
+}
+
+/// How the embeddings of an embedder are stored and compared.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VectorStorage {
+    pub distance: Distance,
+    pub quantization: Option<Quantization>,
+    /// Whether the full precision embeddings are kept next to the quantized trees
+    /// to rescore the nearest neighbors.
+    pub rescoring: bool,
+}
+
+impl VectorStorage {
+    /// Whether the full precision embeddings are stored, only when the trees are quantized.
+    pub fn keeps_full_precision(&self) -> bool {
+        self.quantization.is_some() && self.rescoring
+    }
+}
+
+impl std::fmt::Display for Distance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Distance::Cosine => "cosine",
+            Distance::Dot => "dot",
+            Distance::Euclidean => "euclidean",
+            Distance::Manhattan => "manhattan",
+        };
+        f.write_str(s)
+    }
+}
+
+macro_rules! dispatch {
+    ($kind:ident, $this:expr, $inner:ident => $expr:expr) => {
+        match $this {
+            $kind::Cosine($inner) => $expr,
+            $kind::Dot($inner) => $expr,
+            $kind::Euclidean($inner) => $expr,
+            $kind::Manhattan($inner) => $expr,
+            $kind::BinaryQuantizedCosine($inner) => $expr,
+            $kind::BinaryQuantizedEuclidean($inner) => $expr,
+            $kind::BinaryQuantizedManhattan($inner) => $expr,
+        }
+    };
+}
+
+/// Opens the arroy writer or reader of the distance and quantization of an embedder.
+macro_rules! open_with_storage {
+    ($kind:ident, $storage:expr, $open:expr) => {
+        match ($storage.distance, $storage.quantization) {
+            (Distance::Cosine, None) => $kind::Cosine($open),
+            (Distance::Dot, None) => $kind::Dot($open),
+            (Distance::Euclidean, None) => $kind::Euclidean($open),
+            (Distance::Manhattan, None) => $kind::Manhattan($open),
+            (Distance::Cosine, Some(Quantization::Binary)) => $kind::BinaryQuantizedCosine($open),
+            (Distance::Euclidean, Some(Quantization::Binary)) => {
+                $kind::BinaryQuantizedEuclidean($open)
+            }
+            (Distance::Manhattan, Some(Quantization::Binary)) => {
+                $kind::BinaryQuantizedManhattan($open)
+            }
+            (Distance::Dot, Some(Quantization::Binary)) => {
+                unreachable!("the dot product can't be quantized, it is rejected by the settings")
+            }
+        }
+    };
+}
+
+/// The full precision embeddings of an arroy index, kept when its trees are quantized.
+///
+/// The keys are the arroy index followed by the document id, both in big endian.
+#[derive(Clone, Copy)]
+struct FullPrecisionEmbeddings {
+    database: heed::Database<Bytes, Bytes>,
+    index: u16,
+}
+
+impl FullPrecisionEmbeddings {
+    fn key(&self, item: ItemId) -> [u8; 6] {
+        let mut key = [0; 6];
+        key[..2].copy_from_slice(&self.index.to_be_bytes());
+        key[2..].copy_from_slice(&item.to_be_bytes());
+        key
+    }
+
+    fn get(&self, rtxn: &RoTxn, item: ItemId) -> heed::Result<Option<Vec<f32>>> {
+        Ok(self.database.get(rtxn, &self.key(item))?.map(pod_collect_to_vec))
+    }
+
+    fn put(&self, wtxn: &mut RwTxn, item: ItemId, vector: &[f32]) -> heed::Result<()> {
+        self.database.put(wtxn, &self.key(item), bytemuck::cast_slice(vector))
+    }
+
+    fn delete(&self, wtxn: &mut RwTxn, item: ItemId) -> heed::Result<bool> {
+        self.database.delete(wtxn, &self.key(item))
+    }
+}
+
+enum DistanceWriter {
+    Cosine(arroy::Writer<Angular>),
+    Dot(arroy::Writer<DotProduct>),
+    Euclidean(arroy::Writer<Euclidean>),
+    Manhattan(arroy::Writer<Manhattan>),
+    BinaryQuantizedCosine(arroy::Writer<BinaryQuantizedAngular>),
+    BinaryQuantizedEuclidean(arroy::Writer<BinaryQuantizedEuclidean>),
+    BinaryQuantizedManhattan(arroy::Writer<BinaryQuantizedManhattan>),
+}
+
+/// An arroy writer using the distance and the quantization of its embedder.
+///
+/// When the embedder rescores its neighbors, the full precision embeddings are written next to the trees.
+pub struct ArroyWriter {
+    writer: DistanceWriter,
+    full_precision: Option<FullPrecisionEmbeddings>,
+}
+
+impl ArroyWriter {
+    pub fn new(
+        database: arroy::Database<Unspecified>,
+        full_precision_database: heed::Database<Bytes, Bytes>,
+        index: u16,
+        dimensions: usize,
+        storage: VectorStorage,
+    ) -> arroy::Result<Self> {
+        let writer = open_with_storage!(
+            DistanceWriter,
+            storage,
+            arroy::Writer::new(database.remap_data_type(), index, dimensions)?
+        );
+        let full_precision = storage
+            .keeps_full_precision()
+            .then_some(FullPrecisionEmbeddings { database: full_precision_database, index });
+        Ok(Self { writer, full_precision })
+    }
+
+    pub fn is_empty(&self, rtxn: &RoTxn) -> arroy::Result<bool> {
+        dispatch!(DistanceWriter, &self.writer, writer => writer.is_empty(rtxn))
+    }
+
+    /// Returns the embedding of an item, with its full precision if it is kept.
+    pub fn item_vector(&self, rtxn: &RoTxn, item: ItemId) -> arroy::Result<Option<Vec<f32>>> {
+        if let Some(full_precision) = &self.full_precision {
+            return Ok(full_precision.get(rtxn, item)?);
+        }
+        dispatch!(DistanceWriter, &self.writer, writer => writer.item_vector(rtxn, item))
+    }
+
+    pub fn contains_item(&self, rtxn: &RoTxn, item: ItemId) -> arroy::Result<bool> {
+        dispatch!(DistanceWriter, &self.writer, writer => writer.contains_item(rtxn, item))
+    }
+
+    pub fn add_item(&self, wtxn: &mut RwTxn, item: ItemId, vector: &[f32]) -> arroy::Result<()> {
+        if let Some(full_precision) = &self.full_precision {
+            full_precision.put(wtxn, item, vector)?;
+        }
+        dispatch!(DistanceWriter, &self.writer, writer => writer.add_item(wtxn, item, vector))
+    }
+
+    pub fn del_item(&self, wtxn: &mut RwTxn, item: ItemId) -> arroy::Result<bool> {
+        if let Some(full_precision) = &self.full_precision {
+            full_precision.delete(wtxn, item)?;
+        }
+        dispatch!(DistanceWriter, &self.writer, writer => writer.del_item(wtxn, item))
+    }
+
+    pub fn build<R: Rng + SeedableRng>(
+        &self,
+        wtxn: &mut RwTxn,
+        rng: &mut R,
+        n_trees: Option<usize>,
+    ) -> arroy::Result<()> {
+        dispatch!(DistanceWriter, &self.writer, writer => writer.build(wtxn, rng, n_trees))
+    }
+}
+
+enum DistanceReader<'t> {
+    Cosine(arroy::Reader<'t, Angular>),
+    Dot(arroy::Reader<'t, DotProduct>),
+    Euclidean(arroy::Reader<'t, Euclidean>),
+    Manhattan(arroy::Reader<'t, Manhattan>),
+    BinaryQuantizedCosine(arroy::Reader<'t, BinaryQuantizedAngular>),
+    BinaryQuantizedEuclidean(arroy::Reader<'t, BinaryQuantizedEuclidean>),
+    BinaryQuantizedManhattan(arroy::Reader<'t, BinaryQuantizedManhattan>),
+}
+
+/// An arroy reader using the distance and the quantization of its embedder.
+pub struct ArroyReader<'t> {
+    reader: DistanceReader<'t>,
+    full_precision: Option<FullPrecisionEmbeddings>,
+}
+
+impl<'t> ArroyReader<'t> {
+    pub fn open(
+        rtxn: &'t RoTxn,
+        database: arroy::Database<Unspecified>,
+        full_precision_database: heed::Database<Bytes, Bytes>,
+        index: u16,
+        storage: VectorStorage,
+    ) -> arroy::Result<Self> {
+        let reader = open_with_storage!(
+            DistanceReader,
+            storage,
+            arroy::Reader::open(rtxn, index, database.remap_data_type())?
+        );
+        let full_precision = storage
+            .keeps_full_precision()
+            .then_some(FullPrecisionEmbeddings { database: full_precision_database, index });
+        Ok(Self { reader, full_precision })
+    }
+
+    /// Returns the approximate nearest neighbors of the query, with their distance in the trees.
+    pub fn nns_by_vector(
+        &self,
+        rtxn: &'t RoTxn,
+        query: &[f32],
+        count: usize,
+        candidates: Option<&RoaringBitmap>,
+    ) -> arroy::Result<Vec<(ItemId, f32)>> {
+        dispatch!(DistanceReader, &self.reader, reader => reader.nns_by_vector(rtxn, query, count, None, candidates))
+    }
+
+    /// Returns the embedding of an item, with its full precision if it is kept.
+    pub fn item_vector(&self, rtxn: &'t RoTxn, item: ItemId) -> arroy::Result<Option<Vec<f32>>> {
+        if let Some(full_precision) = &self.full_precision {
+            return Ok(full_precision.get(rtxn, item)?);
+        }
+        dispatch!(DistanceReader, &self.reader, reader => reader.item_vector(rtxn, item))
+    }
+
+    /// Returns the full precision embedding of an item, `None` if the trees are not quantized
+    /// or the embedder doesn't rescore its neighbors.
+    pub fn full_precision_vector(
+        &self,
+        rtxn: &'t RoTxn,
+        item: ItemId,
+    ) -> heed::Result<Option<Vec<f32>>> {
+        match &self.full_precision {
+            Some(full_precision) => full_precision.get(rtxn, item),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether the neighbors can be rescored with their full precision embeddings.
+    pub fn can_rescore(&self) -> bool {
+        self.full_precision.is_some()
+    }
+}